@@ -21,12 +21,17 @@ use std::{collections::HashMap, fmt};
 //
 // > error[E0277]: the trait bound `__S: ScratchSpace` is not satisfied
 // > error[E0277]: the trait bound `__S: Serializer` is not satisfied
+// > error[E0277]: the trait bound `<__S as Fallible>::Error: From<HashIndexLenError>` is not
+// >   satisfied
 //
 // This is because those bounds are required by HashMap and Vec, but we removed the default
 //   generated bounds to prevent a recursive impl.
 // We can fix this by manually specifying the bounds required by HashMap and Vec in an attribute,
 //   and then everything will compile:
-#[archive(bound(serialize = "__S: rkyv::ser::ScratchSpace + rkyv::ser::Serializer"))]
+#[archive(bound(serialize = "
+    __S: rkyv::ser::ScratchSpace + rkyv::ser::Serializer,
+    <__S as rkyv::Fallible>::Error: From<rkyv::collections::hash_index::HashIndexLenError>,
+"))]
 // We'll also add support for validating our archived type. Validation will allow us to check an
 // arbitrary buffer of bytes before accessing it so we can avoid using any unsafe code.
 //
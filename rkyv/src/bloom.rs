@@ -0,0 +1,234 @@
+//! Archived bloom filters for approximate set membership.
+//!
+//! See [`AsBloomFilter`](crate::with::AsBloomFilter) for more details.
+
+use crate::{
+    collections::hash_index::HashBuilder,
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Archived,
+};
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+/// An archived set of `T` that only supports approximate membership checks.
+///
+/// This is the archived type of sets serialized with [`AsBloomFilter`](crate::with::AsBloomFilter).
+/// Instead of the elements of the set, it stores a bit array and the number of bits that are set
+/// for each element. [`probably_contains`](Self::probably_contains) never returns `false` for an
+/// element of the original set, but may return `true` for elements that weren't in it.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedBloomFilter<T> {
+    num_hashes: Archived<u32>,
+    bits: ArchivedVec<u8>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArchivedBloomFilter<T> {
+    /// The number of bits in the filter for each element of the set.
+    pub const BITS_PER_ITEM: usize = 10;
+
+    /// The number of bits that are set in the filter for each element of the set.
+    ///
+    /// Along with [`BITS_PER_ITEM`](Self::BITS_PER_ITEM), this gives a false positive rate of
+    /// about 1%.
+    pub const NUM_HASHES: u32 = 7;
+
+    /// The largest number of hashes that validation accepts.
+    ///
+    /// Checking membership takes time proportional to the number of hashes, so validation rejects
+    /// filters with more hashes than this. Validation also rejects filters with no hashes, which
+    /// would report that they probably contain every value.
+    pub const MAX_NUM_HASHES: u32 = 64;
+
+    #[inline]
+    fn make_hasher() -> HashBuilder {
+        HashBuilder::with_seeds(
+            0x2b4fa49a0c6c0a7b,
+            0x6e8cd05c21f3a1d9,
+            0x93b1d8e7f5a04c63,
+            0xd4c9e1a37b8f2e05,
+        )
+    }
+
+    /// Returns the indices of the bits for a value, given the number of bits in the filter.
+    #[inline]
+    fn bit_indices<Q: Hash + ?Sized>(
+        value: &Q,
+        num_hashes: u32,
+        num_bits: usize,
+    ) -> impl Iterator<Item = usize> {
+        let mut hasher = Self::make_hasher();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // Double hashing derives all of the indices from a single hash
+        let (h1, h2) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+        (0..num_hashes as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits as u64) as usize)
+    }
+
+    /// Gets the number of bits in the filter.
+    #[inline]
+    pub fn num_bits(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Gets the number of bits that are set in the filter for each element.
+    #[inline]
+    pub fn num_hashes(&self) -> u32 {
+        from_archived!(self.num_hashes)
+    }
+
+    /// Returns whether the filter was built from an empty set.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Returns `false` if the value definitely wasn't in the set, and `true` if it probably was.
+    #[inline]
+    pub fn probably_contains<Q: Hash + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        if self.is_empty() {
+            return false;
+        }
+
+        let bits = self.bits.as_slice();
+        Self::bit_indices(value, self.num_hashes(), self.num_bits())
+            .all(|index| bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Resolves an archived bloom filter from the parameters it was serialized with.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the values
+    #[inline]
+    pub unsafe fn resolve_from_resolver(pos: usize, resolver: BloomFilterResolver, out: *mut Self) {
+        let (fp, fo) = out_field!(out.num_hashes);
+        Self::NUM_HASHES.resolve(pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.bits);
+        ArchivedVec::resolve_from_len(resolver.byte_len, pos + fp, resolver.bits, fo);
+    }
+
+    /// Builds and serializes a bloom filter from an iterator of values.
+    pub fn serialize_from_iter<'a, U, I, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<BloomFilterResolver, S::Error>
+    where
+        U: 'a + Hash + ?Sized,
+        I: ExactSizeIterator<Item = &'a U>,
+        S: ScratchSpace + Serializer + ?Sized,
+    {
+        let byte_len = (iter.len() * Self::BITS_PER_ITEM).div_ceil(8);
+        let num_bits = byte_len * 8;
+        let bits = unsafe {
+            let mut bytes = crate::ScratchVec::<u8>::new(serializer, byte_len)?;
+            bytes.set_len(byte_len);
+            bytes.as_mut_slice().fill(0);
+
+            for value in iter {
+                for index in Self::bit_indices(value, Self::NUM_HASHES, num_bits) {
+                    bytes.as_mut_slice()[index / 8] |= 1 << (index % 8);
+                }
+            }
+
+            let bits = ArchivedVec::<u8>::serialize_from_slice(bytes.as_slice(), serializer)?;
+            bytes.free(serializer)?;
+            bits
+        };
+
+        Ok(BloomFilterResolver { byte_len, bits })
+    }
+}
+
+impl<T> fmt::Debug for ArchivedBloomFilter<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedBloomFilter")
+            .field("num_bits", &self.num_bits())
+            .field("num_hashes", &self.num_hashes())
+            .finish()
+    }
+}
+
+/// The resolver for an [`ArchivedBloomFilter`].
+pub struct BloomFilterResolver {
+    byte_len: usize,
+    bits: VecResolver,
+}
+
+/// Errors that can occur while checking an archived bloom filter.
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum BloomFilterError<E> {
+    /// The number of hashes was zero or greater than
+    /// [`MAX_NUM_HASHES`](ArchivedBloomFilter::MAX_NUM_HASHES)
+    InvalidNumHashes(u32),
+    /// An error occurred while checking the bit array
+    BitsError(E),
+}
+
+#[cfg(feature = "validation")]
+impl<E: fmt::Display> fmt::Display for BloomFilterError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BloomFilterError::InvalidNumHashes(n) => write!(f, "invalid number of hashes: {}", n),
+            BloomFilterError::BitsError(e) => write!(f, "error while checking bits: {}", e),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for BloomFilterError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                BloomFilterError::InvalidNumHashes(_) => None,
+                BloomFilterError::BitsError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::validation::ArchiveContext;
+    use bytecheck::{CheckBytes, Error};
+    use core::ptr;
+
+    impl<T, C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedBloomFilter<T>
+    where
+        C::Error: Error,
+    {
+        type Error = BloomFilterError<<ArchivedVec<u8> as CheckBytes<C>>::Error>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            // Integers are valid for any bit pattern and the bit indices are always within the
+            // bounds of the bit array, but the number of hashes bounds how long membership checks
+            // take and a filter without any hashes matches every value
+            let num_hashes = from_archived!(*ptr::addr_of!((*value).num_hashes));
+            if num_hashes == 0 || num_hashes > Self::MAX_NUM_HASHES {
+                return Err(BloomFilterError::InvalidNumHashes(num_hashes));
+            }
+            ArchivedVec::<u8>::check_bytes(ptr::addr_of!((*value).bits), context)
+                .map_err(BloomFilterError::BitsError)?;
+            Ok(&*value)
+        }
+    }
+};
@@ -279,6 +279,10 @@ const _: () = {
         ) -> Result<&'a Self, Self::Error> {
             let rel_ptr = RelPtr::<T>::manual_check_bytes(value.cast(), context)
                 .map_err(OwnedPointerError::PointerCheckBytesError)?;
+            if !context.follows_pointers() {
+                return Ok(&*value);
+            }
+
             let ptr = context
                 .check_subtree_rel_ptr(rel_ptr)
                 .map_err(OwnedPointerError::ContextError)?;
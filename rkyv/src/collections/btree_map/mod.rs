@@ -12,6 +12,7 @@ use core::{
     iter::FusedIterator,
     marker::PhantomData,
     ops::Index,
+    pin::Pin,
     ptr::NonNull,
 };
 use ptr_meta::Pointee;
@@ -236,6 +237,14 @@ impl<K, V> ArchivedBTreeMap<K, V> {
         }
     }
 
+    /// Returns a mutable pointer to a value of the map, derived from a mutable pointer to the map so
+    /// that it can be written through.
+    #[inline]
+    unsafe fn value_mut_ptr(map: *mut Self, value: &V) -> *mut V {
+        let offset = (value as *const V as usize).wrapping_sub(map as usize) as isize;
+        map.cast::<u8>().offset(offset).cast()
+    }
+
     /// Returns `true` if the map contains a value for the specified key.
     ///
     /// The key may be any borrowed form of the map's key type, but the ordering on the borrowed
@@ -260,23 +269,108 @@ impl<K, V> ArchivedBTreeMap<K, V> {
         self.get_key_value(key).map(|(_, v)| v)
     }
 
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// Keys and the structure of the tree are only accessible immutably, since changing them would
+    /// break the ordering of the map. Values can be edited in place as long as their layout is
+    /// preserved, so this is suited to updating scalar values like integers and flags.
+    #[inline]
+    pub fn get_pin<Q: Ord + ?Sized>(self: Pin<&mut Self>, key: &Q) -> Option<Pin<&mut V>>
+    where
+        K: Borrow<Q> + Ord,
+    {
+        unsafe {
+            let map = self.get_unchecked_mut() as *mut Self;
+            (*map)
+                .get(key)
+                .map(|value| Pin::new_unchecked(&mut *Self::value_mut_ptr(map, value)))
+        }
+    }
+
     /// Returns the key-value pair corresponding to the supplied key.
     ///
     /// The supplied key may be any borrowed form of the map’s key type, but the ordering on the
     /// borrowed form must match the ordering on the key type.
+    #[inline]
     pub fn get_key_value<Q: Ord + ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
     where
         K: Borrow<Q> + Ord,
     {
+        self.get_key_value_by(|probe| probe.borrow().cmp(k))
+    }
+
+    /// Returns `true` if the map contains a key for which `f` returns `Ordering::Equal`.
+    ///
+    /// See [`get_key_value_by`](ArchivedBTreeMap::get_key_value_by) for more details.
+    #[inline]
+    pub fn contains_key_by<F: FnMut(&K) -> Ordering>(&self, f: F) -> bool {
+        self.get_key_value_by(f).is_some()
+    }
+
+    /// Returns a reference to the value corresponding to the key for which `f` returns
+    /// `Ordering::Equal`.
+    ///
+    /// See [`get_key_value_by`](ArchivedBTreeMap::get_key_value_by) for more details.
+    #[inline]
+    pub fn get_by<F: FnMut(&K) -> Ordering>(&self, f: F) -> Option<&V> {
+        self.get_key_value_by(f).map(|(_, v)| v)
+    }
+
+    /// Returns the key-value pair for the key for which `f` returns `Ordering::Equal`.
+    ///
+    /// Like [`slice::binary_search_by`], the comparator function should return whether the probed
+    /// key is less than, equal to, or greater than the target. The map is searched in the order it
+    /// was serialized in, which is the order of the unarchived keys. This can be used when the
+    /// ordering of the archived keys doesn't match the ordering of the unarchived keys, or to search
+    /// using a type that doesn't implement `Ord`. Note that validation checks that the archived keys
+    /// are sorted by their own `Ord` implementation, so archives with mismatched orderings can only
+    /// be accessed without validation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::cmp::Ordering;
+    /// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, Archive, Serialize};
+    /// use std::collections::BTreeMap;
+    ///
+    /// // Sorts from largest to smallest, but the archived key uses the default ordering
+    /// #[derive(Archive, Serialize, PartialEq, Eq)]
+    /// #[archive_attr(derive(PartialEq, Eq, PartialOrd, Ord))]
+    /// struct Descending(u32);
+    ///
+    /// impl PartialOrd for Descending {
+    ///     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    ///         Some(self.cmp(other))
+    ///     }
+    /// }
+    ///
+    /// impl Ord for Descending {
+    ///     fn cmp(&self, other: &Self) -> Ordering {
+    ///         other.0.cmp(&self.0)
+    ///     }
+    /// }
+    ///
+    /// let mut value = BTreeMap::new();
+    /// for i in 0..100 {
+    ///     value.insert(Descending(i), i);
+    /// }
+    ///
+    /// let mut serializer = AllocSerializer::<4096>::default();
+    /// serializer.serialize_value(&value).unwrap();
+    /// let bytes = serializer.into_serializer().into_inner();
+    /// let archived = unsafe { archived_root::<BTreeMap<Descending, u32>>(&bytes) };
+    ///
+    /// // Search with the same ordering the map was serialized with
+    /// let value = archived.get_by(|probe| 42.cmp(&probe.0));
+    /// assert_eq!(value, Some(&42));
+    /// ```
+    pub fn get_key_value_by<F: FnMut(&K) -> Ordering>(&self, mut f: F) -> Option<(&K, &V)> {
         if let Some(mut current) = self.root() {
             loop {
                 match current {
                     ClassifiedNode::Inner(node) => {
                         // Binary search for the next node layer
-                        let next = match node
-                            .tail
-                            .binary_search_by(|probe| probe.key.borrow().cmp(k))
-                        {
+                        let next = match node.tail.binary_search_by(|probe| f(&probe.key)) {
                             Ok(i) => unsafe { &*node.tail[i].ptr.as_ptr() },
                             Err(i) => {
                                 if i == 0 {
@@ -290,10 +384,7 @@ impl<K, V> ArchivedBTreeMap<K, V> {
                     }
                     ClassifiedNode::Leaf(node) => {
                         // Binary search for the value
-                        if let Ok(i) = node
-                            .tail
-                            .binary_search_by(|probe| probe.key.borrow().cmp(k))
-                        {
+                        if let Ok(i) = node.tail.binary_search_by(|probe| f(&probe.key)) {
                             let entry = &node.tail[i];
                             break Some((&entry.key, &entry.value));
                         } else {
@@ -343,6 +434,44 @@ impl<K, V> ArchivedBTreeMap<K, V> {
         }
     }
 
+    /// Gets an iterator over the mutable values of the map, in order by key.
+    ///
+    /// Values can be edited in place as long as their layout is preserved. Scalar mutations (e.g.
+    /// incrementing a counter) are always sound, but any edit that would need to move or resize
+    /// out-of-line data cannot be done in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::archived_root_mut;
+    /// use std::{collections::BTreeMap, pin::Pin};
+    ///
+    /// let mut counts = BTreeMap::new();
+    /// counts.insert("a".to_string(), 1u32);
+    /// counts.insert("b".to_string(), 2u32);
+    ///
+    /// let mut bytes = rkyv::to_bytes::<_, 256>(&counts).unwrap();
+    /// let mut archived = unsafe {
+    ///     archived_root_mut::<BTreeMap<String, u32>>(Pin::new(bytes.as_mut_slice()))
+    /// };
+    /// for mut value in archived.as_mut().values_pin() {
+    ///     *value *= 10;
+    /// }
+    ///
+    /// assert_eq!(archived.get("a"), Some(&10));
+    /// assert_eq!(archived.get("b"), Some(&20));
+    /// ```
+    #[inline]
+    pub fn values_pin(self: Pin<&mut Self>) -> ValuesPin<'_, K, V> {
+        unsafe {
+            let map = self.get_unchecked_mut() as *mut Self;
+            ValuesPin {
+                map,
+                inner: RawIter::new((*map).first(), 0, (*map).len()),
+            }
+        }
+    }
+
     /// Resolves a B-tree map from its length.
     ///
     /// # Safety
@@ -736,3 +865,29 @@ impl<'a, K, V> Iterator for Values<'a, K, V> {
 
 impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
 impl<'a, K, V> FusedIterator for Values<'a, K, V> {}
+
+/// An iterator over the mutable values of an archived B-tree map.
+pub struct ValuesPin<'a, K, V> {
+    map: *mut ArchivedBTreeMap<K, V>,
+    inner: RawIter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesPin<'a, K, V> {
+    type Item = Pin<&'a mut V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: The map is borrowed mutably and each value is only yielded once
+        self.inner.next().map(|(_, v)| unsafe {
+            Pin::new_unchecked(&mut *ArchivedBTreeMap::value_mut_ptr(self.map, v))
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValuesPin<'a, K, V> {}
+impl<'a, K, V> FusedIterator for ValuesPin<'a, K, V> {}
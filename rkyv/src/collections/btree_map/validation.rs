@@ -489,6 +489,9 @@ const _: () = {
             if len > 0 {
                 let root_rel_ptr =
                     RelPtr::manual_check_bytes(ptr::addr_of!((*value).root), context)?;
+                if !context.follows_pointers() {
+                    return Ok(&*value);
+                }
 
                 // Walk all the inner nodes, claim their memory, and check their contents
                 let mut nodes = VecDeque::new();
@@ -1,7 +1,12 @@
 //! A helper type that archives index data for hashed collections using
 //! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+//!
+//! Hash indexes record the [`HashAlgorithm`] they were built with, so lookups always use the same
+//! hash function that was used during serialization.
 
-use crate::{Archive, Archived, RelPtr};
+use crate::{Archived, FixedUsize, RelPtr};
+#[allow(deprecated)]
+use core::hash::SipHasher;
 use core::{
     fmt,
     hash::{Hash, Hasher},
@@ -11,38 +16,223 @@ use core::{
 /// The hash builder for archived hash indexes.
 pub use seahash::SeaHasher as HashBuilder;
 
+/// The hash algorithms that archived hash indexes can be built with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum HashAlgorithm {
+    /// [SeaHash](https://docs.rs/seahash), a fast non-cryptographic hash function.
+    ///
+    /// This is the default, and is a good choice for archives built from trusted keys.
+    SeaHash = 0,
+    /// SipHash-2-4, a slower hash function.
+    ///
+    /// The keys of the hash function are fixed and public so that lookups in any archive can
+    /// reproduce the hashes, so this is only a different hash function and doesn't make it any
+    /// harder to craft keys that collide. Building a hash index from many keys that collide can
+    /// take a very long time with either algorithm.
+    SipHash = 1,
+}
+
+impl HashAlgorithm {
+    /// Returns the hash algorithm with the given identifier, or `None` if there isn't one.
+    #[inline]
+    pub const fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::SeaHash),
+            1 => Some(Self::SipHash),
+            _ => None,
+        }
+    }
+
+    /// Returns the identifier of the hash algorithm that is stored in archived hash indexes.
+    #[inline]
+    pub const fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns a new hasher for the hash algorithm. The seeds for each algorithm are fixed for
+    /// reproducibility.
+    #[inline]
+    pub fn hasher(self) -> IndexHasher {
+        match self {
+            Self::SeaHash => IndexHasher::SeaHash(HashBuilder::with_seeds(
+                0x08576fb6170b5f5f,
+                0x587775eeb84a7e46,
+                0xac701115428ee569,
+                0x910feb91b92bb1cd,
+            )),
+            #[allow(deprecated)]
+            Self::SipHash => IndexHasher::SipHash(SipHasher::new_with_keys(
+                0x2fd1c7a6e8b35d09,
+                0x6b0e4a91c3f8d257,
+            )),
+        }
+    }
+}
+
+impl Default for HashAlgorithm {
+    #[inline]
+    fn default() -> Self {
+        Self::SeaHash
+    }
+}
+
+/// The hasher for an archived hash index.
+///
+/// This uses the [`HashAlgorithm`] that the hash index was built with.
+#[derive(Clone)]
+pub enum IndexHasher {
+    /// A SeaHash hasher.
+    SeaHash(HashBuilder),
+    /// A SipHash-2-4 hasher.
+    #[allow(deprecated)]
+    SipHash(SipHasher),
+}
+
+macro_rules! forward_write {
+    ($($name:ident: $ty:ty),*) => {
+        $(
+            #[inline]
+            fn $name(&mut self, n: $ty) {
+                match self {
+                    Self::SeaHash(hasher) => hasher.$name(n),
+                    Self::SipHash(hasher) => hasher.$name(n),
+                }
+            }
+        )*
+    };
+}
+
+impl Hasher for IndexHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        match self {
+            Self::SeaHash(hasher) => hasher.finish(),
+            Self::SipHash(hasher) => hasher.finish(),
+        }
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        match self {
+            Self::SeaHash(hasher) => hasher.write(bytes),
+            Self::SipHash(hasher) => hasher.write(bytes),
+        }
+    }
+
+    // SeaHasher hashes integers differently than their bytes, so these have to be forwarded too
+    forward_write!(
+        write_u8: u8,
+        write_u16: u16,
+        write_u32: u32,
+        write_u64: u64,
+        write_u128: u128,
+        write_usize: usize,
+        write_i8: i8,
+        write_i16: i16,
+        write_i32: i32,
+        write_i64: i64,
+        write_i128: i128,
+        write_isize: isize
+    );
+}
+
+impl fmt::Debug for IndexHasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SeaHash(_) => f.write_str("IndexHasher::SeaHash"),
+            Self::SipHash(_) => f.write_str("IndexHasher::SipHash"),
+        }
+    }
+}
+
+/// An error resulting from building a hash index with too many entries.
+///
+/// The highest bit of the archived length of a hash index records its hash algorithm, so hash
+/// indexes can have at most [`ArchivedHashIndex::MAX_LEN`] entries.
+#[derive(Debug)]
+pub struct HashIndexLenError {
+    /// The number of entries in the hash index
+    pub len: usize,
+}
+
+impl fmt::Display for HashIndexLenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "hash index has {} entries but can have at most {}",
+            self.len,
+            ArchivedHashIndex::MAX_LEN,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for HashIndexLenError {}
+};
+
 #[cfg(feature = "validation")]
 pub mod validation;
 
 /// An archived hash index.
 #[cfg_attr(feature = "strict", repr(C))]
 pub struct ArchivedHashIndex {
+    // The highest bit holds the identifier of the hash algorithm. Displacements can't point to
+    // indices with that bit set, so no hash index is long enough to need it for its length. Hash
+    // indexes built with SeaHash keep the bit clear, so they have the same format as hash indexes
+    // that didn't record their hash algorithm.
     len: Archived<usize>,
     displace: RelPtr<Archived<u32>>,
 }
 
 impl ArchivedHashIndex {
+    const ALGORITHM_SHIFT: u32 = FixedUsize::BITS - 1;
+
+    /// The maximum number of entries that a hash index can have.
+    pub const MAX_LEN: usize = (FixedUsize::MAX >> 1) as usize;
+
+    #[inline]
+    const fn stored_len(&self) -> FixedUsize {
+        from_archived!(self.len)
+    }
+
     /// Gets the number of items in the hash index.
     #[inline]
     pub const fn len(&self) -> usize {
-        from_archived!(self.len) as usize
+        (self.stored_len() & !(1 << Self::ALGORITHM_SHIFT)) as usize
     }
 
+    /// Gets the hash algorithm that the hash index was built with.
     #[inline]
-    fn make_hasher() -> HashBuilder {
-        HashBuilder::with_seeds(
-            0x08576fb6170b5f5f,
-            0x587775eeb84a7e46,
-            0xac701115428ee569,
-            0x910feb91b92bb1cd,
-        )
+    pub const fn algorithm(&self) -> HashAlgorithm {
+        match self.stored_len() >> Self::ALGORITHM_SHIFT {
+            0 => HashAlgorithm::SeaHash,
+            _ => HashAlgorithm::SipHash,
+        }
     }
 
-    /// Gets the hasher for this hash index. The hasher for all archived hash indexes is the same
-    /// for reproducibility.
+    /// Gets the SeaHash hasher that hash indexes built with [`SeaHash`](HashAlgorithm::SeaHash)
+    /// use. The hasher for all of those archived hash indexes is the same for reproducibility.
+    ///
+    /// This isn't the hasher for hash indexes built with other hash algorithms, see
+    /// [`index_hasher`](Self::index_hasher) for the hasher that lookups use.
     #[inline]
     pub fn hasher(&self) -> HashBuilder {
-        Self::make_hasher()
+        match HashAlgorithm::SeaHash.hasher() {
+            IndexHasher::SeaHash(hasher) => hasher,
+            IndexHasher::SipHash(_) => unreachable!(),
+        }
+    }
+
+    /// Gets the hasher for the hash algorithm that this hash index was built with. The hasher for
+    /// all archived hash indexes built with the same hash algorithm is the same for
+    /// reproducibility.
+    #[inline]
+    pub fn index_hasher(&self) -> IndexHasher {
+        self.algorithm().hasher()
     }
 
     #[inline]
@@ -64,7 +254,7 @@ impl ArchivedHashIndex {
         if self.is_empty() {
             return None;
         }
-        let mut hasher = self.hasher();
+        let mut hasher = self.index_hasher();
         k.hash(&mut hasher);
         let displace_index = hasher.finish() % self.len() as u64;
         let displace = self.displace(displace_index as usize);
@@ -74,7 +264,7 @@ impl ArchivedHashIndex {
         } else if displace & 0x80_00_00_00 == 0 {
             Some(displace as usize)
         } else {
-            let mut hasher = self.hasher();
+            let mut hasher = self.index_hasher();
             displace.hash(&mut hasher);
             k.hash(&mut hasher);
             let index = hasher.finish() % self.len() as u64;
@@ -102,8 +292,10 @@ impl ArchivedHashIndex {
         resolver: HashIndexResolver,
         out: *mut Self,
     ) {
-        let (fp, fo) = out_field!(out.len);
-        len.resolve(pos + fp, (), fo);
+        let (_, fo) = out_field!(out.len);
+        debug_assert!(len <= Self::MAX_LEN);
+        let algorithm = (resolver.algorithm.id() as FixedUsize) << Self::ALGORITHM_SHIFT;
+        fo.write(to_archived!(len as FixedUsize | algorithm));
 
         let (fp, fo) = out_field!(out.displace);
         RelPtr::emplace(pos + fp, resolver.displace_pos, fo);
@@ -131,6 +323,7 @@ const _: () = {
         /// - The keys returned by the iterator must be unique.
         /// - `entries` must have a capacity of `iter.len()` entries.
         #[allow(clippy::type_complexity)]
+        #[inline]
         pub unsafe fn build_and_serialize<'a, K, V, S, I>(
             iter: I,
             serializer: &mut S,
@@ -140,9 +333,40 @@ const _: () = {
             K: 'a + Hash,
             V: 'a,
             S: Serializer + ScratchSpace + ?Sized,
+            S::Error: From<HashIndexLenError>,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+        {
+            Self::build_and_serialize_with(iter, HashAlgorithm::default(), serializer, entries)
+        }
+
+        /// Builds and serializes a hash index from an iterator of key-value pairs using the given
+        /// hash algorithm.
+        ///
+        /// Fails with a [`HashIndexLenError`] if the iterator has more than
+        /// [`MAX_LEN`](Self::MAX_LEN) entries.
+        ///
+        /// # Safety
+        ///
+        /// - The keys returned by the iterator must be unique.
+        /// - `entries` must have a capacity of `iter.len()` entries.
+        #[allow(clippy::type_complexity)]
+        pub unsafe fn build_and_serialize_with<'a, K, V, S, I>(
+            iter: I,
+            algorithm: HashAlgorithm,
+            serializer: &mut S,
+            entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
+        ) -> Result<HashIndexResolver, S::Error>
+        where
+            K: 'a + Hash,
+            V: 'a,
+            S: Serializer + ScratchSpace + ?Sized,
+            S::Error: From<HashIndexLenError>,
             I: ExactSizeIterator<Item = (&'a K, &'a V)>,
         {
             let len = iter.len();
+            if len > Self::MAX_LEN {
+                return Err(HashIndexLenError { len }.into());
+            }
 
             let mut bucket_size = ScratchVec::new(serializer, len)?;
             for _ in 0..len {
@@ -152,7 +376,7 @@ const _: () = {
             let mut displaces = ScratchVec::new(serializer, len)?;
 
             for (key, value) in iter {
-                let mut hasher = Self::make_hasher();
+                let mut hasher = algorithm.hasher();
                 key.hash(&mut hasher);
                 let displace = (hasher.finish() % len as u64) as u32;
                 displaces.push((displace, (key, value)));
@@ -185,13 +409,13 @@ const _: () = {
 
                 if bucket_size > 1 {
                     'find_seed: for seed in 0x80_00_00_00u32..=0xFF_FF_FF_FFu32 {
-                        let mut base_hasher = Self::make_hasher();
+                        let mut base_hasher = algorithm.hasher();
                         seed.hash(&mut base_hasher);
 
                         assignments.clear();
 
                         for &(_, (key, _)) in bucket.iter() {
-                            let mut hasher = base_hasher;
+                            let mut hasher = base_hasher.clone();
                             key.hash(&mut hasher);
                             let index = (hasher.finish() % len as u64) as u32;
                             if occupied[index as usize] || assignments.contains(&index) {
@@ -237,7 +461,10 @@ const _: () = {
             displaces.free(serializer)?;
             bucket_size.free(serializer)?;
 
-            Ok(HashIndexResolver { displace_pos })
+            Ok(HashIndexResolver {
+                displace_pos,
+                algorithm,
+            })
         }
     }
 };
@@ -251,4 +478,5 @@ impl fmt::Debug for ArchivedHashIndex {
 /// The resolver for an archived hash index.
 pub struct HashIndexResolver {
     displace_pos: usize,
+    algorithm: HashAlgorithm,
 }
@@ -84,14 +84,19 @@ where
         value: *const Self,
         context: &mut C,
     ) -> Result<&'a Self, Self::Error> {
-        let len = from_archived!(*Archived::<usize>::check_bytes(
+        // The highest bit of the length holds the hash algorithm, which is valid for any bit
+        let len = (from_archived!(*Archived::<usize>::check_bytes(
             ptr::addr_of!((*value).len),
             context,
-        )?) as usize;
+        )?) & !(1 << ArchivedHashIndex::ALGORITHM_SHIFT)) as usize;
         Layout::array::<Archived<u32>>(len)?;
 
         let displace_rel_ptr =
             RelPtr::manual_check_bytes(ptr::addr_of!((*value).displace), context)?;
+        if !context.follows_pointers() {
+            return Ok(&*value);
+        }
+
         let displace_ptr = context
             .check_subtree_ptr::<[Archived<u32>]>(
                 displace_rel_ptr.base(),
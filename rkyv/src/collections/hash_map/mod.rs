@@ -8,13 +8,14 @@ pub mod validation;
 
 use crate::{
     collections::{
-        hash_index::{ArchivedHashIndex, HashIndexResolver},
+        hash_index::{ArchivedHashIndex, HashAlgorithm, HashIndexResolver},
         util::Entry,
     },
     RelPtr,
 };
 #[cfg(feature = "alloc")]
 use crate::{
+    collections::hash_index::HashIndexLenError,
     ser::{ScratchSpace, Serializer},
     Serialize,
 };
@@ -23,6 +24,13 @@ use core::{
 };
 
 /// An archived `HashMap`.
+///
+/// Archived hash maps are laid out as two separate arrays: a displacement table for the
+/// [`ArchivedHashIndex`] and a densely-packed array of key-value entries. Because the index is a
+/// perfect hash, every lookup reads exactly one displacement and at most one entry, regardless of
+/// the number of items in the map. For archives that are memory-mapped, that bounds a lookup to two
+/// page touches (plus any out-of-line data for the key being compared), so there is no probing
+/// sequence to keep cache-local.
 #[cfg_attr(feature = "strict", repr(C))]
 pub struct ArchivedHashMap<K, V> {
     index: ArchivedHashIndex,
@@ -36,13 +44,24 @@ impl<K, V> ArchivedHashMap<K, V> {
         self.index.len()
     }
 
-    /// Gets the hasher for this hashmap. The hasher for all archived hashmaps is the same for
-    /// reproducibility.
+    /// Gets the hasher for hashmaps built with [`SeaHash`](HashAlgorithm::SeaHash). The hasher
+    /// for all of those archived hashmaps is the same for reproducibility.
+    ///
+    /// This is only the hasher for this hashmap if [`hash_algorithm`](Self::hash_algorithm) is
+    /// `SeaHash`.
     #[inline]
     pub fn hasher(&self) -> seahash::SeaHasher {
         self.index.hasher()
     }
 
+    /// Gets the hash algorithm that this hashmap was built with.
+    ///
+    /// Lookups automatically use the same hash algorithm.
+    #[inline]
+    pub const fn hash_algorithm(&self) -> HashAlgorithm {
+        self.index.algorithm()
+    }
+
     #[inline]
     unsafe fn entry(&self, index: usize) -> &Entry<K, V> {
         &*self.entries.as_ptr().add(index)
@@ -162,6 +181,11 @@ impl<K, V> ArchivedHashMap<K, V> {
     }
 
     /// Gets an iterator over the mutable key-value entries in the hash map.
+    ///
+    /// Keys are only accessible immutably, since changing a key would invalidate the hash index.
+    /// Values can be edited in place as long as their layout is preserved, so this is suited to
+    /// updating scalar values like integers and flags. See [`values_pin`](Self::values_pin) for
+    /// an example.
     #[inline]
     pub fn iter_pin(self: Pin<&mut Self>) -> IterPin<K, V> {
         IterPin {
@@ -186,6 +210,32 @@ impl<K, V> ArchivedHashMap<K, V> {
     }
 
     /// Gets an iterator over the mutable values in the hash map.
+    ///
+    /// Values can be edited in place as long as their layout is preserved. Scalar mutations (e.g.
+    /// incrementing a counter) are always sound, but any edit that would need to move or resize
+    /// out-of-line data cannot be done in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::{archived_root_mut, Archived};
+    /// use std::{collections::HashMap, pin::Pin};
+    ///
+    /// let mut counts = HashMap::new();
+    /// counts.insert("a".to_string(), 1u32);
+    /// counts.insert("b".to_string(), 2u32);
+    ///
+    /// let mut bytes = rkyv::to_bytes::<_, 256>(&counts).unwrap();
+    /// let mut archived = unsafe {
+    ///     archived_root_mut::<HashMap<String, u32>>(Pin::new(bytes.as_mut_slice()))
+    /// };
+    /// for mut value in archived.as_mut().values_pin() {
+    ///     *value += 1;
+    /// }
+    ///
+    /// let total: u32 = archived.values().map(|v| *v).sum();
+    /// assert_eq!(total, 5);
+    /// ```
     #[inline]
     pub fn values_pin(self: Pin<&mut Self>) -> ValuesPin<K, V> {
         ValuesPin {
@@ -223,6 +273,7 @@ const _: () = {
         /// # Safety
         ///
         /// The keys returned by the iterator must be unique.
+        #[inline]
         pub unsafe fn serialize_from_iter<'a, KU, VU, S, I>(
             iter: I,
             serializer: &mut S,
@@ -231,6 +282,28 @@ const _: () = {
             KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
             VU: 'a + Serialize<S, Archived = V>,
             S: Serializer + ScratchSpace + ?Sized,
+            S::Error: From<HashIndexLenError>,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        {
+            Self::serialize_from_iter_with(iter, HashAlgorithm::default(), serializer)
+        }
+
+        /// Serializes an iterator of key-value pairs as a hash map using the given hash
+        /// algorithm.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter_with<'a, KU, VU, S, I>(
+            iter: I,
+            algorithm: HashAlgorithm,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            S::Error: From<HashIndexLenError>,
             I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
         {
             use crate::ScratchVec;
@@ -239,8 +312,12 @@ const _: () = {
 
             let mut entries = ScratchVec::new(serializer, len)?;
             entries.set_len(len);
-            let index_resolver =
-                ArchivedHashIndex::build_and_serialize(iter, serializer, &mut entries)?;
+            let index_resolver = ArchivedHashIndex::build_and_serialize_with(
+                iter,
+                algorithm,
+                serializer,
+                &mut entries,
+            )?;
             let mut entries = entries.assume_init();
 
             // Serialize entries
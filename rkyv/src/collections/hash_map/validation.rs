@@ -124,6 +124,10 @@ where
         Layout::array::<Entry<K, V>>(index.len())?;
 
         let entries_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).entries), context)?;
+        if !context.follows_pointers() {
+            return Ok(&*value);
+        }
+
         let entries_ptr = context
             .check_subtree_ptr::<[Entry<K, V>]>(
                 entries_rel_ptr.base(),
@@ -3,9 +3,13 @@
 //! During archiving, hashsets are built into minimal perfect hashsets using
 //! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
 
-use crate::collections::hash_map::{ArchivedHashMap, HashMapResolver, Keys};
+use crate::collections::{
+    hash_index::HashAlgorithm,
+    hash_map::{ArchivedHashMap, HashMapResolver, Keys},
+};
 #[cfg(feature = "alloc")]
 use crate::{
+    collections::hash_index::HashIndexLenError,
     ser::{ScratchSpace, Serializer},
     Serialize,
 };
@@ -45,12 +49,21 @@ impl<K> ArchivedHashSet<K> {
     }
 
     /// Gets the hasher for the underlying hash map.
+    ///
+    /// This is only the hasher for this hash set if [`hash_algorithm`](Self::hash_algorithm) is
+    /// [`SeaHash`](HashAlgorithm::SeaHash).
     #[cfg(feature = "alloc")]
     #[inline]
     pub fn hasher(&self) -> seahash::SeaHasher {
         self.0.hasher()
     }
 
+    /// Gets the hash algorithm that the underlying hash map was built with.
+    #[inline]
+    pub const fn hash_algorithm(&self) -> HashAlgorithm {
+        self.0.hash_algorithm()
+    }
+
     /// Returns whether there are no items in the hash set.
     #[inline]
     pub const fn is_empty(&self) -> bool {
@@ -95,10 +108,33 @@ impl<K> ArchivedHashSet<K> {
     where
         KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
         S: Serializer + ScratchSpace + ?Sized,
+        S::Error: From<HashIndexLenError>,
+        I: ExactSizeIterator<Item = &'a KU>,
+    {
+        Self::serialize_from_iter_with(iter, HashAlgorithm::default(), serializer)
+    }
+
+    /// Serializes an iterator of keys as a hash set using the given hash algorithm.
+    ///
+    /// # Safety
+    ///
+    /// The keys returned by the iterator must be unique.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub unsafe fn serialize_from_iter_with<'a, KU, S, I>(
+        iter: I,
+        algorithm: HashAlgorithm,
+        serializer: &mut S,
+    ) -> Result<HashSetResolver, S::Error>
+    where
+        KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+        S: Serializer + ScratchSpace + ?Sized,
+        S::Error: From<HashIndexLenError>,
         I: ExactSizeIterator<Item = &'a KU>,
     {
-        Ok(HashSetResolver(ArchivedHashMap::serialize_from_iter(
+        Ok(HashSetResolver(ArchivedHashMap::serialize_from_iter_with(
             iter.map(|x| (x, &())),
+            algorithm,
             serializer,
         )?))
     }
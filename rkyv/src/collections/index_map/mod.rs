@@ -215,6 +215,7 @@ impl<K, V> ArchivedIndexMap<K, V> {
 #[cfg(feature = "alloc")]
 const _: () = {
     use crate::{
+        collections::hash_index::HashIndexLenError,
         ser::{ScratchSpace, Serializer},
         Serialize,
     };
@@ -237,6 +238,7 @@ const _: () = {
             I: Clone + ExactSizeIterator<Item = (&'a UK, &'a UV)>,
             F: Fn(&UK) -> usize,
             S: Serializer + ScratchSpace + ?Sized,
+            S::Error: From<HashIndexLenError>,
         {
             use crate::ScratchVec;
 
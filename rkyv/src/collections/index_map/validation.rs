@@ -136,9 +136,15 @@ where
     ) -> Result<&'a Self, Self::Error> {
         let index = ArchivedHashIndex::check_bytes(ptr::addr_of!((*value).index), context)?;
 
-        // Entries
         Layout::array::<Entry<K, V>>(index.len())?;
         let entries_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).entries), context)?;
+        Layout::array::<Archived<usize>>(index.len())?;
+        let pivots_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).pivots), context)?;
+        if !context.follows_pointers() {
+            return Ok(&*value);
+        }
+
+        // Entries
         let entries_ptr = context
             .check_subtree_ptr::<[Entry<K, V>]>(
                 entries_rel_ptr.base(),
@@ -156,8 +162,6 @@ where
             .map_err(IndexMapError::ContextError)?;
 
         // Pivots
-        Layout::array::<Archived<usize>>(index.len())?;
-        let pivots_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).pivots), context)?;
         let pivots_ptr = context
             .check_subtree_ptr::<[Archived<usize>]>(
                 pivots_rel_ptr.base(),
@@ -124,6 +124,7 @@ impl<K> ArchivedIndexSet<K> {
 #[cfg(feature = "alloc")]
 const _: () = {
     use crate::{
+        collections::hash_index::HashIndexLenError,
         ser::{ScratchSpace, Serializer},
         Serialize,
     };
@@ -146,6 +147,7 @@ const _: () = {
             I: Clone + ExactSizeIterator<Item = &'a UK>,
             F: Fn(&UK) -> usize,
             S: ScratchSpace + Serializer + ?Sized,
+            S::Error: From<HashIndexLenError>,
         {
             Ok(IndexSetResolver(
                 ArchivedIndexMap::serialize_from_iter_index(
@@ -8,6 +8,9 @@ pub mod hash_set;
 // TODO: move these into a separate crate when indexmap adds rkyv support
 pub mod index_map;
 pub mod index_set;
+pub mod soa_map;
+pub mod sorted_vec;
+pub mod sorted_vec_map;
 pub mod util;
 
 pub use self::btree_map::ArchivedBTreeMap;
@@ -17,3 +20,6 @@ pub use self::hash_set::ArchivedHashSet;
 // TODO: move these into a separate crate when indexmap adds rkyv support
 pub use self::index_map::ArchivedIndexMap;
 pub use self::index_set::ArchivedIndexSet;
+pub use self::soa_map::ArchivedSoAMap;
+pub use self::sorted_vec::ArchivedSortedVec;
+pub use self::sorted_vec_map::ArchivedSortedVecMap;
@@ -0,0 +1,217 @@
+//! Archived struct-of-arrays map implementation.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Serialize,
+};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    iter::{FusedIterator, Zip},
+    slice,
+};
+
+/// An archived map that stores its keys and values in two separate arrays.
+///
+/// This is the archived type of maps serialized with [`MapAsSoA`](crate::with::MapAsSoA). The keys
+/// are sorted, and the value at each index belongs to the key at the same index. Because the values
+/// are contiguous, they can be scanned in bulk with [`values_slice`](Self::values_slice). Lookups
+/// perform a binary search over the keys.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedSoAMap<K, V> {
+    keys: ArchivedVec<K>,
+    values: ArchivedVec<V>,
+}
+
+impl<K, V> ArchivedSoAMap<K, V> {
+    /// Gets the number of items in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns whether there are no items in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Gets the keys of the map as a slice in ascending order.
+    #[inline]
+    pub fn keys_slice(&self) -> &[K] {
+        self.keys.as_slice()
+    }
+
+    /// Gets the values of the map as a slice, in the same order as their keys.
+    #[inline]
+    pub fn values_slice(&self) -> &[V] {
+        self.values.as_slice()
+    }
+
+    #[inline]
+    fn find<Q: Ord + ?Sized>(&self, k: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.keys_slice()
+            .binary_search_by(|key| key.borrow().cmp(k))
+            .ok()
+    }
+
+    /// Finds the key-value entry for a key.
+    #[inline]
+    pub fn get_key_value<Q: Ord + ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(k)
+            .map(|index| (&self.keys_slice()[index], &self.values_slice()[index]))
+    }
+
+    /// Gets the value associated with the given key.
+    #[inline]
+    pub fn get<Q: Ord + ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(k).map(|index| &self.values_slice()[index])
+    }
+
+    /// Returns whether a key is present in the map.
+    #[inline]
+    pub fn contains_key<Q: Ord + ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.find(k).is_some()
+    }
+
+    /// Gets an iterator over the key-value entries in the map in ascending key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.keys_slice().iter().zip(self.values_slice().iter()),
+        }
+    }
+
+    /// Resolves an archived struct-of-arrays map from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the map
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: SoAMapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.keys);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.keys, fo);
+        let (fp, fo) = out_field!(out.values);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.values, fo);
+    }
+
+    /// Serializes an iterator of key-value pairs as a struct-of-arrays map.
+    ///
+    /// The keys returned by the iterator should be unique, and the archived keys should be ordered
+    /// the same way as the unarchived keys. Otherwise, the archived map will fail to validate.
+    pub fn serialize_from_iter<'a, KU, VU, S, I>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<SoAMapResolver, S::Error>
+    where
+        KU: 'a + Serialize<S, Archived = K> + Ord,
+        VU: 'a + Serialize<S, Archived = V>,
+        S: ScratchSpace + Serializer + ?Sized,
+        I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+    {
+        use crate::ScratchVec;
+
+        unsafe {
+            let mut entries = ScratchVec::new(serializer, iter.len())?;
+            for entry in iter {
+                entries.push(entry);
+            }
+            entries.as_mut_slice().sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+            let keys = ArchivedVec::<K>::serialize_from_iter::<KU, _, _, _>(
+                entries.iter().map(|(key, _)| *key),
+                serializer,
+            )?;
+            let values = ArchivedVec::<V>::serialize_from_iter::<VU, _, _, _>(
+                entries.iter().map(|(_, value)| *value),
+                serializer,
+            )?;
+
+            entries.free(serializer)?;
+
+            Ok(SoAMapResolver { keys, values })
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedSoAMap<K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ArchivedSoAMap<K, V> {}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ArchivedSoAMap<K, V> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for ArchivedSoAMap<K, V> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+/// The resolver for an [`ArchivedSoAMap`].
+pub struct SoAMapResolver {
+    keys: VecResolver,
+    values: VecResolver,
+}
+
+/// An iterator over the key-value pairs of a struct-of-arrays map.
+pub struct Iter<'a, K, V> {
+    inner: Zip<slice::Iter<'a, K>, slice::Iter<'a, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
@@ -0,0 +1,99 @@
+//! Validation implementation for ArchivedSoAMap.
+
+use crate::{collections::soa_map::ArchivedSoAMap, validation::ArchiveContext, vec::ArchivedVec};
+use bytecheck::{CheckBytes, Error};
+use core::{fmt, ptr};
+
+/// Errors that can occur while checking an archived struct-of-arrays map.
+#[derive(Debug)]
+pub enum SoAMapError<K, V> {
+    /// An error occurred while checking the keys
+    CheckKeysError(K),
+    /// An error occurred while checking the values
+    CheckValuesError(V),
+    /// The number of keys and values were different
+    LengthMismatch {
+        /// The number of keys
+        keys: usize,
+        /// The number of values
+        values: usize,
+    },
+    /// A key was not greater than the key before it
+    UnsortedKey {
+        /// The index of the key
+        index: usize,
+    },
+}
+
+impl<K: fmt::Display, V: fmt::Display> fmt::Display for SoAMapError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoAMapError::CheckKeysError(e) => write!(f, "keys check error: {}", e),
+            SoAMapError::CheckValuesError(e) => write!(f, "values check error: {}", e),
+            SoAMapError::LengthMismatch { keys, values } => {
+                write!(f, "length mismatch: {} keys but {} values", keys, values)
+            }
+            SoAMapError::UnsortedKey { index } => write!(
+                f,
+                "key at index {} is not greater than the previous key",
+                index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<K: Error + 'static, V: Error + 'static> Error for SoAMapError<K, V> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                SoAMapError::CheckKeysError(e) => Some(e as &dyn Error),
+                SoAMapError::CheckValuesError(e) => Some(e as &dyn Error),
+                SoAMapError::LengthMismatch { .. } | SoAMapError::UnsortedKey { .. } => None,
+            }
+        }
+    }
+};
+
+impl<K, V, C> CheckBytes<C> for ArchivedSoAMap<K, V>
+where
+    K: CheckBytes<C> + Ord,
+    V: CheckBytes<C>,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = SoAMapError<
+        <ArchivedVec<K> as CheckBytes<C>>::Error,
+        <ArchivedVec<V> as CheckBytes<C>>::Error,
+    >;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let keys = ArchivedVec::<K>::check_bytes(ptr::addr_of!((*value).keys), context)
+            .map_err(SoAMapError::CheckKeysError)?;
+        let values = ArchivedVec::<V>::check_bytes(ptr::addr_of!((*value).values), context)
+            .map_err(SoAMapError::CheckValuesError)?;
+
+        if keys.len() != values.len() {
+            return Err(SoAMapError::LengthMismatch {
+                keys: keys.len(),
+                values: values.len(),
+            });
+        }
+        if !context.follows_pointers() {
+            return Ok(&*value);
+        }
+
+        for (i, pair) in keys.as_slice().windows(2).enumerate() {
+            if pair[0] >= pair[1] {
+                return Err(SoAMapError::UnsortedKey { index: i + 1 });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
@@ -0,0 +1,214 @@
+//! Archived sorted vec implementation.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Serialize,
+};
+use core::{borrow::Borrow, cmp::Ordering, fmt, hash, ops::Deref, slice};
+
+/// An archived `Vec` whose elements are sorted and unique.
+///
+/// This is the archived type of vecs serialized with [`SortedVec`](crate::with::SortedVec). The
+/// elements are in strictly ascending order, which is checked during validation. This means that
+/// lookups with [`binary_search`](Self::binary_search) are always valid on a checked archive.
+#[repr(transparent)]
+pub struct ArchivedSortedVec<T> {
+    inner: ArchivedVec<T>,
+}
+
+impl<T> ArchivedSortedVec<T> {
+    /// Gets the number of elements in the vec.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns whether there are no elements in the vec.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Gets the elements of the vec as a slice in ascending order.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.inner.as_slice()
+    }
+
+    /// Gets the elements of the vec as an archived vec.
+    #[inline]
+    pub fn as_vec(&self) -> &ArchivedVec<T> {
+        &self.inner
+    }
+
+    /// Binary searches the vec for the given element.
+    ///
+    /// If the element is found, returns `Ok` with its index. Otherwise, returns `Err` with the index
+    /// where it could be inserted while maintaining sorted order.
+    #[inline]
+    pub fn binary_search<Q: Ord + ?Sized>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+    {
+        self.as_slice()
+            .binary_search_by(|element| element.borrow().cmp(value))
+    }
+
+    /// Gets the element equal to the given value, if any.
+    #[inline]
+    pub fn get<Q: Ord + ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+    {
+        self.binary_search(value)
+            .ok()
+            .map(|index| &self.as_slice()[index])
+    }
+
+    /// Returns whether the vec contains the given value.
+    #[inline]
+    pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+    {
+        self.binary_search(value).is_ok()
+    }
+
+    /// Gets an iterator over the elements of the vec in ascending order.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    /// Resolves an archived sorted vec from a given resolver.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the vec
+    #[inline]
+    pub unsafe fn resolve_from_resolver(pos: usize, resolver: SortedVecResolver, out: *mut Self) {
+        let (fp, fo) = out_field!(out.inner);
+        ArchivedVec::resolve_from_len(resolver.len, pos + fp, resolver.inner, fo);
+    }
+
+    /// Serializes an iterator of elements as a sorted vec.
+    ///
+    /// The elements are sorted and duplicate elements are only serialized once. The archived
+    /// elements must be ordered the same way as the unarchived elements, otherwise the archived vec
+    /// will fail to validate.
+    pub fn serialize_from_iter<'a, U, S, I>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<SortedVecResolver, S::Error>
+    where
+        U: 'a + Serialize<S, Archived = T> + Ord,
+        S: ScratchSpace + Serializer + ?Sized,
+        I: ExactSizeIterator<Item = &'a U>,
+    {
+        use crate::ScratchVec;
+
+        unsafe {
+            let mut elements = ScratchVec::new(serializer, iter.len())?;
+            for element in iter {
+                elements.push(element);
+            }
+
+            let slice = elements.as_mut_slice();
+            slice.sort_unstable();
+            let mut len = 0;
+            for i in 0..slice.len() {
+                if len == 0 || slice[len - 1] != slice[i] {
+                    slice[len] = slice[i];
+                    len += 1;
+                }
+            }
+
+            let inner = ArchivedVec::<T>::serialize_from_iter::<U, _, _, _>(
+                elements.as_slice()[..len].iter().copied(),
+                serializer,
+            )?;
+
+            elements.free(serializer)?;
+
+            Ok(SortedVecResolver { len, inner })
+        }
+    }
+}
+
+impl<T> AsRef<[T]> for ArchivedSortedVec<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> Borrow<[T]> for ArchivedSortedVec<T> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedSortedVec<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Deref for ArchivedSortedVec<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for ArchivedSortedVec<T> {}
+
+impl<T: hash::Hash> hash::Hash for ArchivedSortedVec<T> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: Ord> Ord for ArchivedSortedVec<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<ArchivedSortedVec<U>> for ArchivedSortedVec<T> {
+    #[inline]
+    fn eq(&self, other: &ArchivedSortedVec<U>) -> bool {
+        self.as_slice().eq(other.as_slice())
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedSortedVec<T> {
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice().eq(other)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for ArchivedSortedVec<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+/// The resolver for an [`ArchivedSortedVec`].
+pub struct SortedVecResolver {
+    len: usize,
+    inner: VecResolver,
+}
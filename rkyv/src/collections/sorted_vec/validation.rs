@@ -0,0 +1,87 @@
+//! Validation implementation for ArchivedSortedVec.
+
+use crate::{
+    collections::sorted_vec::ArchivedSortedVec, validation::ArchiveContext, vec::ArchivedVec,
+};
+use bytecheck::{CheckBytes, Error};
+use core::{cmp::Ordering, fmt, ptr};
+
+/// Errors that can occur while checking an archived sorted vec.
+#[derive(Debug)]
+pub enum SortedVecError<E> {
+    /// An error occurred while checking the elements
+    CheckElementsError(E),
+    /// An element was less than the element before it
+    UnsortedElement {
+        /// The index of the element
+        index: usize,
+    },
+    /// An element was equal to the element before it
+    DuplicateElement {
+        /// The index of the element
+        index: usize,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for SortedVecError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortedVecError::CheckElementsError(e) => write!(f, "elements check error: {}", e),
+            SortedVecError::UnsortedElement { index } => write!(
+                f,
+                "element at index {} is less than the previous element",
+                index
+            ),
+            SortedVecError::DuplicateElement { index } => write!(
+                f,
+                "element at index {} is equal to the previous element",
+                index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for SortedVecError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                SortedVecError::CheckElementsError(e) => Some(e as &dyn Error),
+                SortedVecError::UnsortedElement { .. }
+                | SortedVecError::DuplicateElement { .. } => None,
+            }
+        }
+    }
+};
+
+impl<T, C> CheckBytes<C> for ArchivedSortedVec<T>
+where
+    T: CheckBytes<C> + Ord,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = SortedVecError<<ArchivedVec<T> as CheckBytes<C>>::Error>;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let elements = ArchivedVec::<T>::check_bytes(ptr::addr_of!((*value).inner), context)
+            .map_err(SortedVecError::CheckElementsError)?;
+        if !context.follows_pointers() {
+            return Ok(&*value);
+        }
+
+        for (i, pair) in elements.as_slice().windows(2).enumerate() {
+            match pair[0].cmp(&pair[1]) {
+                Ordering::Less => (),
+                Ordering::Equal => return Err(SortedVecError::DuplicateElement { index: i + 1 }),
+                Ordering::Greater => return Err(SortedVecError::UnsortedElement { index: i + 1 }),
+            }
+        }
+
+        Ok(&*value)
+    }
+}
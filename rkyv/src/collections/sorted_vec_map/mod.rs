@@ -0,0 +1,193 @@
+//! Archived sorted vec map implementation.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{
+    collections::util::Entry,
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Serialize,
+};
+use core::{borrow::Borrow, cmp::Ordering, fmt, iter::FusedIterator, slice};
+
+/// An archived map that stores its entries in a `Vec` sorted by key.
+///
+/// This is the archived type of maps serialized with [`AsSortedVec`](crate::with::AsSortedVec).
+/// Because the entries are sorted, the archived bytes are deterministic regardless of the iteration
+/// order of the original map. Lookups perform a binary search over the entries.
+#[repr(transparent)]
+pub struct ArchivedSortedVecMap<K, V> {
+    entries: ArchivedVec<Entry<K, V>>,
+}
+
+impl<K, V> ArchivedSortedVecMap<K, V> {
+    /// Gets the number of items in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no items in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets the entries of the map as a slice sorted by key.
+    #[inline]
+    pub fn as_slice(&self) -> &[Entry<K, V>] {
+        self.entries.as_slice()
+    }
+
+    #[inline]
+    fn find<Q: Ord + ?Sized>(&self, k: &Q) -> Option<&Entry<K, V>>
+    where
+        K: Borrow<Q>,
+    {
+        let entries = self.as_slice();
+        entries
+            .binary_search_by(|entry| entry.key.borrow().cmp(k))
+            .ok()
+            .map(|index| &entries[index])
+    }
+
+    /// Finds the key-value entry for a key.
+    #[inline]
+    pub fn get_key_value<Q: Ord + ?Sized>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(k).map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Gets the value associated with the given key.
+    #[inline]
+    pub fn get<Q: Ord + ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.find(k).map(|entry| &entry.value)
+    }
+
+    /// Returns whether a key is present in the map.
+    #[inline]
+    pub fn contains_key<Q: Ord + ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.find(k).is_some()
+    }
+
+    /// Gets an iterator over the key-value entries in the map in ascending key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.as_slice().iter(),
+        }
+    }
+
+    /// Resolves an archived sorted vec map from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of elements that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the map
+    #[inline]
+    pub unsafe fn resolve_from_len(len: usize, pos: usize, resolver: VecResolver, out: *mut Self) {
+        let (fp, fo) = out_field!(out.entries);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver, fo);
+    }
+
+    /// Serializes an iterator of key-value pairs as a sorted vec map.
+    ///
+    /// The keys returned by the iterator should be unique, and the archived keys should be ordered
+    /// the same way as the unarchived keys. Otherwise, the archived map will fail to validate.
+    pub fn serialize_from_iter<'a, KU, VU, S, I>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<VecResolver, S::Error>
+    where
+        KU: 'a + Serialize<S, Archived = K> + Ord,
+        VU: 'a + Serialize<S, Archived = V>,
+        S: ScratchSpace + Serializer + ?Sized,
+        I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+    {
+        use crate::ScratchVec;
+
+        unsafe {
+            let mut entries = ScratchVec::new(serializer, iter.len())?;
+            for (key, value) in iter {
+                entries.push(Entry { key, value });
+            }
+            entries
+                .as_mut_slice()
+                .sort_unstable_by(|a, b| a.key.cmp(b.key));
+
+            let resolver =
+                ArchivedVec::<Entry<K, V>>::serialize_from_iter::<Entry<&KU, &VU>, _, _, _>(
+                    entries.iter(),
+                    serializer,
+                )?;
+
+            entries.free(serializer)?;
+
+            Ok(resolver)
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedSortedVecMap<K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ArchivedSortedVecMap<K, V> {}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ArchivedSortedVecMap<K, V> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for ArchivedSortedVecMap<K, V> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+/// An iterator over the key-value pairs of a sorted vec map.
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
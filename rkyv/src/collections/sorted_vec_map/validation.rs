@@ -0,0 +1,78 @@
+//! Validation implementation for ArchivedSortedVecMap.
+
+use crate::{
+    collections::{sorted_vec_map::ArchivedSortedVecMap, util::Entry},
+    validation::ArchiveContext,
+    vec::ArchivedVec,
+};
+use bytecheck::{CheckBytes, Error};
+use core::{fmt, ptr};
+
+/// Errors that can occur while checking an archived sorted vec map.
+#[derive(Debug)]
+pub enum SortedVecMapError<E> {
+    /// An error occurred while checking the entries
+    CheckEntriesError(E),
+    /// A key was not greater than the key before it
+    UnsortedKey {
+        /// The index of the key
+        index: usize,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for SortedVecMapError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortedVecMapError::CheckEntriesError(e) => write!(f, "entries check error: {}", e),
+            SortedVecMapError::UnsortedKey { index } => write!(
+                f,
+                "key at index {} is not greater than the previous key",
+                index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for SortedVecMapError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                SortedVecMapError::CheckEntriesError(e) => Some(e as &dyn Error),
+                SortedVecMapError::UnsortedKey { .. } => None,
+            }
+        }
+    }
+};
+
+impl<K, V, C> CheckBytes<C> for ArchivedSortedVecMap<K, V>
+where
+    K: CheckBytes<C> + Ord,
+    V: CheckBytes<C>,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = SortedVecMapError<<ArchivedVec<Entry<K, V>> as CheckBytes<C>>::Error>;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let entries =
+            ArchivedVec::<Entry<K, V>>::check_bytes(ptr::addr_of!((*value).entries), context)
+                .map_err(SortedVecMapError::CheckEntriesError)?;
+        if !context.follows_pointers() {
+            return Ok(&*value);
+        }
+
+        for (i, pair) in entries.as_slice().windows(2).enumerate() {
+            if pair[0].key >= pair[1].key {
+                return Err(SortedVecMapError::UnsortedKey { index: i + 1 });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(feature = "alloc")]
+use crate::de::{SharedDeserializeRegistry, SharedPointer};
+use crate::{de::DeserializeContext, Fallible, Infallible};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use ::alloc::boxed::Box;
+
+/// A deserializer that adds a context to another deserializer.
+///
+/// The context can be accessed by wrappers through [`DeserializeContext`], and all other
+/// capabilities are provided by the inner deserializer.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{
+///     de::{deserializers::ContextDeserializer, DeserializeContext},
+///     with::{ArchiveWith, DeserializeWith, SerializeWith},
+///     Archive, Archived, Deserialize, Fallible, Infallible, Resolver, Serialize,
+/// };
+///
+/// // Counts the number of wrapped fields that were deserialized
+/// struct Counted;
+///
+/// impl ArchiveWith<u32> for Counted {
+///     type Archived = Archived<u32>;
+///     type Resolver = Resolver<u32>;
+///
+///     unsafe fn resolve_with(field: &u32, pos: usize, _: (), out: *mut Self::Archived) {
+///         field.resolve(pos, (), out);
+///     }
+/// }
+///
+/// impl<S: Fallible + ?Sized> SerializeWith<u32, S> for Counted {
+///     fn serialize_with(field: &u32, serializer: &mut S) -> Result<(), S::Error> {
+///         field.serialize(serializer)
+///     }
+/// }
+///
+/// impl<D> DeserializeWith<Archived<u32>, u32, D> for Counted
+/// where
+///     D: DeserializeContext<usize> + ?Sized,
+/// {
+///     fn deserialize_with(field: &Archived<u32>, deserializer: &mut D) -> Result<u32, D::Error> {
+///         *deserializer.context() += 1;
+///         field.deserialize(deserializer)
+///     }
+/// }
+///
+/// #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+/// struct Example {
+///     #[with(Counted)]
+///     a: u32,
+///     #[with(Counted)]
+///     b: u32,
+///     c: u32,
+/// }
+///
+/// let value = Example { a: 1, b: 2, c: 3 };
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<Example>(&bytes) };
+///
+/// let mut deserializer = ContextDeserializer::new(0usize, Infallible);
+/// let deserialized: Example = archived.deserialize(&mut deserializer).unwrap();
+/// assert_eq!(deserialized, value);
+/// assert_eq!(deserializer.into_context(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct ContextDeserializer<C, D = Infallible> {
+    context: C,
+    deserializer: D,
+}
+
+impl<C, D> ContextDeserializer<C, D> {
+    /// Creates a new context deserializer from a context and an inner deserializer.
+    #[inline]
+    pub fn new(context: C, deserializer: D) -> Self {
+        Self {
+            context,
+            deserializer,
+        }
+    }
+
+    /// Consumes the context deserializer and returns the components.
+    #[inline]
+    pub fn into_components(self) -> (C, D) {
+        (self.context, self.deserializer)
+    }
+
+    /// Consumes the context deserializer and returns the context.
+    ///
+    /// The inner deserializer is discarded.
+    #[inline]
+    pub fn into_context(self) -> C {
+        self.context
+    }
+}
+
+impl<C, D: Fallible> Fallible for ContextDeserializer<C, D> {
+    type Error = D::Error;
+}
+
+impl<C, D: Fallible> DeserializeContext<C> for ContextDeserializer<C, D> {
+    #[inline]
+    fn context(&mut self) -> &mut C {
+        &mut self.context
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, D: SharedDeserializeRegistry> SharedDeserializeRegistry for ContextDeserializer<C, D> {
+    #[inline]
+    fn get_shared_ptr(&mut self, ptr: *const u8) -> Option<&dyn SharedPointer> {
+        self.deserializer.get_shared_ptr(ptr)
+    }
+
+    #[inline]
+    fn add_shared_ptr(
+        &mut self,
+        ptr: *const u8,
+        shared: Box<dyn SharedPointer>,
+    ) -> Result<(), Self::Error> {
+        self.deserializer.add_shared_ptr(ptr, shared)
+    }
+}
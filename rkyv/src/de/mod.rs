@@ -2,13 +2,107 @@
 
 pub mod deserializers;
 
+use crate::Fallible;
 #[cfg(feature = "alloc")]
-use crate::{ArchiveUnsized, DeserializeUnsized, Fallible};
+use crate::{rc::ArchiveSharedPointer, ArchiveUnsized, DeserializeUnsized};
 #[cfg(all(feature = "alloc", not(feature = "std")))]
 use ::alloc::boxed::Box;
 #[cfg(feature = "alloc")]
 use ::core::alloc::Layout;
 
+/// A deserializer that carries some user-provided context.
+///
+/// This can be used to provide external resources (e.g. an interner or a handle to a pool) while
+/// deserializing. Wrappers can bound their deserializer by `DeserializeContext` in their
+/// [`DeserializeWith`](crate::with::DeserializeWith) implementations to access the context. See
+/// [`ContextDeserializer`](deserializers::ContextDeserializer) for an example.
+pub trait DeserializeContext<C: ?Sized>: Fallible {
+    /// Returns the context of the deserializer.
+    fn context(&mut self) -> &mut C;
+}
+
+/// A builder that a type can be deserialized through.
+///
+/// Deriving `Deserialize` with `#[archive(deserialize_into_builder = "...")]` deserializes a struct
+/// through its builder instead of constructing it directly. The builder is created with `Default`,
+/// then each field is deserialized and passed to the builder method with the same name as the
+/// field. A method is called as `builder.field(value)` and its return value is ignored, so it
+/// usually takes `&mut self`. Finally, the builder is built with [`build`](Self::build). If
+/// building fails, the error is converted into the error of the deserializer with `From`.
+///
+/// This is useful when the fields of a type are private or its invariants have to be checked
+/// whenever it's constructed.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{de::DeserializeBuilder, Archive, Deserialize, Fallible, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug)]
+/// #[archive(deserialize_into_builder = "RangeBuilder")]
+/// pub struct Range {
+///     start: u32,
+///     end: u32,
+/// }
+///
+/// #[derive(Default)]
+/// pub struct RangeBuilder {
+///     start: u32,
+///     end: u32,
+/// }
+///
+/// impl RangeBuilder {
+///     pub fn start(&mut self, start: u32) -> &mut Self {
+///         self.start = start;
+///         self
+///     }
+///
+///     pub fn end(&mut self, end: u32) -> &mut Self {
+///         self.end = end;
+///         self
+///     }
+/// }
+///
+/// #[derive(Debug)]
+/// pub struct InvalidRange;
+///
+/// impl DeserializeBuilder<Range> for RangeBuilder {
+///     type Error = InvalidRange;
+///
+///     fn build(self) -> Result<Range, InvalidRange> {
+///         if self.start <= self.end {
+///             Ok(Range { start: self.start, end: self.end })
+///         } else {
+///             Err(InvalidRange)
+///         }
+///     }
+/// }
+///
+/// struct RangeDeserializer;
+///
+/// impl Fallible for RangeDeserializer {
+///     type Error = InvalidRange;
+/// }
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&Range { start: 1, end: 4 }).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<Range>(&bytes) };
+/// let range: Range = archived.deserialize(&mut RangeDeserializer).unwrap();
+/// assert_eq!((range.start, range.end), (1, 4));
+///
+/// // The builder rejects ranges that end before they start
+/// let bytes = rkyv::to_bytes::<_, 256>(&Range { start: 4, end: 1 }).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<Range>(&bytes) };
+/// let result: Result<Range, _> = archived.deserialize(&mut RangeDeserializer);
+/// assert!(result.is_err());
+/// ```
+pub trait DeserializeBuilder<T> {
+    /// The error that can occur when building the value.
+    type Error;
+
+    /// Builds the value from the deserialized fields.
+    fn build(self) -> Result<T, Self::Error>;
+}
+
 /// A deserializable shared pointer type.
 #[cfg(feature = "alloc")]
 pub trait SharedPointer {
@@ -16,6 +110,40 @@ pub trait SharedPointer {
     fn data_address(&self) -> *const ();
 }
 
+/// A shared pointer that can be deserialized from an [`ArchivedRc`](crate::rc::ArchivedRc).
+///
+/// Implementing this trait for an [`ArchiveSharedPointer`] provides a `Deserialize` implementation
+/// that deduplicates shared memory in the same way as `Rc` and `Arc`.
+///
+/// # Safety
+///
+/// The deserializer deduplicates shared pointers by the address of their archived value, so
+/// `from_raw` may be called with the data address of any shared pointer that was deserialized from
+/// the same archived value. Implementors must guarantee that:
+///
+/// - The shared pointers returned from `from_box` have a
+///   [`data_address`](SharedPointer::data_address) that points to their shared value.
+/// - `from_raw` is sound for the data address of any shared pointer with the same `Target` and
+///   `Flavor`. All shared pointer types with the same `Flavor` must allocate and count references
+///   to their shared values in the same way.
+#[cfg(feature = "alloc")]
+pub unsafe trait DeserializeSharedPointer:
+    ArchiveSharedPointer + SharedPointer + Clone + Sized + 'static
+{
+    /// Creates a new shared pointer that owns the given boxed value.
+    fn from_box(boxed: Box<Self::Target>) -> Self;
+
+    /// Creates a shared pointer from a pointer to its shared value.
+    ///
+    /// Like `Rc::from_raw`, the returned shared pointer takes ownership of one reference to the
+    /// shared value.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the shared value of a live shared pointer of this type.
+    unsafe fn from_raw(ptr: *const Self::Target) -> Self;
+}
+
 /// A registry that tracks deserialized shared memory.
 ///
 /// This trait is required to deserialize shared pointers.
@@ -0,0 +1,368 @@
+//! Human-readable layout dumps of archives for debugging.
+//!
+//! A layout dump is a tree of the values in an archive with their byte offsets and sizes. Relative
+//! pointers are printed with the offset of their target, followed by the target itself. This makes
+//! it possible to see how an archive is laid out and where a corrupt archive diverges from what was
+//! expected.
+//!
+//! Archived types implement [`DumpLayout`] to describe their layout. It's implemented for archived
+//! primitives, strings, vecs, boxes, and options, and it can be derived for archived types with
+//! `#[archive(dump_layout)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{archived_root, dump::dump_layout, Archive, Serialize};
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(dump_layout)]
+//! struct Example {
+//!     id: u32,
+//!     name: String,
+//!     scores: Vec<u16>,
+//! }
+//!
+//! let value = Example {
+//!     id: 42,
+//!     name: "a name that is not inlined".to_string(),
+//!     scores: vec![1, 2],
+//! };
+//! let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+//! let archived = unsafe { archived_root::<Example>(&bytes) };
+//!
+//! let mut dump = String::new();
+//! dump_layout(&bytes, archived, &mut dump).unwrap();
+//! // These offsets and sizes are for the default `size_32` feature
+//! assert_eq!(
+//!     dump,
+//!     "\
+//! root: ArchivedExample @ 0x20 (20 bytes)
+//!   id: u32 @ 0x30 (4 bytes) = 42
+//!   name: ArchivedString @ 0x20 (8 bytes) -> 0x0
+//!     *: str @ 0x0 (26 bytes) = \"a name that is not inlined\"
+//!   scores: ArchivedVec<u16> @ 0x28 (8 bytes) -> 0x1a
+//!     *: [u16] @ 0x1a (4 bytes)
+//!       [0]: u16 @ 0x1a (2 bytes) = 1
+//!       [1]: u16 @ 0x1c (2 bytes) = 2
+//! ",
+//! );
+//! ```
+
+use crate::{
+    boxed::ArchivedBox, option::ArchivedOption, string::ArchivedString, vec::ArchivedVec,
+    ArchivePointee,
+};
+use core::{
+    any::type_name,
+    fmt::{self, Debug, Display, Write},
+    mem::size_of_val,
+};
+
+/// An archived type that can describe its layout to a [`LayoutDumper`].
+///
+/// This can be derived for archived types with `#[archive(dump_layout)]`.
+pub trait DumpLayout {
+    /// Writes the layout of this value to the dumper with the given name.
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result;
+}
+
+/// Writes layout dumps of archived values.
+///
+/// Each value is written on its own line as its name, its type, its offset from the start of the
+/// archive, and its size. Children are indented beneath their parent.
+pub struct LayoutDumper<'a> {
+    base: usize,
+    out: &'a mut dyn Write,
+    depth: usize,
+}
+
+impl<'a> LayoutDumper<'a> {
+    /// Creates a new layout dumper for the given archive that writes to the given output.
+    #[inline]
+    pub fn new(bytes: &[u8], out: &'a mut dyn Write) -> Self {
+        Self {
+            base: bytes.as_ptr() as usize,
+            out,
+            depth: 0,
+        }
+    }
+
+    /// Returns the offset of the given value from the start of the archive.
+    #[inline]
+    pub fn offset_of<T: ?Sized>(&self, value: &T) -> isize {
+        (value as *const T as *const u8 as usize).wrapping_sub(self.base) as isize
+    }
+
+    /// Writes the layout of the given value with the given name.
+    #[inline]
+    pub fn dump<T: DumpLayout + ?Sized>(&mut self, name: &dyn Display, value: &T) -> fmt::Result {
+        value.dump_layout(self, name)
+    }
+
+    /// Writes a value which has no children, followed by its debug representation.
+    pub fn leaf<T: ?Sized>(
+        &mut self,
+        name: &dyn Display,
+        value: &T,
+        debug: &dyn Debug,
+    ) -> fmt::Result {
+        self.write_header(name, value, None)?;
+        writeln!(self.out, " = {:?}", debug)
+    }
+
+    /// Writes a value, followed by its children indented beneath it.
+    pub fn node<T: ?Sized>(
+        &mut self,
+        name: &dyn Display,
+        value: &T,
+        children: impl FnOnce(&mut Self) -> fmt::Result,
+    ) -> fmt::Result {
+        self.write_header(name, value, None)?;
+        writeln!(self.out)?;
+        self.indented(children)
+    }
+
+    /// Writes an enum variant, followed by its fields indented beneath it.
+    pub fn variant<T: ?Sized>(
+        &mut self,
+        name: &dyn Display,
+        value: &T,
+        variant: &str,
+        fields: impl FnOnce(&mut Self) -> fmt::Result,
+    ) -> fmt::Result {
+        self.write_header(name, value, Some(variant))?;
+        writeln!(self.out)?;
+        self.indented(fields)
+    }
+
+    /// Writes a relative pointer with the offset of its target, followed by the target indented
+    /// beneath it.
+    pub fn pointer<T: ?Sized, U: DumpLayout + ?Sized>(
+        &mut self,
+        name: &dyn Display,
+        value: &T,
+        target: &U,
+    ) -> fmt::Result {
+        self.write_header(name, value, None)?;
+        writeln!(self.out, " -> {:#x}", self.offset_of(target))?;
+        self.indented(|dumper| dumper.dump(&"*", target))
+    }
+
+    #[inline]
+    fn indented(&mut self, f: impl FnOnce(&mut Self) -> fmt::Result) -> fmt::Result {
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn write_header<T: ?Sized>(
+        &mut self,
+        name: &dyn Display,
+        value: &T,
+        variant: Option<&str>,
+    ) -> fmt::Result {
+        write!(
+            self.out,
+            "{:indent$}{}: ",
+            "",
+            name,
+            indent = self.depth * 2
+        )?;
+        write_short_type_name(self.out, type_name::<T>())?;
+        if let Some(variant) = variant {
+            write!(self.out, "::{}", variant)?;
+        }
+        write!(
+            self.out,
+            " @ {:#x} ({} bytes)",
+            self.offset_of(value),
+            size_of_val(value)
+        )
+    }
+}
+
+// Strips the module path from each type in a type name, so `alloc::vec::Vec<u8>` becomes `Vec<u8>`
+fn write_short_type_name(out: &mut dyn Write, name: &str) -> fmt::Result {
+    let is_path_char = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+    let mut rest = name;
+    while !rest.is_empty() {
+        let end = rest.find(|c| !is_path_char(c)).unwrap_or(rest.len());
+        let (path, tail) = rest.split_at(end);
+        out.write_str(path.rsplit("::").next().unwrap_or(path))?;
+        let end = tail.find(is_path_char).unwrap_or(tail.len());
+        let (punctuation, tail) = tail.split_at(end);
+        out.write_str(punctuation)?;
+        rest = tail;
+    }
+    Ok(())
+}
+
+/// Writes a layout dump of the given archived value to the given output.
+///
+/// The value must be located in `bytes` for the offsets to be meaningful. See the
+/// [module-level documentation](self) for an example.
+#[inline]
+pub fn dump_layout<T: DumpLayout + ?Sized>(
+    bytes: &[u8],
+    value: &T,
+    out: &mut dyn Write,
+) -> fmt::Result {
+    LayoutDumper::new(bytes, out).dump(&"root", value)
+}
+
+macro_rules! impl_primitive {
+    ($type:ty) => {
+        impl DumpLayout for $type {
+            #[inline]
+            fn dump_layout(
+                &self,
+                dumper: &mut LayoutDumper<'_>,
+                name: &dyn Display,
+            ) -> fmt::Result {
+                dumper.leaf(name, self, self)
+            }
+        }
+    };
+    (@multibyte $type:ty) => {
+        const _: () = {
+            #[cfg(not(any(feature = "archive_le", feature = "archive_be")))]
+            type Archived = $type;
+            #[cfg(feature = "archive_le")]
+            type Archived = crate::rend::LittleEndian<$type>;
+            #[cfg(feature = "archive_be")]
+            type Archived = crate::rend::BigEndian<$type>;
+
+            impl DumpLayout for Archived {
+                #[inline]
+                fn dump_layout(
+                    &self,
+                    dumper: &mut LayoutDumper<'_>,
+                    name: &dyn Display,
+                ) -> fmt::Result {
+                    dumper.leaf(name, self, &from_archived!(*self))
+                }
+            }
+        };
+    };
+}
+
+impl_primitive!(());
+impl_primitive!(bool);
+impl_primitive!(i8);
+impl_primitive!(u8);
+impl_primitive!(@multibyte i16);
+impl_primitive!(@multibyte i32);
+impl_primitive!(@multibyte i64);
+impl_primitive!(@multibyte i128);
+impl_primitive!(@multibyte u16);
+impl_primitive!(@multibyte u32);
+impl_primitive!(@multibyte u64);
+impl_primitive!(@multibyte u128);
+impl_primitive!(@multibyte f32);
+impl_primitive!(@multibyte f64);
+impl_primitive!(@multibyte char);
+
+#[cfg(not(feature = "strict"))]
+macro_rules! impl_tuple {
+    ($($type:ident $index:tt),+) => {
+        impl<$($type: DumpLayout),+> DumpLayout for ($($type,)+) {
+            #[inline]
+            fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+                dumper.node(name, self, |dumper| {
+                    $(dumper.dump(&stringify!($index), &self.$index)?;)+
+                    Ok(())
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10);
+#[cfg(not(feature = "strict"))]
+impl_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11);
+
+impl DumpLayout for str {
+    #[inline]
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+        dumper.leaf(name, self, &self)
+    }
+}
+
+impl<T: DumpLayout> DumpLayout for [T] {
+    #[inline]
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+        dumper.node(name, self, |dumper| {
+            for (i, item) in self.iter().enumerate() {
+                dumper.dump(&format_args!("[{}]", i), item)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<T: DumpLayout, const N: usize> DumpLayout for [T; N] {
+    #[inline]
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+        dumper.node(name, self, |dumper| {
+            for (i, item) in self.iter().enumerate() {
+                dumper.dump(&format_args!("[{}]", i), item)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl DumpLayout for ArchivedString {
+    #[inline]
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+        dumper.pointer(name, self, self.as_str())
+    }
+}
+
+impl<T: DumpLayout> DumpLayout for ArchivedVec<T> {
+    #[inline]
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+        dumper.pointer(name, self, self.as_slice())
+    }
+}
+
+impl<T: ArchivePointee + DumpLayout + ?Sized> DumpLayout for ArchivedBox<T> {
+    #[inline]
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+        dumper.pointer(name, self, self.get())
+    }
+}
+
+impl<T: DumpLayout> DumpLayout for ArchivedOption<T> {
+    #[inline]
+    fn dump_layout(&self, dumper: &mut LayoutDumper<'_>, name: &dyn Display) -> fmt::Result {
+        match self {
+            ArchivedOption::None => dumper.variant(name, self, "None", |_| Ok(())),
+            ArchivedOption::Some(value) => {
+                dumper.variant(name, self, "Some", |dumper| dumper.dump(&"0", value))
+            }
+        }
+    }
+}
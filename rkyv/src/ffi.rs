@@ -167,17 +167,23 @@ pub struct CStringResolver {
 
 #[cfg(feature = "validation")]
 const _: () = {
-    use crate::validation::{
-        owned::{CheckOwnedPointerError, OwnedPointerError},
-        ArchiveContext,
+    use crate::{
+        validation::{owned::OwnedPointerError, ArchiveContext},
+        ArchivePointee,
     };
     use bytecheck::{CheckBytes, Error};
+    use core::slice;
+    use std::ffi::FromBytesWithNulError;
 
     impl<C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedCString
     where
         C::Error: Error,
     {
-        type Error = CheckOwnedPointerError<CStr, C>;
+        type Error = OwnedPointerError<
+            <<CStr as ArchivePointee>::ArchivedMetadata as CheckBytes<C>>::Error,
+            FromBytesWithNulError,
+            C::Error,
+        >;
 
         #[inline]
         unsafe fn check_bytes<'a>(
@@ -186,6 +192,10 @@ const _: () = {
         ) -> Result<&'a Self, Self::Error> {
             let rel_ptr = RelPtr::<CStr>::manual_check_bytes(value.cast(), context)
                 .map_err(OwnedPointerError::PointerCheckBytesError)?;
+            if !context.follows_pointers() {
+                return Ok(&*value);
+            }
+
             let ptr = context
                 .check_subtree_rel_ptr(rel_ptr)
                 .map_err(OwnedPointerError::ContextError)?;
@@ -193,7 +203,9 @@ const _: () = {
             let range = context
                 .push_prefix_subtree(ptr)
                 .map_err(OwnedPointerError::ContextError)?;
-            CStr::check_bytes(ptr, context).map_err(OwnedPointerError::ValueCheckBytesError)?;
+            // C strings may contain arbitrary bytes, but must end with the only nul byte
+            let bytes = slice::from_raw_parts(ptr.cast::<u8>(), ptr_meta::metadata(ptr));
+            CStr::from_bytes_with_nul(bytes).map_err(OwnedPointerError::ValueCheckBytesError)?;
             context
                 .pop_prefix_range(range)
                 .map_err(OwnedPointerError::ContextError)?;
@@ -0,0 +1,138 @@
+//! Archived versions of `glam` vector, quaternion, and matrix types.
+//!
+//! Each archived type stores the components of the original type as an array of archived floats,
+//! in the same order as the array returned by `to_array` (for vectors and quaternions) or
+//! `to_cols_array` (for column-major matrices). Archived types are only aligned to their floats,
+//! so types like `Vec4` and `Mat4` that are SIMD-aligned in `glam` are less aligned in the archive.
+
+use crate::Archived;
+use core::fmt;
+use glam::{
+    DMat2, DMat3, DMat4, DQuat, DVec2, DVec3, DVec4, Mat2, Mat3, Mat4, Quat, Vec2, Vec3, Vec4,
+};
+
+macro_rules! impl_archived_glam {
+    ($archived:ident, $ty:ident, $float:ty, $len:literal, $from_array:ident) => {
+        #[doc = concat!("An archived [`", stringify!($ty), "`](glam::", stringify!($ty), ").")]
+        ///
+        /// This has the same layout as an array of the archived floats.
+        #[repr(transparent)]
+        pub struct $archived(pub(crate) [Archived<$float>; $len]);
+
+        impl $archived {
+            /// Gets the archived components.
+            #[inline]
+            pub fn as_array(&self) -> &[Archived<$float>; $len] {
+                &self.0
+            }
+
+            #[doc = concat!("Gets the archived value as a `", stringify!($ty), "`.")]
+            #[inline]
+            pub fn to_glam(&self) -> $ty {
+                let array = self.0.map(|x| from_archived!(x));
+                glam_from_array!($ty, $from_array, array)
+            }
+        }
+
+        impl fmt::Debug for $archived {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.to_glam().fmt(f)
+            }
+        }
+
+        impl fmt::Display for $archived {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.to_glam(), f)
+            }
+        }
+
+        impl PartialEq for $archived {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.to_glam() == other.to_glam()
+            }
+        }
+
+        impl PartialEq<$ty> for $archived {
+            #[inline]
+            fn eq(&self, other: &$ty) -> bool {
+                self.to_glam() == *other
+            }
+        }
+
+        impl PartialEq<$archived> for $ty {
+            #[inline]
+            fn eq(&self, other: &$archived) -> bool {
+                other.eq(self)
+            }
+        }
+    };
+}
+
+// Vectors and quaternions are built from arrays by value, and matrices by reference
+macro_rules! glam_from_array {
+    ($ty:ident, from_array, $array:expr) => {
+        $ty::from_array($array)
+    };
+    ($ty:ident, from_cols_array, $array:expr) => {
+        $ty::from_cols_array(&$array)
+    };
+}
+
+impl_archived_glam!(ArchivedVec2, Vec2, f32, 2, from_array);
+impl_archived_glam!(ArchivedVec3, Vec3, f32, 3, from_array);
+impl_archived_glam!(ArchivedVec4, Vec4, f32, 4, from_array);
+impl_archived_glam!(ArchivedQuat, Quat, f32, 4, from_array);
+impl_archived_glam!(ArchivedMat2, Mat2, f32, 4, from_cols_array);
+impl_archived_glam!(ArchivedMat3, Mat3, f32, 9, from_cols_array);
+impl_archived_glam!(ArchivedMat4, Mat4, f32, 16, from_cols_array);
+impl_archived_glam!(ArchivedDVec2, DVec2, f64, 2, from_array);
+impl_archived_glam!(ArchivedDVec3, DVec3, f64, 3, from_array);
+impl_archived_glam!(ArchivedDVec4, DVec4, f64, 4, from_array);
+impl_archived_glam!(ArchivedDQuat, DQuat, f64, 4, from_array);
+impl_archived_glam!(ArchivedDMat2, DMat2, f64, 4, from_cols_array);
+impl_archived_glam!(ArchivedDMat3, DMat3, f64, 9, from_cols_array);
+impl_archived_glam!(ArchivedDMat4, DMat4, f64, 16, from_cols_array);
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use bytecheck::CheckBytes;
+
+    macro_rules! impl_check_bytes {
+        ($archived:ident, $float:ty, $len:literal) => {
+            // Floats are valid for any bit pattern, so every array of them is a valid value
+            impl<C: ?Sized> CheckBytes<C> for $archived
+            where
+                [Archived<$float>; $len]: CheckBytes<C>,
+            {
+                type Error = <[Archived<$float>; $len] as CheckBytes<C>>::Error;
+
+                #[inline]
+                unsafe fn check_bytes<'a>(
+                    value: *const Self,
+                    context: &mut C,
+                ) -> Result<&'a Self, Self::Error> {
+                    <[Archived<$float>; $len]>::check_bytes(value.cast(), context)?;
+                    Ok(&*value)
+                }
+            }
+        };
+    }
+
+    impl_check_bytes!(ArchivedVec2, f32, 2);
+    impl_check_bytes!(ArchivedVec3, f32, 3);
+    impl_check_bytes!(ArchivedVec4, f32, 4);
+    impl_check_bytes!(ArchivedQuat, f32, 4);
+    impl_check_bytes!(ArchivedMat2, f32, 4);
+    impl_check_bytes!(ArchivedMat3, f32, 9);
+    impl_check_bytes!(ArchivedMat4, f32, 16);
+    impl_check_bytes!(ArchivedDVec2, f64, 2);
+    impl_check_bytes!(ArchivedDVec3, f64, 3);
+    impl_check_bytes!(ArchivedDVec4, f64, 4);
+    impl_check_bytes!(ArchivedDQuat, f64, 4);
+    impl_check_bytes!(ArchivedDMat2, f64, 4);
+    impl_check_bytes!(ArchivedDMat3, f64, 9);
+    impl_check_bytes!(ArchivedDMat4, f64, 16);
+};
@@ -1,6 +1,15 @@
-use crate::{niche::option_box::ArchivedOptionBox, ArchivePointee};
+use crate::{
+    niche::{option_box::ArchivedOptionBox, option_rc::ArchivedOptionRc},
+    ArchivePointee,
+};
+#[cfg(all(not(feature = "std"), has_atomics))]
+use ::alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
-use ::alloc::boxed::Box;
+use ::alloc::{boxed::Box, rc::Rc};
+#[cfg(all(feature = "std", has_atomics))]
+use ::std::sync::Arc;
+#[cfg(feature = "std")]
+use ::std::rc::Rc;
 
 impl<T, U> PartialEq<Option<Box<T>>> for ArchivedOptionBox<U>
 where
@@ -31,3 +40,41 @@ where
         other.eq(self)
     }
 }
+
+macro_rules! impl_option_rc_partial_eq {
+    ($ptr:ident) => {
+        impl<T, U, F> PartialEq<Option<$ptr<T>>> for ArchivedOptionRc<U, F>
+        where
+            T: ?Sized,
+            U: ArchivePointee + PartialEq<T> + ?Sized,
+        {
+            #[inline]
+            fn eq(&self, other: &Option<$ptr<T>>) -> bool {
+                if let Some(self_value) = self.as_deref() {
+                    if let Some(other_value) = other.as_deref() {
+                        self_value.eq(other_value)
+                    } else {
+                        false
+                    }
+                } else {
+                    other.is_none()
+                }
+            }
+        }
+
+        impl<T, U, F> PartialEq<ArchivedOptionRc<T, F>> for Option<$ptr<U>>
+        where
+            T: ArchivePointee + PartialEq<U> + ?Sized,
+            U: ?Sized,
+        {
+            #[inline]
+            fn eq(&self, other: &ArchivedOptionRc<T, F>) -> bool {
+                other.eq(self)
+            }
+        }
+    };
+}
+
+impl_option_rc_partial_eq!(Rc);
+#[cfg(has_atomics)]
+impl_option_rc_partial_eq!(Arc);
@@ -1,6 +1,6 @@
 use crate::{
-    de::{SharedDeserializeRegistry, SharedPointer},
-    rc::{ArchivedRc, ArchivedRcWeak, RcResolver, RcWeakResolver},
+    de::{DeserializeSharedPointer, SharedDeserializeRegistry, SharedPointer},
+    rc::{ArchiveSharedPointer, ArchivedRc, ArchivedRcWeak, RcResolver, RcWeakResolver},
     ser::{Serializer, SharedSerializeRegistry},
     Archive, ArchivePointee, ArchiveUnsized, Deserialize, DeserializeUnsized, Serialize,
     SerializeUnsized,
@@ -15,6 +15,27 @@ use ::std::sync;
 #[cfg(feature = "std")]
 use ::std::{alloc, rc};
 
+// Shared pointers
+
+impl<P, D> Deserialize<P, D> for ArchivedRc<<P::Target as ArchiveUnsized>::Archived, P::Flavor>
+where
+    P: DeserializeSharedPointer,
+    <P::Target as ArchiveUnsized>::Archived: DeserializeUnsized<P::Target, D>,
+    D: SharedDeserializeRegistry + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<P, D::Error> {
+        let raw_shared_ptr = deserializer.deserialize_shared(
+            self.get(),
+            |ptr| P::from_box(unsafe { Box::from_raw(ptr) }),
+            |layout| unsafe { alloc::alloc(layout) },
+        )?;
+        let shared_ptr = unsafe { P::from_raw(raw_shared_ptr) };
+        forget(shared_ptr.clone());
+        Ok(shared_ptr)
+    }
+}
+
 // Rc
 
 /// The flavor type for `Rc`.
@@ -27,13 +48,37 @@ impl<T: ?Sized> SharedPointer for rc::Rc<T> {
     }
 }
 
+impl<T: ArchiveUnsized + ?Sized> ArchiveSharedPointer for rc::Rc<T> {
+    type Target = T;
+    type Flavor = RcFlavor;
+
+    #[inline]
+    fn as_target(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+// SAFETY: `Rc` is the only shared pointer with the `RcFlavor` flavor, and `Rc::from_raw` is sound
+// for the data address of any `Rc`.
+unsafe impl<T: ArchiveUnsized + ?Sized + 'static> DeserializeSharedPointer for rc::Rc<T> {
+    #[inline]
+    fn from_box(boxed: Box<T>) -> Self {
+        rc::Rc::from(boxed)
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        rc::Rc::from_raw(ptr)
+    }
+}
+
 impl<T: ArchiveUnsized + ?Sized> Archive for rc::Rc<T> {
     type Archived = ArchivedRc<T::Archived, RcFlavor>;
     type Resolver = RcResolver<T::MetadataResolver>;
 
     #[inline]
     unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
-        ArchivedRc::resolve_from_ref(self.as_ref(), pos, resolver, out);
+        self.resolve_shared(pos, resolver, out);
     }
 }
 
@@ -44,26 +89,7 @@ where
 {
     #[inline]
     fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
-        ArchivedRc::<T::Archived, RcFlavor>::serialize_from_ref(self.as_ref(), serializer)
-    }
-}
-
-impl<T, D> Deserialize<rc::Rc<T>, D> for ArchivedRc<T::Archived, RcFlavor>
-where
-    T: ArchiveUnsized + ?Sized + 'static,
-    T::Archived: DeserializeUnsized<T, D>,
-    D: SharedDeserializeRegistry + ?Sized,
-{
-    #[inline]
-    fn deserialize(&self, deserializer: &mut D) -> Result<rc::Rc<T>, D::Error> {
-        let raw_shared_ptr = deserializer.deserialize_shared(
-            self.get(),
-            |ptr| rc::Rc::<T>::from(unsafe { Box::from_raw(ptr) }),
-            |layout| unsafe { alloc::alloc(layout) },
-        )?;
-        let shared_ptr = unsafe { rc::Rc::<T>::from_raw(raw_shared_ptr) };
-        forget(shared_ptr.clone());
-        Ok(shared_ptr)
+        self.serialize_shared(serializer)
     }
 }
 
@@ -138,6 +164,32 @@ impl<T: ?Sized> SharedPointer for sync::Arc<T> {
     }
 }
 
+#[cfg(has_atomics)]
+impl<T: ArchiveUnsized + ?Sized> ArchiveSharedPointer for sync::Arc<T> {
+    type Target = T;
+    type Flavor = ArcFlavor;
+
+    #[inline]
+    fn as_target(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+#[cfg(has_atomics)]
+// SAFETY: `Arc` is the only shared pointer with the `ArcFlavor` flavor, and `Arc::from_raw` is
+// sound for the data address of any `Arc`.
+unsafe impl<T: ArchiveUnsized + ?Sized + 'static> DeserializeSharedPointer for sync::Arc<T> {
+    #[inline]
+    fn from_box(boxed: Box<T>) -> Self {
+        sync::Arc::from(boxed)
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        sync::Arc::from_raw(ptr)
+    }
+}
+
 #[cfg(has_atomics)]
 impl<T: ArchiveUnsized + ?Sized> Archive for sync::Arc<T> {
     type Archived = ArchivedRc<T::Archived, ArcFlavor>;
@@ -145,7 +197,7 @@ impl<T: ArchiveUnsized + ?Sized> Archive for sync::Arc<T> {
 
     #[inline]
     unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
-        ArchivedRc::resolve_from_ref(self.as_ref(), pos, resolver, out);
+        self.resolve_shared(pos, resolver, out);
     }
 }
 
@@ -157,26 +209,7 @@ where
 {
     #[inline]
     fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
-        ArchivedRc::<T::Archived, ArcFlavor>::serialize_from_ref(self.as_ref(), serializer)
-    }
-}
-
-#[cfg(has_atomics)]
-impl<T: ArchiveUnsized + ?Sized + 'static, D: SharedDeserializeRegistry + ?Sized>
-    Deserialize<sync::Arc<T>, D> for ArchivedRc<T::Archived, ArcFlavor>
-where
-    T::Archived: DeserializeUnsized<T, D>,
-{
-    #[inline]
-    fn deserialize(&self, deserializer: &mut D) -> Result<sync::Arc<T>, D::Error> {
-        let raw_shared_ptr = deserializer.deserialize_shared(
-            self.get(),
-            |ptr| sync::Arc::<T>::from(unsafe { Box::from_raw(ptr) }),
-            |layout| unsafe { alloc::alloc(layout) },
-        )?;
-        let shared_ptr = unsafe { sync::Arc::<T>::from_raw(raw_shared_ptr) };
-        forget(shared_ptr.clone());
-        Ok(shared_ptr)
+        self.serialize_shared(serializer)
     }
 }
 
@@ -1,10 +1,10 @@
 use crate::{
-    string::{ArchivedString, StringResolver},
+    string::{inline::ArchivedInlineStr, ArchivedString, StringResolver},
     Archive, Deserialize, DeserializeUnsized, Fallible, Serialize, SerializeUnsized,
 };
-use ::core::cmp::Ordering;
 #[cfg(not(feature = "std"))]
 use ::alloc::string::{String, ToString};
+use ::core::cmp::Ordering;
 
 impl Archive for String {
     type Archived = ArchivedString;
@@ -63,3 +63,17 @@ impl PartialOrd<String> for ArchivedString {
         self.as_str().partial_cmp(other.as_str())
     }
 }
+
+impl<const N: usize> PartialEq<String> for ArchivedInlineStr<N> {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        PartialEq::eq(self.as_str(), other.as_str())
+    }
+}
+
+impl<const N: usize> PartialEq<ArchivedInlineStr<N>> for String {
+    #[inline]
+    fn eq(&self, other: &ArchivedInlineStr<N>) -> bool {
+        PartialEq::eq(other.as_str(), self.as_str())
+    }
+}
@@ -1,9 +1,16 @@
 use crate::{
-    ser::{ScratchSpace, Serializer},
+    ser::{
+        max_scratch::{slice_max_scratch, MaxScratch},
+        ScratchSpace, Serializer,
+    },
+    string::{ArchivedString, StringResolver},
     vec::{ArchivedVec, VecResolver},
-    Archive, Archived, Deserialize, Fallible, Serialize,
+    with::{ArchiveWith, CapacityError, CheckCapacity, DeserializeWith, SerializeWith},
+    Archive, Archived, Deserialize, DeserializeUnsized, Fallible, Serialize, SerializeUnsized,
 };
-use arrayvec::ArrayVec;
+use arrayvec::{ArrayString, ArrayVec};
+
+// ArrayVec
 
 impl<T, const CAP: usize> Archive for ArrayVec<T, CAP>
 where
@@ -28,6 +35,10 @@ where
     }
 }
 
+impl<T: Archive + MaxScratch, const CAP: usize> MaxScratch for ArrayVec<T, CAP> {
+    const MAX_SCRATCH: usize = slice_max_scratch::<T>(CAP);
+}
+
 impl<T, D: Fallible + ?Sized, const CAP: usize> Deserialize<ArrayVec<T, CAP>, D>
     for ArchivedVec<Archived<T>>
 where
@@ -44,10 +55,118 @@ where
     }
 }
 
+// ArrayString
+
+impl<const CAP: usize> Archive for ArrayString<CAP> {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedString::resolve_from_str(self.as_str(), pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized, const CAP: usize> Serialize<S> for ArrayString<CAP>
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(self.as_str(), serializer)
+    }
+}
+
+impl<const CAP: usize> MaxScratch for ArrayString<CAP> {
+    const MAX_SCRATCH: usize = 0;
+}
+
+impl<D: Fallible + ?Sized, const CAP: usize> Deserialize<ArrayString<CAP>, D> for ArchivedString
+where
+    str: DeserializeUnsized<str, D>,
+{
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<ArrayString<CAP>, D::Error> {
+        assert!(
+            self.len() <= CAP,
+            "archived string of length {} exceeds ArrayString capacity {}",
+            self.len(),
+            CAP,
+        );
+        Ok(ArrayString::from(self.as_str()).unwrap())
+    }
+}
+
+impl<const CAP: usize> PartialEq<ArrayString<CAP>> for ArchivedString {
+    #[inline]
+    fn eq(&self, other: &ArrayString<CAP>) -> bool {
+        PartialEq::eq(self.as_str(), other.as_str())
+    }
+}
+
+impl<const CAP: usize> PartialEq<ArchivedString> for ArrayString<CAP> {
+    #[inline]
+    fn eq(&self, other: &ArchivedString) -> bool {
+        PartialEq::eq(other.as_str(), self.as_str())
+    }
+}
+
+// CheckCapacity for ArrayString
+
+impl<const CAP: usize> ArchiveWith<ArrayString<CAP>> for CheckCapacity {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &ArrayString<CAP>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized, const CAP: usize> SerializeWith<ArrayString<CAP>, S> for CheckCapacity
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(
+        field: &ArrayString<CAP>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        field.serialize(serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized, const CAP: usize> DeserializeWith<ArchivedString, ArrayString<CAP>, D>
+    for CheckCapacity
+where
+    str: DeserializeUnsized<str, D>,
+    D::Error: From<CapacityError>,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedString,
+        deserializer: &mut D,
+    ) -> Result<ArrayString<CAP>, D::Error> {
+        if field.len() > CAP {
+            return Err(CapacityError {
+                len: field.len(),
+                capacity: CAP,
+            }
+            .into());
+        }
+        field.deserialize(deserializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{archived_root, ser::Serializer, Deserialize, Infallible};
-    use arrayvec::ArrayVec;
+    use arrayvec::{ArrayString, ArrayVec};
 
     #[test]
     fn array_vec() {
@@ -65,4 +184,110 @@ mod tests {
         let deserialized: ArrayVec<i32, 4> = archived.deserialize(&mut Infallible).unwrap();
         assert_eq!(value, deserialized);
     }
+
+    #[test]
+    fn array_vec_max_scratch() {
+        use crate::{
+            ser::{
+                max_scratch::MaxScratch,
+                serializers::{BufferScratch, BufferSerializer, CompositeSerializer},
+            },
+            AlignedBytes,
+        };
+
+        type Nested = ArrayVec<ArrayVec<i32, 2>, 3>;
+        const SCRATCH: usize = <Nested as MaxScratch>::MAX_SCRATCH;
+        assert_ne!(SCRATCH, 0);
+
+        let mut value = Nested::new();
+        for i in 0..3 {
+            value.push(ArrayVec::from([i, i + 1]));
+        }
+
+        // Scratch space of exactly the bound is enough to serialize a full value
+        let mut serializer = CompositeSerializer::new(
+            BufferSerializer::new(AlignedBytes::<256>::default()),
+            BufferScratch::new(AlignedBytes::<SCRATCH>::default()),
+            Infallible,
+        );
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Nested>(&result[0..end]) };
+        assert_eq!(archived[2].as_slice(), &[2, 3]);
+    }
+
+    #[test]
+    fn array_string() {
+        use crate::ser::serializers::CoreSerializer;
+
+        let value = ArrayString::<16>::from("hello world").unwrap();
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<ArrayString<16>>(&result[0..end]) };
+        assert_eq!(archived, &value);
+
+        let deserialized: ArrayString<16> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    fn array_string_capacity() {
+        use crate::{
+            ser::serializers::CoreSerializer,
+            string::ArchivedString,
+            with::{CapacityError, CheckCapacity, DeserializeWith},
+            Fallible,
+        };
+
+        struct CapacityDeserializer;
+
+        impl Fallible for CapacityDeserializer {
+            type Error = CapacityError;
+        }
+
+        let value = ArrayString::<16>::from("hello world").unwrap();
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<ArrayString<16>>(&result[0..end]) };
+
+        // Exactly at capacity
+        let deserialized: ArrayString<11> =
+            CheckCapacity::deserialize_with(archived, &mut CapacityDeserializer).unwrap();
+        assert_eq!(deserialized.as_str(), "hello world");
+
+        // Over capacity
+        assert!(matches!(
+            <CheckCapacity as DeserializeWith<ArchivedString, ArrayString<4>, _>>::deserialize_with(
+                archived,
+                &mut CapacityDeserializer,
+            ),
+            Err(CapacityError {
+                len: 11,
+                capacity: 4,
+            })
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_string_over_capacity() {
+        use crate::ser::serializers::CoreSerializer;
+
+        let value = ArrayString::<16>::from("hello world").unwrap();
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<ArrayString<16>>(&result[0..end]) };
+
+        let _: ArrayString<4> = archived.deserialize(&mut Infallible).unwrap();
+    }
 }
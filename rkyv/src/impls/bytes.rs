@@ -0,0 +1,131 @@
+use crate::{
+    ser::Serializer,
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Fallible, Serialize,
+};
+use bytes::{Bytes, BytesMut};
+
+// Bytes
+
+impl Archive for Bytes {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_slice(self.as_ref(), pos, resolver, out);
+    }
+}
+
+impl<S: Serializer + ?Sized> Serialize<S> for Bytes {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Safety: u8 is always copy-safe
+        unsafe { ArchivedVec::serialize_copy_from_slice(self.as_ref(), serializer) }
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<Bytes, D> for ArchivedVec<u8> {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<Bytes, D::Error> {
+        Ok(Bytes::copy_from_slice(self.as_slice()))
+    }
+}
+
+impl PartialEq<Bytes> for ArchivedVec<u8> {
+    #[inline]
+    fn eq(&self, other: &Bytes) -> bool {
+        self.as_slice().eq(other.as_ref())
+    }
+}
+
+impl PartialEq<ArchivedVec<u8>> for Bytes {
+    #[inline]
+    fn eq(&self, other: &ArchivedVec<u8>) -> bool {
+        other.eq(self)
+    }
+}
+
+// BytesMut
+
+impl Archive for BytesMut {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_slice(self.as_ref(), pos, resolver, out);
+    }
+}
+
+impl<S: Serializer + ?Sized> Serialize<S> for BytesMut {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Safety: u8 is always copy-safe
+        unsafe { ArchivedVec::serialize_copy_from_slice(self.as_ref(), serializer) }
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<BytesMut, D> for ArchivedVec<u8> {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<BytesMut, D::Error> {
+        Ok(BytesMut::from(self.as_slice()))
+    }
+}
+
+impl PartialEq<BytesMut> for ArchivedVec<u8> {
+    #[inline]
+    fn eq(&self, other: &BytesMut) -> bool {
+        self.as_slice().eq(other.as_ref())
+    }
+}
+
+impl PartialEq<ArchivedVec<u8>> for BytesMut {
+    #[inline]
+    fn eq(&self, other: &ArchivedVec<u8>) -> bool {
+        other.eq(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{archived_root, ser::Serializer, Deserialize, Infallible};
+    use bytes::{Bytes, BytesMut};
+
+    #[test]
+    fn bytes() {
+        use crate::ser::serializers::CoreSerializer;
+
+        let value = Bytes::from_static(b"hello world");
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Bytes>(&result[0..end]) };
+        assert_eq!(archived.as_slice(), b"hello world");
+        assert_eq!(archived, &value);
+
+        let deserialized: Bytes = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    fn bytes_mut() {
+        use crate::ser::serializers::CoreSerializer;
+
+        let mut value = BytesMut::with_capacity(64);
+        value.extend_from_slice(b"hello world");
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<BytesMut>(&result[0..end]) };
+        assert_eq!(archived.as_slice(), b"hello world");
+        assert_eq!(archived, &value);
+
+        let deserialized: BytesMut = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+}
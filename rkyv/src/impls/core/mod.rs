@@ -8,10 +8,13 @@ use crate::{
 use core::{alloc::Layout, mem::ManuallyDrop, ptr, str};
 use ptr_meta::Pointee;
 
+#[cfg(any(feature = "core_net", feature = "std"))]
+pub mod net;
 pub mod ops;
 pub mod option;
 pub mod primitive;
 pub mod result;
+pub mod task;
 pub mod time;
 
 impl<T> ArchivePointee for T {
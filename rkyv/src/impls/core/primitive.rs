@@ -1,4 +1,6 @@
-use crate::{Archive, Archived, Deserialize, Fallible, FixedIsize, FixedUsize, Serialize};
+use crate::{
+    vec::ArchiveNative, Archive, Archived, Deserialize, Fallible, FixedIsize, FixedUsize, Serialize,
+};
 #[cfg(has_atomics)]
 use core::sync::atomic::{
     AtomicBool, AtomicI16, AtomicI32, AtomicI8, AtomicIsize, AtomicU16, AtomicU32, AtomicU8,
@@ -10,7 +12,7 @@ use core::{
     marker::{PhantomData, PhantomPinned},
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
-        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Saturating, Wrapping,
     },
 };
 
@@ -181,6 +183,31 @@ impl_atomic!(@multibyte AtomicU32, u32);
 #[cfg(has_atomics_64)]
 impl_atomic!(@multibyte AtomicU64, u64);
 
+// ArchiveNative
+
+unsafe impl ArchiveNative for bool {}
+unsafe impl ArchiveNative for i8 {}
+unsafe impl ArchiveNative for u8 {}
+
+// Multibyte primitives are only archived natively if the archive endianness matches the target
+#[cfg(any(
+    not(any(feature = "archive_le", feature = "archive_be")),
+    all(target_endian = "little", feature = "archive_le"),
+    all(target_endian = "big", feature = "archive_be"),
+))]
+const _: () = {
+    unsafe impl ArchiveNative for i16 {}
+    unsafe impl ArchiveNative for i32 {}
+    unsafe impl ArchiveNative for i64 {}
+    unsafe impl ArchiveNative for i128 {}
+    unsafe impl ArchiveNative for u16 {}
+    unsafe impl ArchiveNative for u32 {}
+    unsafe impl ArchiveNative for u64 {}
+    unsafe impl ArchiveNative for u128 {}
+    unsafe impl ArchiveNative for f32 {}
+    unsafe impl ArchiveNative for f64 {}
+};
+
 // PhantomData
 
 impl<T: ?Sized> Archive for PhantomData<T> {
@@ -397,3 +424,33 @@ impl<D: Fallible + ?Sized> Deserialize<AtomicIsize, D> for Archived<AtomicIsize>
         Ok((from_archived!(*self) as isize).into())
     }
 }
+
+// Wrapping and Saturating
+
+macro_rules! impl_num_wrapper {
+    ($wrapper:ident, $($prim:ty),* $(,)?) => {
+        $(
+            impl Archive for $wrapper<$prim> {
+                type Archived = Archived<$prim>;
+                type Resolver = ();
+
+                #[inline]
+                unsafe fn resolve(&self, pos: usize, _: Self::Resolver, out: *mut Self::Archived) {
+                    self.0.resolve(pos, (), out);
+                }
+            }
+
+            impl_primitive!(@serialize $wrapper<$prim>);
+
+            impl<D: Fallible + ?Sized> Deserialize<$wrapper<$prim>, D> for Archived<$prim> {
+                #[inline]
+                fn deserialize(&self, deserializer: &mut D) -> Result<$wrapper<$prim>, D::Error> {
+                    Deserialize::<$prim, D>::deserialize(self, deserializer).map($wrapper)
+                }
+            }
+        )*
+    };
+}
+
+impl_num_wrapper!(Wrapping, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_num_wrapper!(Saturating, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
@@ -0,0 +1,73 @@
+use crate::{task::ArchivedPoll, Archive, Deserialize, Fallible, Serialize};
+use core::{hint::unreachable_unchecked, ptr, task::Poll};
+
+#[allow(dead_code)]
+#[repr(u8)]
+enum ArchivedPollTag {
+    Ready,
+    Pending,
+}
+
+#[repr(C)]
+struct ArchivedPollVariantReady<T>(ArchivedPollTag, T);
+
+impl<T: Archive> Archive for Poll<T> {
+    type Archived = ArchivedPoll<T::Archived>;
+    type Resolver = Poll<T::Resolver>;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        match resolver {
+            Poll::Ready(resolver) => {
+                let out = out.cast::<ArchivedPollVariantReady<T::Archived>>();
+                ptr::addr_of_mut!((*out).0).write(ArchivedPollTag::Ready);
+
+                let (fp, fo) = out_field!(out.1);
+                match self {
+                    Poll::Ready(value) => value.resolve(pos + fp, resolver, fo),
+                    Poll::Pending => unreachable_unchecked(),
+                }
+            }
+            Poll::Pending => {
+                let out = out.cast::<ArchivedPollTag>();
+                out.write(ArchivedPollTag::Pending);
+            }
+        }
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + ?Sized> Serialize<S> for Poll<T> {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(match self {
+            Poll::Ready(value) => Poll::Ready(value.serialize(serializer)?),
+            Poll::Pending => Poll::Pending,
+        })
+    }
+}
+
+impl<T, D> Deserialize<Poll<T>, D> for ArchivedPoll<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<Poll<T>, D::Error> {
+        Ok(match self {
+            ArchivedPoll::Ready(value) => Poll::Ready(value.deserialize(deserializer)?),
+            ArchivedPoll::Pending => Poll::Pending,
+        })
+    }
+}
+
+impl<T, U: PartialEq<T>> PartialEq<Poll<T>> for ArchivedPoll<U> {
+    #[inline]
+    fn eq(&self, other: &Poll<T>) -> bool {
+        match (self, other) {
+            (ArchivedPoll::Ready(a), Poll::Ready(b)) => a.eq(b),
+            (ArchivedPoll::Pending, Poll::Pending) => true,
+            _ => false,
+        }
+    }
+}
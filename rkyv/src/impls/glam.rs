@@ -0,0 +1,106 @@
+use crate::{glam::*, Archive, Deserialize, Fallible, Serialize};
+use glam::{
+    DMat2, DMat3, DMat4, DQuat, DVec2, DVec3, DVec4, Mat2, Mat3, Mat4, Quat, Vec2, Vec3, Vec4,
+};
+
+macro_rules! impl_glam {
+    ($ty:ident, $archived:ident, $len:literal, $to_array:ident) => {
+        impl Archive for $ty {
+            type Archived = $archived;
+            type Resolver = ();
+
+            #[inline]
+            unsafe fn resolve(&self, pos: usize, _: Self::Resolver, out: *mut Self::Archived) {
+                // Safety: the archived type is repr(transparent) over the archived array
+                self.$to_array().resolve(pos, [(); $len], out.cast());
+            }
+        }
+
+        impl<S: Fallible + ?Sized> Serialize<S> for $ty {
+            #[inline]
+            fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized> Deserialize<$ty, D> for $archived {
+            #[inline]
+            fn deserialize(&self, _: &mut D) -> Result<$ty, D::Error> {
+                Ok(self.to_glam())
+            }
+        }
+    };
+}
+
+impl_glam!(Vec2, ArchivedVec2, 2, to_array);
+impl_glam!(Vec3, ArchivedVec3, 3, to_array);
+impl_glam!(Vec4, ArchivedVec4, 4, to_array);
+impl_glam!(Quat, ArchivedQuat, 4, to_array);
+impl_glam!(Mat2, ArchivedMat2, 4, to_cols_array);
+impl_glam!(Mat3, ArchivedMat3, 9, to_cols_array);
+impl_glam!(Mat4, ArchivedMat4, 16, to_cols_array);
+impl_glam!(DVec2, ArchivedDVec2, 2, to_array);
+impl_glam!(DVec3, ArchivedDVec3, 3, to_array);
+impl_glam!(DVec4, ArchivedDVec4, 4, to_array);
+impl_glam!(DQuat, ArchivedDQuat, 4, to_array);
+impl_glam!(DMat2, ArchivedDMat2, 4, to_cols_array);
+impl_glam!(DMat3, ArchivedDMat3, 9, to_cols_array);
+impl_glam!(DMat4, ArchivedDMat4, 16, to_cols_array);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archived_root,
+        glam::{ArchivedMat4, ArchivedVec3},
+        ser::{serializers::AlignedSerializer, Serializer},
+        util::AlignedVec,
+        Archive, Deserialize, Infallible, Serialize,
+    };
+    use core::mem::{align_of, size_of};
+    use glam::{DQuat, Mat4, Quat, Vec3};
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[archive(crate = "crate")]
+    #[archive(compare(PartialEq))]
+    #[cfg_attr(feature = "validation", archive(check_bytes))]
+    #[archive_attr(derive(Debug))]
+    struct Transform {
+        position: Vec3,
+        rotation: Quat,
+        matrix: Mat4,
+        precise: DQuat,
+    }
+
+    #[test]
+    fn glam() {
+        let rotation = Quat::from_rotation_y(1.25);
+        let value = Transform {
+            position: Vec3::new(1.0, -2.5, 3.75),
+            rotation,
+            matrix: Mat4::from_rotation_translation(rotation, Vec3::new(4.0, 5.0, 6.0)),
+            precise: DQuat::from_rotation_x(-0.5),
+        };
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_inner();
+        let archived = unsafe { archived_root::<Transform>(buf.as_ref()) };
+
+        assert_eq!(archived, &value);
+        #[cfg(feature = "validation")]
+        crate::check_archived_root::<Transform>(buf.as_ref()).unwrap();
+        assert_eq!(archived.position.to_glam(), value.position);
+        assert_eq!(archived.matrix.to_glam(), value.matrix);
+        assert_eq!(
+            archived.matrix.as_array().map(|x| from_archived!(x)),
+            value.matrix.to_cols_array()
+        );
+
+        assert_eq!(size_of::<ArchivedVec3>(), size_of::<Vec3>());
+        assert_eq!(size_of::<ArchivedMat4>(), size_of::<Mat4>());
+        assert_eq!(align_of::<ArchivedMat4>(), align_of::<f32>());
+
+        let deserialized: Transform = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    collections::index_map::{ArchivedIndexMap, IndexMapResolver},
+    collections::{
+        hash_index::HashIndexLenError,
+        index_map::{ArchivedIndexMap, IndexMapResolver},
+    },
     ser::{ScratchSpace, Serializer},
     Archive, Deserialize, Fallible, Serialize,
 };
@@ -20,6 +23,7 @@ where
     K: Hash + Eq + Serialize<S>,
     V: Serialize<S>,
     S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<HashIndexLenError>,
     RandomState: BuildHasher,
 {
     fn serialize(&self, serializer: &mut S) -> Result<IndexMapResolver, S::Error> {
@@ -1,5 +1,8 @@
 use crate::{
-    collections::index_set::{ArchivedIndexSet, IndexSetResolver},
+    collections::{
+        hash_index::HashIndexLenError,
+        index_set::{ArchivedIndexSet, IndexSetResolver},
+    },
     ser::{ScratchSpace, Serializer},
     Archive, Deserialize, Fallible, Serialize,
 };
@@ -19,6 +22,7 @@ impl<K, S, RandomState> Serialize<S> for IndexSet<K, RandomState>
 where
     K: Hash + Eq + Serialize<S>,
     S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<HashIndexLenError>,
     RandomState: BuildHasher,
 {
     fn serialize(&self, serializer: &mut S) -> Result<IndexSetResolver, S::Error> {
@@ -0,0 +1,169 @@
+use crate::{
+    ordered_float::{ArchivedNotNan, ArchivedOrderedFloat, FloatPrimitive},
+    Archive, Deserialize, Fallible, Serialize,
+};
+use ordered_float::{NotNan, OrderedFloat};
+
+impl<F: FloatPrimitive> Archive for OrderedFloat<F> {
+    type Archived = ArchivedOrderedFloat<F>;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, _: Self::Resolver, out: *mut Self::Archived) {
+        // Safety: ArchivedOrderedFloat is repr(transparent) over the archived float
+        self.0.resolve(pos, (), out.cast());
+    }
+}
+
+impl<F: FloatPrimitive, S: Fallible + ?Sized> Serialize<S> for OrderedFloat<F> {
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<F: FloatPrimitive, D: Fallible + ?Sized> Deserialize<OrderedFloat<F>, D>
+    for ArchivedOrderedFloat<F>
+{
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<OrderedFloat<F>, D::Error> {
+        Ok(self.get())
+    }
+}
+
+impl<F: FloatPrimitive> Archive for NotNan<F> {
+    type Archived = ArchivedNotNan<F>;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, _: Self::Resolver, out: *mut Self::Archived) {
+        // Safety: ArchivedNotNan is repr(transparent) over the archived float
+        self.into_inner().resolve(pos, (), out.cast());
+    }
+}
+
+impl<F: FloatPrimitive, S: Fallible + ?Sized> Serialize<S> for NotNan<F> {
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<F: FloatPrimitive, D: Fallible + ?Sized> Deserialize<NotNan<F>, D> for ArchivedNotNan<F> {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<NotNan<F>, D::Error> {
+        Ok(self.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archived_root,
+        ser::{serializers::AlignedSerializer, Serializer},
+        util::AlignedVec,
+        Deserialize, Infallible,
+    };
+    use ordered_float::{NotNan, OrderedFloat};
+
+    #[test]
+    fn ordered_float() {
+        let value = OrderedFloat(f64::NAN);
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_inner();
+        let archived = unsafe { archived_root::<OrderedFloat<f64>>(buf.as_ref()) };
+
+        assert_eq!(archived, &value);
+        assert!(archived.get().is_nan());
+
+        let deserialized: OrderedFloat<f64> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    fn not_nan() {
+        let value = NotNan::new(1.5f32).unwrap();
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_inner();
+        let archived = unsafe { archived_root::<NotNan<f32>>(buf.as_ref()) };
+
+        assert_eq!(archived, &value);
+
+        let deserialized: NotNan<f32> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ordered_float_map_keys() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert(OrderedFloat(1.5f64), 1u32);
+        hash_map.insert(OrderedFloat(-0.25f64), 2u32);
+        hash_map.insert(OrderedFloat(f64::NAN), 3u32);
+
+        let mut serializer = crate::ser::serializers::AllocSerializer::<256>::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<HashMap<OrderedFloat<f64>, u32>>(buf.as_ref()) };
+
+        assert_eq!(archived.len(), 3);
+        for (key, value) in archived.iter() {
+            assert_eq!(hash_map[&key.get()], *value);
+            assert_eq!(archived.get(key), Some(value));
+        }
+
+        let btree_map = hash_map
+            .into_iter()
+            .map(|(k, v)| (NotNan::new(k.0).unwrap_or_default(), v))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut serializer = crate::ser::serializers::AllocSerializer::<256>::default();
+        serializer.serialize_value(&btree_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<BTreeMap<NotNan<f64>, u32>>(buf.as_ref()) };
+
+        let keys = archived.keys().map(|k| k.get()).collect::<Vec<_>>();
+        assert_eq!(keys, btree_map.keys().copied().collect::<Vec<_>>());
+        assert_eq!(
+            archived.get_key_value_by(|k| k.get().cmp(&NotNan::new(1.5).unwrap())),
+            Some((archived.keys().nth(2).unwrap(), &1)),
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "validation")]
+    fn check_not_nan() {
+        use crate::{check_archived_root, ordered_float::NotNanError};
+
+        let value = NotNan::new(2.5f64).unwrap();
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_inner();
+        assert!(check_archived_root::<NotNan<f64>>(buf.as_ref()).is_ok());
+
+        // Overwrite the archived float with a NaN in the byte order of the archive
+        #[cfg(not(any(feature = "archive_le", feature = "archive_be")))]
+        let nan = f64::NAN.to_ne_bytes();
+        #[cfg(feature = "archive_le")]
+        let nan = f64::NAN.to_le_bytes();
+        #[cfg(feature = "archive_be")]
+        let nan = f64::NAN.to_be_bytes();
+        buf.as_mut_slice()[..8].copy_from_slice(&nan);
+        assert!(matches!(
+            check_archived_root::<NotNan<f64>>(buf.as_ref()),
+            Err(crate::validation::CheckArchiveError::CheckBytesError(
+                NotNanError::IsNan
+            )),
+        ));
+
+        // OrderedFloat accepts NaN
+        assert!(check_archived_root::<OrderedFloat<f64>>(buf.as_ref()).is_ok());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    collections::hash_map::{ArchivedHashMap, HashMapResolver},
+    collections::{
+        hash_index::HashIndexLenError,
+        hash_map::{ArchivedHashMap, HashMapResolver},
+    },
     ser::{ScratchSpace, Serializer},
     Archive, Deserialize, Fallible, Serialize,
 };
@@ -28,6 +31,7 @@ where
     K::Archived: Hash + Eq,
     V: Serialize<S>,
     S: Serializer + ScratchSpace + ?Sized,
+    S::Error: From<HashIndexLenError>,
 {
     #[inline]
     fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
@@ -1,5 +1,8 @@
 use crate::{
-    collections::hash_set::{ArchivedHashSet, HashSetResolver},
+    collections::{
+        hash_index::HashIndexLenError,
+        hash_set::{ArchivedHashSet, HashSetResolver},
+    },
     ser::{ScratchSpace, Serializer},
     Archive, Deserialize, Fallible, Serialize,
 };
@@ -27,6 +30,7 @@ where
     K::Archived: Hash + Eq,
     K: Serialize<S> + Hash + Eq,
     S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<HashIndexLenError>,
 {
     #[inline]
     fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
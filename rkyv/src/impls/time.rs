@@ -0,0 +1,101 @@
+use crate::{
+    time::time_crate::{ArchivedDate, ArchivedDuration, ArchivedOffsetDateTime},
+    Archive, Deserialize, Fallible, Serialize,
+};
+use time::{Date, Duration, OffsetDateTime};
+
+macro_rules! impl_time {
+    ($ty:ident, $archived:ident) => {
+        impl Archive for $ty {
+            type Archived = $archived;
+            type Resolver = ();
+
+            #[inline]
+            unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
+                $archived::emplace(*self, out);
+            }
+        }
+
+        impl<S: Fallible + ?Sized> Serialize<S> for $ty {
+            #[inline]
+            fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized> Deserialize<$ty, D> for $archived {
+            #[inline]
+            fn deserialize(&self, _: &mut D) -> Result<$ty, D::Error> {
+                Ok(self.get())
+            }
+        }
+    };
+}
+
+impl_time!(Date, ArchivedDate);
+impl_time!(OffsetDateTime, ArchivedOffsetDateTime);
+impl_time!(Duration, ArchivedDuration);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archived_root,
+        ser::{serializers::AlignedSerializer, Serializer},
+        util::AlignedVec,
+        Archive, Deserialize, Infallible, Serialize,
+    };
+    use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[archive(crate = "crate")]
+    #[archive(compare(PartialEq))]
+    #[cfg_attr(feature = "validation", archive(check_bytes))]
+    #[archive_attr(derive(Debug))]
+    struct Event {
+        date: Date,
+        at: OffsetDateTime,
+        elapsed: Duration,
+    }
+
+    fn archive(value: &Event) -> AlignedVec {
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(value).unwrap();
+        serializer.into_inner()
+    }
+
+    #[test]
+    fn time() {
+        let date = Date::from_calendar_date(2024, Month::February, 29).unwrap();
+        let value = Event {
+            date,
+            at: PrimitiveDateTime::new(date, Time::from_hms_nano(23, 59, 58, 123_456_789).unwrap())
+                .assume_offset(UtcOffset::from_hms(-9, -30, 0).unwrap()),
+            elapsed: Duration::new(-5, -250_000_000),
+        };
+
+        let buf = archive(&value);
+        #[cfg(feature = "validation")]
+        crate::check_archived_root::<Event>(buf.as_ref()).unwrap();
+        let archived = unsafe { archived_root::<Event>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+
+        assert_eq!(
+            (
+                archived.date.year(),
+                archived.date.month(),
+                archived.date.day()
+            ),
+            (2024, 2, 29)
+        );
+        assert_eq!(archived.at.hour(), 23);
+        assert_eq!(archived.at.offset_seconds(), -(9 * 3600 + 30 * 60));
+        assert_eq!(archived.elapsed.whole_seconds(), -5);
+        assert_eq!(archived.elapsed.subsec_nanoseconds(), -250_000_000);
+
+        // The local date, time, and offset are all preserved
+        let deserialized: Event = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+        assert_eq!(deserialized.at.offset(), value.at.offset());
+        assert_eq!(deserialized.at.date(), value.at.date());
+    }
+}
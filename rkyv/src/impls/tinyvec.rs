@@ -1,6 +1,10 @@
 use crate::{
-    ser::{ScratchSpace, Serializer},
+    ser::{
+        max_scratch::{slice_max_scratch, MaxScratch},
+        ScratchSpace, Serializer,
+    },
     vec::{ArchivedVec, VecResolver},
+    with::{ArchiveWith, CapacityError, CheckCapacity, DeserializeWith, SerializeWith},
     Archive, Archived, Deserialize, Fallible, Serialize,
 };
 #[cfg(feature = "tinyvec_alloc")]
@@ -32,6 +36,13 @@ where
     }
 }
 
+impl<A: Array> MaxScratch for ArrayVec<A>
+where
+    A::Item: Archive + MaxScratch,
+{
+    const MAX_SCRATCH: usize = slice_max_scratch::<A::Item>(A::CAPACITY);
+}
+
 impl<A: Array, D: Fallible + ?Sized> Deserialize<ArrayVec<A>, D> for ArchivedVec<Archived<A::Item>>
 where
     A::Item: Archive,
@@ -39,6 +50,12 @@ where
 {
     #[inline]
     fn deserialize(&self, deserializer: &mut D) -> Result<ArrayVec<A>, D::Error> {
+        assert!(
+            self.len() <= A::CAPACITY,
+            "archived vec of length {} exceeds ArrayVec capacity {}",
+            self.len(),
+            A::CAPACITY,
+        );
         let mut result = ArrayVec::new();
         for item in self.as_slice() {
             result.push(item.deserialize(deserializer)?);
@@ -47,6 +64,60 @@ where
     }
 }
 
+// CheckCapacity for ArrayVec
+
+impl<A: Array> ArchiveWith<ArrayVec<A>> for CheckCapacity
+where
+    A::Item: Archive,
+{
+    type Archived = ArchivedVec<Archived<A::Item>>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &ArrayVec<A>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+impl<A: Array, S: ScratchSpace + Serializer + ?Sized> SerializeWith<ArrayVec<A>, S>
+    for CheckCapacity
+where
+    A::Item: Serialize<S>,
+{
+    #[inline]
+    fn serialize_with(field: &ArrayVec<A>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        field.serialize(serializer)
+    }
+}
+
+impl<A: Array, D: Fallible + ?Sized> DeserializeWith<ArchivedVec<Archived<A::Item>>, ArrayVec<A>, D>
+    for CheckCapacity
+where
+    A::Item: Archive,
+    Archived<A::Item>: Deserialize<A::Item, D>,
+    D::Error: From<CapacityError>,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedVec<Archived<A::Item>>,
+        deserializer: &mut D,
+    ) -> Result<ArrayVec<A>, D::Error> {
+        if field.len() > A::CAPACITY {
+            return Err(CapacityError {
+                len: field.len(),
+                capacity: A::CAPACITY,
+            }
+            .into());
+        }
+        field.deserialize(deserializer)
+    }
+}
+
 // SliceVec
 
 impl<'s, T: Archive> Archive for SliceVec<'s, T> {
@@ -133,6 +204,75 @@ mod tests {
         assert_eq!(value, deserialized);
     }
 
+    #[test]
+    fn array_vec_capacity() {
+        use crate::{
+            ser::serializers::CoreSerializer,
+            vec::ArchivedVec,
+            with::{CapacityError, CheckCapacity, DeserializeWith},
+            Fallible,
+        };
+
+        struct CapacityDeserializer;
+
+        impl Fallible for CapacityDeserializer {
+            type Error = CapacityError;
+        }
+
+        // Exactly at capacity
+        let value = array_vec!([i32; 4] => 10, 20, 40, 80);
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<ArrayVec<[i32; 4]>>(&result[0..end]) };
+
+        let deserialized: ArrayVec<[i32; 4]> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+        let deserialized: ArrayVec<[i32; 4]> =
+            CheckCapacity::deserialize_with(archived, &mut CapacityDeserializer).unwrap();
+        assert_eq!(value, deserialized);
+
+        // Over capacity
+        let value = array_vec!([i32; 8] => 10, 20, 40, 80, 160);
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<ArrayVec<[i32; 8]>>(&result[0..end]) };
+
+        let result: Result<ArrayVec<[i32; 4]>, _> =
+            <CheckCapacity as DeserializeWith<ArchivedVec<i32>, _, _>>::deserialize_with(
+                archived,
+                &mut CapacityDeserializer,
+            );
+        assert!(matches!(
+            result,
+            Err(CapacityError {
+                len: 5,
+                capacity: 4,
+            })
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_vec_over_capacity() {
+        use crate::ser::serializers::CoreSerializer;
+
+        let value = array_vec!([i32; 8] => 10, 20, 40, 80, 160);
+
+        let mut serializer = CoreSerializer::<256, 256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<ArrayVec<[i32; 8]>>(&result[0..end]) };
+
+        let _: ArrayVec<[i32; 4]> = archived.deserialize(&mut Infallible).unwrap();
+    }
+
     #[test]
     fn slice_vec() {
         use crate::ser::serializers::CoreSerializer;
@@ -63,11 +63,23 @@
 //! - `copy_unsafe`: Automatically opts all potentially copyable types into copy optimization. This
 //!   broadly improves performance but may cause uninitialized bytes to be copied to the output.
 //!   Requires nightly.
+//! - `core_net`: Implements `Archive` for the IP and socket address types in `core::net` so they're
+//!   available without `std`. Requires Rust 1.77 or later.
+//! - `debug`: Enables human-readable layout dumps of archives through the `dump` module.
+//! - `reflect`: Enables generic reflection over the fields of archived values through the
+//!   `reflect` module.
 //! - `size_16`: Archives integral `*size` types as 16-bit integers. This is intended to be used
 //!   only for small archives and may not handle large, more general data.
 //! - `size_32`: Archives integral `*size` types as 32-bit integers. Enabled by default.
 //! - `size_64`: Archives integral `*size` types as 64-bit integers. This is intended to be used
 //!   only for very large archives and may cause unnecessary data bloat.
+//!
+//!   *Note*: The size feature determines the layout of archived types, so archives created with one
+//!   size feature can't be read by a build with a different size feature. Because the layouts of
+//!   different builds never exist in the same program, archives can't be converted in place.
+//!   Instead, archives can be migrated with two programs that exchange data in a size-independent
+//!   format: one that deserializes with the old size feature, and another that serializes with the
+//!   new one.
 //! - `std`: Enables standard library support. Enabled by default.
 //! - `strict`: Guarantees that types will have the same representations across platforms and
 //!   compilations. This is already the case in practice, but this feature provides a guarantee
@@ -87,9 +99,13 @@
 //!
 //! Crates supported by rkyv:
 //!
+//! - [`bytes`](https://docs.rs/bytes)
+//! - [`glam`](https://docs.rs/glam) *Vectors, quaternions, and matrices of `f32` and `f64`.*
 //! - [`indexmap`](https://docs.rs/indexmap)
+//! - [`ordered-float`](https://docs.rs/ordered-float)
 //! - [`rend`](https://docs.rs/rend) *Enabled automatically when using endian-specific archive
-//!   features.*
+//!   features. Also provides the `AsLittleEndian` and `AsBigEndian` wrappers.*
+//! - [`time`](https://docs.rs/time) *`Date`, `OffsetDateTime`, and `Duration`.*
 //! - [`tinyvec`](https://docs.rs/tinyvec)
 //! - [`uuid`](https://docs.rs/uuid)
 //!
@@ -143,29 +159,46 @@ pub mod macros;
 
 #[cfg(feature = "bitvec")]
 pub mod bitvec;
+pub mod bloom;
 pub mod boxed;
 pub mod collections;
 #[cfg(feature = "copy")]
 pub mod copy;
 pub mod de;
+#[cfg(feature = "debug")]
+pub mod dump;
 // This is pretty unfortunate. CStr doesn't rely on the rest of std, but it's not in core.
 // If CStr ever gets moved into `core` then this module will no longer need cfg(feature = "std")
 #[cfg(feature = "std")]
 pub mod ffi;
+#[cfg(feature = "glam")]
+pub mod glam;
 mod impls;
+#[cfg(feature = "alloc")]
+pub mod multi;
 pub mod net;
 pub mod niche;
+pub mod num;
 pub mod ops;
 pub mod option;
+#[cfg(feature = "ordered-float")]
+pub mod ordered_float;
+pub mod quantize;
 pub mod rc;
+#[cfg(feature = "reflect")]
+pub mod reflect;
 pub mod rel_ptr;
 pub mod result;
 pub mod ser;
 pub mod string;
+pub mod tag;
+pub mod task;
 pub mod time;
 pub mod util;
 #[cfg(feature = "validation")]
 pub mod validation;
+pub mod variants;
+pub mod varint;
 pub mod vec;
 pub mod with;
 
@@ -183,7 +216,10 @@ pub use util::*;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "validation")))]
 pub use validation::{
     check_archived_root_with_context, check_archived_value_with_context,
-    validators::{check_archived_root, check_archived_value, from_bytes},
+    validators::{
+        access_with_header, check_archived_root, check_archived_value, check_root_shallow,
+        from_bytes,
+    },
 };
 
 /// A type that can produce an error.
@@ -203,6 +239,10 @@ pub trait Fallible {
 ///
 /// This type can be used to serialize and deserialize types that cannot fail to serialize or
 /// deserialize.
+///
+/// `Infallible` doesn't allocate, so it can be used as a deserializer without the `alloc` feature.
+/// Primitives, `Option`s, `Result`s, arrays, tuples, and derived types made of them can all be
+/// deserialized with it.
 #[derive(Debug)]
 pub struct Infallible;
 
@@ -442,6 +482,61 @@ pub trait Serialize<S: Fallible + ?Sized>: Archive {
 /// capabilities (e.g. [`SharedDeserializeRegistry`](de::SharedDeserializeRegistry)).
 ///
 /// This can be derived with [`Deserialize`](macro@Deserialize).
+///
+/// # Migrating types
+///
+/// An archived type can implement `Deserialize` for any number of target types, not just the type
+/// it was archived from. This can be used to migrate data archived with an old version of a type
+/// into a new version, for example when enum variants are renamed or merged. Only the archived
+/// layout of the old type needs to be kept around. Deriving [`Archive`](macro@Archive) on a copy of
+/// the old definition is enough, and the old type itself never needs to be constructed.
+///
+/// ```
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, Archive, Deserialize, Fallible, Infallible, Serialize};
+///
+/// // The layout that the data was archived with
+/// #[derive(Archive, Serialize)]
+/// enum OldShape {
+///     Circle { radius: f32 },
+///     Square(f32),
+///     Rectangle(f32, f32),
+/// }
+///
+/// // The type that the data should be deserialized into
+/// #[derive(Debug, PartialEq)]
+/// enum Shape {
+///     Round { radius: f32 },
+///     Quad { width: f32, height: f32 },
+/// }
+///
+/// impl<D: Fallible + ?Sized> Deserialize<Shape, D> for ArchivedOldShape {
+///     fn deserialize(&self, _: &mut D) -> Result<Shape, D::Error> {
+///         Ok(match self {
+///             ArchivedOldShape::Circle { radius } => Shape::Round { radius: *radius },
+///             ArchivedOldShape::Square(side) => Shape::Quad { width: *side, height: *side },
+///             ArchivedOldShape::Rectangle(width, height) => Shape::Quad {
+///                 width: *width,
+///                 height: *height,
+///             },
+///         })
+///     }
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&OldShape::Square(2.0)).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// let archived = unsafe { archived_root::<OldShape>(&bytes) };
+/// let shape: Shape = archived.deserialize(&mut Infallible).unwrap();
+/// assert_eq!(shape, Shape::Quad { width: 2.0, height: 2.0 });
+/// ```
+///
+/// Matching on an archived enum reads its tag directly, so the archived type must have exactly the
+/// same layout as the type the data was serialized with. Changing the order or fields of the
+/// variants, their types, or the `repr` of the old definition changes its layout and makes reading
+/// old data undefined behavior when using [`archived_root`]. Validating the data with
+/// `check_archived_root` first rejects invalid tags and fields, but it can't detect data that was
+/// archived with a different layout that happens to be valid.
 pub trait Deserialize<T, D: Fallible + ?Sized> {
     /// Deserializes using the given deserializer
     fn deserialize(&self, deserializer: &mut D) -> Result<T, D::Error>;
@@ -110,6 +110,55 @@ macro_rules! to_archived {
     }};
 }
 
+/// Asserts at compile time that the archived version of a type has the given size and alignment.
+///
+/// Changing the layout of an archived type breaks compatibility with previously-created archives.
+/// This macro can be used to guard against accidental layout changes, and fails to compile if the
+/// size or alignment of `Archived<T>` doesn't match.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{assert_archived_layout, Archive};
+///
+/// #[derive(Archive)]
+/// #[archive_attr(repr(C))]
+/// struct Example {
+///     a: u32,
+///     b: u16,
+/// }
+///
+/// assert_archived_layout!(Example, size = 8, align = 4);
+/// ```
+///
+/// ```compile_fail
+/// use rkyv::{assert_archived_layout, Archive};
+///
+/// #[derive(Archive)]
+/// #[archive_attr(repr(C))]
+/// struct Example {
+///     a: u32,
+///     b: u16,
+/// }
+///
+/// assert_archived_layout!(Example, size = 6, align = 4);
+/// ```
+#[macro_export]
+macro_rules! assert_archived_layout {
+    ($ty:ty, size = $size:expr, align = $align:expr $(,)?) => {
+        const _: () = {
+            ::core::assert!(
+                ::core::mem::size_of::<$crate::Archived<$ty>>() == $size,
+                "the size of the archived type does not match the expected size",
+            );
+            ::core::assert!(
+                ::core::mem::align_of::<$crate::Archived<$ty>>() == $align,
+                "the alignment of the archived type does not match the expected alignment",
+            );
+        };
+    };
+}
+
 #[cfg(not(any(feature = "size_16", feature = "size_32", feature = "size_64")))]
 core::compile_error!(r#"one of ["size_16", "size_32", or "size_64"] features must be enabled"#);
 
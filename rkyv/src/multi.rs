@@ -0,0 +1,451 @@
+//! Archives that contain multiple independent roots.
+//!
+//! A multi-archive is a sequence of independently serialized values followed by a trailing offset
+//! table. The offset table is an archived `Vec<usize>` of the positions of each value, and is
+//! located at the end of the archive like a regular root. Each value can be accessed by index
+//! without touching any of the others.
+//!
+//! A bundle is like a multi-archive that's indexed by key instead of by position. The values in a
+//! bundle are serialized with the same shared pointer registry, so sub-values that are shared
+//! between them are only stored once. The index is an archived `BTreeMap<K, usize>` of the
+//! positions of each value.
+
+#[cfg(feature = "validation")]
+use crate::{
+    collections::ArchivedBTreeMap,
+    validation::{
+        validators::{check_archived_root, check_archived_value, DefaultValidator},
+        CheckTypeError,
+    },
+    vec::ArchivedVec,
+    Archive, Archived,
+};
+use crate::{
+    ser::{ScratchSpace, Serializer, SharedSerializeRegistry},
+    Serialize,
+};
+#[cfg(not(feature = "std"))]
+use ::alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "validation")]
+use bytecheck::CheckBytes;
+#[cfg(feature = "validation")]
+use core::borrow::Borrow;
+#[cfg(feature = "validation")]
+use core::fmt;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+/// Serializes a sequence of values into a single multi-archive.
+///
+/// Values are numbered in the order they're serialized. After all of the values have been
+/// serialized, [`finish`](MultiArchiveSerializer::finish) must be called to write the offset table.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{multi::MultiArchiveSerializer, ser::serializers::AllocSerializer};
+///
+/// let mut serializer = MultiArchiveSerializer::new(AllocSerializer::<256>::default());
+/// serializer.serialize_value(&"hello".to_string()).unwrap();
+/// serializer.serialize_value(&"world".to_string()).unwrap();
+/// let bytes = serializer.finish().unwrap().into_serializer().into_inner();
+///
+/// # #[cfg(feature = "validation")]
+/// # {
+/// use rkyv::multi::MultiArchive;
+///
+/// let archive = MultiArchive::<String>::new(&bytes).unwrap();
+/// assert_eq!(archive.len(), 2);
+/// assert_eq!(archive.get(1).unwrap(), "world");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MultiArchiveSerializer<S, T> {
+    serializer: S,
+    positions: Vec<usize>,
+    _phantom: PhantomData<fn(&T)>,
+}
+
+impl<S: Serializer + ScratchSpace, T: Serialize<S>> MultiArchiveSerializer<S, T> {
+    /// Creates a new multi-archive serializer that writes to the given serializer.
+    #[inline]
+    pub fn new(serializer: S) -> Self {
+        Self {
+            serializer,
+            positions: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of values that have been serialized so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns whether no values have been serialized yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Serializes a value into the archive and returns its index.
+    #[inline]
+    pub fn serialize_value(&mut self, value: &T) -> Result<usize, S::Error> {
+        let pos = self.serializer.serialize_value(value)?;
+        self.positions.push(pos);
+        Ok(self.positions.len() - 1)
+    }
+
+    /// Writes the offset table and returns the underlying serializer.
+    #[inline]
+    pub fn finish(mut self) -> Result<S, S::Error>
+    where
+        Vec<usize>: Serialize<S>,
+    {
+        self.serializer.serialize_value(&self.positions)?;
+        Ok(self.serializer)
+    }
+}
+
+/// Errors that can occur while accessing a value in a [`MultiArchive`].
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum MultiArchiveError<E> {
+    /// The index was out of bounds.
+    IndexOutOfBounds {
+        /// The index of the value
+        index: usize,
+        /// The number of values in the archive
+        len: usize,
+    },
+    /// The value failed to validate.
+    CheckBytesError(E),
+}
+
+#[cfg(feature = "validation")]
+impl<E: fmt::Display> fmt::Display for MultiArchiveError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IndexOutOfBounds { index, len } => write!(
+                f,
+                "index out of bounds: index {} with {} values",
+                index, len
+            ),
+            Self::CheckBytesError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for MultiArchiveError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::IndexOutOfBounds { .. } => None,
+                Self::CheckBytesError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+/// A multi-archive of values of type `T`.
+///
+/// Creating a multi-archive validates its offset table, and each value is validated when it's
+/// accessed. See [`MultiArchiveSerializer`] for an example.
+#[cfg(feature = "validation")]
+pub struct MultiArchive<'a, T> {
+    bytes: &'a [u8],
+    positions: &'a ArchivedVec<Archived<usize>>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "validation")]
+impl<'a, T: Archive> MultiArchive<'a, T> {
+    /// Checks the offset table of the given multi-archive.
+    #[inline]
+    pub fn new(
+        bytes: &'a [u8],
+    ) -> Result<Self, CheckTypeError<ArchivedVec<Archived<usize>>, DefaultValidator<'a>>> {
+        Ok(Self {
+            bytes,
+            positions: check_archived_root::<Vec<usize>>(bytes)?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the number of values in the archive.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns whether the archive contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the position of the value at the given index within the archive.
+    #[inline]
+    pub fn pos(&self, index: usize) -> Option<usize> {
+        self.positions
+            .get(index)
+            .map(|pos| from_archived!(*pos) as usize)
+    }
+
+    /// Checks the value at the given index and returns a reference to it.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn get(
+        &self,
+        index: usize,
+    ) -> Result<&'a T::Archived, MultiArchiveError<CheckTypeError<T::Archived, DefaultValidator<'a>>>>
+    where
+        T::Archived: CheckBytes<DefaultValidator<'a>>,
+    {
+        let pos = self.pos(index).ok_or(MultiArchiveError::IndexOutOfBounds {
+            index,
+            len: self.len(),
+        })?;
+        check_archived_value::<T>(self.bytes, pos).map_err(MultiArchiveError::CheckBytesError)
+    }
+}
+
+/// Serializes many values into a single bundle that can be accessed by key.
+///
+/// All of the values are serialized with the same serializer, so sub-values behind the same shared
+/// pointer (like an `Rc` or `Arc`) are only stored once even if they're shared between multiple
+/// values. This can make a bundle much smaller than the sum of the archives of its values. Because
+/// shared pointers are tracked by address, the serializer borrows every value it serializes until
+/// the bundle is finished.
+///
+/// After all of the values have been serialized, [`finish`](BundleSerializer::finish) must be
+/// called to write the index.
+///
+/// # Example
+///
+/// ```
+/// use std::rc::Rc;
+/// use rkyv::{multi::BundleSerializer, ser::serializers::AllocSerializer, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[cfg_attr(feature = "validation", archive(check_bytes))]
+/// struct Record {
+///     schema: Rc<String>,
+///     id: u32,
+/// }
+///
+/// let schema = Rc::new("a schema that is shared between all of the values".to_string());
+/// let values = [
+///     Record { schema: schema.clone(), id: 1 },
+///     Record { schema: schema.clone(), id: 2 },
+///     Record { schema: schema.clone(), id: 3 },
+/// ];
+///
+/// let mut serializer = BundleSerializer::new(AllocSerializer::<256>::default());
+/// for (i, value) in values.iter().enumerate() {
+///     serializer.serialize_value(format!("value {}", i), value).unwrap();
+/// }
+/// let bytes = serializer.finish().unwrap().into_serializer().into_inner();
+///
+/// # #[cfg(feature = "validation")]
+/// # {
+/// use rkyv::multi::Bundle;
+///
+/// let bundle = Bundle::<String, Record>::new(&bytes).unwrap();
+/// assert_eq!(bundle.len(), 3);
+/// let first = bundle.get("value 0").unwrap();
+/// let second = bundle.get("value 1").unwrap();
+/// assert_eq!(first.id, 1);
+/// assert_eq!(second.id, 2);
+///
+/// // The schema is only stored once
+/// assert!(core::ptr::eq(&*first.schema, &*second.schema));
+/// # }
+/// ```
+///
+/// Values can't be dropped before the bundle is finished:
+///
+/// ```compile_fail
+/// use rkyv::{multi::BundleSerializer, ser::serializers::AllocSerializer};
+///
+/// let mut serializer = BundleSerializer::new(AllocSerializer::<256>::default());
+/// {
+///     let value = "a temporary value".to_string();
+///     serializer.serialize_value(0u32, &value).unwrap();
+/// }
+/// serializer.finish().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct BundleSerializer<'a, S, K, T> {
+    serializer: S,
+    index: BTreeMap<K, usize>,
+    _phantom: PhantomData<fn() -> &'a T>,
+}
+
+impl<'a, S, K, T> BundleSerializer<'a, S, K, T>
+where
+    S: Serializer + ScratchSpace + SharedSerializeRegistry,
+    K: Ord,
+    T: Serialize<S>,
+{
+    /// Creates a new bundle serializer that writes to the given serializer.
+    #[inline]
+    pub fn new(serializer: S) -> Self {
+        Self {
+            serializer,
+            index: BTreeMap::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of values that have been serialized so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns whether no values have been serialized yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Serializes a value into the bundle under the given key and returns its position.
+    ///
+    /// If the bundle already has a value with the same key, the key is updated to point to the new
+    /// value. The old value is still stored, but it can't be accessed.
+    #[inline]
+    pub fn serialize_value(&mut self, key: K, value: &'a T) -> Result<usize, S::Error> {
+        let pos = self.serializer.serialize_value(value)?;
+        self.index.insert(key, pos);
+        Ok(pos)
+    }
+
+    /// Writes the index and returns the underlying serializer.
+    #[inline]
+    pub fn finish(mut self) -> Result<S, S::Error>
+    where
+        BTreeMap<K, usize>: Serialize<S>,
+    {
+        self.serializer.serialize_value(&self.index)?;
+        Ok(self.serializer)
+    }
+}
+
+/// Errors that can occur while accessing a value in a [`Bundle`].
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum BundleError<E> {
+    /// The bundle doesn't have a value for the key.
+    KeyNotFound,
+    /// The value failed to validate.
+    CheckBytesError(E),
+}
+
+#[cfg(feature = "validation")]
+impl<E: fmt::Display> fmt::Display for BundleError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyNotFound => write!(f, "key not found in bundle"),
+            Self::CheckBytesError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for BundleError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::KeyNotFound => None,
+                Self::CheckBytesError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+/// A bundle of values of type `T` indexed by keys of type `K`.
+///
+/// Creating a bundle validates its index, and each value is validated when it's accessed. Values
+/// are validated against the whole bundle because their shared sub-values may be stored anywhere
+/// in it. See [`BundleSerializer`] for an example.
+#[cfg(feature = "validation")]
+pub struct Bundle<'a, K: Archive, T> {
+    bytes: &'a [u8],
+    index: &'a ArchivedBTreeMap<K::Archived, Archived<usize>>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "validation")]
+impl<'a, K: Archive + Ord, T: Archive> Bundle<'a, K, T>
+where
+    K::Archived: Ord,
+{
+    /// Checks the index of the given bundle.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn new(
+        bytes: &'a [u8],
+    ) -> Result<
+        Self,
+        CheckTypeError<ArchivedBTreeMap<K::Archived, Archived<usize>>, DefaultValidator<'a>>,
+    >
+    where
+        K::Archived: CheckBytes<DefaultValidator<'a>>,
+    {
+        Ok(Self {
+            bytes,
+            index: check_archived_root::<BTreeMap<K, usize>>(bytes)?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the number of values in the bundle.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns whether the bundle contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Returns an iterator over the keys of the bundle in sorted order.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &'a K::Archived> {
+        self.index.keys()
+    }
+
+    /// Returns the position of the value with the given key within the bundle.
+    #[inline]
+    pub fn pos<Q: Ord + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K::Archived: Borrow<Q>,
+    {
+        self.index.get(key).map(|pos| from_archived!(*pos) as usize)
+    }
+
+    /// Checks the value with the given key and returns a reference to it.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn get<Q: Ord + ?Sized>(
+        &self,
+        key: &Q,
+    ) -> Result<&'a T::Archived, BundleError<CheckTypeError<T::Archived, DefaultValidator<'a>>>>
+    where
+        K::Archived: Borrow<Q>,
+        T::Archived: CheckBytes<DefaultValidator<'a>>,
+    {
+        let pos = self.pos(key).ok_or(BundleError::KeyNotFound)?;
+        check_archived_value::<T>(self.bytes, pos).map_err(BundleError::CheckBytesError)
+    }
+}
@@ -3,3 +3,69 @@
 #[cfg(feature = "alloc")]
 pub mod option_box;
 pub mod option_nonzero;
+#[cfg(feature = "alloc")]
+pub mod option_rc;
+
+use crate::Archived;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8,
+};
+
+/// An archived type with an invalid bit pattern, called its niche.
+///
+/// Enums with `#[archive(niche)]` store their unit variant as the niche of a field in their other
+/// variant instead of storing a separate tag.
+///
+/// # Safety
+///
+/// - `resolve_niche` must write a bit pattern that isn't a valid value of the type.
+/// - `is_niche` must return `true` for the bit pattern written by `resolve_niche` and `false` for
+///   every valid value of the type.
+pub unsafe trait ArchivedNiche {
+    /// Writes the niche to the given output.
+    ///
+    /// # Safety
+    ///
+    /// `out` must be aligned and valid for writes of `size_of::<Self>()` bytes.
+    unsafe fn resolve_niche(out: *mut Self);
+
+    /// Returns whether the given value holds the niche.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be aligned and point to `size_of::<Self>()` initialized bytes. Those bytes may
+    /// not be a valid value of the type.
+    unsafe fn is_niche(value: *const Self) -> bool;
+}
+
+macro_rules! impl_archived_niche {
+    ($ar:ty, $repr:ty, $niche:expr) => {
+        // SAFETY: The niche is outside of the range of valid values for the type. It's the same
+        // regardless of endianness, so the archived value can be read as its native representation.
+        unsafe impl ArchivedNiche for $ar {
+            #[inline]
+            unsafe fn resolve_niche(out: *mut Self) {
+                out.cast::<$repr>().write_unaligned($niche);
+            }
+
+            #[inline]
+            unsafe fn is_niche(value: *const Self) -> bool {
+                value.cast::<$repr>().read_unaligned() == $niche
+            }
+        }
+    };
+}
+
+impl_archived_niche!(Archived<NonZeroI8>, i8, 0);
+impl_archived_niche!(Archived<NonZeroI16>, i16, 0);
+impl_archived_niche!(Archived<NonZeroI32>, i32, 0);
+impl_archived_niche!(Archived<NonZeroI64>, i64, 0);
+impl_archived_niche!(Archived<NonZeroI128>, i128, 0);
+impl_archived_niche!(Archived<NonZeroU8>, u8, 0);
+impl_archived_niche!(Archived<NonZeroU16>, u16, 0);
+impl_archived_niche!(Archived<NonZeroU32>, u32, 0);
+impl_archived_niche!(Archived<NonZeroU64>, u64, 0);
+impl_archived_niche!(Archived<NonZeroU128>, u128, 0);
+impl_archived_niche!(Archived<bool>, u8, 2);
+impl_archived_niche!(Archived<char>, u32, u32::MAX);
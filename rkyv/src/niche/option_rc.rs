@@ -0,0 +1,205 @@
+//! A niched archived `Option<Rc<T>>` and `Option<Arc<T>>` that uses less space.
+
+use crate::{
+    rc::{ArchivedRc, RcResolver},
+    ser::{Serializer, SharedSerializeRegistry},
+    ArchivePointee, ArchiveUnsized, MetadataResolver, SerializeUnsized,
+};
+use core::{
+    cmp::{self, Eq, Ord, PartialEq, PartialOrd},
+    fmt, hash,
+    hint::unreachable_unchecked,
+    ops::Deref,
+};
+
+/// A niched archived `Option<Rc<T>>` or `Option<Arc<T>>`.
+///
+/// It uses less space by storing the `None` variant as a null pointer. Shared values are always
+/// serialized before the pointers to them, so a shared pointer can never point to itself.
+#[repr(transparent)]
+pub struct ArchivedOptionRc<T: ArchivePointee + ?Sized, F> {
+    inner: ArchivedRc<T, F>,
+}
+
+impl<T: ArchivePointee + ?Sized, F> ArchivedOptionRc<T, F> {
+    /// Returns `true` if the option rc is a `None` value.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.as_ref().is_none()
+    }
+
+    /// Returns `true` if the option rc is a `Some` value.
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        self.as_ref().is_some()
+    }
+
+    /// Converts to an `Option<&ArchivedRc<T, F>>`.
+    #[inline]
+    pub fn as_ref(&self) -> Option<&ArchivedRc<T, F>> {
+        if self.inner.is_null() {
+            None
+        } else {
+            Some(&self.inner)
+        }
+    }
+
+    /// Returns an iterator over the possibly contained value.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, ArchivedRc<T, F>> {
+        Iter {
+            inner: self.as_ref(),
+        }
+    }
+
+    /// Converts from `&ArchivedOptionRc<T, F>` to `Option<&T>`.
+    #[inline]
+    pub fn as_deref(&self) -> Option<&T> {
+        self.as_ref().map(|x| (*x).deref())
+    }
+}
+
+impl<T: ArchivePointee + ?Sized, F> ArchivedOptionRc<T, F>
+where
+    T::ArchivedMetadata: Default,
+{
+    /// Resolves an `ArchivedOptionRc<T::Archived, F>` from an `Option<&T>`.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `field`
+    #[inline]
+    pub unsafe fn resolve_from_option<U: ArchiveUnsized<Archived = T> + ?Sized>(
+        field: Option<&U>,
+        pos: usize,
+        resolver: OptionRcResolver<MetadataResolver<U>>,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.inner);
+        if let Some(value) = field {
+            let resolver = if let OptionRcResolver::Some(rc_resolver) = resolver {
+                rc_resolver
+            } else {
+                unreachable_unchecked();
+            };
+
+            ArchivedRc::resolve_from_ref(value, pos + fp, resolver, fo)
+        } else {
+            ArchivedRc::emplace_null(pos + fp, fo);
+        }
+    }
+
+    /// Serializes an `ArchivedOptionRc<T::Archived, F>` from an `Option<&T>`.
+    ///
+    /// Shared values are only serialized once, the same as for `ArchivedRc`.
+    #[inline]
+    pub fn serialize_from_option<U, S>(
+        field: Option<&U>,
+        serializer: &mut S,
+    ) -> Result<OptionRcResolver<MetadataResolver<U>>, S::Error>
+    where
+        U: SerializeUnsized<S, Archived = T> + ?Sized,
+        S: Serializer + SharedSerializeRegistry + ?Sized,
+    {
+        if let Some(value) = field {
+            Ok(OptionRcResolver::Some(
+                ArchivedRc::<T, F>::serialize_from_ref(value, serializer)?,
+            ))
+        } else {
+            Ok(OptionRcResolver::None)
+        }
+    }
+}
+
+impl<T: ArchivePointee + fmt::Debug + ?Sized, F> fmt::Debug for ArchivedOptionRc<T, F> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_ref() {
+            Some(inner) => inner.fmt(f),
+            None => f.debug_tuple("None").finish(),
+        }
+    }
+}
+
+impl<T: ArchivePointee + Eq + ?Sized, F> Eq for ArchivedOptionRc<T, F> {}
+
+impl<T: ArchivePointee + hash::Hash + ?Sized, F> hash::Hash for ArchivedOptionRc<T, F> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state)
+    }
+}
+
+impl<T: ArchivePointee + Ord + ?Sized, F> Ord for ArchivedOptionRc<T, F> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_ref().cmp(&other.as_ref())
+    }
+}
+
+impl<T: ArchivePointee + PartialEq + ?Sized, F> PartialEq for ArchivedOptionRc<T, F> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref().eq(&other.as_ref())
+    }
+}
+
+impl<T: ArchivePointee + PartialOrd + ?Sized, F> PartialOrd for ArchivedOptionRc<T, F> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.as_ref().partial_cmp(&other.as_ref())
+    }
+}
+
+/// An iterator over a reference to the `Some` variant of an `ArchivedOptionRc`.
+///
+/// This iterator yields one value if the `ArchivedOptionRc` is a `Some`, otherwise none.
+///
+/// This `struct` is created by the [`ArchivedOptionRc::iter`] function.
+pub type Iter<'a, T> = crate::option::Iter<'a, T>;
+
+/// The resolver for [`ArchivedOptionRc`].
+pub enum OptionRcResolver<T> {
+    /// The `ArchivedOptionRc` was `None`
+    None,
+    /// The resolver for the `ArchivedRc`
+    Some(RcResolver<T>),
+}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::{
+        rc::validation::SharedPointerError,
+        validation::{ArchiveContext, LayoutRaw, SharedContext},
+        RelPtr,
+    };
+    use bytecheck::{CheckBytes, Error};
+    use ptr_meta::Pointee;
+
+    impl<T, F, C> CheckBytes<C> for ArchivedOptionRc<T, F>
+    where
+        T: ArchivePointee + CheckBytes<C> + LayoutRaw + Pointee + ?Sized + 'static,
+        C: ArchiveContext + SharedContext + ?Sized,
+        T::ArchivedMetadata: CheckBytes<C>,
+        C::Error: Error,
+        F: 'static,
+    {
+        type Error =
+            SharedPointerError<<T::ArchivedMetadata as CheckBytes<C>>::Error, T::Error, C::Error>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let rel_ptr = RelPtr::<T>::manual_check_bytes(value.cast(), context)
+                .map_err(SharedPointerError::PointerCheckBytesError)?;
+            // Null pointers are `None` and don't claim any shared memory
+            if !rel_ptr.is_null() {
+                ArchivedRc::<T, F>::check_bytes(value.cast(), context)?;
+            }
+            Ok(&*value)
+        }
+    }
+};
@@ -0,0 +1,90 @@
+//! Arithmetic helpers for archived integers.
+
+use crate::Archived;
+
+/// Arithmetic on archived integers that returns native integers.
+///
+/// Archived integers may be stored with a fixed endianness, so they can't always be used in
+/// arithmetic directly. These methods load the archived value and perform the operation on the
+/// native integer instead:
+///
+/// ```
+/// use rkyv::{num::ArchivedInteger, Archived};
+///
+/// fn next_id(id: &Archived<u32>) -> Option<u32> {
+///     id.checked_add(1)
+/// }
+/// ```
+///
+/// This is implemented for the archived versions of all fixed-width integer types. `usize` and
+/// `isize` are archived as fixed-width integers, so their archived versions use the methods of the
+/// fixed-width type that they're archived as.
+pub trait ArchivedInteger {
+    /// The native integer type that this archived integer stores.
+    type Native;
+
+    /// Returns the native value of the archived integer.
+    fn value(&self) -> Self::Native;
+
+    /// Checked integer addition. Returns `None` if overflow occurred.
+    fn checked_add(&self, rhs: Self::Native) -> Option<Self::Native>;
+
+    /// Checked integer subtraction. Returns `None` if overflow occurred.
+    fn checked_sub(&self, rhs: Self::Native) -> Option<Self::Native>;
+
+    /// Checked integer multiplication. Returns `None` if overflow occurred.
+    fn checked_mul(&self, rhs: Self::Native) -> Option<Self::Native>;
+
+    /// Wrapping (modular) addition.
+    fn wrapping_add(&self, rhs: Self::Native) -> Self::Native;
+
+    /// Wrapping (modular) subtraction.
+    fn wrapping_sub(&self, rhs: Self::Native) -> Self::Native;
+
+    /// Wrapping (modular) multiplication.
+    fn wrapping_mul(&self, rhs: Self::Native) -> Self::Native;
+
+    /// Saturating integer addition.
+    fn saturating_add(&self, rhs: Self::Native) -> Self::Native;
+
+    /// Saturating integer subtraction.
+    fn saturating_sub(&self, rhs: Self::Native) -> Self::Native;
+
+    /// Saturating integer multiplication.
+    fn saturating_mul(&self, rhs: Self::Native) -> Self::Native;
+}
+
+macro_rules! impl_archived_integer {
+    ($($native:ty),* $(,)?) => {
+        $(
+            impl ArchivedInteger for Archived<$native> {
+                type Native = $native;
+
+                #[inline]
+                fn value(&self) -> $native {
+                    from_archived!(*self)
+                }
+
+                impl_archived_integer! {
+                    @ops Option<$native>: checked_add, checked_sub, checked_mul
+                }
+                impl_archived_integer! {
+                    @ops $native: wrapping_add, wrapping_sub, wrapping_mul
+                }
+                impl_archived_integer! {
+                    @ops $native: saturating_add, saturating_sub, saturating_mul
+                }
+            }
+        )*
+    };
+    (@ops $ret:ty: $($op:ident),*) => {
+        $(
+            #[inline]
+            fn $op(&self, rhs: Self::Native) -> $ret {
+                self.value().$op(rhs)
+            }
+        )*
+    };
+}
+
+impl_archived_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
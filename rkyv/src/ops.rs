@@ -2,7 +2,7 @@
 
 use core::{
     cmp, fmt,
-    ops::{Bound, RangeBounds},
+    ops::{Bound, ControlFlow, RangeBounds},
 };
 
 /// An archived [`Range`](::core::ops::Range).
@@ -236,3 +236,75 @@ impl<T> RangeBounds<T> for ArchivedRangeToInclusive<T> {
         Bound::Included(&self.end)
     }
 }
+
+/// An archived [`Bound`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+pub enum ArchivedBound<T> {
+    /// An inclusive bound.
+    Included(T),
+    /// An exclusive bound.
+    Excluded(T),
+    /// An infinite endpoint. Indicates that there is no bound in this direction.
+    Unbounded,
+}
+
+impl<T> ArchivedBound<T> {
+    /// Converts from `&ArchivedBound<T>` to `Bound<&T>`.
+    ///
+    /// Pairs of converted bounds implement [`RangeBounds`], so they can be used to look up ranges
+    /// directly from archived bounds.
+    #[inline]
+    pub fn as_ref(&self) -> Bound<&T> {
+        match self {
+            ArchivedBound::Included(value) => Bound::Included(value),
+            ArchivedBound::Excluded(value) => Bound::Excluded(value),
+            ArchivedBound::Unbounded => Bound::Unbounded,
+        }
+    }
+
+    /// Converts from `&mut ArchivedBound<T>` to `Bound<&mut T>`.
+    #[inline]
+    pub fn as_mut(&mut self) -> Bound<&mut T> {
+        match self {
+            ArchivedBound::Included(value) => Bound::Included(value),
+            ArchivedBound::Excluded(value) => Bound::Excluded(value),
+            ArchivedBound::Unbounded => Bound::Unbounded,
+        }
+    }
+}
+
+/// An archived [`ControlFlow`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+pub enum ArchivedControlFlow<B, C> {
+    /// Move on to the next phase of the operation as normal.
+    Continue(C),
+    /// Exit the operation without running subsequent phases.
+    Break(B),
+}
+
+impl<B, C> ArchivedControlFlow<B, C> {
+    /// Returns `true` if this is a [`Break`](ArchivedControlFlow::Break) variant.
+    #[inline]
+    pub const fn is_break(&self) -> bool {
+        matches!(self, ArchivedControlFlow::Break(_))
+    }
+
+    /// Returns `true` if this is a [`Continue`](ArchivedControlFlow::Continue) variant.
+    #[inline]
+    pub const fn is_continue(&self) -> bool {
+        matches!(self, ArchivedControlFlow::Continue(_))
+    }
+
+    /// Converts from `&ArchivedControlFlow<B, C>` to `ControlFlow<&B, &C>`.
+    #[inline]
+    pub fn as_ref(&self) -> ControlFlow<&B, &C> {
+        match self {
+            ArchivedControlFlow::Continue(value) => ControlFlow::Continue(value),
+            ArchivedControlFlow::Break(value) => ControlFlow::Break(value),
+        }
+    }
+}
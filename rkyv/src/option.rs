@@ -50,6 +50,35 @@ impl<T> ArchivedOption<T> {
         }
     }
 
+    /// Zips the option with another option, returning references to both values if both are
+    /// `Some`.
+    ///
+    /// Like [`as_ref`](ArchivedOption::as_ref), this returns an `Option` of references to the
+    /// archived values.
+    #[inline]
+    pub fn zip<'a, U>(&'a self, other: &'a ArchivedOption<U>) -> Option<(&'a T, &'a U)> {
+        match (self, other) {
+            (ArchivedOption::Some(a), ArchivedOption::Some(b)) => Some((a, b)),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` if the option is `None`, otherwise calls `f` with a reference to the
+    /// contained value and returns a reference to the value of the archived option it returns.
+    ///
+    /// This is useful for reaching through nested archived options, for example an optional field
+    /// of an optional archived struct.
+    #[inline]
+    pub fn and_then<'a, U, F>(&'a self, f: F) -> Option<&'a U>
+    where
+        F: FnOnce(&'a T) -> &'a ArchivedOption<U>,
+    {
+        match self {
+            ArchivedOption::None => None,
+            ArchivedOption::Some(value) => f(value).as_ref(),
+        }
+    }
+
     /// Converts to an `Option<&mut T>`.
     #[inline]
     pub fn as_mut(&mut self) -> Option<&mut T> {
@@ -0,0 +1,228 @@
+//! Archived versions of `ordered_float` types.
+
+use crate::Archive;
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+use ordered_float::{NotNan, OrderedFloat};
+
+/// A floating-point type that can be wrapped in an `OrderedFloat` or `NotNan`.
+pub trait FloatPrimitive: Archive<Resolver = ()> + Copy {
+    /// Gets the value of an archived float.
+    fn from_archived_float(archived: &Self::Archived) -> Self;
+
+    /// Returns whether the float is NaN.
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_float_primitive {
+    ($ty:ty) => {
+        impl FloatPrimitive for $ty {
+            #[inline]
+            fn from_archived_float(archived: &Self::Archived) -> Self {
+                from_archived!(*archived)
+            }
+
+            #[inline]
+            fn is_nan(self) -> bool {
+                <$ty>::is_nan(self)
+            }
+        }
+    };
+}
+
+impl_float_primitive!(f32);
+impl_float_primitive!(f64);
+
+/// An archived [`OrderedFloat`].
+///
+/// This has the same layout as the archived float.
+#[repr(transparent)]
+pub struct ArchivedOrderedFloat<F: FloatPrimitive>(pub(crate) F::Archived);
+
+impl<F: FloatPrimitive> ArchivedOrderedFloat<F> {
+    /// Gets the value of the float.
+    #[inline]
+    pub fn get(&self) -> OrderedFloat<F> {
+        OrderedFloat(F::from_archived_float(&self.0))
+    }
+}
+
+/// An archived [`NotNan`].
+///
+/// This has the same layout as the archived float. When validated, NaN values are rejected.
+#[repr(transparent)]
+pub struct ArchivedNotNan<F: FloatPrimitive>(pub(crate) F::Archived);
+
+impl<F: FloatPrimitive> ArchivedNotNan<F> {
+    /// Gets the value of the float.
+    #[inline]
+    pub fn get(&self) -> NotNan<F> {
+        // Safety: archived `NotNan`s are only created from `NotNan`s and NaN is rejected during
+        // validation.
+        unsafe { NotNan::new_unchecked(F::from_archived_float(&self.0)) }
+    }
+}
+
+macro_rules! impl_archived_float {
+    ($archived:ident, $unarchived:ident) => {
+        impl<F: FloatPrimitive + fmt::Debug> fmt::Debug for $archived<F> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                F::from_archived_float(&self.0).fmt(f)
+            }
+        }
+
+        impl<F: FloatPrimitive + fmt::Display> fmt::Display for $archived<F> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&F::from_archived_float(&self.0), f)
+            }
+        }
+
+        impl<F: FloatPrimitive> PartialEq for $archived<F>
+        where
+            $unarchived<F>: PartialEq,
+        {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.get() == other.get()
+            }
+        }
+
+        impl<F: FloatPrimitive> Eq for $archived<F> where $unarchived<F>: Eq {}
+
+        impl<F: FloatPrimitive> PartialOrd for $archived<F>
+        where
+            $unarchived<F>: Ord,
+        {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<F: FloatPrimitive> Ord for $archived<F>
+        where
+            $unarchived<F>: Ord,
+        {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.get().cmp(&other.get())
+            }
+        }
+
+        // Hashes the same as the unarchived value so archived hash maps can be built from it
+        impl<F: FloatPrimitive> Hash for $archived<F>
+        where
+            $unarchived<F>: Hash,
+        {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.get().hash(state)
+            }
+        }
+
+        impl<F: FloatPrimitive> PartialEq<$unarchived<F>> for $archived<F>
+        where
+            $unarchived<F>: PartialEq,
+        {
+            #[inline]
+            fn eq(&self, other: &$unarchived<F>) -> bool {
+                self.get() == *other
+            }
+        }
+
+        impl<F: FloatPrimitive> PartialEq<$archived<F>> for $unarchived<F>
+        where
+            $unarchived<F>: PartialEq,
+        {
+            #[inline]
+            fn eq(&self, other: &$archived<F>) -> bool {
+                other.eq(self)
+            }
+        }
+    };
+}
+
+impl_archived_float!(ArchivedOrderedFloat, OrderedFloat);
+impl_archived_float!(ArchivedNotNan, NotNan);
+
+/// An error resulting from an invalid archived `NotNan`.
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum NotNanError<E> {
+    /// The archived float was invalid
+    FloatError(E),
+    /// The archived float was NaN
+    IsNan,
+}
+
+#[cfg(feature = "validation")]
+impl<E: fmt::Display> fmt::Display for NotNanError<E> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotNanError::FloatError(e) => write!(f, "float check error: {}", e),
+            NotNanError::IsNan => write!(f, "NotNan float was NaN"),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for NotNanError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                NotNanError::FloatError(e) => Some(e as &dyn Error),
+                NotNanError::IsNan => None,
+            }
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use bytecheck::CheckBytes;
+
+    impl<F: FloatPrimitive, C: ?Sized> CheckBytes<C> for ArchivedOrderedFloat<F>
+    where
+        F::Archived: CheckBytes<C>,
+    {
+        type Error = <F::Archived as CheckBytes<C>>::Error;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            F::Archived::check_bytes(value.cast(), context)?;
+            Ok(&*value)
+        }
+    }
+
+    impl<F: FloatPrimitive, C: ?Sized> CheckBytes<C> for ArchivedNotNan<F>
+    where
+        F::Archived: CheckBytes<C>,
+    {
+        type Error = NotNanError<<F::Archived as CheckBytes<C>>::Error>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let inner =
+                F::Archived::check_bytes(value.cast(), context).map_err(NotNanError::FloatError)?;
+            if F::from_archived_float(inner).is_nan() {
+                Err(NotNanError::IsNan)
+            } else {
+                Ok(&*value)
+            }
+        }
+    }
+};
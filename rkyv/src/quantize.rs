@@ -0,0 +1,323 @@
+//! Archived quantized floating-point vectors.
+//!
+//! See [`Quantize`](crate::with::Quantize) for more details.
+
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Archived,
+};
+use core::{fmt, iter::FusedIterator};
+
+/// An archived `Vec<f32>` whose values are quantized to `BITS`-bit integers.
+///
+/// This is the archived type of vecs serialized with [`Quantize`](crate::with::Quantize). The
+/// quantized values are packed tightly into bytes, so each value takes exactly `BITS` bits. Values
+/// are dequantized by linearly mapping them back onto the range between the minimum and maximum of
+/// the original values.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedQuantizedVec<const BITS: u8> {
+    min: Archived<f32>,
+    max: Archived<f32>,
+    len: Archived<usize>,
+    data: ArchivedVec<u8>,
+}
+
+impl<const BITS: u8> ArchivedQuantizedVec<BITS> {
+    const VALID_BITS: () = assert!(
+        BITS >= 1 && BITS <= 16,
+        "quantized values must have between 1 and 16 bits"
+    );
+
+    const MAX_QUANTIZED: u32 = (1 << BITS) - 1;
+
+    #[inline]
+    fn byte_len(len: usize) -> Option<usize> {
+        Some(len.checked_mul(BITS as usize)?.div_ceil(8))
+    }
+
+    /// Gets the number of values in the vec.
+    #[inline]
+    pub fn len(&self) -> usize {
+        from_archived!(self.len) as usize
+    }
+
+    /// Returns whether there are no values in the vec.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the smallest value that can be dequantized.
+    #[inline]
+    pub fn min(&self) -> f32 {
+        from_archived!(self.min)
+    }
+
+    /// Gets the largest value that can be dequantized.
+    #[inline]
+    pub fn max(&self) -> f32 {
+        from_archived!(self.max)
+    }
+
+    /// Gets the quantized value at the given index, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get_quantized(&self, index: usize) -> Option<u16> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let bytes = self.data.as_slice();
+        let bit = index * BITS as usize;
+        let mut word = 0u32;
+        for (i, byte) in bytes[bit / 8..].iter().take(3).enumerate() {
+            word |= (*byte as u32) << (8 * i);
+        }
+        Some(((word >> (bit % 8)) & Self::MAX_QUANTIZED) as u16)
+    }
+
+    /// Gets the approximate value at the given index, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get_dequantized(&self, index: usize) -> Option<f32> {
+        self.get_quantized(index).map(|q| self.dequantize(q))
+    }
+
+    #[inline]
+    fn dequantize(&self, quantized: u16) -> f32 {
+        let (min, max) = (self.min(), self.max());
+        min + (max - min) * (quantized as f32 / Self::MAX_QUANTIZED as f32)
+    }
+
+    /// Gets an iterator over the approximate values of the vec.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, BITS> {
+        Iter {
+            inner: self,
+            index: 0,
+        }
+    }
+
+    /// Resolves an archived quantized vec from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of values that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the values
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: QuantizedVecResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.min);
+        resolver.min.resolve(pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.max);
+        resolver.max.resolve(pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.len);
+        len.resolve(pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.data);
+        ArchivedVec::resolve_from_len(resolver.byte_len, pos + fp, resolver.data, fo);
+    }
+
+    /// Quantizes and serializes a slice of values.
+    ///
+    /// The finite values are mapped onto the range between the smallest and largest finite value.
+    /// Infinite values are clamped to that range, and NaNs are quantized to the smallest value.
+    pub fn serialize_from_slice<S>(
+        values: &[f32],
+        serializer: &mut S,
+    ) -> Result<QuantizedVecResolver, S::Error>
+    where
+        S: ScratchSpace + Serializer + ?Sized,
+    {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_BITS;
+
+        let (mut min, mut max) = (f32::INFINITY, f32::NEG_INFINITY);
+        for &value in values.iter().filter(|value| value.is_finite()) {
+            min = min.min(value);
+            max = max.max(value);
+        }
+        if min > max {
+            // There are no finite values
+            min = 0.0;
+            max = 0.0;
+        }
+
+        let byte_len = Self::byte_len(values.len()).unwrap();
+        let data = unsafe {
+            let mut bytes = crate::ScratchVec::new(serializer, byte_len)?;
+            bytes.set_len(byte_len);
+            bytes.as_mut_slice().fill(0);
+
+            for (index, &value) in values.iter().enumerate() {
+                let quantized = if max > min && !value.is_nan() {
+                    let scaled = (value.clamp(min, max) - min) / (max - min);
+                    // Rounds to the nearest integer, `f32::round` isn't available in `no_std`
+                    (scaled * Self::MAX_QUANTIZED as f32 + 0.5) as u32
+                } else {
+                    0
+                };
+
+                let bit = index * BITS as usize;
+                let word = quantized << (bit % 8);
+                for (i, byte) in bytes.as_mut_slice()[bit / 8..]
+                    .iter_mut()
+                    .take(3)
+                    .enumerate()
+                {
+                    *byte |= (word >> (8 * i)) as u8;
+                }
+            }
+
+            let data = ArchivedVec::<u8>::serialize_from_slice(bytes.as_slice(), serializer)?;
+            bytes.free(serializer)?;
+            data
+        };
+
+        Ok(QuantizedVecResolver {
+            min,
+            max,
+            byte_len,
+            data,
+        })
+    }
+}
+
+impl<const BITS: u8> fmt::Debug for ArchivedQuantizedVec<BITS> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// The resolver for an [`ArchivedQuantizedVec`].
+pub struct QuantizedVecResolver {
+    min: f32,
+    max: f32,
+    byte_len: usize,
+    data: VecResolver,
+}
+
+/// An iterator over the approximate values of an [`ArchivedQuantizedVec`].
+pub struct Iter<'a, const BITS: u8> {
+    inner: &'a ArchivedQuantizedVec<BITS>,
+    index: usize,
+}
+
+impl<const BITS: u8> Iterator for Iter<'_, BITS> {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.inner.get_dequantized(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.inner.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const BITS: u8> ExactSizeIterator for Iter<'_, BITS> {}
+impl<const BITS: u8> FusedIterator for Iter<'_, BITS> {}
+
+/// Errors that can occur while checking an archived quantized vec.
+#[derive(Debug)]
+pub enum QuantizedVecError<E> {
+    /// An error occurred while checking the quantized data
+    CheckDataError(E),
+    /// The minimum or maximum value was not finite, or the minimum was greater than the maximum
+    InvalidRange {
+        /// The minimum value
+        min: f32,
+        /// The maximum value
+        max: f32,
+    },
+    /// The quantized data didn't have the right length for the number of values
+    LengthMismatch {
+        /// The number of values
+        len: usize,
+        /// The number of bytes of quantized data
+        byte_len: usize,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for QuantizedVecError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuantizedVecError::CheckDataError(e) => write!(f, "data check error: {}", e),
+            QuantizedVecError::InvalidRange { min, max } => {
+                write!(f, "invalid quantization range: min {}, max {}", min, max)
+            }
+            QuantizedVecError::LengthMismatch { len, byte_len } => write!(
+                f,
+                "{} bytes of quantized data do not match the length {}",
+                byte_len, len
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for QuantizedVecError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                QuantizedVecError::CheckDataError(e) => Some(e as &dyn Error),
+                QuantizedVecError::InvalidRange { .. }
+                | QuantizedVecError::LengthMismatch { .. } => None,
+            }
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::validation::ArchiveContext;
+    use bytecheck::{CheckBytes, Error};
+    use core::ptr;
+
+    impl<const BITS: u8, C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedQuantizedVec<BITS>
+    where
+        C::Error: Error,
+    {
+        type Error = QuantizedVecError<<ArchivedVec<u8> as CheckBytes<C>>::Error>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            #[allow(clippy::let_unit_value)]
+            let () = Self::VALID_BITS;
+
+            let data = ArchivedVec::<u8>::check_bytes(ptr::addr_of!((*value).data), context)
+                .map_err(QuantizedVecError::CheckDataError)?;
+
+            // Floats and integers are valid for any bit pattern
+            let value = &*value;
+            let (min, max) = (value.min(), value.max());
+            if !min.is_finite() || !max.is_finite() || min > max {
+                return Err(QuantizedVecError::InvalidRange { min, max });
+            }
+
+            let len = value.len();
+            if Self::byte_len(len) != Some(data.len()) {
+                return Err(QuantizedVecError::LengthMismatch {
+                    len,
+                    byte_len: data.len(),
+                });
+            }
+
+            Ok(value)
+        }
+    }
+};
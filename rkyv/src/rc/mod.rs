@@ -9,11 +9,127 @@ use crate::{
 };
 use core::{borrow::Borrow, cmp, fmt, hash, marker::PhantomData, ops::Deref, pin::Pin, ptr};
 
+/// A shared pointer that is archived as an [`ArchivedRc`].
+///
+/// Implementing this trait for a reference-counted pointer type provides the logic to archive it
+/// with the same layout and shared memory deduplication as `Rc` and `Arc`. `Archive` and
+/// `Serialize` can then be implemented by forwarding to
+/// [`resolve_shared`](ArchiveSharedPointer::resolve_shared) and
+/// [`serialize_shared`](ArchiveSharedPointer::serialize_shared). Validation is the same as for
+/// any other `ArchivedRc`, and deserialization can be provided by implementing
+/// [`DeserializeSharedPointer`](crate::de::DeserializeSharedPointer).
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{
+///     rc::{ArchiveSharedPointer, ArchivedSharedPointer, RcResolver},
+///     ser::{Serializer, SharedSerializeRegistry},
+///     Archive,
+///     ArchiveUnsized,
+///     MetadataResolver,
+///     Serialize,
+///     SerializeUnsized,
+/// };
+/// use std::rc::Rc;
+///
+/// pub struct MyRc<T: ?Sized>(Rc<T>);
+///
+/// pub struct MyRcFlavor;
+///
+/// impl<T: ArchiveUnsized + ?Sized> ArchiveSharedPointer for MyRc<T> {
+///     type Target = T;
+///     type Flavor = MyRcFlavor;
+///
+///     fn as_target(&self) -> &T {
+///         &self.0
+///     }
+/// }
+///
+/// impl<T: ArchiveUnsized + ?Sized> Archive for MyRc<T> {
+///     type Archived = ArchivedSharedPointer<Self>;
+///     type Resolver = RcResolver<MetadataResolver<T>>;
+///
+///     unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+///         self.resolve_shared(pos, resolver, out);
+///     }
+/// }
+///
+/// impl<T, S> Serialize<S> for MyRc<T>
+/// where
+///     T: SerializeUnsized<S> + ?Sized,
+///     S: Serializer + SharedSerializeRegistry + ?Sized,
+/// {
+///     fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+///         self.serialize_shared(serializer)
+///     }
+/// }
+///
+/// let shared = Rc::new(42);
+/// let value = (MyRc(shared.clone()), MyRc(shared));
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<(MyRc<i32>, MyRc<i32>)>(&bytes) };
+/// assert_eq!(*archived.0, 42);
+/// assert!(core::ptr::eq(archived.0.get(), archived.1.get()));
+/// ```
+pub trait ArchiveSharedPointer {
+    /// The type of the shared value.
+    type Target: ArchiveUnsized + ?Sized;
+
+    /// The flavor of the archived shared pointer.
+    ///
+    /// Shared pointers with different flavors may not point to the same memory during validation.
+    type Flavor;
+
+    /// Returns a reference to the shared value.
+    fn as_target(&self) -> &Self::Target;
+
+    /// Resolves an archived shared pointer from this shared pointer.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing this shared pointer
+    #[inline]
+    unsafe fn resolve_shared(
+        &self,
+        pos: usize,
+        resolver: RcResolver<MetadataResolver<Self::Target>>,
+        out: *mut ArchivedSharedPointer<Self>,
+    ) {
+        ArchivedRc::resolve_from_ref(self.as_target(), pos, resolver, out);
+    }
+
+    /// Serializes the shared value, or reuses the existing serialized value if it has already been
+    /// serialized.
+    #[inline]
+    fn serialize_shared<S>(
+        &self,
+        serializer: &mut S,
+    ) -> Result<RcResolver<MetadataResolver<Self::Target>>, S::Error>
+    where
+        Self::Target: SerializeUnsized<S>,
+        S: Serializer + SharedSerializeRegistry + ?Sized,
+    {
+        ArchivedSharedPointer::<Self>::serialize_from_ref(self.as_target(), serializer)
+    }
+}
+
+/// Alias for the archived version of some [`ArchiveSharedPointer`] type.
+pub type ArchivedSharedPointer<P> = ArchivedRc<
+    <<P as ArchiveSharedPointer>::Target as ArchiveUnsized>::Archived,
+    <P as ArchiveSharedPointer>::Flavor,
+>;
+
 /// An archived `Rc`.
 ///
 /// This is a thin wrapper around a [`RelPtr`] to the archived type paired with a "flavor" type.
 /// Because there may be many varieties of shared pointers and they may not be used together, the
 /// flavor helps check that memory is not being shared incorrectly during validation.
+///
+/// The pointee may be unsized, so shared pointers like `Rc<str>` and `Arc<[T]>` are archived with
+/// their metadata stored alongside the relative pointer. Each distinct allocation is only
+/// serialized once.
 #[repr(transparent)]
 pub struct ArchivedRc<T: ArchivePointee + ?Sized, F>(RelPtr<T>, PhantomData<F>);
 
@@ -52,6 +168,12 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
         value.resolve_unsized(pos + fp, resolver.pos, resolver.metadata_resolver, fo);
     }
 
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.0.is_null()
+    }
+
     /// Serializes an archived `Rc` from a given reference.
     #[inline]
     pub fn serialize_from_ref<
@@ -77,6 +199,18 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
     }
 }
 
+impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F>
+where
+    T::ArchivedMetadata: Default,
+{
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn emplace_null(pos: usize, out: *mut Self) {
+        let (fp, fo) = out_field!(out.0);
+        RelPtr::emplace_null(pos + fp, fo);
+    }
+}
+
 impl<T: ArchivePointee + ?Sized, F> AsRef<T> for ArchivedRc<T, F> {
     #[inline]
     fn as_ref(&self) -> &T {
@@ -162,10 +296,12 @@ pub struct RcResolver<T> {
 
 /// An archived `rc::Weak`.
 ///
-/// This is essentially just an optional [`ArchivedRc`].
+/// This is essentially just an optional [`ArchivedRc`]. Weak pointers that can't be upgraded when
+/// they're serialized (for example because all of their strong pointers were already dropped)
+/// archive as `None`, and deserialize to an empty weak pointer like `Weak::new()`.
 #[repr(u8)]
 pub enum ArchivedRcWeak<T: ArchivePointee + ?Sized, F> {
-    /// A null weak pointer
+    /// A null or expired weak pointer
     None,
     /// A weak pointer to some shared pointer
     Some(ArchivedRc<T, F>),
@@ -174,7 +310,7 @@ pub enum ArchivedRcWeak<T: ArchivePointee + ?Sized, F> {
 impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
     /// Attempts to upgrade the weak pointer to an `ArchivedArc`.
     ///
-    /// Returns `None` if a null weak pointer was serialized.
+    /// Returns `None` if a null or expired weak pointer was serialized.
     #[inline]
     pub fn upgrade(&self) -> Option<&ArchivedRc<T, F>> {
         match self {
@@ -207,17 +343,19 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
         resolver: RcWeakResolver<MetadataResolver<U>>,
         out: *mut Self,
     ) {
-        match resolver {
-            RcWeakResolver::None => {
-                let out = out.cast::<ArchivedRcWeakVariantNone>();
-                ptr::addr_of_mut!((*out).0).write(ArchivedRcWeakTag::None);
-            }
-            RcWeakResolver::Some(resolver) => {
+        match (resolver, value) {
+            (RcWeakResolver::Some(resolver), Some(value)) => {
                 let out = out.cast::<ArchivedRcWeakVariantSome<T, F>>();
                 ptr::addr_of_mut!((*out).0).write(ArchivedRcWeakTag::Some);
 
                 let (fp, fo) = out_field!(out.1);
-                ArchivedRc::resolve_from_ref(value.unwrap(), pos + fp, resolver, fo);
+                ArchivedRc::resolve_from_ref(value, pos + fp, resolver, fo);
+            }
+            // The weak pointer may have expired after it was serialized, in which case its target
+            // is left unreferenced
+            _ => {
+                let out = out.cast::<ArchivedRcWeakVariantNone>();
+                ptr::addr_of_mut!((*out).0).write(ArchivedRcWeakTag::None);
             }
         }
     }
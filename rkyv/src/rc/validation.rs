@@ -118,6 +118,10 @@ where
     ) -> Result<&'a Self, Self::Error> {
         let rel_ptr = RelPtr::<T>::manual_check_bytes(value.cast(), context)
             .map_err(SharedPointerError::PointerCheckBytesError)?;
+        if !context.follows_pointers() {
+            return Ok(&*value);
+        }
+
         let ptr = context
             .check_rel_ptr(rel_ptr)
             .map_err(SharedPointerError::ContextError)?;
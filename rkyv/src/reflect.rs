@@ -0,0 +1,279 @@
+//! Generic reflection over the fields of archived values.
+//!
+//! Reflection lets generic tooling like format exporters and debug dumps walk any archived value
+//! and read its leaf values by name, without any code that's specific to the archived type.
+//!
+//! Archived values implement [`ReflectValue`] to describe themselves as a [`Value`]. Archived
+//! structs and enums also implement [`ReflectArchived`], which visits each of their fields by name.
+//! `ReflectValue` is implemented for archived primitives, strings, vecs, arrays, boxes, and
+//! options, and both traits can be derived for archived types with `#[archive(reflect)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use core::fmt::Write;
+//! use rkyv::{
+//!     archived_root,
+//!     reflect::{ReflectValue, Value},
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(reflect)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(reflect)]
+//! struct Path {
+//!     name: String,
+//!     points: Vec<Point>,
+//!     closed: bool,
+//!     width: Option<f32>,
+//! }
+//!
+//! fn to_json(value: &dyn ReflectValue, out: &mut String) {
+//!     match value.reflect() {
+//!         Value::Unit | Value::Option(None) => out.push_str("null"),
+//!         Value::Bool(b) => write!(out, "{}", b).unwrap(),
+//!         Value::Int(i) => write!(out, "{}", i).unwrap(),
+//!         Value::Uint(u) => write!(out, "{}", u).unwrap(),
+//!         Value::Float(f) if f.is_finite() => write!(out, "{}", f).unwrap(),
+//!         Value::Float(_) => out.push_str("null"),
+//!         Value::Char(c) => write!(out, "{:?}", c.to_string()).unwrap(),
+//!         Value::Str(s) => write!(out, "{:?}", s).unwrap(),
+//!         Value::Option(Some(value)) => to_json(value, out),
+//!         Value::Seq(seq) => {
+//!             out.push('[');
+//!             for i in 0..seq.len() {
+//!                 if i != 0 {
+//!                     out.push(',');
+//!                 }
+//!                 to_json(seq.get(i).unwrap(), out);
+//!             }
+//!             out.push(']');
+//!         }
+//!         Value::Struct(value) => {
+//!             out.push('{');
+//!             let mut first = true;
+//!             value.for_each_field(&mut |name, field| {
+//!                 if !first {
+//!                     out.push(',');
+//!                 }
+//!                 first = false;
+//!                 write!(out, "{:?}:", name).unwrap();
+//!                 to_json(field, out);
+//!             });
+//!             out.push('}');
+//!         }
+//!     }
+//! }
+//!
+//! let value = Path {
+//!     name: "triangle".to_string(),
+//!     points: vec![Point { x: 0, y: 0 }, Point { x: 1, y: 2 }],
+//!     closed: true,
+//!     width: None,
+//! };
+//! let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+//! let archived = unsafe { archived_root::<Path>(&bytes) };
+//!
+//! let mut json = String::new();
+//! to_json(archived, &mut json);
+//! assert_eq!(
+//!     json,
+//!     r#"{"name":"triangle","points":[{"x":0,"y":0},{"x":1,"y":2}],"closed":true,"width":null}"#,
+//! );
+//! ```
+
+use crate::{
+    boxed::ArchivedBox, option::ArchivedOption, string::ArchivedString, vec::ArchivedVec,
+    ArchivePointee,
+};
+
+/// A reflected archived value.
+#[derive(Clone, Copy)]
+pub enum Value<'a> {
+    /// The unit value `()`
+    Unit,
+    /// A boolean
+    Bool(bool),
+    /// A signed integer
+    Int(i128),
+    /// An unsigned integer
+    Uint(u128),
+    /// A floating-point number
+    Float(f64),
+    /// A character
+    Char(char),
+    /// A string
+    Str(&'a str),
+    /// A sequence of values, like an archived vec or an array
+    Seq(&'a dyn ReflectSeq),
+    /// An optional value
+    Option(Option<&'a dyn ReflectValue>),
+    /// An archived struct or enum with named or indexed fields
+    Struct(&'a dyn ReflectArchived),
+}
+
+/// An archived value that can be reflected.
+///
+/// This can be derived for archived types with `#[archive(reflect)]`.
+pub trait ReflectValue {
+    /// Returns the reflected value.
+    fn reflect(&self) -> Value<'_>;
+}
+
+/// An archived struct or enum whose fields can be visited by name.
+///
+/// This can be derived for archived types with `#[archive(reflect)]`.
+pub trait ReflectArchived {
+    /// Returns the name of the archived type, without its generic parameters.
+    fn type_name(&self) -> &'static str;
+
+    /// Returns the name of the active variant if this is an archived enum.
+    fn variant_name(&self) -> Option<&'static str>;
+
+    /// Calls `f` with the name and value of each field in declaration order.
+    ///
+    /// Tuple struct and tuple variant fields are named by their index. For archived enums, only the
+    /// fields of the active variant are visited.
+    fn for_each_field(&self, f: &mut dyn FnMut(&str, &dyn ReflectValue));
+}
+
+/// A sequence of reflected values.
+pub trait ReflectSeq {
+    /// Returns the number of values in the sequence.
+    fn len(&self) -> usize;
+
+    /// Returns whether the sequence is empty.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value at the given index, or `None` if it's out of bounds.
+    fn get(&self, index: usize) -> Option<&dyn ReflectValue>;
+}
+
+macro_rules! impl_primitive {
+    ($type:ty, $variant:ident) => {
+        impl ReflectValue for $type {
+            #[inline]
+            fn reflect(&self) -> Value<'_> {
+                Value::$variant((*self).into())
+            }
+        }
+    };
+    (@multibyte $type:ty, $variant:ident) => {
+        const _: () = {
+            #[cfg(not(any(feature = "archive_le", feature = "archive_be")))]
+            type Archived = $type;
+            #[cfg(feature = "archive_le")]
+            type Archived = crate::rend::LittleEndian<$type>;
+            #[cfg(feature = "archive_be")]
+            type Archived = crate::rend::BigEndian<$type>;
+
+            impl ReflectValue for Archived {
+                #[inline]
+                fn reflect(&self) -> Value<'_> {
+                    Value::$variant(from_archived!(*self).into())
+                }
+            }
+        };
+    };
+}
+
+impl_primitive!(bool, Bool);
+impl_primitive!(i8, Int);
+impl_primitive!(u8, Uint);
+impl_primitive!(@multibyte i16, Int);
+impl_primitive!(@multibyte i32, Int);
+impl_primitive!(@multibyte i64, Int);
+impl_primitive!(@multibyte i128, Int);
+impl_primitive!(@multibyte u16, Uint);
+impl_primitive!(@multibyte u32, Uint);
+impl_primitive!(@multibyte u64, Uint);
+impl_primitive!(@multibyte u128, Uint);
+impl_primitive!(@multibyte f32, Float);
+impl_primitive!(@multibyte f64, Float);
+impl_primitive!(@multibyte char, Char);
+
+impl ReflectValue for () {
+    #[inline]
+    fn reflect(&self) -> Value<'_> {
+        Value::Unit
+    }
+}
+
+impl ReflectValue for str {
+    #[inline]
+    fn reflect(&self) -> Value<'_> {
+        Value::Str(self)
+    }
+}
+
+impl ReflectValue for ArchivedString {
+    #[inline]
+    fn reflect(&self) -> Value<'_> {
+        Value::Str(self.as_str())
+    }
+}
+
+impl<T: ReflectValue, const N: usize> ReflectSeq for [T; N] {
+    #[inline]
+    fn len(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn ReflectValue> {
+        self.as_slice()
+            .get(index)
+            .map(|value| value as &dyn ReflectValue)
+    }
+}
+
+impl<T: ReflectValue, const N: usize> ReflectValue for [T; N] {
+    #[inline]
+    fn reflect(&self) -> Value<'_> {
+        Value::Seq(self)
+    }
+}
+
+impl<T: ReflectValue> ReflectSeq for ArchivedVec<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        ArchivedVec::len(self)
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn ReflectValue> {
+        self.as_slice()
+            .get(index)
+            .map(|value| value as &dyn ReflectValue)
+    }
+}
+
+impl<T: ReflectValue> ReflectValue for ArchivedVec<T> {
+    #[inline]
+    fn reflect(&self) -> Value<'_> {
+        Value::Seq(self)
+    }
+}
+
+impl<T: ArchivePointee + ReflectValue + ?Sized> ReflectValue for ArchivedBox<T> {
+    #[inline]
+    fn reflect(&self) -> Value<'_> {
+        self.get().reflect()
+    }
+}
+
+impl<T: ReflectValue> ReflectValue for ArchivedOption<T> {
+    #[inline]
+    fn reflect(&self) -> Value<'_> {
+        Value::Option(self.as_ref().map(|value| value as &dyn ReflectValue))
+    }
+}
@@ -0,0 +1,468 @@
+//! Building archives directly, without a value to serialize.
+
+use crate::{
+    ser::Serializer,
+    string::{ArchivedString, StringResolver},
+    vec::{ArchivedVec, VecResolver},
+    Archive, RawRelPtr,
+};
+use core::{fmt, marker::PhantomData, mem, slice};
+
+/// An error that can occur while building an archive.
+#[derive(Debug)]
+pub enum BuildError<E> {
+    /// A field was started before the end of the data already written to its struct
+    FieldOutOfOrder {
+        /// The offset of the field within the struct
+        offset: usize,
+        /// The number of bytes already written to the struct
+        written: usize,
+    },
+    /// More bytes were written to a struct than fit in it
+    StructOverrun {
+        /// The size of the struct
+        size: usize,
+        /// The number of bytes written to the struct
+        written: usize,
+    },
+    /// An element of a slice wasn't the size of the element type
+    ElementSizeMismatch {
+        /// The index of the element
+        index: usize,
+        /// The size of the element type
+        expected: usize,
+        /// The number of bytes written for the element
+        written: usize,
+    },
+    /// A struct started past the position of the builder, so it was started by a different builder
+    StructNotStarted {
+        /// The position of the struct
+        start: usize,
+        /// The position of the builder
+        pos: usize,
+    },
+    /// The builder was padded to a position that it had already passed
+    PaddedBackwards {
+        /// The position to pad to
+        target: usize,
+        /// The position of the builder
+        pos: usize,
+    },
+    /// The serializer encountered an error
+    SerializerError(E),
+}
+
+impl<E: fmt::Display> fmt::Display for BuildError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::FieldOutOfOrder { offset, written } => write!(
+                f,
+                "field at offset {} started after {} bytes were already written to its struct",
+                offset, written
+            ),
+            BuildError::StructOverrun { size, written } => {
+                write!(f, "wrote {} bytes to a struct of size {}", written, size)
+            }
+            BuildError::ElementSizeMismatch {
+                index,
+                expected,
+                written,
+            } => write!(
+                f,
+                "element {} was {} bytes but the element type is {} bytes",
+                index, written, expected
+            ),
+            BuildError::StructNotStarted { start, pos } => write!(
+                f,
+                "struct starts at position {} but the builder is only at position {}",
+                start, pos
+            ),
+            BuildError::PaddedBackwards { target, pos } => write!(
+                f,
+                "tried to pad to position {} but the builder was already at position {}",
+                target, pos
+            ),
+            BuildError::SerializerError(e) => write!(f, "serializer error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for BuildError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                BuildError::FieldOutOfOrder { .. }
+                | BuildError::StructOverrun { .. }
+                | BuildError::ElementSizeMismatch { .. }
+                | BuildError::StructNotStarted { .. }
+                | BuildError::PaddedBackwards { .. } => None,
+                BuildError::SerializerError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+/// A struct that is being built by an [`ArchiveBuilder`].
+///
+/// This is returned by [`start_struct`](ArchiveBuilder::start_struct) and must be passed to
+/// [`finish_struct`](ArchiveBuilder::finish_struct) once all of its fields have been written.
+#[derive(Debug)]
+pub struct StructStart<T> {
+    pos: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> StructStart<T> {
+    /// Returns the position of the struct.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+/// A slice of `T` written by an [`ArchiveBuilder`].
+///
+/// This can be pointed to with [`write_vec`](ArchiveBuilder::write_vec).
+#[derive(Debug)]
+pub struct SliceRef<T> {
+    pos: usize,
+    len: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> SliceRef<T> {
+    /// Returns the position of the first element of the slice.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of elements in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the slice is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// A string written by an [`ArchiveBuilder`].
+///
+/// This can be pointed to with [`write_string`](ArchiveBuilder::write_string).
+pub struct StrRef<'a> {
+    value: &'a str,
+    resolver: StringResolver,
+}
+
+/// Builds an archive piece by piece, without a value to serialize.
+///
+/// This exposes the primitives that `Serialize` implementations use, so that tools can write
+/// archives from data that doesn't exist as Rust values. The archived types still have to exist
+/// so their layouts are known, and the builder is responsible for writing bytes that are valid for
+/// them.
+///
+/// Just like when serializing, the data that an object points to must be written before the object
+/// itself. Strings and slices are written first with [`write_str`](ArchiveBuilder::write_str) and
+/// [`write_slice`](ArchiveBuilder::write_slice), then the structs that contain them are written
+/// with [`start_struct`](ArchiveBuilder::start_struct), [`field`](ArchiveBuilder::field), and
+/// [`finish_struct`](ArchiveBuilder::finish_struct). Fields must be written in increasing order of
+/// their offsets, and the builder fills any padding between them with zeroes. Structs can be nested
+/// by starting a struct at the offset of a field.
+///
+/// The last object written is the root of the archive, and can be accessed with
+/// [`archived_root`](crate::archived_root) or checked with `check_archived_root`.
+///
+/// # Examples
+/// ```
+/// use core::mem::offset_of;
+/// use rkyv::{archived_root, ser::{builder::ArchiveBuilder, serializers::AlignedSerializer}};
+/// use rkyv::{AlignedVec, Archive};
+///
+/// #[derive(Archive)]
+/// #[archive_attr(repr(C))]
+/// struct Record {
+///     id: u32,
+///     name: String,
+///     scores: Vec<f32>,
+/// }
+///
+/// let mut builder = ArchiveBuilder::new(AlignedSerializer::new(AlignedVec::new()));
+///
+/// // Write the out-of-line data first
+/// let name = builder.write_str("a name long enough to be stored out of line").unwrap();
+/// let scores = builder.write_slice([1.5f32, 2.5, 4.0]).unwrap();
+///
+/// // Then write the root
+/// let record = builder.start_struct::<ArchivedRecord>().unwrap();
+/// builder.field(&record, offset_of!(ArchivedRecord, id)).unwrap();
+/// builder.write(&42u32).unwrap();
+/// builder.field(&record, offset_of!(ArchivedRecord, name)).unwrap();
+/// builder.write_string(name).unwrap();
+/// builder.field(&record, offset_of!(ArchivedRecord, scores)).unwrap();
+/// builder.write_vec(&scores).unwrap();
+/// builder.finish_struct(record).unwrap();
+///
+/// let bytes = builder.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Record>(&bytes) };
+/// assert_eq!(archived.id, 42);
+/// assert_eq!(archived.name, "a name long enough to be stored out of line");
+/// assert_eq!(archived.scores.as_slice(), &[1.5, 2.5, 4.0]);
+/// ```
+#[derive(Debug)]
+pub struct ArchiveBuilder<S> {
+    serializer: S,
+}
+
+impl<S> ArchiveBuilder<S> {
+    /// Creates a new archive builder that writes to the given serializer.
+    #[inline]
+    pub fn new(serializer: S) -> Self {
+        Self { serializer }
+    }
+
+    /// Consumes the archive builder and returns the serializer it was writing to.
+    #[inline]
+    pub fn into_serializer(self) -> S {
+        self.serializer
+    }
+}
+
+impl<S: Serializer> ArchiveBuilder<S> {
+    /// Returns the current position of the builder.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.serializer.pos()
+    }
+
+    /// Writes zeroes until the builder reaches the given position.
+    fn pad_to(&mut self, pos: usize) -> Result<(), BuildError<S::Error>> {
+        const ZEROES: [u8; 32] = [0; 32];
+
+        let mut padding = pos
+            .checked_sub(self.pos())
+            .ok_or(BuildError::PaddedBackwards {
+                target: pos,
+                pos: self.pos(),
+            })?;
+        while padding > 0 {
+            let len = padding.min(ZEROES.len());
+            self.serializer
+                .write(&ZEROES[..len])
+                .map_err(BuildError::SerializerError)?;
+            padding -= len;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of bytes written since the given struct was started.
+    fn written_since<T>(&self, start: &StructStart<T>) -> Result<usize, BuildError<S::Error>> {
+        self.pos()
+            .checked_sub(start.pos)
+            .ok_or(BuildError::StructNotStarted {
+                start: start.pos,
+                pos: self.pos(),
+            })
+    }
+
+    /// Aligns the builder for a `T`, then writes a `T` initialized by `f`.
+    ///
+    /// `f` is called with the position of the `T` and a pointer to zeroed memory for it.
+    ///
+    /// # Safety
+    ///
+    /// `f` must initialize the `T` as if it were located at the given position.
+    unsafe fn emplace<T>(
+        &mut self,
+        f: impl FnOnce(usize, *mut T),
+    ) -> Result<usize, BuildError<S::Error>> {
+        let pos = self
+            .serializer
+            .align_for::<T>()
+            .map_err(BuildError::SerializerError)?;
+
+        let mut resolved = mem::MaybeUninit::<T>::uninit();
+        resolved.as_mut_ptr().write_bytes(0, 1);
+        f(pos, resolved.as_mut_ptr());
+
+        let data = resolved.as_ptr().cast::<u8>();
+        self.serializer
+            .write(slice::from_raw_parts(data, mem::size_of::<T>()))
+            .map_err(BuildError::SerializerError)?;
+        Ok(pos)
+    }
+
+    /// Writes a primitive value like an integer, float, `bool`, or `char`, and returns its
+    /// position.
+    ///
+    /// Any type that doesn't need to serialize any dependencies can be written this way.
+    #[inline]
+    pub fn write<T: Archive<Resolver = ()>>(
+        &mut self,
+        value: &T,
+    ) -> Result<usize, BuildError<S::Error>> {
+        // SAFETY: Types with a resolver of `()` don't have any dependencies to serialize
+        unsafe { self.emplace(|pos, out| value.resolve(pos, (), out)) }
+    }
+
+    /// Writes a slice of primitive values from an iterator.
+    #[inline]
+    pub fn write_slice<T, I>(
+        &mut self,
+        iter: I,
+    ) -> Result<SliceRef<T::Archived>, BuildError<S::Error>>
+    where
+        T: Archive<Resolver = ()>,
+        I: IntoIterator<Item = T>,
+    {
+        self.write_slice_with(iter, |builder, value| builder.write(&value).map(|_| ()))
+    }
+
+    /// Writes a slice of `T` from an iterator, calling `write_element` to write each element.
+    ///
+    /// `write_element` must write exactly one `T`, usually by starting and finishing a struct. The
+    /// dependencies of the elements must be written before the slice.
+    pub fn write_slice_with<T, I, F>(
+        &mut self,
+        iter: I,
+        mut write_element: F,
+    ) -> Result<SliceRef<T>, BuildError<S::Error>>
+    where
+        I: IntoIterator,
+        F: FnMut(&mut Self, I::Item) -> Result<(), BuildError<S::Error>>,
+    {
+        let pos = self
+            .serializer
+            .align_for::<T>()
+            .map_err(BuildError::SerializerError)?;
+
+        let mut len = 0;
+        for value in iter {
+            let start = self.pos();
+            write_element(self, value)?;
+            let written = self.pos() - start;
+            if written != mem::size_of::<T>() {
+                return Err(BuildError::ElementSizeMismatch {
+                    index: len,
+                    expected: mem::size_of::<T>(),
+                    written,
+                });
+            }
+            len += 1;
+        }
+
+        Ok(SliceRef {
+            pos,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Writes the bytes of a string if they need to be stored out of line.
+    ///
+    /// Short strings are stored inline, so nothing may be written until the string is pointed to
+    /// with [`write_string`](ArchiveBuilder::write_string).
+    #[inline]
+    pub fn write_str<'a>(&mut self, value: &'a str) -> Result<StrRef<'a>, BuildError<S::Error>> {
+        let resolver = ArchivedString::serialize_from_str(value, &mut self.serializer)
+            .map_err(BuildError::SerializerError)?;
+        Ok(StrRef { value, resolver })
+    }
+
+    /// Starts writing a struct of type `T` and returns its start.
+    ///
+    /// The fields of the struct are written by calling [`field`](ArchiveBuilder::field) with the
+    /// offset of each field, followed by the value of the field.
+    #[inline]
+    pub fn start_struct<T>(&mut self) -> Result<StructStart<T>, BuildError<S::Error>> {
+        let pos = self
+            .serializer
+            .align_for::<T>()
+            .map_err(BuildError::SerializerError)?;
+        Ok(StructStart {
+            pos,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Pads the struct up to the field at the given offset, so the field can be written next.
+    pub fn field<T>(
+        &mut self,
+        parent: &StructStart<T>,
+        offset: usize,
+    ) -> Result<(), BuildError<S::Error>> {
+        let written = self.written_since(parent)?;
+        if offset < written {
+            Err(BuildError::FieldOutOfOrder { offset, written })
+        } else if offset > mem::size_of::<T>() {
+            Err(BuildError::StructOverrun {
+                size: mem::size_of::<T>(),
+                written: offset,
+            })
+        } else {
+            self.pad_to(parent.pos + offset)
+        }
+    }
+
+    /// Pads the struct up to its size and returns its position.
+    pub fn finish_struct<T>(
+        &mut self,
+        start: StructStart<T>,
+    ) -> Result<usize, BuildError<S::Error>> {
+        let written = self.written_since(&start)?;
+        if written > mem::size_of::<T>() {
+            Err(BuildError::StructOverrun {
+                size: mem::size_of::<T>(),
+                written,
+            })
+        } else {
+            self.pad_to(start.pos + mem::size_of::<T>())?;
+            Ok(start.pos)
+        }
+    }
+
+    /// Writes a relative pointer to the given position and returns its position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset between the relative pointer and its target doesn't fit in an
+    /// `isize`.
+    #[inline]
+    pub fn write_rel_ptr(&mut self, to: usize) -> Result<usize, BuildError<S::Error>> {
+        // SAFETY: `emplace` passes the position of the relative pointer
+        unsafe { self.emplace(|pos, out| RawRelPtr::emplace(pos, to, out)) }
+    }
+
+    /// Writes an [`ArchivedVec`] of the given slice and returns its position.
+    #[inline]
+    pub fn write_vec<T>(&mut self, slice: &SliceRef<T>) -> Result<usize, BuildError<S::Error>> {
+        // SAFETY: The slice was written with `len` elements at `slice.pos`
+        unsafe {
+            self.emplace(|pos, out| {
+                ArchivedVec::<T>::resolve_from_len(
+                    slice.len,
+                    pos,
+                    VecResolver::from_pos(slice.pos),
+                    out,
+                )
+            })
+        }
+    }
+
+    /// Writes an [`ArchivedString`] of the given string and returns its position.
+    #[inline]
+    pub fn write_string(&mut self, value: StrRef<'_>) -> Result<usize, BuildError<S::Error>> {
+        // SAFETY: The resolver is the result of serializing the string
+        unsafe {
+            self.emplace(|pos, out| {
+                ArchivedString::resolve_from_str(value.value, pos, value.resolver, out)
+            })
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Compile-time bounds on the scratch space needed to serialize a type.
+
+use crate::Archive;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use ::alloc::{boxed::Box, string::String};
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    mem,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+};
+
+/// A type with a statically known upper bound on the scratch space needed to serialize it.
+///
+/// Scratch space that is at least `MAX_SCRATCH` bytes large can serialize any value of the type
+/// without running out, so a [`BufferScratch`](crate::ser::serializers::BufferScratch) of that size
+/// never needs to fall back or resize. The bound accounts for the padding that may be needed to
+/// align each scratch allocation.
+///
+/// This can be derived by adding `#[archive(max_scratch)]` when deriving `Archive`. The derived
+/// bound is the sum of the bounds of every field, which is conservative since fields are serialized
+/// one at a time and release their scratch space before the next field is serialized.
+///
+/// Collections whose length isn't known at compile time (like `Vec` and `HashMap`) need scratch
+/// space proportional to their length, so they don't implement this trait. Collections with a
+/// fixed capacity use [`slice_max_scratch`] to compute their bound.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{
+///     ser::{
+///         max_scratch::MaxScratch,
+///         serializers::{AlignedSerializer, CompositeSerializer, DynamicHeapScratch},
+///         Serializer,
+///     },
+///     AlignedVec, Archive, Infallible, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(max_scratch)]
+/// struct Record {
+///     id: u64,
+///     name: String,
+///     tags: [Option<Box<str>>; 4],
+/// }
+///
+/// // Reserve exactly as much scratch space as a record could need
+/// let mut scratch = DynamicHeapScratch::new();
+/// scratch.reserve(Record::MAX_SCRATCH);
+///
+/// let mut serializer = CompositeSerializer::new(
+///     AlignedSerializer::new(AlignedVec::new()),
+///     scratch,
+///     Infallible,
+/// );
+/// serializer
+///     .serialize_value(&Record {
+///         id: 42,
+///         name: "answer".to_string(),
+///         tags: [Some("a".into()), None, None, None],
+///     })
+///     .unwrap();
+/// ```
+pub trait MaxScratch {
+    /// The maximum number of bytes of scratch space needed to serialize any value of this type.
+    const MAX_SCRATCH: usize;
+}
+
+/// Returns the maximum scratch space needed to serialize a slice of `T` with at most `len`
+/// elements.
+///
+/// Slices allocate scratch space for the resolvers of their elements, and hold it while each
+/// element is serialized.
+#[inline]
+pub const fn slice_max_scratch<T: Archive + MaxScratch>(len: usize) -> usize {
+    let size = len * mem::size_of::<T::Resolver>();
+    let resolvers = if size == 0 {
+        0
+    } else {
+        size + mem::align_of::<T::Resolver>() - 1
+    };
+    resolvers + T::MAX_SCRATCH
+}
+
+macro_rules! impl_no_scratch {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl MaxScratch for $type {
+                const MAX_SCRATCH: usize = 0;
+            }
+        )*
+    };
+}
+
+impl_no_scratch!(
+    (),
+    PhantomPinned,
+    bool,
+    char,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+);
+
+impl<T: ?Sized> MaxScratch for PhantomData<T> {
+    const MAX_SCRATCH: usize = 0;
+}
+
+impl<T: MaxScratch> MaxScratch for Option<T> {
+    const MAX_SCRATCH: usize = T::MAX_SCRATCH;
+}
+
+// Array elements are serialized one at a time and their resolvers are kept inline
+impl<T: MaxScratch, const N: usize> MaxScratch for [T; N] {
+    const MAX_SCRATCH: usize = T::MAX_SCRATCH;
+}
+
+macro_rules! impl_tuple {
+    () => ();
+    ($type:ident, $($rest:ident,)*) => {
+        impl<$type: MaxScratch, $($rest: MaxScratch),*> MaxScratch for ($type, $($rest,)*) {
+            const MAX_SCRATCH: usize = $type::MAX_SCRATCH $(+ $rest::MAX_SCRATCH)*;
+        }
+
+        impl_tuple! { $($rest,)* }
+    };
+}
+
+#[cfg(not(feature = "strict"))]
+impl_tuple! { T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, }
+
+#[cfg(feature = "alloc")]
+impl MaxScratch for String {
+    const MAX_SCRATCH: usize = 0;
+}
+
+#[cfg(feature = "alloc")]
+impl MaxScratch for Box<str> {
+    const MAX_SCRATCH: usize = 0;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: MaxScratch> MaxScratch for Box<T> {
+    const MAX_SCRATCH: usize = T::MAX_SCRATCH;
+}
@@ -1,9 +1,11 @@
 //! Serialization traits, serializers, and adapters.
 
+pub mod builder;
+pub mod max_scratch;
 pub mod serializers;
 
 use crate::{Archive, ArchiveUnsized, Fallible, RelPtr, Serialize, SerializeUnsized};
-use core::{alloc::Layout, mem, ptr::NonNull, slice};
+use core::{alloc::Layout, fmt, mem, ptr::NonNull, slice};
 
 /// A byte sink that knows where it is.
 ///
@@ -80,6 +82,61 @@ pub trait Serializer: Fallible {
         unsafe { self.resolve_aligned(value, resolver) }
     }
 
+    /// Archives the given object so that the archive ends exactly at `total` bytes, and returns the
+    /// position it was archived at.
+    ///
+    /// Zero bytes are written between the serialized dependencies of the object and the root, so
+    /// the root stays at the end of the archive and can be accessed with
+    /// [`archived_root`](crate::archived_root) and `check_archived_root` as usual. This is useful
+    /// for storing archives in fixed-size slots.
+    ///
+    /// Returns [`PadToError::Overflow`] if the archive would be larger than `total` bytes, and
+    /// [`PadToError::Unaligned`] if a root ending at `total` wouldn't be properly aligned.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}};
+    ///
+    /// let mut serializer = AllocSerializer::<256>::default();
+    /// serializer.serialize_value_padded(&"hello world".to_string(), 64).unwrap();
+    /// let bytes = serializer.into_serializer().into_inner();
+    ///
+    /// assert_eq!(bytes.len(), 64);
+    /// let archived = unsafe { archived_root::<String>(&bytes) };
+    /// assert_eq!(archived, "hello world");
+    /// ```
+    #[inline]
+    fn serialize_value_padded<T: Serialize<Self>>(
+        &mut self,
+        value: &T,
+        total: usize,
+    ) -> Result<usize, PadToError<Self::Error>> {
+        const ZEROES: [u8; 32] = [0; 32];
+
+        let resolver = value.serialize(self).map_err(PadToError::SerializerError)?;
+
+        let align = mem::align_of::<T::Archived>();
+        if total & (align - 1) != 0 {
+            return Err(PadToError::Unaligned { total, align });
+        }
+        let size = ((self.pos() + align - 1) & !(align - 1)) + mem::size_of::<T::Archived>();
+        if size > total {
+            return Err(PadToError::Overflow { size, total });
+        }
+
+        let mut padding = total - mem::size_of::<T::Archived>() - self.pos();
+        while padding > 0 {
+            let len = padding.min(ZEROES.len());
+            self.write(&ZEROES[..len])
+                .map_err(PadToError::SerializerError)?;
+            padding -= len;
+        }
+        unsafe {
+            self.resolve_aligned(value, resolver)
+                .map_err(PadToError::SerializerError)
+        }
+    }
+
     /// Resolves the given reference with its resolver and writes the archived reference.
     ///
     /// Returns the position of the written archived `RelPtr`.
@@ -121,6 +178,59 @@ pub trait Serializer: Fallible {
     }
 }
 
+/// An error that can occur while padding an archive to a total size.
+#[derive(Debug)]
+pub enum PadToError<E> {
+    /// The archive is larger than the total size
+    Overflow {
+        /// The size of the archive without padding
+        size: usize,
+        /// The total size the archive was padded to
+        total: usize,
+    },
+    /// A root ending at the total size would not be aligned
+    Unaligned {
+        /// The total size the archive was padded to
+        total: usize,
+        /// The alignment of the root
+        align: usize,
+    },
+    /// The serializer encountered an error
+    SerializerError(E),
+}
+
+impl<E: fmt::Display> fmt::Display for PadToError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PadToError::Overflow { size, total } => write!(
+                f,
+                "archive size {} is larger than the padded size {}",
+                size, total
+            ),
+            PadToError::Unaligned { total, align } => write!(
+                f,
+                "padded size {} is not a multiple of the root alignment {}",
+                total, align
+            ),
+            PadToError::SerializerError(e) => write!(f, "serializer error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for PadToError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                PadToError::Overflow { .. } | PadToError::Unaligned { .. } => None,
+                PadToError::SerializerError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
 // Someday this can probably be replaced with alloc::Allocator
 
 /// A serializer that can allocate scratch space.
@@ -3,7 +3,7 @@ use crate::{
     AlignedBytes, AlignedVec, Archive, ArchiveUnsized, Fallible, RelPtr,
 };
 #[cfg(not(feature = "std"))]
-use ::alloc::{alloc, boxed::Box, vec::Vec};
+use ::alloc::{alloc, boxed::Box, vec, vec::Vec};
 #[cfg(feature = "std")]
 use ::std::alloc;
 use core::{
@@ -108,6 +108,86 @@ impl<A: Borrow<AlignedVec> + BorrowMut<AlignedVec>> Serializer for AlignedSerial
     }
 }
 
+/// A serializer that writes into an [`AlignedVec`] starting at a given position.
+///
+/// Unlike [`AlignedSerializer`], which always appends to the end of the vec, this serializer
+/// overwrites the bytes of the vec starting at its position and only grows the vec when writing
+/// past the end. If the starting position is past the end of the vec, the vec is first padded with
+/// zeroes up to it. This makes it possible to serialize several values into reserved regions of one
+/// larger buffer. Nothing stops a value from overwriting the bytes after its region, so each region
+/// must be large enough for the value serialized into it.
+///
+/// Positions are always relative to the start of the vec, so the relative pointers in each value
+/// resolve correctly within the whole buffer and padding is computed from the start of the vec.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     archived_value,
+///     ser::{serializers::OffsetSerializer, Serializer},
+///     AlignedVec,
+/// };
+///
+/// let mut buf = AlignedVec::new();
+/// buf.resize(512, 0);
+///
+/// let mut serializer = OffsetSerializer::new(&mut buf, 256);
+/// let second = serializer.serialize_value(&"the second value".to_string()).unwrap();
+///
+/// let mut serializer = OffsetSerializer::new(&mut buf, 0);
+/// let first = serializer.serialize_value(&[1u32, 2, 3]).unwrap();
+/// assert_eq!(buf.len(), 512);
+///
+/// let archived = unsafe { archived_value::<[u32; 3]>(buf.as_ref(), first) };
+/// assert_eq!(archived, &[1, 2, 3]);
+/// let archived = unsafe { archived_value::<String>(buf.as_ref(), second) };
+/// assert_eq!(archived, "the second value");
+/// ```
+#[derive(Debug)]
+pub struct OffsetSerializer<A> {
+    inner: A,
+    pos: usize,
+}
+
+impl<A: Borrow<AlignedVec> + BorrowMut<AlignedVec>> OffsetSerializer<A> {
+    /// Creates a new `OffsetSerializer` that starts writing at the given position of the vec.
+    #[inline]
+    pub fn new(mut inner: A, pos: usize) -> Self {
+        let vec = inner.borrow_mut();
+        if vec.len() < pos {
+            vec.resize(pos, 0);
+        }
+        Self { inner, pos }
+    }
+
+    /// Consumes the serializer and returns the underlying type.
+    #[inline]
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+}
+
+impl<A> Fallible for OffsetSerializer<A> {
+    type Error = Infallible;
+}
+
+impl<A: Borrow<AlignedVec> + BorrowMut<AlignedVec>> Serializer for OffsetSerializer<A> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        let vec = self.inner.borrow_mut();
+        let overwritten = bytes.len().min(vec.len() - self.pos);
+        vec.as_mut_slice()[self.pos..self.pos + overwritten].copy_from_slice(&bytes[..overwritten]);
+        vec.extend_from_slice(&bytes[overwritten..]);
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
 /// Fixed-size scratch space allocated on the heap.
 #[derive(Debug)]
 pub struct HeapScratch<const N: usize> {
@@ -162,6 +242,64 @@ impl<const N: usize> ScratchSpace for HeapScratch<N> {
     }
 }
 
+/// Scratch space allocated on the heap with a size chosen at runtime.
+///
+/// Unlike [`HeapScratch`], the capacity of this scratch space doesn't need to be known at compile
+/// time. This makes it possible to size scratch space based on measurements taken at runtime (for
+/// example with a [`ScratchTracker`](crate::ser::serializers::ScratchTracker)) so that a
+/// [`FallbackScratch`](crate::ser::serializers::FallbackScratch) never needs to fall back.
+#[derive(Debug, Default)]
+pub struct DynamicHeapScratch {
+    inner: BufferScratch<Box<[u8]>>,
+    capacity: usize,
+}
+
+impl DynamicHeapScratch {
+    /// Creates a new heap scratch space with no capacity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new heap scratch space with the given capacity in bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: BufferScratch::new(vec![0u8; capacity].into_boxed_slice()),
+            capacity,
+        }
+    }
+
+    /// Returns the capacity of the scratch space in bytes.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Ensures that the scratch space has a capacity of at least `capacity` bytes.
+    ///
+    /// If the scratch space must grow, it is reallocated. This must be called between
+    /// serializations, while none of the scratch space is in use.
+    pub fn reserve(&mut self, capacity: usize) {
+        if self.capacity() < capacity {
+            *self = Self::with_capacity(capacity);
+        }
+    }
+}
+
+impl Fallible for DynamicHeapScratch {
+    type Error = <BufferScratch<Box<[u8]>> as Fallible>::Error;
+}
+
+impl ScratchSpace for DynamicHeapScratch {
+    #[inline]
+    unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, Self::Error> {
+        self.inner.push_scratch(layout)
+    }
+
+    #[inline]
+    unsafe fn pop_scratch(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), Self::Error> {
+        self.inner.pop_scratch(ptr, layout)
+    }
+}
+
 /// Errors that can occur when allocating with the global allocator.
 #[derive(Debug)]
 pub enum AllocScratchError {
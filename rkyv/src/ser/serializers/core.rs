@@ -149,6 +149,127 @@ impl<T: AsMut<[u8]>> Serializer for BufferSerializer<T> {
     }
 }
 
+/// The error type returned by a [`LimitedSerializer`].
+#[derive(Debug)]
+pub enum LimitedSerializerError<E> {
+    /// Writing would have exceeded the size limit.
+    SizeLimitExceeded {
+        /// The maximum number of bytes that may be written
+        limit: usize,
+    },
+    /// The inner serializer encountered an error.
+    SerializerError(E),
+}
+
+impl<E: fmt::Display> fmt::Display for LimitedSerializerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeLimitExceeded { limit } => {
+                write!(f, "serialized size exceeded the limit of {} bytes", limit)
+            }
+            Self::SerializerError(e) => write!(f, "serialization error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for LimitedSerializerError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::SizeLimitExceeded { .. } => None,
+                Self::SerializerError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+/// A passthrough serializer that limits the number of bytes written to the inner serializer.
+///
+/// Every write is checked against the limit before it's passed on, including the padding written to
+/// align values. A write that would exceed the limit fails with
+/// [`SizeLimitExceeded`](LimitedSerializerError::SizeLimitExceeded) and isn't performed, so
+/// serializing untrusted data stops as soon as it gets too large. Only bytes written through the
+/// limited serializer count towards the limit, not bytes that the inner serializer already holds.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     ser::{
+///         serializers::{AlignedSerializer, LimitedSerializer, LimitedSerializerError},
+///         Serializer,
+///     },
+///     AlignedVec,
+/// };
+///
+/// let mut serializer = LimitedSerializer::new(AlignedSerializer::new(AlignedVec::new()), 64);
+/// match serializer.serialize_value(&[0u32; 32]) {
+///     Err(LimitedSerializerError::SizeLimitExceeded { limit }) => assert_eq!(limit, 64),
+///     _ => panic!("expected the size limit to be exceeded"),
+/// }
+/// assert_eq!(serializer.bytes_written(), 0);
+/// ```
+#[derive(Debug)]
+pub struct LimitedSerializer<S> {
+    inner: S,
+    limit: usize,
+    bytes_written: usize,
+}
+
+impl<S> LimitedSerializer<S> {
+    /// Creates a new limited serializer that writes at most `limit` bytes to the inner serializer.
+    #[inline]
+    pub fn new(inner: S, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            bytes_written: 0,
+        }
+    }
+
+    /// Returns the maximum number of bytes that may be written.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the number of bytes that have been written so far.
+    #[inline]
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+
+    /// Consumes the serializer and returns the inner serializer.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Fallible> Fallible for LimitedSerializer<S> {
+    type Error = LimitedSerializerError<S::Error>;
+}
+
+impl<S: Serializer> Serializer for LimitedSerializer<S> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        if bytes.len() > self.limit - self.bytes_written {
+            return Err(LimitedSerializerError::SizeLimitExceeded { limit: self.limit });
+        }
+        self.inner
+            .write(bytes)
+            .map_err(LimitedSerializerError::SerializerError)?;
+        self.bytes_written += bytes.len();
+        Ok(())
+    }
+}
+
 /// Errors that can occur when using a fixed-size allocator.
 ///
 /// Pairing a fixed-size allocator with a fallback allocator can help prevent running out of scratch
@@ -239,7 +360,7 @@ impl<T> Fallible for BufferScratch<T> {
     type Error = FixedSizeScratchError;
 }
 
-impl<T: DerefMut<Target = U>, U: AsMut<[u8]>> ScratchSpace for BufferScratch<T> {
+impl<T: DerefMut<Target = U>, U: AsMut<[u8]> + ?Sized> ScratchSpace for BufferScratch<T> {
     #[inline]
     unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, Self::Error> {
         if self.ptr.is_none() {
@@ -301,6 +422,11 @@ impl<M, F> FallbackScratch<M, F> {
     pub fn new(main: M, fallback: F) -> Self {
         Self { main, fallback }
     }
+
+    /// Consumes the fallback scratch, returning the main and backup scratch.
+    pub fn into_components(self) -> (M, F) {
+        (self.main, self.fallback)
+    }
 }
 
 impl<M: Default, F: Default> Default for FallbackScratch<M, F> {
@@ -332,7 +458,59 @@ impl<M: ScratchSpace, F: ScratchSpace> ScratchSpace for FallbackScratch<M, F> {
     }
 }
 
+/// The error returned by [`ScratchTracker::finish`] when scratch space hasn't been returned.
+#[derive(Debug)]
+pub struct ScratchLeakError {
+    /// The number of bytes of scratch space that were never popped
+    pub bytes: usize,
+    /// The number of allocations that were never popped
+    pub allocations: usize,
+}
+
+impl fmt::Display for ScratchLeakError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "scratch space was not returned: {} bytes leaked in {} allocations",
+            self.bytes, self.allocations
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for ScratchLeakError {}
+};
+
 /// A passthrough scratch space allocator that tracks scratch space usage.
+///
+/// Every `push_scratch` must be paired with a `pop_scratch` once serialization succeeds. Calling
+/// [`finish`](ScratchTracker::finish) after serializing checks this, which catches `Serialize`
+/// implementations that allocate scratch space without returning it.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     ser::{
+///         serializers::{AlignedSerializer, AllocScratch, CompositeSerializer, ScratchTracker},
+///         Serializer,
+///     },
+///     AlignedVec,
+///     Infallible,
+/// };
+///
+/// let mut serializer = CompositeSerializer::new(
+///     AlignedSerializer::new(AlignedVec::new()),
+///     ScratchTracker::new(AllocScratch::default()),
+///     Infallible,
+/// );
+/// serializer.serialize_value(&vec![vec![1, 2], vec![3, 4]]).unwrap();
+///
+/// let (_, tracker, _) = serializer.into_components();
+/// assert!(tracker.finish().is_ok());
+/// ```
 #[derive(Debug)]
 pub struct ScratchTracker<T> {
     inner: T,
@@ -384,6 +562,33 @@ impl<T> ScratchTracker<T> {
     pub fn min_buffer_size_max_error(&self) -> usize {
         self.max_allocations * (self.max_alignment - 1)
     }
+
+    /// Returns the number of bytes that are currently allocated.
+    pub fn bytes_allocated(&self) -> usize {
+        self.bytes_allocated
+    }
+
+    /// Returns the number of allocations that haven't been popped yet.
+    pub fn allocations(&self) -> usize {
+        self.allocations
+    }
+
+    /// Consumes the tracker and returns the inner scratch space, checking that all of the scratch
+    /// space allocated through it was returned.
+    ///
+    /// If serialization failed, scratch space may not have been returned even when every
+    /// `Serialize` implementation is correct. This should only be called after serialization
+    /// succeeds.
+    pub fn finish(self) -> Result<T, ScratchLeakError> {
+        if self.allocations == 0 {
+            Ok(self.inner)
+        } else {
+            Err(ScratchLeakError {
+                bytes: self.bytes_allocated,
+                allocations: self.allocations,
+            })
+        }
+    }
 }
 
 impl<T: Fallible> Fallible for ScratchTracker<T> {
@@ -9,6 +9,7 @@ mod std;
 #[cfg(feature = "alloc")]
 use crate::AlignedVec;
 use crate::{
+    collections::hash_index::HashIndexLenError,
     ser::{ScratchSpace, Serializer, SharedSerializeRegistry},
     AlignedBytes, Archive, ArchiveUnsized, Fallible, Infallible,
 };
@@ -24,6 +25,40 @@ pub use self::core::*;
 pub use self::std::*;
 
 /// The default serializer error.
+///
+/// Each variant identifies the component of the [`CompositeSerializer`] that failed, so a caller
+/// can tell a recoverable failure apart from a fatal one. For example, running out of scratch space
+/// is reported as a `ScratchSpaceError` carrying the requested and remaining sizes, and can be
+/// retried with a larger scratch space, while an error from the underlying writer is reported as a
+/// `SerializerError`.
+///
+/// ```
+/// use rkyv::ser::{
+///     serializers::{
+///         AlignedSerializer, AllocScratch, AllocScratchError, CompositeSerializer,
+///         CompositeSerializerError,
+///     },
+///     Serializer,
+/// };
+/// use rkyv::AlignedVec;
+///
+/// let value = vec![String::from("hello world"); 64];
+///
+/// let mut serializer = CompositeSerializer::new(
+///     AlignedSerializer::new(AlignedVec::new()),
+///     AllocScratch::with_limit(16),
+///     rkyv::Infallible,
+/// );
+/// match serializer.serialize_value(&value) {
+///     Err(CompositeSerializerError::ScratchSpaceError(AllocScratchError::ExceededLimit {
+///         requested,
+///         remaining,
+///     })) => {
+///         assert!(requested > remaining);
+///     }
+///     _ => panic!("expected scratch space to be exhausted"),
+/// }
+/// ```
 #[derive(Debug)]
 pub enum CompositeSerializerError<S, C, H> {
     /// An error occurred while serializing
@@ -32,6 +67,14 @@ pub enum CompositeSerializerError<S, C, H> {
     ScratchSpaceError(C),
     /// An error occurred while serializing shared memory
     SharedError(H),
+    /// A hash index had too many entries
+    HashIndexError(HashIndexLenError),
+}
+
+impl<S, C, H> From<HashIndexLenError> for CompositeSerializerError<S, C, H> {
+    fn from(e: HashIndexLenError) -> Self {
+        Self::HashIndexError(e)
+    }
 }
 
 impl<S, C, H> fmt::Display for CompositeSerializerError<S, C, H>
@@ -45,6 +88,7 @@ where
             Self::SerializerError(e) => write!(f, "serialization error: {}", e),
             Self::ScratchSpaceError(e) => write!(f, "scratch space error: {}", e),
             Self::SharedError(e) => write!(f, "shared memory error: {}", e),
+            Self::HashIndexError(e) => write!(f, "hash index error: {}", e),
         }
     }
 }
@@ -64,6 +108,7 @@ const _: () = {
                 Self::SerializerError(e) => Some(e as &dyn Error),
                 Self::ScratchSpaceError(e) => Some(e as &dyn Error),
                 Self::SharedError(e) => Some(e as &dyn Error),
+                Self::HashIndexError(e) => Some(e as &dyn Error),
             }
         }
     }
@@ -232,3 +277,39 @@ pub type AllocSerializer<const N: usize> = CompositeSerializer<
     FallbackScratch<HeapScratch<N>, AllocScratch>,
     SharedSerializeMap,
 >;
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> AllocSerializer<N> {
+    /// Creates a new serializer that writes into a vec with the given initial capacity.
+    ///
+    /// If the serialized size of the value is known or can be estimated ahead of time, this avoids
+    /// reallocating and over-allocating the output while serializing. To also bound how much the
+    /// output grows when the capacity is exceeded, create the serializer from a vec with a
+    /// [growth strategy](AlignedVec::set_growth) instead:
+    ///
+    /// ```
+    /// use rkyv::{
+    ///     ser::{serializers::{AlignedSerializer, AllocSerializer}, Serializer},
+    ///     AlignedVec, AlignedVecGrowth,
+    /// };
+    ///
+    /// let mut vec = AlignedVec::with_capacity(1024);
+    /// vec.set_growth(AlignedVecGrowth::Bounded(4096));
+    /// let mut serializer = AllocSerializer::<256>::new(
+    ///     AlignedSerializer::new(vec),
+    ///     Default::default(),
+    ///     Default::default(),
+    /// );
+    /// serializer.serialize_value(&[0u8; 2000]).unwrap();
+    /// let bytes = serializer.into_serializer().into_inner();
+    /// assert!(bytes.capacity() <= 2000 + 4096);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(
+            AlignedSerializer::new(AlignedVec::with_capacity(capacity)),
+            Default::default(),
+            Default::default(),
+        )
+    }
+}
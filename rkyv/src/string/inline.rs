@@ -0,0 +1,259 @@
+//! An archived string stored inline with a fixed capacity.
+//!
+//! See [`InlineStr`](crate::with::InlineStr) for more details.
+
+use core::{borrow::Borrow, cmp, fmt, hash, ops::Deref, ptr, str};
+
+/// Errors that can occur while serializing an [`InlineStr`](crate::with::InlineStr) wrapper.
+#[derive(Debug)]
+pub enum InlineStrError {
+    /// The string was too long to fit in the inline capacity
+    TooLong {
+        /// The length of the string in bytes
+        len: usize,
+        /// The maximum length of the string in bytes
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for InlineStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InlineStrError::TooLong { len, capacity } => write!(
+                f,
+                "string of length {} exceeds inline capacity {}",
+                len, capacity
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for InlineStrError {}
+};
+
+/// An archived string with a fixed inline capacity of `N` bytes.
+///
+/// The string is stored as a length byte followed by `N` bytes of contents, so every archived
+/// value has the same size regardless of the length of the string. Unused bytes are zeroed. Because
+/// the length is a single byte, the capacity is limited to 255 bytes even if `N` is larger.
+#[repr(C)]
+pub struct ArchivedInlineStr<const N: usize> {
+    len: u8,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> ArchivedInlineStr<N> {
+    /// The maximum length of a string in bytes.
+    pub const CAPACITY: usize = if N < u8::MAX as usize {
+        N
+    } else {
+        u8::MAX as usize
+    };
+
+    /// Extracts a string slice containing the entire `ArchivedInlineStr`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Returns a byte slice of the contents of the `ArchivedInlineStr`.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { self.bytes.get_unchecked(..self.len as usize) }
+    }
+
+    /// Checks that a string fits within the inline capacity.
+    #[inline]
+    pub fn check_len(value: &str) -> Result<(), InlineStrError> {
+        if value.len() > Self::CAPACITY {
+            Err(InlineStrError::TooLong {
+                len: value.len(),
+                capacity: Self::CAPACITY,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Writes an archived inline string from a given `str`.
+    ///
+    /// # Safety
+    ///
+    /// - `out` must point to memory suitable for holding an `ArchivedInlineStr<N>`
+    /// - `value` must have been checked with [`check_len`](ArchivedInlineStr::check_len)
+    #[inline]
+    pub unsafe fn emplace(value: &str, out: *mut Self) {
+        let len = value.len();
+        ptr::addr_of_mut!((*out).len).write(len as u8);
+        let bytes = ptr::addr_of_mut!((*out).bytes).cast::<u8>();
+        ptr::copy_nonoverlapping(value.as_ptr(), bytes, len);
+        ptr::write_bytes(bytes.add(len), 0, N - len);
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArchivedInlineStr<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Borrow<str> for ArchivedInlineStr<N> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArchivedInlineStr<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Deref for ArchivedInlineStr<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Display for ArchivedInlineStr<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Eq for ArchivedInlineStr<N> {}
+
+impl<const N: usize> hash::Hash for ArchivedInlineStr<N> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl<const N: usize> Ord for ArchivedInlineStr<N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize> PartialEq for ArchivedInlineStr<N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialOrd for ArchivedInlineStr<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArchivedInlineStr<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        PartialEq::eq(self.as_str(), *other)
+    }
+}
+
+impl<const N: usize> PartialEq<str> for ArchivedInlineStr<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        PartialEq::eq(self.as_str(), other)
+    }
+}
+
+impl<const N: usize> PartialEq<ArchivedInlineStr<N>> for &str {
+    #[inline]
+    fn eq(&self, other: &ArchivedInlineStr<N>) -> bool {
+        PartialEq::eq(other.as_str(), *self)
+    }
+}
+
+impl<const N: usize> PartialEq<ArchivedInlineStr<N>> for str {
+    #[inline]
+    fn eq(&self, other: &ArchivedInlineStr<N>) -> bool {
+        PartialEq::eq(other.as_str(), self)
+    }
+}
+
+/// Errors that can occur while checking an [`ArchivedInlineStr`].
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum CheckInlineStrError {
+    /// The length was greater than the inline capacity
+    LengthOutOfBounds {
+        /// The length of the string in bytes
+        len: usize,
+        /// The maximum length of the string in bytes
+        capacity: usize,
+    },
+    /// The contents were not valid UTF-8
+    InvalidUtf8(str::Utf8Error),
+}
+
+#[cfg(feature = "validation")]
+impl fmt::Display for CheckInlineStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckInlineStrError::LengthOutOfBounds { len, capacity } => write!(
+                f,
+                "inline string length {} exceeds capacity {}",
+                len, capacity
+            ),
+            CheckInlineStrError::InvalidUtf8(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for CheckInlineStrError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                CheckInlineStrError::LengthOutOfBounds { .. } => None,
+                CheckInlineStrError::InvalidUtf8(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::Fallible;
+    use bytecheck::CheckBytes;
+
+    impl<C: Fallible + ?Sized, const N: usize> CheckBytes<C> for ArchivedInlineStr<N> {
+        type Error = CheckInlineStrError;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
+            // The length and bytes are always valid, but they may not form a valid string
+            let value = &*value;
+            let len = value.len as usize;
+            if len > Self::CAPACITY {
+                return Err(CheckInlineStrError::LengthOutOfBounds {
+                    len,
+                    capacity: Self::CAPACITY,
+                });
+            }
+            str::from_utf8(&value.bytes[..len]).map_err(CheckInlineStrError::InvalidUtf8)?;
+            Ok(value)
+        }
+    }
+};
@@ -1,8 +1,9 @@
 //! Archived versions of string types.
 
+pub mod inline;
 pub mod repr;
 
-use crate::{Fallible, SerializeUnsized};
+use crate::{ArchiveUnsized, Fallible, RelPtr, SerializeUnsized};
 use core::{
     borrow::Borrow,
     cmp, fmt, hash,
@@ -17,16 +18,42 @@ use repr::{ArchivedStringRepr, INLINE_CAPACITY};
 /// This has inline and out-of-line representations. Short strings will use the available space
 /// inside the structure to store the string, and long strings will store a
 /// [`RelPtr`](crate::RelPtr) to a `str` instead.
+///
+/// Borrowed string slices archived with [`Inline`](crate::with::Inline) use [`ArchivedStr`]
+/// instead, which never stores the string inline.
 #[repr(transparent)]
 pub struct ArchivedString(repr::ArchivedStringRepr);
 
 impl ArchivedString {
     /// Extracts a string slice containing the entire `ArchivedString`.
+    ///
+    /// This does not re-validate the contents of the string. The bytes of an archived string are
+    /// always valid UTF-8, either because they were checked during validation or because the caller
+    /// of [`archived_root`](crate::archived_root) guaranteed it.
     #[inline]
     pub fn as_str(&self) -> &str {
         self.0.as_str()
     }
 
+    /// Returns a byte slice of the contents of the `ArchivedString`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}};
+    ///
+    /// let mut serializer = AllocSerializer::<256>::default();
+    /// serializer.serialize_value(&"hello world".to_string()).unwrap();
+    /// let bytes = serializer.into_serializer().into_inner();
+    /// let archived = unsafe { archived_root::<String>(&bytes) };
+    ///
+    /// assert_eq!(archived.as_bytes(), b"hello world");
+    /// ```
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.bytes()
+    }
+
     /// Extracts a pinned mutable string slice containing the entire `ArchivedString`.
     #[inline]
     pub fn pin_mut_str(self: Pin<&mut Self>) -> Pin<&mut str> {
@@ -119,8 +146,8 @@ impl hash::Hash for ArchivedString {
 }
 
 macro_rules! impl_index {
-    ($index:ty) => {
-        impl Index<$index> for ArchivedString {
+    ($ty:ty, $index:ty) => {
+        impl Index<$index> for $ty {
             type Output = str;
 
             #[inline]
@@ -129,14 +156,17 @@ macro_rules! impl_index {
             }
         }
     };
+    ($ty:ty) => {
+        impl_index!($ty, Range<usize>);
+        impl_index!($ty, RangeFrom<usize>);
+        impl_index!($ty, RangeFull);
+        impl_index!($ty, RangeInclusive<usize>);
+        impl_index!($ty, RangeTo<usize>);
+        impl_index!($ty, RangeToInclusive<usize>);
+    };
 }
 
-impl_index!(Range<usize>);
-impl_index!(RangeFrom<usize>);
-impl_index!(RangeFull);
-impl_index!(RangeInclusive<usize>);
-impl_index!(RangeTo<usize>);
-impl_index!(RangeToInclusive<usize>);
+impl_index!(ArchivedString);
 
 impl Ord for ArchivedString {
     #[inline]
@@ -220,9 +250,231 @@ pub struct StringResolver {
     pos: usize,
 }
 
+/// An archived `&str`.
+///
+/// This is a relative pointer and a length, and always stores the contents of the string
+/// out-of-line. It's produced when a `&str` is archived with [`Inline`](crate::with::Inline).
+/// Owned strings are archived as [`ArchivedString`] instead, and `Box<str>` is archived as an
+/// [`ArchivedBox<str>`](crate::boxed::ArchivedBox) which has the same layout as `ArchivedStr`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, with::Inline, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example<'a> {
+///     #[with(Inline)]
+///     name: &'a str,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Example { name: "hello world" }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Example>(&bytes) };
+///
+/// assert_eq!(archived.name, "hello world");
+/// assert!(archived.name.starts_with("hello"));
+/// ```
+#[repr(transparent)]
+pub struct ArchivedStr(RelPtr<str>);
+
+impl ArchivedStr {
+    /// Extracts a string slice containing the entire `ArchivedStr`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        unsafe { &*self.0.as_ptr() }
+    }
+
+    /// Returns a byte slice of the contents of the `ArchivedStr`.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+
+    /// Extracts a pinned mutable string slice containing the entire `ArchivedStr`.
+    #[inline]
+    pub fn pin_mut_str(self: Pin<&mut Self>) -> Pin<&mut str> {
+        unsafe { self.map_unchecked_mut(|s| &mut *s.0.as_mut_ptr()) }
+    }
+
+    /// Resolves an archived string slice from a given `str`.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `value`
+    #[inline]
+    pub unsafe fn resolve_from_str(value: &str, pos: usize, resolver: StrResolver, out: *mut Self) {
+        let (fp, fo) = out_field!(out.0);
+        value.resolve_unsized(pos + fp, resolver.pos, (), fo);
+    }
+
+    /// Serializes an archived string slice from a given `str`.
+    #[inline]
+    pub fn serialize_from_str<S: Fallible + ?Sized>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<StrResolver, S::Error>
+    where
+        str: SerializeUnsized<S>,
+    {
+        Ok(StrResolver {
+            pos: value.serialize_unsized(serializer)?,
+        })
+    }
+}
+
+impl AsRef<str> for ArchivedStr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for ArchivedStr {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for ArchivedStr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Deref for ArchivedStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for ArchivedStr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Eq for ArchivedStr {}
+
+impl hash::Hash for ArchivedStr {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl_index!(ArchivedStr);
+
+impl Ord for ArchivedStr {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialEq for ArchivedStr {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialOrd for ArchivedStr {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq<&str> for ArchivedStr {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        PartialEq::eq(self.as_str(), *other)
+    }
+}
+
+impl PartialEq<str> for ArchivedStr {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        PartialEq::eq(self.as_str(), other)
+    }
+}
+
+impl PartialEq<ArchivedStr> for &str {
+    #[inline]
+    fn eq(&self, other: &ArchivedStr) -> bool {
+        PartialEq::eq(other.as_str(), *self)
+    }
+}
+
+impl PartialEq<ArchivedStr> for str {
+    #[inline]
+    fn eq(&self, other: &ArchivedStr) -> bool {
+        PartialEq::eq(other.as_str(), self)
+    }
+}
+
+impl PartialEq<ArchivedString> for ArchivedStr {
+    #[inline]
+    fn eq(&self, other: &ArchivedString) -> bool {
+        PartialEq::eq(self.as_str(), other.as_str())
+    }
+}
+
+impl PartialEq<ArchivedStr> for ArchivedString {
+    #[inline]
+    fn eq(&self, other: &ArchivedStr) -> bool {
+        PartialEq::eq(self.as_str(), other.as_str())
+    }
+}
+
+impl PartialOrd<&str> for ArchivedStr {
+    #[inline]
+    fn partial_cmp(&self, other: &&str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(*other)
+    }
+}
+
+impl PartialOrd<str> for ArchivedStr {
+    #[inline]
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other)
+    }
+}
+
+impl PartialOrd<ArchivedStr> for &str {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedStr) -> Option<cmp::Ordering> {
+        self.partial_cmp(&other.as_str())
+    }
+}
+
+impl PartialOrd<ArchivedStr> for str {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedStr) -> Option<cmp::Ordering> {
+        self.partial_cmp(other.as_str())
+    }
+}
+
+/// The resolver for `&str`.
+pub struct StrResolver {
+    pos: usize,
+}
+
 #[cfg(feature = "validation")]
 const _: () = {
-    use crate::validation::{owned::OwnedPointerError, ArchiveContext};
+    use crate::validation::{
+        owned::{CheckOwnedPointerError, OwnedPointerError},
+        ArchiveContext,
+    };
     use bytecheck::{CheckBytes, Error};
 
     impl<C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedString
@@ -247,7 +499,7 @@ const _: () = {
             if repr.is_inline() {
                 str::check_bytes(repr.as_str_ptr(), context)
                     .map_err(OwnedPointerError::ValueCheckBytesError)?;
-            } else {
+            } else if context.follows_pointers() {
                 let base = value.cast();
                 let offset = repr.out_of_line_offset();
                 let metadata = repr.len();
@@ -268,4 +520,37 @@ const _: () = {
             Ok(&*value)
         }
     }
+
+    impl<C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedStr
+    where
+        C::Error: Error,
+    {
+        type Error = CheckOwnedPointerError<str, C>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let rel_ptr = RelPtr::<str>::manual_check_bytes(value.cast(), context)
+                .map_err(OwnedPointerError::PointerCheckBytesError)?;
+            if !context.follows_pointers() {
+                return Ok(&*value);
+            }
+
+            let ptr = context
+                .check_subtree_rel_ptr(rel_ptr)
+                .map_err(OwnedPointerError::ContextError)?;
+
+            let range = context
+                .push_prefix_subtree(ptr)
+                .map_err(OwnedPointerError::ContextError)?;
+            str::check_bytes(ptr, context).map_err(OwnedPointerError::ValueCheckBytesError)?;
+            context
+                .pop_prefix_range(range)
+                .map_err(OwnedPointerError::ContextError)?;
+
+            Ok(&*value)
+        }
+    }
 };
@@ -3,7 +3,9 @@
 use crate::{Archived, FixedIsize, FixedUsize};
 use core::{marker::PhantomPinned, mem, ptr, slice, str};
 
-const OFFSET_BYTES: usize = mem::size_of::<FixedIsize>();
+pub(crate) const OFFSET_BYTES: usize = mem::size_of::<FixedIsize>();
+/// The position of the little-endian offset in an out-of-line representation.
+pub(crate) const OFFSET_POS: usize = mem::size_of::<Archived<usize>>();
 
 #[derive(Clone, Copy)]
 #[repr(C)]
@@ -0,0 +1,142 @@
+//! Archived tags which are checked against a set of valid tags at runtime.
+//!
+//! See [`CheckedTag`](crate::with::CheckedTag) for more details.
+
+use crate::Archive;
+use core::{fmt, marker::PhantomData};
+
+/// An unsigned integer type that can be archived as a [`CheckedTag`](crate::with::CheckedTag).
+pub trait TagPrimitive: Archive<Resolver = ()> + Copy {
+    /// Gets the value of an archived tag.
+    fn from_archived_tag(archived: &Self::Archived) -> Self;
+
+    /// Converts the tag to a `u64` so it can be checked by a
+    /// [`TagContext`](crate::validation::TagContext).
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! impl_tag_primitive {
+    ($ty:ty) => {
+        impl TagPrimitive for $ty {
+            #[inline]
+            fn from_archived_tag(archived: &Self::Archived) -> Self {
+                from_archived!(*archived)
+            }
+
+            #[inline]
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+        }
+    };
+}
+
+// Bytes have no endianness, so they aren't wrapped when archived
+impl TagPrimitive for u8 {
+    #[inline]
+    fn from_archived_tag(archived: &Self::Archived) -> Self {
+        *archived
+    }
+
+    #[inline]
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+}
+
+impl_tag_primitive!(u16);
+impl_tag_primitive!(u32);
+impl_tag_primitive!(u64);
+
+/// An archived tag which is checked at runtime.
+///
+/// This has the same layout as the archived integer. The marker type `F` identifies the set of
+/// valid tags that it's checked against during validation.
+#[repr(transparent)]
+pub struct ArchivedCheckedTag<T: TagPrimitive, F: ?Sized> {
+    tag: T::Archived,
+    _phantom: PhantomData<F>,
+}
+
+impl<T: TagPrimitive, F: ?Sized> ArchivedCheckedTag<T, F> {
+    /// Gets the value of the tag.
+    #[inline]
+    pub fn get(&self) -> T {
+        T::from_archived_tag(&self.tag)
+    }
+
+    /// Resolves an archived tag from a given value.
+    ///
+    /// # Safety
+    ///
+    /// `pos` must be the position of `out` within the archive.
+    #[inline]
+    pub unsafe fn resolve_from_value(value: &T, pos: usize, out: *mut Self) {
+        let (fp, fo) = out_field!(out.tag);
+        value.resolve(pos + fp, (), fo);
+    }
+}
+
+impl<T: TagPrimitive + fmt::Debug, F: ?Sized> fmt::Debug for ArchivedCheckedTag<T, F> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: TagPrimitive + PartialEq, F: ?Sized> PartialEq<T> for ArchivedCheckedTag<T, F> {
+    #[inline]
+    fn eq(&self, other: &T) -> bool {
+        self.get() == *other
+    }
+}
+
+/// An error resulting from a tag which wasn't in the set of valid tags.
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub struct InvalidTagError {
+    /// The invalid tag
+    pub tag: u64,
+}
+
+#[cfg(feature = "validation")]
+impl fmt::Display for InvalidTagError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid tag: {}", self.tag)
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for InvalidTagError {}
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::validation::TagContext;
+    use bytecheck::CheckBytes;
+
+    impl<T: TagPrimitive, F: ?Sized, C: TagContext<F> + ?Sized> CheckBytes<C>
+        for ArchivedCheckedTag<T, F>
+    {
+        type Error = InvalidTagError;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            // Every bit pattern is a valid integer, so only the value needs to be checked
+            let value = &*value;
+            let tag = value.get().to_u64();
+            if context.is_valid_tag(tag) {
+                Ok(value)
+            } else {
+                Err(InvalidTagError { tag })
+            }
+        }
+    }
+};
@@ -0,0 +1,37 @@
+//! Archived versions of `task` types.
+
+use core::task::Poll;
+
+/// An archived [`Poll`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+pub enum ArchivedPoll<T> {
+    /// Represents that a value is immediately ready.
+    Ready(T),
+    /// Represents that a value is not ready yet.
+    Pending,
+}
+
+impl<T> ArchivedPoll<T> {
+    /// Returns `true` if this is a [`Ready`](ArchivedPoll::Ready) variant.
+    #[inline]
+    pub const fn is_ready(&self) -> bool {
+        matches!(self, ArchivedPoll::Ready(_))
+    }
+
+    /// Returns `true` if this is a [`Pending`](ArchivedPoll::Pending) variant.
+    #[inline]
+    pub const fn is_pending(&self) -> bool {
+        matches!(self, ArchivedPoll::Pending)
+    }
+
+    /// Converts from `&ArchivedPoll<T>` to `Poll<&T>`.
+    #[inline]
+    pub fn as_ref(&self) -> Poll<&T> {
+        match self {
+            ArchivedPoll::Ready(value) => Poll::Ready(value),
+            ArchivedPoll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -2,6 +2,9 @@
 
 use crate::Archived;
 
+#[cfg(feature = "time")]
+pub mod time_crate;
+
 /// An archived [`Duration`](core::time::Duration).
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "strict", repr(C))]
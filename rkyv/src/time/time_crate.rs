@@ -0,0 +1,453 @@
+//! Archived versions of types from the `time` crate.
+
+use crate::Archived;
+use core::fmt;
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// An archived [`Date`].
+///
+/// The date is stored as its year, month, and day.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedDate {
+    year: Archived<i32>,
+    month: u8,
+    day: u8,
+}
+
+impl ArchivedDate {
+    /// Returns the year of the date.
+    #[inline]
+    pub fn year(&self) -> i32 {
+        from_archived!(self.year)
+    }
+
+    /// Returns the month of the date, from 1 to 12.
+    #[inline]
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month, from 1 to 31.
+    #[inline]
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    #[inline]
+    fn try_get(&self) -> Option<Date> {
+        let month = Month::try_from(self.month).ok()?;
+        Date::from_calendar_date(self.year(), month, self.day).ok()
+    }
+
+    /// Gets the date.
+    #[inline]
+    pub fn get(&self) -> Date {
+        self.try_get().expect("invalid archived date")
+    }
+
+    /// Constructs an archived date at the given position.
+    ///
+    /// # Safety
+    ///
+    /// `out` must point to memory suitable for holding an `ArchivedDate`.
+    #[inline]
+    pub unsafe fn emplace(date: Date, out: *mut ArchivedDate) {
+        use core::ptr::addr_of_mut;
+
+        addr_of_mut!((*out).year).write(to_archived!(date.year()));
+        addr_of_mut!((*out).month).write(date.month() as u8);
+        addr_of_mut!((*out).day).write(date.day());
+    }
+}
+
+/// An archived [`OffsetDateTime`].
+///
+/// The date and time are stored in the local time of the offset, along with the offset in seconds
+/// east of UTC.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedOffsetDateTime {
+    date: ArchivedDate,
+    nanosecond: Archived<u32>,
+    offset: Archived<i32>,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl ArchivedOffsetDateTime {
+    /// Returns the date in the local time of the offset.
+    #[inline]
+    pub fn date(&self) -> &ArchivedDate {
+        &self.date
+    }
+
+    /// Returns the hour in the local time of the offset, from 0 to 23.
+    #[inline]
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Returns the minute within the hour, from 0 to 59.
+    #[inline]
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Returns the second within the minute, from 0 to 59.
+    #[inline]
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Returns the nanosecond within the second, from 0 to 999,999,999.
+    #[inline]
+    pub fn nanosecond(&self) -> u32 {
+        from_archived!(self.nanosecond)
+    }
+
+    /// Returns the offset from UTC in seconds.
+    #[inline]
+    pub fn offset_seconds(&self) -> i32 {
+        from_archived!(self.offset)
+    }
+
+    #[inline]
+    fn try_time(&self) -> Option<Time> {
+        Time::from_hms_nano(self.hour, self.minute, self.second, self.nanosecond()).ok()
+    }
+
+    #[inline]
+    fn try_offset(&self) -> Option<UtcOffset> {
+        UtcOffset::from_whole_seconds(self.offset_seconds()).ok()
+    }
+
+    /// Gets the date and time.
+    #[inline]
+    pub fn get(&self) -> OffsetDateTime {
+        let date = self.date.get();
+        let time = self.try_time().expect("invalid archived time");
+        let offset = self.try_offset().expect("invalid archived offset");
+        PrimitiveDateTime::new(date, time).assume_offset(offset)
+    }
+
+    /// Constructs an archived date and time at the given position.
+    ///
+    /// # Safety
+    ///
+    /// `out` must point to memory suitable for holding an `ArchivedOffsetDateTime`.
+    #[inline]
+    pub unsafe fn emplace(date_time: OffsetDateTime, out: *mut ArchivedOffsetDateTime) {
+        use core::ptr::addr_of_mut;
+
+        ArchivedDate::emplace(date_time.date(), addr_of_mut!((*out).date));
+        addr_of_mut!((*out).nanosecond).write(to_archived!(date_time.nanosecond()));
+        addr_of_mut!((*out).offset).write(to_archived!(date_time.offset().whole_seconds()));
+        addr_of_mut!((*out).hour).write(date_time.hour());
+        addr_of_mut!((*out).minute).write(date_time.minute());
+        addr_of_mut!((*out).second).write(date_time.second());
+    }
+}
+
+/// An archived [`Duration`].
+///
+/// Unlike [`core::time::Duration`], this duration may be negative. The nanoseconds always have the
+/// same sign as the seconds.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedDuration {
+    seconds: Archived<i64>,
+    nanoseconds: Archived<i32>,
+}
+
+impl ArchivedDuration {
+    /// Returns the number of whole seconds in the duration.
+    #[inline]
+    pub fn whole_seconds(&self) -> i64 {
+        from_archived!(self.seconds)
+    }
+
+    /// Returns the fractional part of the duration in nanoseconds.
+    #[inline]
+    pub fn subsec_nanoseconds(&self) -> i32 {
+        from_archived!(self.nanoseconds)
+    }
+
+    #[cfg(feature = "validation")]
+    #[inline]
+    fn is_valid(&self) -> bool {
+        let (seconds, nanoseconds) = (self.whole_seconds(), self.subsec_nanoseconds());
+        nanoseconds.unsigned_abs() < 1_000_000_000
+            && (seconds == 0 || nanoseconds == 0 || (seconds < 0) == (nanoseconds < 0))
+    }
+
+    /// Gets the duration.
+    #[inline]
+    pub fn get(&self) -> Duration {
+        Duration::new(self.whole_seconds(), self.subsec_nanoseconds())
+    }
+
+    /// Constructs an archived duration at the given position.
+    ///
+    /// # Safety
+    ///
+    /// `out` must point to memory suitable for holding an `ArchivedDuration`.
+    #[inline]
+    pub unsafe fn emplace(duration: Duration, out: *mut ArchivedDuration) {
+        use core::ptr::addr_of_mut;
+
+        addr_of_mut!((*out).seconds).write(to_archived!(duration.whole_seconds()));
+        addr_of_mut!((*out).nanoseconds).write(to_archived!(duration.subsec_nanoseconds()));
+    }
+}
+
+macro_rules! impl_archived_time {
+    ($archived:ident, $ty:ident) => {
+        impl fmt::Debug for $archived {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.get().fmt(f)
+            }
+        }
+
+        impl fmt::Display for $archived {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.get(), f)
+            }
+        }
+
+        impl PartialEq for $archived {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.get() == other.get()
+            }
+        }
+
+        impl Eq for $archived {}
+
+        impl PartialOrd for $archived {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $archived {
+            #[inline]
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.get().cmp(&other.get())
+            }
+        }
+
+        impl PartialEq<$ty> for $archived {
+            #[inline]
+            fn eq(&self, other: &$ty) -> bool {
+                self.get() == *other
+            }
+        }
+
+        impl PartialEq<$archived> for $ty {
+            #[inline]
+            fn eq(&self, other: &$archived) -> bool {
+                other.eq(self)
+            }
+        }
+    };
+}
+
+impl_archived_time!(ArchivedDate, Date);
+impl_archived_time!(ArchivedOffsetDateTime, OffsetDateTime);
+impl_archived_time!(ArchivedDuration, Duration);
+
+/// Errors that can occur while checking archived `time` types.
+#[derive(Debug)]
+pub enum TimeError {
+    /// The year, month, and day were not a valid date
+    InvalidDate {
+        /// The year
+        year: i32,
+        /// The month
+        month: u8,
+        /// The day of the month
+        day: u8,
+    },
+    /// The hour, minute, second, and nanosecond were not a valid time of day
+    InvalidTime {
+        /// The hour
+        hour: u8,
+        /// The minute
+        minute: u8,
+        /// The second
+        second: u8,
+        /// The nanosecond
+        nanosecond: u32,
+    },
+    /// The offset from UTC was out of range
+    InvalidOffset {
+        /// The offset in seconds
+        seconds: i32,
+    },
+    /// The nanoseconds of a duration were out of range or had a different sign than the seconds
+    InvalidDuration {
+        /// The whole seconds
+        seconds: i64,
+        /// The fractional nanoseconds
+        nanoseconds: i32,
+    },
+}
+
+impl fmt::Display for TimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeError::InvalidDate { year, month, day } => {
+                write!(
+                    f,
+                    "invalid date: year {}, month {}, day {}",
+                    year, month, day
+                )
+            }
+            TimeError::InvalidTime {
+                hour,
+                minute,
+                second,
+                nanosecond,
+            } => write!(
+                f,
+                "invalid time: {}:{}:{}.{:09}",
+                hour, minute, second, nanosecond
+            ),
+            TimeError::InvalidOffset { seconds } => {
+                write!(f, "invalid UTC offset: {} seconds", seconds)
+            }
+            TimeError::InvalidDuration {
+                seconds,
+                nanoseconds,
+            } => write!(
+                f,
+                "invalid duration: {} seconds and {} nanoseconds",
+                seconds, nanoseconds
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TimeError {}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use bytecheck::CheckBytes;
+
+    impl<C: ?Sized> CheckBytes<C> for ArchivedDate {
+        type Error = TimeError;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
+            // Integers are valid for any bit pattern, but not every combination is a valid date
+            let value = &*value;
+            if value.try_get().is_none() {
+                return Err(TimeError::InvalidDate {
+                    year: value.year(),
+                    month: value.month,
+                    day: value.day,
+                });
+            }
+            Ok(value)
+        }
+    }
+
+    impl<C: ?Sized> CheckBytes<C> for ArchivedOffsetDateTime {
+        type Error = TimeError;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            ArchivedDate::check_bytes(core::ptr::addr_of!((*value).date), context)?;
+            let value = &*value;
+            if value.try_time().is_none() {
+                return Err(TimeError::InvalidTime {
+                    hour: value.hour,
+                    minute: value.minute,
+                    second: value.second,
+                    nanosecond: value.nanosecond(),
+                });
+            }
+            if value.try_offset().is_none() {
+                return Err(TimeError::InvalidOffset {
+                    seconds: value.offset_seconds(),
+                });
+            }
+            Ok(value)
+        }
+    }
+
+    impl<C: ?Sized> CheckBytes<C> for ArchivedDuration {
+        type Error = TimeError;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
+            let value = &*value;
+            if !value.is_valid() {
+                return Err(TimeError::InvalidDuration {
+                    seconds: value.whole_seconds(),
+                    nanoseconds: value.subsec_nanoseconds(),
+                });
+            }
+            Ok(value)
+        }
+    }
+};
+
+#[cfg(all(test, feature = "validation"))]
+mod tests {
+    use super::*;
+    use bytecheck::CheckBytes;
+
+    fn check<T: CheckBytes<()>>(value: &T) -> bool {
+        unsafe { T::check_bytes(value, &mut ()).is_ok() }
+    }
+
+    fn date(year: i32, month: u8, day: u8) -> ArchivedDate {
+        ArchivedDate {
+            year: to_archived!(year),
+            month,
+            day,
+        }
+    }
+
+    #[test]
+    fn check_ranges() {
+        assert!(check(&date(2024, 2, 29)));
+        assert!(check(&date(2023, 12, 31)));
+        assert!(!check(&date(2023, 13, 1)));
+        assert!(!check(&date(2023, 0, 1)));
+        assert!(!check(&date(2023, 2, 29)));
+        assert!(!check(&date(2023, 4, 31)));
+        assert!(!check(&date(i32::MAX, 1, 1)));
+
+        let date_time = |hour, nanosecond, offset| ArchivedOffsetDateTime {
+            date: date(2024, 1, 1),
+            nanosecond: to_archived!(nanosecond),
+            offset: to_archived!(offset),
+            hour,
+            minute: 59,
+            second: 59,
+        };
+        assert!(check(&date_time(23, 999_999_999, -3600)));
+        assert!(!check(&date_time(24, 0, 0)));
+        assert!(!check(&date_time(0, 1_000_000_000, 0)));
+        assert!(!check(&date_time(0, 0, 26 * 3600)));
+
+        let duration = |seconds: i64, nanoseconds: i32| ArchivedDuration {
+            seconds: to_archived!(seconds),
+            nanoseconds: to_archived!(nanoseconds),
+        };
+        assert!(check(&duration(-5, -250_000_000)));
+        assert!(check(&duration(0, -1)));
+        assert!(check(&duration(i64::MAX, 999_999_999)));
+        assert!(!check(&duration(5, -1)));
+        assert!(!check(&duration(-5, 1)));
+        assert!(!check(&duration(0, 1_000_000_000)));
+    }
+}
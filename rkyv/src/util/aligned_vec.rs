@@ -52,6 +52,26 @@ pub struct AlignedVec {
     ptr: NonNull<u8>,
     cap: usize,
     len: usize,
+    growth: AlignedVecGrowth,
+}
+
+/// The strategy an [`AlignedVec`] uses to grow its capacity when it runs out of space.
+///
+/// See [`AlignedVec::set_growth`] for more details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AlignedVecGrowth {
+    /// Rounds the required capacity up to the next power of two.
+    ///
+    /// This is the default strategy. It keeps the number of reallocations low, but the capacity
+    /// can be up to twice as large as the length of the vector.
+    #[default]
+    Doubling,
+    /// Rounds the required capacity up to the next power of two, but grows by at most the given
+    /// number of bytes at a time.
+    ///
+    /// This bounds the memory that's allocated but unused. If the required capacity exceeds the
+    /// bound, the vector grows to exactly the required capacity.
+    Bounded(usize),
 }
 
 impl Drop for AlignedVec {
@@ -92,6 +112,7 @@ impl AlignedVec {
             ptr: NonNull::dangling(),
             cap: 0,
             len: 0,
+            growth: AlignedVecGrowth::Doubling,
         }
     }
 
@@ -143,10 +164,52 @@ impl AlignedVec {
                 ptr,
                 cap: capacity,
                 len: 0,
+                growth: AlignedVecGrowth::Doubling,
             }
         }
     }
 
+    /// Returns the strategy the vector uses to grow its capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::{AlignedVec, AlignedVecGrowth};
+    ///
+    /// let vec = AlignedVec::new();
+    /// assert_eq!(vec.growth(), AlignedVecGrowth::Doubling);
+    /// ```
+    #[inline]
+    pub fn growth(&self) -> AlignedVecGrowth {
+        self.growth
+    }
+
+    /// Sets the strategy the vector uses to grow its capacity when it runs out of space.
+    ///
+    /// By default, the capacity is doubled. When writing very large archives, doubling can
+    /// allocate a lot of memory that is never used. [`AlignedVecGrowth::Bounded`] limits how much
+    /// the capacity grows at a time instead, at the cost of reallocating more often. Combined with
+    /// a capacity hint from [`with_capacity`](AlignedVec::with_capacity), this bounds the memory
+    /// used while serializing.
+    ///
+    /// The growth strategy only applies to `reserve`, `push`, and `extend_from_slice`.
+    /// `reserve_exact` always grows to exactly the requested capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::{AlignedVec, AlignedVecGrowth};
+    ///
+    /// let mut vec = AlignedVec::with_capacity(4096);
+    /// vec.set_growth(AlignedVecGrowth::Bounded(100));
+    /// vec.extend_from_slice(&[0; 4097]);
+    ///
+    /// // Doubling would have grown the capacity to 8192
+    /// assert_eq!(vec.capacity(), 4196);
+    /// ```
+    #[inline]
+    pub fn set_growth(&mut self, growth: AlignedVecGrowth) {
+        self.growth = growth;
+    }
+
     #[inline]
     fn layout(&self) -> alloc::Layout {
         unsafe { alloc::Layout::from_size_align_unchecked(self.cap, Self::ALIGNMENT) }
@@ -410,7 +473,8 @@ impl AlignedVec {
     ///
     /// Capacity after this call will be `new_cap` rounded up to next power of 2,
     /// unless that would exceed maximum capacity, in which case capacity
-    /// is capped at the maximum.
+    /// is capped at the maximum. With a [bounded growth strategy](AlignedVec::set_growth),
+    /// capacity grows by at most the bound unless `new_cap` is larger.
     ///
     /// This is same growth strategy used by `reserve`, `push` and `extend_from_slice`.
     ///
@@ -443,7 +507,7 @@ impl AlignedVec {
     pub unsafe fn grow_capacity_to(&mut self, new_cap: usize) {
         debug_assert!(new_cap > self.cap);
 
-        let new_cap = if new_cap > (isize::MAX as usize + 1) >> 1 {
+        let rounded_cap = if new_cap > (isize::MAX as usize + 1) >> 1 {
             // Rounding up to next power of 2 would result in `isize::MAX + 1` or higher,
             // which exceeds max capacity. So cap at max instead.
             assert!(
@@ -455,6 +519,12 @@ impl AlignedVec {
             // Cannot overflow due to check above
             new_cap.next_power_of_two()
         };
+        let new_cap = match self.growth {
+            AlignedVecGrowth::Doubling => rounded_cap,
+            AlignedVecGrowth::Bounded(max_step) => {
+                rounded_cap.min(new_cap.max(self.cap.saturating_add(max_step)))
+            }
+        };
         self.change_capacity(new_cap);
     }
 
@@ -656,6 +726,83 @@ impl AlignedVec {
         }
     }
 
+    /// Returns the number of padding bytes needed to align the length of the vector to `align`.
+    #[inline]
+    fn padding_for(&self, align: usize) -> usize {
+        assert!(
+            align.is_power_of_two() && align <= Self::ALIGNMENT,
+            "`align` must be a power of two no greater than AlignedVec::ALIGNMENT"
+        );
+        (align - (self.len & (align - 1))) & (align - 1)
+    }
+
+    /// Reserves capacity for at least `additional` more bytes to be inserted into the given
+    /// `AlignedVec` after padding its length to a multiple of `align`. The collection may reserve
+    /// more space to avoid frequent reallocations. Does nothing if capacity is already sufficient.
+    ///
+    /// Because the buffer itself is always aligned to [`ALIGNMENT`](AlignedVec::ALIGNMENT), a
+    /// position that is a multiple of `align` is also an address that is aligned to `align`. After
+    /// calling `reserve_aligned`, the vector can be padded to the next multiple of `align` and then
+    /// have `additional` bytes written to it without reallocating. This means that pointers into
+    /// the buffer stay valid while serializing a value that fits in the reserved space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two, if `align` is greater than
+    /// [`ALIGNMENT`](AlignedVec::ALIGNMENT), or if the new capacity exceeds `isize::MAX - 15`
+    /// bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::AlignedVec;
+    ///
+    /// let mut vec = AlignedVec::new();
+    /// vec.push(1);
+    /// vec.reserve_aligned(10, 8);
+    /// assert!(vec.capacity() >= 18);
+    /// ```
+    #[inline]
+    pub fn reserve_aligned(&mut self, additional: usize, align: usize) {
+        let padding = self.padding_for(align);
+        self.reserve(
+            additional
+                .checked_add(padding)
+                .expect("cannot reserve a larger AlignedVec"),
+        );
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more bytes to be inserted into the
+    /// given `AlignedVec` after padding its length to a multiple of `align`. Does nothing if the
+    /// capacity is already sufficient.
+    ///
+    /// This provides the same guarantees as [`reserve_aligned`](AlignedVec::reserve_aligned), but
+    /// doesn't reserve any extra space. Prefer `reserve_aligned` if future insertions are expected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two, if `align` is greater than
+    /// [`ALIGNMENT`](AlignedVec::ALIGNMENT), or if the new capacity exceeds `isize::MAX - 15`
+    /// bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::AlignedVec;
+    ///
+    /// let mut vec = AlignedVec::new();
+    /// vec.push(1);
+    /// vec.reserve_exact_aligned(10, 8);
+    /// assert_eq!(vec.capacity(), 18);
+    /// ```
+    #[inline]
+    pub fn reserve_exact_aligned(&mut self, additional: usize, align: usize) {
+        let padding = self.padding_for(align);
+        self.reserve_exact(
+            additional
+                .checked_add(padding)
+                .expect("cannot reserve a larger AlignedVec"),
+        );
+    }
+
     /// Forces the length of the vector to `new_len`.
     ///
     /// This is a low-level operation that maintains none of the normal invariants of the type.
@@ -886,6 +1033,7 @@ impl Clone for AlignedVec {
         unsafe {
             let mut result = AlignedVec::with_capacity(self.len);
             result.len = self.len;
+            result.growth = self.growth;
             core::ptr::copy_nonoverlapping(self.as_ptr(), result.as_mut_ptr(), self.len);
             result
         }
@@ -21,7 +21,8 @@ use crate::{
 };
 use crate::{Archive, ArchiveUnsized, Deserialize, RelPtr, Serialize};
 use core::{
-    mem,
+    alloc::Layout,
+    fmt, mem,
     ops::{Deref, DerefMut},
     pin::Pin,
 };
@@ -58,7 +59,8 @@ fn check_alignment<T>(ptr: *const u8) {
 ///
 /// # Safety
 ///
-/// A `T::Archived` must be archived at the given position in the byte slice.
+/// A `T::Archived` must be archived at the given position in the byte slice. The value must be
+/// aligned to at least [`archived_alignment::<T>()`](archived_alignment).
 #[inline]
 pub unsafe fn archived_value<T: Archive + ?Sized>(bytes: &[u8], pos: usize) -> &T::Archived {
     #[cfg(debug_assertions)]
@@ -143,6 +145,8 @@ pub unsafe fn archived_unsized_value_mut<T: ArchiveUnsized + ?Sized>(
 ///
 /// - The byte slice must represent an archived object
 /// - The root of the object must be stored at the end of the slice (this is the default behavior)
+/// - The root must be aligned to at least [`archived_alignment::<T>()`](archived_alignment). Use
+///   [`try_archived_root`] to check this instead.
 #[inline]
 pub unsafe fn archived_root<T: Archive + ?Sized>(bytes: &[u8]) -> &T::Archived {
     archived_value::<T>(bytes, bytes.len() - mem::size_of::<T::Archived>())
@@ -167,6 +171,219 @@ pub unsafe fn archived_root_mut<T: Archive + ?Sized>(
     archived_value_mut::<T>(bytes, pos)
 }
 
+/// Returns the alignment required by the archived version of `T`.
+///
+/// Byte slices passed to [`archived_root`] and the other access functions must be aligned to at
+/// least this alignment. Objects that the root points to may require a larger alignment, so this is
+/// only enough for the whole archive if the root doesn't point to anything more aligned than
+/// itself. The buffers that serializers like [`AlignedVec`](crate::AlignedVec) write to are always
+/// aligned to 16 bytes, which is enough for all of the types in this crate.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{archived_alignment, Archived};
+///
+/// assert_eq!(archived_alignment::<u8>(), 1);
+/// assert_eq!(archived_alignment::<(u8, u32)>(), core::mem::align_of::<Archived<u32>>());
+///
+/// // It can be used in constant expressions
+/// const ALIGN: usize = archived_alignment::<u64>();
+/// ```
+#[inline]
+pub const fn archived_alignment<T: Archive>() -> usize {
+    mem::align_of::<T::Archived>()
+}
+
+/// Errors that can occur while accessing the root of an archive with [`try_archived_root`].
+#[derive(Debug)]
+pub enum AccessRootError {
+    /// The byte slice was too small to contain the root
+    BufferTooSmall {
+        /// The length of the byte slice
+        len: usize,
+        /// The size of the archived root
+        size: usize,
+    },
+    /// The root was not aligned to the alignment of its archived type
+    MisalignedBuffer {
+        /// The alignment of the archived root
+        expected_align: usize,
+        /// The actual alignment of the root in memory
+        actual_align: usize,
+    },
+}
+
+impl fmt::Display for AccessRootError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessRootError::BufferTooSmall { len, size } => write!(
+                f,
+                "buffer too small: the buffer is {} bytes but the root is {} bytes",
+                len, size
+            ),
+            AccessRootError::MisalignedBuffer {
+                expected_align,
+                actual_align,
+            } => write!(
+                f,
+                "misaligned buffer: expected the root to be aligned to {} bytes but it was aligned to {} bytes",
+                expected_align, actual_align
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AccessRootError {}
+
+/// Casts an archived value from the given byte slice by calculating the root position, checking
+/// that the root is in bounds and properly aligned.
+///
+/// This returns an error instead of causing undefined behavior when the byte slice is too small to
+/// contain the root or the root doesn't have the alignment required by its archived type (see
+/// [`archived_alignment`]). Like [`archived_root`], it doesn't check the contents of the archive.
+///
+/// # Safety
+///
+/// - The byte slice must represent an archived object
+/// - The root of the object must be stored at the end of the slice (this is the default behavior)
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{archived_root, try_archived_root, AccessRootError, AlignedBytes};
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&42u32).unwrap();
+/// let archived = unsafe { try_archived_root::<u32>(&bytes).unwrap() };
+/// assert_eq!(*archived, 42);
+///
+/// let mut buffer = AlignedBytes([0u8; 8]);
+/// buffer[1..5].copy_from_slice(&bytes);
+/// let result = unsafe { try_archived_root::<u32>(&buffer[1..5]) };
+/// assert!(matches!(result, Err(AccessRootError::MisalignedBuffer { .. })));
+/// ```
+#[inline]
+pub unsafe fn try_archived_root<T: Archive>(bytes: &[u8]) -> Result<&T::Archived, AccessRootError> {
+    let size = mem::size_of::<T::Archived>();
+    let pos = bytes
+        .len()
+        .checked_sub(size)
+        .ok_or(AccessRootError::BufferTooSmall {
+            len: bytes.len(),
+            size,
+        })?;
+
+    let expected_align = archived_alignment::<T>();
+    let address = bytes.as_ptr() as usize + pos;
+    if address & (expected_align - 1) != 0 {
+        return Err(AccessRootError::MisalignedBuffer {
+            expected_align,
+            actual_align: 1 << address.trailing_zeros(),
+        });
+    }
+
+    Ok(&*bytes.as_ptr().add(pos).cast())
+}
+
+/// An error resulting from reinterpreting an archived value as an archived type with a different
+/// layout with [`reinterpret_archived`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LayoutMismatch {
+    /// The layout of the archived type being reinterpreted
+    pub from: Layout,
+    /// The layout of the archived type it was reinterpreted as
+    pub to: Layout,
+}
+
+impl fmt::Display for LayoutMismatch {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archived layouts don't match: size {} and alignment {} can't be reinterpreted as size {} and alignment {}",
+            self.from.size(),
+            self.from.align(),
+            self.to.size(),
+            self.to.align(),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LayoutMismatch {}
+
+/// Reinterprets an archived `T` as an archived `U`, checking that their archived types have the
+/// same size and alignment.
+///
+/// This supports evolving a type while keeping its archived layout, so that data archived with the
+/// old version can be accessed through the new version in place. If the sizes or alignments of the
+/// archived types differ, a [`LayoutMismatch`] is returned instead.
+///
+/// Size and alignment are only a coarse check. If both archived types were derived with
+/// `#[archive(field_offsets)]`, their `FIELD_OFFSETS` can also be compared to check that the
+/// fields are at the same positions. To reinterpret a whole archive without any of these
+/// requirements, check it as the new type with `check_archived_root` instead.
+///
+/// # Safety
+///
+/// Every field of the archived `U` must be valid for the bytes of the corresponding field of the
+/// archived `T`. That is, they must have the same layout, and every valid archived `T` must also be
+/// a valid archived `U`.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{archived_root, reinterpret_archived, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(field_offsets)]
+/// struct PointV1 {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// #[derive(Archive)]
+/// #[archive(field_offsets)]
+/// struct PointV2 {
+///     horizontal: u32,
+///     vertical: u32,
+/// }
+///
+/// #[derive(Archive)]
+/// struct Point3 {
+///     x: u32,
+///     y: u32,
+///     z: u32,
+/// }
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&PointV1 { x: 1, y: 2 }).unwrap();
+/// let v1 = unsafe { archived_root::<PointV1>(&bytes) };
+///
+/// let offsets = |fields: &[(&str, usize)]| fields.iter().map(|f| f.1).collect::<Vec<_>>();
+/// assert_eq!(
+///     offsets(ArchivedPointV1::FIELD_OFFSETS),
+///     offsets(ArchivedPointV2::FIELD_OFFSETS),
+/// );
+/// let v2 = unsafe { reinterpret_archived::<PointV1, PointV2>(v1).unwrap() };
+/// assert_eq!((v2.horizontal, v2.vertical), (1, 2));
+///
+/// assert!(unsafe { reinterpret_archived::<PointV1, Point3>(v1) }.is_err());
+/// ```
+#[inline]
+pub unsafe fn reinterpret_archived<T: Archive, U: Archive>(
+    value: &T::Archived,
+) -> Result<&U::Archived, LayoutMismatch> {
+    let from = Layout::new::<T::Archived>();
+    let to = Layout::new::<U::Archived>();
+    if from != to {
+        return Err(LayoutMismatch { from, to });
+    }
+
+    Ok(&*(value as *const T::Archived).cast::<U::Archived>())
+}
+
 /// Casts a [`RelPtr`] to the given unsized type from the given byte slice by calculating the root
 /// position.
 ///
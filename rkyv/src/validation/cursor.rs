@@ -0,0 +1,204 @@
+//! A cursor for manually navigating archives.
+
+use crate::{
+    validation::{
+        validators::{check_archived_value, DefaultValidator},
+        CheckTypeError,
+    },
+    Archive, RawRelPtr,
+};
+use bytecheck::CheckBytes;
+use core::{fmt, mem::size_of};
+
+/// Errors that can occur while moving a [`Cursor`].
+#[derive(Debug)]
+pub enum CursorError {
+    /// The new position would be outside of the archive.
+    OutOfBounds {
+        /// The position that the cursor was moving to
+        pos: isize,
+        /// The length of the archive
+        len: usize,
+    },
+    /// There isn't enough space at the position of the cursor for a relative pointer.
+    PointerOverrun {
+        /// The position of the cursor
+        pos: usize,
+        /// The length of the archive
+        len: usize,
+    },
+    /// The position of the cursor isn't properly aligned for a relative pointer.
+    UnalignedPointer {
+        /// The position of the cursor
+        pos: usize,
+        /// The required alignment of a relative pointer
+        align: usize,
+    },
+}
+
+impl fmt::Display for CursorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CursorError::OutOfBounds { pos, len } => write!(
+                f,
+                "cursor moved out of bounds: position {} with archive length {}",
+                pos, len
+            ),
+            CursorError::PointerOverrun { pos, len } => write!(
+                f,
+                "not enough space for a relative pointer: position {} with archive length {}",
+                pos, len
+            ),
+            CursorError::UnalignedPointer { pos, align } => write!(
+                f,
+                "relative pointer is unaligned: position {} requires alignment {}",
+                pos, align
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for CursorError {}
+};
+
+/// A position within an archive that can be moved around manually.
+///
+/// A cursor only tracks a byte position, so it doesn't know the type of the value it points to.
+/// Moving the cursor is always safe, and [`get`](Cursor::get) validates the value at the current
+/// position before returning a typed reference to it.
+///
+/// Field names aren't stored in archives, so fields are stepped into by their byte offset. The
+/// offset of a field can be found with `core::mem::offset_of!` on the archived type.
+///
+/// # Examples
+/// ```
+/// use rkyv::{ser::{Serializer, serializers::AllocSerializer}, validation::cursor::Cursor};
+///
+/// let value = vec!["hello".to_string(), "world".to_string()];
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// // An archived vec starts with a relative pointer to its elements
+/// let second = Cursor::root::<Vec<String>>(&bytes)
+///     .follow_ptr()
+///     .unwrap()
+///     .index::<String>(1)
+///     .unwrap();
+/// assert_eq!(second.get::<String>().unwrap(), "world");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a new cursor at the given position in the archive.
+    #[inline]
+    pub fn new(bytes: &'a [u8], pos: usize) -> Result<Self, CursorError> {
+        Self { bytes, pos: 0 }.move_to(pos as isize)
+    }
+
+    /// Creates a new cursor at the root of the archive, assuming the root is of type `T`.
+    ///
+    /// If the archive is too small to hold a root of type `T`, the cursor is placed at the start
+    /// of the archive.
+    #[inline]
+    pub fn root<T: Archive>(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: bytes.len().saturating_sub(size_of::<T::Archived>()),
+        }
+    }
+
+    /// Returns the bytes of the archive.
+    #[inline]
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the position of the cursor in the archive.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    fn move_to(self, pos: isize) -> Result<Self, CursorError> {
+        if pos < 0 || pos as usize > self.bytes.len() {
+            Err(CursorError::OutOfBounds {
+                pos,
+                len: self.bytes.len(),
+            })
+        } else {
+            Ok(Self {
+                bytes: self.bytes,
+                pos: pos as usize,
+            })
+        }
+    }
+
+    /// Moves the cursor by the given number of bytes.
+    ///
+    /// This can be used to step into a field of a struct by passing the offset of the field.
+    #[inline]
+    pub fn offset(self, offset: isize) -> Result<Self, CursorError> {
+        let pos = (self.pos as isize)
+            .checked_add(offset)
+            .ok_or(CursorError::OutOfBounds {
+                pos: isize::MAX,
+                len: self.bytes.len(),
+            })?;
+        self.move_to(pos)
+    }
+
+    /// Moves the cursor to the `index`th element of an array of `T`s starting at the cursor.
+    #[inline]
+    pub fn index<T: Archive>(self, index: usize) -> Result<Self, CursorError> {
+        let offset = index
+            .checked_mul(size_of::<T::Archived>())
+            .and_then(|offset| isize::try_from(offset).ok())
+            .ok_or(CursorError::OutOfBounds {
+                pos: isize::MAX,
+                len: self.bytes.len(),
+            })?;
+        self.offset(offset)
+    }
+
+    /// Reads the relative pointer at the cursor and moves the cursor to its target.
+    #[inline]
+    pub fn follow_ptr(self) -> Result<Self, CursorError> {
+        let len = self.bytes.len();
+        if len - self.pos < size_of::<RawRelPtr>() {
+            return Err(CursorError::PointerOverrun { pos: self.pos, len });
+        }
+        let ptr = unsafe { self.bytes.as_ptr().add(self.pos) };
+        let align = core::mem::align_of::<RawRelPtr>();
+        if (ptr as usize) & (align - 1) != 0 {
+            return Err(CursorError::UnalignedPointer {
+                pos: self.pos,
+                align,
+            });
+        }
+        // SAFETY: The pointer is in bounds and aligned, and any bit pattern is a valid offset.
+        let offset = unsafe { &*ptr.cast::<RawRelPtr>() }.offset();
+        self.offset(offset)
+    }
+
+    /// Checks the value at the cursor and returns a reference to it, assuming it's of type `T`.
+    #[inline]
+    pub fn get<T: Archive>(
+        &self,
+    ) -> Result<&'a T::Archived, CheckTypeError<T::Archived, DefaultValidator<'a>>>
+    where
+        T::Archived: CheckBytes<DefaultValidator<'a>>,
+    {
+        check_archived_value::<T>(self.bytes, self.pos)
+    }
+}
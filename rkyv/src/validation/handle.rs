@@ -0,0 +1,204 @@
+//! Handles that package a validated buffer together with its typed root.
+//!
+//! [`ArchiveRef`] borrows some bytes and [`OwnedArchive`] owns them in an [`AlignedVec`]. Both
+//! validate the archive when they're created, so the root can be accessed afterward without any
+//! more checks or `unsafe`.
+
+use crate::{
+    archived_root,
+    validation::{
+        validators::{check_archived_root, DefaultValidator, DefaultValidatorError},
+        CheckArchiveError, CheckTypeError,
+    },
+    AlignedVec, Archive,
+};
+use bytecheck::CheckBytes;
+use core::{fmt, marker::PhantomData};
+
+#[inline]
+fn subslice(bytes: &[u8], offset: usize, len: usize) -> Option<&[u8]> {
+    bytes.get(offset..offset.checked_add(len)?)
+}
+
+/// A borrowed archive that has been validated for a root of type `T`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{validation::handle::ArchiveRef, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+///
+/// let archive = ArchiveRef::<Example>::new(&bytes).unwrap();
+/// assert_eq!(archive.root().name, "pi");
+/// assert_eq!(archive.root().value, 31415926);
+/// assert_eq!(archive.bytes().len(), bytes.len());
+/// assert!(archive.subslice(0, 4).is_some());
+/// assert!(archive.subslice(bytes.len() - 2, 4).is_none());
+/// ```
+pub struct ArchiveRef<'a, T: Archive> {
+    bytes: &'a [u8],
+    root: &'a T::Archived,
+}
+
+impl<'a, T: Archive> ArchiveRef<'a, T> {
+    /// Checks the given archive for a root of type `T`.
+    ///
+    /// This fails if the bytes are too short to hold the root, or aren't aligned for any of the
+    /// archived types inside.
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Result<Self, CheckTypeError<T::Archived, DefaultValidator<'a>>>
+    where
+        T::Archived: CheckBytes<DefaultValidator<'a>>,
+    {
+        Ok(Self {
+            bytes,
+            root: check_archived_root::<T>(bytes)?,
+        })
+    }
+
+    /// Returns the archived root.
+    #[inline]
+    pub fn root(&self) -> &'a T::Archived {
+        self.root
+    }
+
+    /// Returns the bytes of the archive.
+    #[inline]
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the `len` bytes of the archive starting at `offset`, or `None` if any of them are out
+    /// of bounds.
+    #[inline]
+    pub fn subslice(&self, offset: usize, len: usize) -> Option<&'a [u8]> {
+        subslice(self.bytes, offset, len)
+    }
+}
+
+impl<'a, T: Archive> Clone for ArchiveRef<'a, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: Archive> Copy for ArchiveRef<'a, T> {}
+
+impl<'a, T: Archive> fmt::Debug for ArchiveRef<'a, T>
+where
+    T::Archived: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+/// An owned archive that has been validated for a root of type `T`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{validation::handle::OwnedArchive, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+///
+/// let archive = OwnedArchive::<Example>::new(bytes).unwrap();
+/// assert_eq!(archive.root().name, "pi");
+/// assert_eq!(archive.as_archive_ref().root().value, 31415926);
+///
+/// let bytes = archive.into_bytes();
+/// assert!(OwnedArchive::<Example>::new(bytes).is_ok());
+/// ```
+pub struct OwnedArchive<T> {
+    bytes: AlignedVec,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Archive> OwnedArchive<T> {
+    /// Checks the given archive for a root of type `T` and takes ownership of it.
+    ///
+    /// This fails if the bytes are too short to hold the root. The bytes of an [`AlignedVec`] are
+    /// always aligned for archived types.
+    #[inline]
+    pub fn new<E>(bytes: AlignedVec) -> Result<Self, CheckArchiveError<E, DefaultValidatorError>>
+    where
+        T::Archived: for<'a> CheckBytes<DefaultValidator<'a>, Error = E>,
+    {
+        check_archived_root::<T>(&bytes)?;
+        Ok(Self {
+            bytes,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the archived root.
+    #[inline]
+    pub fn root(&self) -> &T::Archived {
+        // SAFETY: The bytes were validated for a root of type `T` when the archive was created and
+        // they can't be modified afterward.
+        unsafe { archived_root::<T>(&self.bytes) }
+    }
+
+    /// Returns the bytes of the archive.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Returns the `len` bytes of the archive starting at `offset`, or `None` if any of them are out
+    /// of bounds.
+    #[inline]
+    pub fn subslice(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        subslice(self.bytes.as_slice(), offset, len)
+    }
+
+    /// Returns a borrowed handle to the archive.
+    #[inline]
+    pub fn as_archive_ref(&self) -> ArchiveRef<'_, T> {
+        ArchiveRef {
+            bytes: self.bytes.as_slice(),
+            root: self.root(),
+        }
+    }
+
+    /// Consumes the archive and returns its bytes.
+    #[inline]
+    pub fn into_bytes(self) -> AlignedVec {
+        self.bytes
+    }
+}
+
+impl<T: Archive> fmt::Debug for OwnedArchive<T>
+where
+    T::Archived: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root().fmt(f)
+    }
+}
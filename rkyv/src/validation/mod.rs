@@ -1,5 +1,24 @@
 //! Validation implementations and helper types.
-
+//!
+//! # Reachable memory
+//!
+//! Validation only reads the parts of an archive that are reachable from the root object. Starting
+//! from the root, each object is checked in place and every relative pointer it contains is
+//! bounds-checked against the buffer before the object it points to is checked in turn. Bounds
+//! checks and subtree claims only compare addresses, so bytes that are not reachable from the root
+//! are never read.
+//!
+//! This makes it practical to validate archives that are backed by a lazily-loaded memory map:
+//! passing the mapped bytes to [`check_archived_root`](validators::check_archived_root) only faults
+//! in the pages that hold the root and the objects it transitively points to. The reachable region
+//! is exactly the set of byte ranges that the validator claims (the root plus each pointed-to
+//! subtree), and shared pointers are only checked the first time they're encountered. The size of
+//! this region can be measured with [`reachable_bytes`](validators::reachable_bytes). Validation
+//! cannot be done from a streaming reader, since relative pointers may point to any earlier part
+//! of the archive.
+
+pub mod cursor;
+pub mod handle;
 pub mod owned;
 pub mod validators;
 
@@ -257,6 +276,22 @@ pub trait ArchiveContext: Fallible {
 
     /// Verifies that all outstanding claims have been returned.
     fn finish(&mut self) -> Result<(), Self::Error>;
+
+    /// Returns whether the objects that relative pointers point to should be checked.
+    ///
+    /// If this returns `false`, only the bytes of the object being checked are validated. Its
+    /// relative pointers are checked, but the objects they point to are not. See
+    /// [`check_root_shallow`](validators::check_root_shallow) for more details.
+    ///
+    /// A context that returns `false` doesn't make the checked value safe to access, so it must
+    /// only be used where the result of `check_bytes` is discarded. Implementations of `CheckBytes`
+    /// that read the objects their fields point to (for example, to check that the elements of a
+    /// vec are sorted) must return early when this returns `false`, because the objects haven't
+    /// been checked and may be out of bounds.
+    #[inline]
+    fn follows_pointers(&self) -> bool {
+        true
+    }
 }
 
 /// A context that can validate shared archive memory.
@@ -270,6 +305,17 @@ pub trait SharedContext: Fallible {
         -> Result<bool, Self::Error>;
 }
 
+/// A context that can check tags against a set of valid tags provided at runtime.
+///
+/// Tags marked with [`CheckedTag<F>`](crate::with::CheckedTag) require this kind of context to
+/// validate. The marker type `F` identifies the set of valid tags, so fields marked with different
+/// markers can be checked against different sets. See
+/// [`TagValidator`](validators::TagValidator) for a validator that checks tags with a predicate.
+pub trait TagContext<F: ?Sized>: Fallible {
+    /// Returns whether the given tag is valid.
+    fn is_valid_tag(&mut self, tag: u64) -> bool;
+}
+
 /// Errors that can occur when checking an archive.
 #[derive(Debug)]
 pub enum CheckArchiveError<T, C> {
@@ -211,6 +211,8 @@ pub struct ArchiveValidator<'a> {
     subtree_range: Range<*const u8>,
     subtree_depth: usize,
     max_subtree_depth: usize,
+    claimed_bytes: usize,
+    claimed_start: *const u8,
 }
 
 // SAFETY: ArchiveValidator is safe to send to another thread
@@ -236,6 +238,8 @@ impl<'a> ArchiveValidator<'a> {
             subtree_range: bytes.as_ptr_range(),
             subtree_depth: 0,
             max_subtree_depth,
+            claimed_bytes: 0,
+            claimed_start: bytes.as_ptr_range().end,
         }
     }
 
@@ -253,6 +257,24 @@ impl<'a> ArchiveValidator<'a> {
     pub fn alignment(&self) -> usize {
         1 << self.log_alignment()
     }
+
+    /// Returns the number of bytes that have been claimed by subtrees so far.
+    ///
+    /// After validating a root, this is the number of bytes reachable from it. Padding between
+    /// objects is not reachable.
+    #[inline]
+    pub fn claimed_bytes(&self) -> usize {
+        self.claimed_bytes
+    }
+
+    /// Returns the position of the first byte that has been claimed by a subtree so far.
+    ///
+    /// After validating a root, every byte reachable from it lies between this position and the end
+    /// of the root. If no bytes have been claimed, this is the length of the archive.
+    #[inline]
+    pub fn claimed_start(&self) -> usize {
+        self.claimed_start as usize - self.bytes.as_ptr() as usize
+    }
 }
 
 impl<'a> Fallible for ArchiveValidator<'a> {
@@ -368,6 +390,10 @@ impl<'a> ArchiveContext for ArchiveValidator<'a> {
             };
             self.subtree_depth += 1;
             self.subtree_range.end = root;
+            self.claimed_bytes += end.offset_from(root) as usize;
+            if root < self.claimed_start {
+                self.claimed_start = root;
+            }
             Ok(result)
         }
     }
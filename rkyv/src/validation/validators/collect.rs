@@ -0,0 +1,360 @@
+//! A validator that collects recoverable errors instead of stopping at the first one.
+
+use crate::{
+    validation::{
+        check_archived_root_with_context,
+        validators::{
+            ArchiveError, DefaultValidator, DefaultValidatorError, SharedError, ValidatorBuilder,
+        },
+        ArchiveContext, CheckArchiveError, CheckTypeError, SharedContext,
+    },
+    Archive, Fallible,
+};
+#[cfg(not(feature = "std"))]
+use ::alloc::vec::Vec;
+use bytecheck::CheckBytes;
+use core::{
+    alloc::{Layout, LayoutError},
+    any::TypeId,
+};
+
+/// An error collected while validating an archive, along with where it occurred.
+#[derive(Debug)]
+pub struct CollectedError<E> {
+    /// The offset in the archive that the error refers to, if it's known
+    pub offset: Option<usize>,
+    /// The error that occurred
+    pub error: E,
+}
+
+/// A validator that records recoverable errors and keeps validating.
+///
+/// Some errors leave the validator in a state where it can still safely read the rest of the
+/// archive. These errors are recorded and validation continues as if they hadn't occurred:
+///
+/// - Subtree pointers that point outside of their subtree, for example to memory that has already
+///   been claimed by another object ([`ArchiveError::SubtreePointerOutOfBounds`] and
+///   [`ArchiveError::SubtreePointerOverrun`]). The pointer has already been checked to be in bounds
+///   of the archive when these are reported.
+/// - Shared pointers that point to the same memory as a different type
+///   ([`SharedError::TypeMismatch`]). The memory isn't checked again as the new type.
+///
+/// All other errors, including every error reported by `CheckBytes` implementations, stop
+/// validation. Out of bounds and unaligned pointers can't be safely dereferenced, and an invalid
+/// value can't be skipped because its size and the location of the values after it may depend on
+/// it.
+///
+/// Once the maximum number of errors has been reached, the next recoverable error stops validation
+/// as well. Following a recovered pointer may recurse into memory that's already been checked, so
+/// archives with cyclic or deeply nested pointers can recurse once for every recorded error. Use
+/// [`with_max_depth`](CollectingValidator::with_max_depth) to bound the recursion when
+/// `max_errors` is large.
+#[derive(Debug)]
+pub struct CollectingValidator<'a> {
+    inner: DefaultValidator<'a>,
+    base: *const u8,
+    max_errors: usize,
+    errors: Vec<CollectedError<DefaultValidatorError>>,
+    fatal_offset: Option<usize>,
+}
+
+// SAFETY: CollectingValidator is safe to send to another thread
+// This trait is not automatically implemented because the struct contains a pointer
+unsafe impl<'a> Send for CollectingValidator<'a> {}
+
+// SAFETY: CollectingValidator is safe to share between threads
+// This trait is not automatically implemented because the struct contains a pointer
+unsafe impl<'a> Sync for CollectingValidator<'a> {}
+
+impl<'a> CollectingValidator<'a> {
+    /// Creates a new validator from a byte range that records up to `max_errors` errors.
+    ///
+    /// At least one error is always recorded, so a `max_errors` of zero behaves the same as one.
+    #[inline]
+    pub fn new(bytes: &'a [u8], max_errors: usize) -> Self {
+        Self::with_max_depth(bytes, max_errors, usize::MAX)
+    }
+
+    /// Creates a new validator from a byte range that records up to `max_errors` errors and
+    /// validates subtrees down to a maximum depth.
+    ///
+    /// Exceeding the maximum depth stops validation. See
+    /// [`ArchiveValidator::with_max_depth`](super::ArchiveValidator::with_max_depth) for more
+    /// details.
+    #[inline]
+    pub fn with_max_depth(bytes: &'a [u8], max_errors: usize, max_subtree_depth: usize) -> Self {
+        Self {
+            inner: ValidatorBuilder::new()
+                .with_max_depth(max_subtree_depth)
+                .build(bytes),
+            base: bytes.as_ptr(),
+            max_errors: max_errors.max(1),
+            errors: Vec::new(),
+            fatal_offset: None,
+        }
+    }
+
+    /// Returns the recoverable errors that have been recorded so far.
+    #[inline]
+    pub fn errors(&self) -> &[CollectedError<DefaultValidatorError>] {
+        &self.errors
+    }
+
+    /// Consumes the validator and returns the recoverable errors that were recorded.
+    #[inline]
+    pub fn into_errors(self) -> Vec<CollectedError<DefaultValidatorError>> {
+        self.errors
+    }
+
+    /// Returns the offset of the last error that stopped validation, if it's known.
+    #[inline]
+    pub fn fatal_offset(&self) -> Option<usize> {
+        self.fatal_offset
+    }
+
+    fn offset_of(&self, ptr: *const u8) -> Option<usize> {
+        (ptr as usize).checked_sub(self.base as usize)
+    }
+
+    fn error_offset(&self, error: &DefaultValidatorError) -> Option<usize> {
+        match error {
+            DefaultValidatorError::ArchiveError(e) => match e {
+                ArchiveError::Overflow { base, .. } | ArchiveError::OutOfBounds { base, .. } => {
+                    self.offset_of(*base)
+                }
+                ArchiveError::Overrun { ptr, .. }
+                | ArchiveError::Unaligned { ptr, .. }
+                | ArchiveError::SubtreePointerOutOfBounds { ptr, .. }
+                | ArchiveError::SubtreePointerOverrun { ptr, .. } => self.offset_of(*ptr),
+                ArchiveError::Underaligned { .. }
+                | ArchiveError::RangePoppedOutOfOrder { .. }
+                | ArchiveError::UnpoppedSubtreeRanges { .. }
+                | ArchiveError::ExceededMaximumSubtreeDepth { .. }
+                | ArchiveError::LayoutError { .. } => None,
+            },
+            DefaultValidatorError::SharedError(_) => None,
+        }
+    }
+
+    /// Records the error if there's room for it, and returns it otherwise.
+    fn recover(
+        &mut self,
+        offset: Option<usize>,
+        error: DefaultValidatorError,
+    ) -> Result<(), DefaultValidatorError> {
+        if self.errors.len() + 1 < self.max_errors {
+            self.errors.push(CollectedError { offset, error });
+            Ok(())
+        } else {
+            self.fatal_offset = offset;
+            Err(error)
+        }
+    }
+
+    fn fatal<T>(
+        &mut self,
+        result: Result<T, DefaultValidatorError>,
+    ) -> Result<T, DefaultValidatorError> {
+        if let Err(error) = &result {
+            self.fatal_offset = self.error_offset(error);
+        }
+        result
+    }
+}
+
+impl<'a> Fallible for CollectingValidator<'a> {
+    type Error = DefaultValidatorError;
+}
+
+impl<'a> ArchiveContext for CollectingValidator<'a> {
+    type PrefixRange = <DefaultValidator<'a> as ArchiveContext>::PrefixRange;
+    type SuffixRange = <DefaultValidator<'a> as ArchiveContext>::SuffixRange;
+
+    #[inline]
+    unsafe fn bounds_check_ptr(
+        &mut self,
+        base: *const u8,
+        offset: isize,
+    ) -> Result<*const u8, Self::Error> {
+        let result = self.inner.bounds_check_ptr(base, offset);
+        self.fatal(result)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        let result = self.inner.bounds_check_layout(data_address, layout);
+        self.fatal(result)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_subtree_ptr_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        match self
+            .inner
+            .bounds_check_subtree_ptr_layout(data_address, layout)
+        {
+            Err(
+                error @ DefaultValidatorError::ArchiveError(
+                    ArchiveError::SubtreePointerOutOfBounds { .. }
+                    | ArchiveError::SubtreePointerOverrun { .. },
+                ),
+            ) => self.recover(self.offset_of(data_address), error),
+            result => self.fatal(result),
+        }
+    }
+
+    #[inline]
+    unsafe fn push_prefix_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Self::PrefixRange, Self::Error> {
+        let result = self.inner.push_prefix_subtree_range(root, end);
+        self.fatal(result)
+    }
+
+    #[inline]
+    fn pop_prefix_range(&mut self, range: Self::PrefixRange) -> Result<(), Self::Error> {
+        let result = self.inner.pop_prefix_range(range);
+        self.fatal(result)
+    }
+
+    #[inline]
+    unsafe fn push_suffix_subtree_range(
+        &mut self,
+        start: *const u8,
+        root: *const u8,
+    ) -> Result<Self::SuffixRange, Self::Error> {
+        let result = self.inner.push_suffix_subtree_range(start, root);
+        self.fatal(result)
+    }
+
+    #[inline]
+    fn pop_suffix_range(&mut self, range: Self::SuffixRange) -> Result<(), Self::Error> {
+        let result = self.inner.pop_suffix_range(range);
+        self.fatal(result)
+    }
+
+    #[inline]
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        let result = self.inner.finish();
+        self.fatal(result)
+    }
+
+    #[inline]
+    fn wrap_layout_error(error: LayoutError) -> Self::Error {
+        DefaultValidator::wrap_layout_error(error)
+    }
+}
+
+impl<'a> SharedContext for CollectingValidator<'a> {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        ptr: *const u8,
+        type_id: TypeId,
+    ) -> Result<bool, Self::Error> {
+        match self.inner.register_shared_ptr(ptr, type_id) {
+            Err(error @ DefaultValidatorError::SharedError(SharedError::TypeMismatch { .. })) => {
+                // The memory was already checked as the previous type, so skip checking it again
+                self.recover(self.offset_of(ptr), error).map(|()| false)
+            }
+            result => self.fatal(result),
+        }
+    }
+}
+
+/// Checks the given archive for a root of the given type and collects up to `max_errors` errors.
+///
+/// Unlike [`check_archived_root`](super::check_archived_root), this continues past errors that can
+/// be safely recovered from and returns all of them together. See [`CollectingValidator`] for which
+/// errors are recoverable. The last error in the returned list may be one that couldn't be
+/// recovered from, in which case validation stopped there.
+///
+/// Offsets are known for errors reported by the validator. Errors reported by `CheckBytes`
+/// implementations (like an invalid enum tag) don't have an offset unless they were caused by a
+/// validator error.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     ser::{Serializer, serializers::AllocSerializer},
+///     validation::validators::check_archived_root_collect_errors,
+/// };
+///
+/// let value = vec!["a string long enough to be stored out of line".to_string()];
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// let archived = check_archived_root_collect_errors::<Vec<String>>(&bytes, 16).unwrap();
+/// assert_eq!(archived[0], value[0]);
+///
+/// let errors = check_archived_root_collect_errors::<Vec<String>>(&bytes[1..], 16).unwrap_err();
+/// assert!(!errors.is_empty());
+/// ```
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn check_archived_root_collect_errors<'a, T: Archive>(
+    bytes: &'a [u8],
+    max_errors: usize,
+) -> Result<
+    &'a T::Archived,
+    Vec<CollectedError<CheckTypeError<T::Archived, CollectingValidator<'a>>>>,
+>
+where
+    T::Archived: CheckBytes<CollectingValidator<'a>>,
+{
+    check_archived_root_collect_errors_with_max_depth::<T>(bytes, max_errors, usize::MAX)
+}
+
+/// Checks the given archive for a root of the given type, collects up to `max_errors` errors, and
+/// validates subtrees down to a maximum depth.
+///
+/// See [`check_archived_root_collect_errors`] and [`CollectingValidator::with_max_depth`] for more
+/// details.
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn check_archived_root_collect_errors_with_max_depth<'a, T: Archive>(
+    bytes: &'a [u8],
+    max_errors: usize,
+    max_subtree_depth: usize,
+) -> Result<
+    &'a T::Archived,
+    Vec<CollectedError<CheckTypeError<T::Archived, CollectingValidator<'a>>>>,
+>
+where
+    T::Archived: CheckBytes<CollectingValidator<'a>>,
+{
+    let mut validator = CollectingValidator::with_max_depth(bytes, max_errors, max_subtree_depth);
+    let result = check_archived_root_with_context::<T, CollectingValidator>(bytes, &mut validator);
+    let fatal_offset = validator.fatal_offset();
+    let mut errors = validator
+        .into_errors()
+        .into_iter()
+        .map(|e| CollectedError {
+            offset: e.offset,
+            error: CheckArchiveError::ContextError(e.error),
+        })
+        .collect::<Vec<_>>();
+
+    match result {
+        Ok(root) if errors.is_empty() => Ok(root),
+        Ok(_) => Err(errors),
+        Err(error) => {
+            errors.push(CollectedError {
+                offset: fatal_offset,
+                error,
+            });
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! Compaction of archives by copying the data that's reachable from the root.
+
+use crate::{
+    rel_ptr::Offset,
+    string::repr::{OFFSET_BYTES, OFFSET_POS},
+    validation::{
+        check_archived_root_with_context,
+        validators::{DefaultValidator, DefaultValidatorError},
+        ArchiveContext, CheckTypeError, SharedContext,
+    },
+    AlignedVec, Archive, Archived, Fallible, FixedIsize,
+};
+#[cfg(not(feature = "std"))]
+use ::alloc::vec::Vec;
+use bytecheck::CheckBytes;
+use core::{
+    alloc::{Layout, LayoutError},
+    any::TypeId,
+    fmt,
+    mem::size_of,
+    ptr,
+};
+
+/// Errors that can occur while compacting an archive.
+#[derive(Debug)]
+pub enum CompactError<E> {
+    /// The archive failed to validate
+    CheckError(E),
+    /// A relative pointer was checked while validating the archive, but the memory it points to
+    /// wasn't
+    UnsupportedPointer {
+        /// The position of the pointer in the original archive
+        pos: usize,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for CompactError<E> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompactError::CheckError(e) => write!(f, "archive check error: {}", e),
+            CompactError::UnsupportedPointer { pos } => {
+                write!(f, "unsupported pointer at position {}", pos)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for CompactError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                CompactError::CheckError(e) => Some(e as &dyn Error),
+                CompactError::UnsupportedPointer { .. } => None,
+            }
+        }
+    }
+};
+
+#[derive(Clone, Copy, Debug)]
+struct Region {
+    start: usize,
+    end: usize,
+    align: usize,
+}
+
+/// A validator that records the memory and pointers that are reachable from the root of an
+/// archive.
+///
+/// This is the validator that [`compact`] checks archives with. All validation is forwarded to a
+/// [`DefaultValidator`].
+#[derive(Debug)]
+pub struct CompactValidator<'a> {
+    inner: DefaultValidator<'a>,
+    base: usize,
+    regions: Vec<Region>,
+    pointers: Vec<(usize, usize)>,
+}
+
+impl<'a> CompactValidator<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            inner: DefaultValidator::new(bytes),
+            base: bytes.as_ptr() as usize,
+            regions: Vec::new(),
+            pointers: Vec::new(),
+        }
+    }
+
+    fn record_region(&mut self, data_address: *const u8, layout: &Layout) {
+        let start = data_address as usize - self.base;
+        self.regions.push(Region {
+            start,
+            end: start + layout.size(),
+            align: layout.align(),
+        });
+    }
+}
+
+impl<'a> Fallible for CompactValidator<'a> {
+    type Error = DefaultValidatorError;
+}
+
+impl<'a> ArchiveContext for CompactValidator<'a> {
+    type PrefixRange = <DefaultValidator<'a> as ArchiveContext>::PrefixRange;
+    type SuffixRange = <DefaultValidator<'a> as ArchiveContext>::SuffixRange;
+
+    #[inline]
+    unsafe fn bounds_check_ptr(
+        &mut self,
+        base: *const u8,
+        offset: isize,
+    ) -> Result<*const u8, Self::Error> {
+        let target = self.inner.bounds_check_ptr(base, offset)?;
+        self.pointers
+            .push((base as usize - self.base, target as usize - self.base));
+        Ok(target)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.inner.bounds_check_layout(data_address, layout)?;
+        self.record_region(data_address, layout);
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn bounds_check_subtree_ptr_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .bounds_check_subtree_ptr_layout(data_address, layout)?;
+        self.record_region(data_address, layout);
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn push_prefix_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Self::PrefixRange, Self::Error> {
+        self.inner.push_prefix_subtree_range(root, end)
+    }
+
+    #[inline]
+    fn pop_prefix_range(&mut self, range: Self::PrefixRange) -> Result<(), Self::Error> {
+        self.inner.pop_prefix_range(range)
+    }
+
+    #[inline]
+    unsafe fn push_suffix_subtree_range(
+        &mut self,
+        start: *const u8,
+        root: *const u8,
+    ) -> Result<Self::SuffixRange, Self::Error> {
+        self.inner.push_suffix_subtree_range(start, root)
+    }
+
+    #[inline]
+    fn pop_suffix_range(&mut self, range: Self::SuffixRange) -> Result<(), Self::Error> {
+        self.inner.pop_suffix_range(range)
+    }
+
+    #[inline]
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        self.inner.finish()
+    }
+
+    #[inline]
+    fn wrap_layout_error(error: LayoutError) -> Self::Error {
+        DefaultValidator::wrap_layout_error(error)
+    }
+}
+
+impl<'a> SharedContext for CompactValidator<'a> {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        ptr: *const u8,
+        type_id: TypeId,
+    ) -> Result<bool, Self::Error> {
+        self.inner.register_shared_ptr(ptr, type_id)
+    }
+}
+
+/// Returns the position in the compacted archive of `size` bytes at `pos` in the original archive,
+/// if they were copied.
+fn relocate(regions: &[(Region, usize)], pos: usize, size: usize) -> Option<usize> {
+    let i = regions.partition_point(|(region, _)| region.start <= pos);
+    let (region, new_start) = regions[..i].last()?;
+    (pos + size <= region.end).then(|| new_start + (pos - region.start))
+}
+
+#[derive(Clone, Copy)]
+enum PointerKind {
+    RelPtr,
+    String,
+}
+
+/// Returns how the pointer at `pos` stores its offset to `target`, if it's stored in a way that can
+/// be rewritten.
+///
+/// `regions` must be sorted.
+fn stored_pointer(
+    bytes: &[u8],
+    regions: &[Region],
+    pos: usize,
+    target: usize,
+) -> Option<PointerKind> {
+    let offset = target as isize - pos as isize;
+
+    if pos + size_of::<Archived<isize>>() <= bytes.len() {
+        // SAFETY: the offset is in bounds of the archive
+        let stored =
+            unsafe { ptr::read_unaligned(bytes.as_ptr().add(pos).cast::<Archived<isize>>()) };
+        if stored.to_isize() == offset {
+            return Some(PointerKind::RelPtr);
+        }
+    }
+
+    // Out-of-line strings store their length followed by their offset in little-endian, relative
+    // to the start of the string. Their offsets are always negative, so they can't be mistaken for
+    // a relative pointer to the length. The bytes of the string must have been checked as well.
+    let mut stored = [0; OFFSET_BYTES];
+    stored.copy_from_slice(bytes.get(pos + OFFSET_POS..pos + OFFSET_POS + OFFSET_BYTES)?);
+    if FixedIsize::from_le_bytes(stored) as isize != offset {
+        return None;
+    }
+    // SAFETY: the length is in bounds of the archive because the offset after it is
+    let len = unsafe { ptr::read_unaligned(bytes.as_ptr().add(pos).cast::<Archived<usize>>()) };
+    let end = target + from_archived!(len) as usize;
+    let i = regions.partition_point(|region| (region.start, region.end) < (target, end));
+    regions
+        .get(i)
+        .filter(|region| region.start == target && region.end == end)
+        .map(|_| PointerKind::String)
+}
+
+/// Checks the given archive for a root of the given type and copies the data that's reachable from
+/// the root into a new archive.
+///
+/// This is a copying garbage collector for archives. Every object that's reached while validating
+/// the archive is copied into the new archive in its original order, and everything else (like
+/// leftover data from values that were replaced or built in several passes) is left out. Relative
+/// pointers are rewritten to point into the compacted layout, and each object keeps its alignment.
+///
+/// The archived data is copied without being deserialized, so `T` only has to be checkable and no
+/// information is lost. Shared values are only copied once, and shared pointers that pointed to the
+/// same value still do afterward.
+///
+/// Relative pointers with an `Archived<isize>` offset, like [`RelPtr`](crate::RelPtr), and the
+/// pointers of archived strings are rewritten. Other offsets that are checked while validating, like
+/// the sizes of B-tree nodes, can't be rewritten. Instead, all of the bytes between their start and
+/// end are kept so the offsets stay the same.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     ser::{Serializer, serializers::AllocSerializer},
+///     validation::validators::{check_archived_root, compact, unreachable_bytes},
+/// };
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// // This value isn't reachable from the root
+/// serializer.serialize_value(&"leftover data from a previous value".to_string()).unwrap();
+/// serializer.serialize_value(&vec!["the root".to_string()]).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// assert!(unreachable_bytes::<Vec<String>>(&bytes).unwrap() > 0);
+///
+/// let compacted = compact::<Vec<String>>(&bytes).unwrap();
+/// assert!(compacted.len() < bytes.len());
+/// assert_eq!(unreachable_bytes::<Vec<String>>(&compacted).unwrap(), 0);
+/// assert_eq!(check_archived_root::<Vec<String>>(&compacted).unwrap()[0], "the root");
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn compact<'a, T: Archive>(
+    bytes: &'a [u8],
+) -> Result<AlignedVec, CompactError<CheckTypeError<T::Archived, CompactValidator<'a>>>>
+where
+    T::Archived: CheckBytes<CompactValidator<'a>>,
+{
+    let mut validator = CompactValidator::new(bytes);
+    check_archived_root_with_context::<T, CompactValidator>(bytes, &mut validator)
+        .map_err(CompactError::CheckError)?;
+
+    let mut regions = validator.regions;
+    regions.sort_unstable_by_key(|region| (region.start, region.end));
+    let mut pointers = Vec::new();
+    let mut pinned = Vec::new();
+    // The first pointer checked is the root, which is relative to the start of the archive instead
+    // of being stored in it
+    for &(pos, target) in validator.pointers.iter().skip(1) {
+        match stored_pointer(bytes, &regions, pos, target) {
+            Some(kind) => pointers.push((pos, target, kind)),
+            // Offsets that aren't stored as relative pointers (like the sizes of B-tree nodes) can't
+            // be rewritten, so the bytes between them are kept together instead
+            None => pinned.push(Region {
+                start: pos.min(target),
+                end: pos.max(target),
+                align: 1,
+            }),
+        }
+    }
+
+    // Merge the overlapping and adjacent regions so each byte is only copied once
+    regions.extend(pinned);
+    regions.sort_unstable_by_key(|region| region.start);
+    let mut merged = Vec::<(Region, usize)>::new();
+    for region in regions {
+        match merged.last_mut() {
+            Some((last, _)) if region.start <= last.end => {
+                last.end = last.end.max(region.end);
+                last.align = last.align.max(region.align);
+            }
+            _ => merged.push((region, 0)),
+        }
+    }
+
+    let mut result = AlignedVec::with_capacity(merged.iter().map(|(r, _)| r.end - r.start).sum());
+    for (region, new_start) in merged.iter_mut() {
+        // Keep the region at the same position modulo its alignment
+        let padding = region.start.wrapping_sub(result.len()) & (region.align - 1);
+        result.resize(result.len() + padding, 0);
+        *new_start = result.len();
+        result.extend_from_slice(&bytes[region.start..region.end]);
+    }
+
+    for (pos, target, kind) in pointers {
+        let unsupported = || CompactError::UnsupportedPointer { pos };
+        let new_target = relocate(&merged, target, 0).ok_or_else(unsupported)?;
+        match kind {
+            PointerKind::RelPtr => {
+                let new_pos =
+                    relocate(&merged, pos, size_of::<Archived<isize>>()).ok_or_else(unsupported)?;
+                let offset = <Archived<isize> as Offset>::between(new_pos, new_target)
+                    .map_err(|_| unsupported())?;
+                // SAFETY: the pointer was relocated into the compacted archive
+                unsafe {
+                    ptr::write_unaligned(
+                        result.as_mut_ptr().add(new_pos).cast::<Archived<isize>>(),
+                        offset,
+                    );
+                }
+            }
+            PointerKind::String => {
+                let new_pos =
+                    relocate(&merged, pos, OFFSET_POS + OFFSET_BYTES).ok_or_else(unsupported)?;
+                let offset = FixedIsize::try_from(new_target as isize - new_pos as isize)
+                    .map_err(|_| unsupported())?;
+                result[new_pos + OFFSET_POS..new_pos + OFFSET_POS + OFFSET_BYTES]
+                    .copy_from_slice(&offset.to_le_bytes());
+            }
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,118 @@
+//! Extraction of archived subtrees as standalone archives.
+
+use crate::{
+    validation::{check_archived_value_with_context, validators::DefaultValidator, CheckTypeError},
+    AlignedVec, Archive,
+};
+use bytecheck::CheckBytes;
+use core::{fmt, mem::size_of};
+
+/// Errors that can occur while extracting a subtree from an archive.
+#[derive(Debug)]
+pub enum ExtractSubtreeError<E> {
+    /// The archived value was not located inside of the archive
+    NotInArchive,
+    /// The subtree rooted at the archived value failed to validate
+    CheckError(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ExtractSubtreeError<E> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractSubtreeError::NotInArchive => {
+                write!(f, "archived value is not located inside of the archive")
+            }
+            ExtractSubtreeError::CheckError(e) => write!(f, "subtree check error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for ExtractSubtreeError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                ExtractSubtreeError::NotInArchive => None,
+                ExtractSubtreeError::CheckError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+/// Copies the subtree rooted at an archived value out of an archive so it can be used as a
+/// standalone archive.
+///
+/// `value` must be a reference into `bytes`, for example a field of a root that was checked with
+/// [`check_archived_root`](super::check_archived_root). The subtree is validated starting from
+/// `value`, and the bytes from the first byte it can reach to the end of `value` are copied into a
+/// new buffer. The returned buffer can be accessed with [`archived_root`](crate::archived_root) or
+/// [`check_archived_root`](super::check_archived_root) as an archive of `T`.
+///
+/// Relative pointers don't need to be rewritten because the subtree keeps the same relative
+/// layout. Bytes that lie between the parts of the subtree but aren't reachable from it, like the
+/// data of sibling fields, are copied as well. The start of the copy is rounded down to a multiple
+/// of [`AlignedVec::ALIGNMENT`] to keep the subtree aligned.
+///
+/// Data that the subtree shares with the rest of the archive through shared pointers like `Rc` and
+/// `Arc` is included in the copy. However, the subtree must be valid on its own: if shared data was
+/// serialized by the parent before it would have been serialized by the subtree, the subtree is
+/// laid out out of order and extraction fails with [`ExtractSubtreeError::CheckError`].
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     archived_root, check_archived_root,
+///     ser::{Serializer, serializers::AllocSerializer},
+///     validation::validators::extract_subtree,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Envelope {
+///     header: String,
+///     payload: Vec<String>,
+/// }
+///
+/// let value = Envelope {
+///     header: "routing information for the proxy".to_string(),
+///     payload: vec!["the payload of the message".to_string()],
+/// };
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = check_archived_root::<Envelope>(&bytes).unwrap();
+///
+/// let payload = extract_subtree::<Vec<String>>(&bytes, &archived.payload).unwrap();
+/// let archived_payload = unsafe { archived_root::<Vec<String>>(&payload) };
+/// assert_eq!(archived_payload, &archived.payload);
+/// ```
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn extract_subtree<'a, T: Archive>(
+    bytes: &'a [u8],
+    value: &T::Archived,
+) -> Result<AlignedVec, ExtractSubtreeError<CheckTypeError<T::Archived, DefaultValidator<'a>>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    let pos = (value as *const T::Archived as usize)
+        .checked_sub(bytes.as_ptr() as usize)
+        .filter(|&pos| pos <= bytes.len() && bytes.len() - pos >= size_of::<T::Archived>())
+        .ok_or(ExtractSubtreeError::NotInArchive)?;
+
+    let mut validator = DefaultValidator::new(bytes);
+    check_archived_value_with_context::<T, DefaultValidator>(bytes, pos, &mut validator)
+        .map_err(ExtractSubtreeError::CheckError)?;
+
+    let start = validator.claimed_start() & !(AlignedVec::ALIGNMENT - 1);
+    let end = pos + size_of::<T::Archived>();
+
+    let mut result = AlignedVec::with_capacity(end - start);
+    result.extend_from_slice(&bytes[start..end]);
+    Ok(result)
+}
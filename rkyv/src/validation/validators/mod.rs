@@ -1,7 +1,13 @@
 //! Validators that can check archived types.
 
 mod archive;
+mod collect;
+mod compact;
+mod extract;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod shared;
+mod tag;
 mod util;
 
 use crate::{
@@ -13,12 +19,18 @@ use crate::{
 };
 pub use archive::*;
 use bytecheck::CheckBytes;
+pub use collect::*;
+pub use compact::*;
 use core::{
     alloc::{Layout, LayoutError},
     any::TypeId,
     fmt,
 };
+pub use extract::*;
+#[cfg(feature = "rayon")]
+pub use parallel::*;
 pub use shared::*;
+pub use tag::*;
 pub use util::*;
 
 /// The default validator error.
@@ -58,6 +70,7 @@ const _: () = {
 pub struct DefaultValidator<'a> {
     archive: ArchiveValidator<'a>,
     shared: SharedValidator,
+    shallow: bool,
 }
 
 impl<'a> DefaultValidator<'a> {
@@ -67,8 +80,137 @@ impl<'a> DefaultValidator<'a> {
         Self {
             archive: ArchiveValidator::new(bytes),
             shared: SharedValidator::new(),
+            shallow: false,
         }
     }
+
+    /// Creates a new validator from a byte range that tracks at most `max_shared_ptrs` distinct
+    /// shared pointers.
+    ///
+    /// See [`SharedValidator::with_max_shared_ptrs`] for more details.
+    #[inline]
+    pub fn with_max_shared_ptrs(bytes: &'a [u8], max_shared_ptrs: usize) -> Self {
+        Self {
+            archive: ArchiveValidator::new(bytes),
+            shared: SharedValidator::with_max_shared_ptrs(max_shared_ptrs),
+            shallow: false,
+        }
+    }
+
+    /// Returns the number of bytes that have been claimed by subtrees so far.
+    ///
+    /// See [`ArchiveValidator::claimed_bytes`] for more details.
+    #[inline]
+    pub fn claimed_bytes(&self) -> usize {
+        self.archive.claimed_bytes()
+    }
+
+    /// Returns the position of the first byte that has been claimed by a subtree so far.
+    ///
+    /// See [`ArchiveValidator::claimed_start`] for more details.
+    #[inline]
+    pub fn claimed_start(&self) -> usize {
+        self.archive.claimed_start()
+    }
+}
+
+/// A builder that configures the limits of a [`DefaultValidator`].
+///
+/// By default, there are no limits on the subtree depth or the number of shared pointers, which
+/// matches [`DefaultValidator::new`] and [`check_archived_root`].
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     ser::{Serializer, serializers::AllocSerializer},
+///     validation::validators::ValidatorBuilder,
+/// };
+///
+/// let value = vec![vec![1u32, 2, 3], vec![4, 5]];
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// let archived = ValidatorBuilder::new()
+///     .with_max_depth(3)
+///     .with_max_shared_ptrs(0)
+///     .access::<Vec<Vec<u32>>>(&bytes)
+///     .unwrap();
+/// assert_eq!(archived[1], [4, 5]);
+///
+/// // The root, the outer vec, and the inner vecs are validated as nested subtrees
+/// assert!(ValidatorBuilder::new()
+///     .with_max_depth(2)
+///     .access::<Vec<Vec<u32>>>(&bytes)
+///     .is_err());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ValidatorBuilder {
+    max_subtree_depth: usize,
+    max_shared_ptrs: usize,
+}
+
+impl ValidatorBuilder {
+    /// Creates a new validator builder with no limits.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            max_subtree_depth: usize::MAX,
+            max_shared_ptrs: usize::MAX,
+        }
+    }
+
+    /// Sets the maximum depth that subtrees may be validated down to.
+    ///
+    /// See [`ArchiveValidator::with_max_depth`] for more details.
+    #[inline]
+    pub fn with_max_depth(mut self, max_subtree_depth: usize) -> Self {
+        self.max_subtree_depth = max_subtree_depth;
+        self
+    }
+
+    /// Sets the maximum number of distinct shared pointers that may be tracked.
+    ///
+    /// See [`SharedValidator::with_max_shared_ptrs`] for more details.
+    #[inline]
+    pub fn with_max_shared_ptrs(mut self, max_shared_ptrs: usize) -> Self {
+        self.max_shared_ptrs = max_shared_ptrs;
+        self
+    }
+
+    /// Creates a validator for the given bytes with the configured limits.
+    #[inline]
+    pub fn build(self, bytes: &[u8]) -> DefaultValidator<'_> {
+        DefaultValidator {
+            archive: ArchiveValidator::with_max_depth(bytes, self.max_subtree_depth),
+            shared: SharedValidator::with_max_shared_ptrs(self.max_shared_ptrs),
+            shallow: false,
+        }
+    }
+
+    /// Checks the given archive for a root of the given type with the configured limits.
+    ///
+    /// This is equivalent to [`check_archived_root`] with a validator created by
+    /// [`build`](ValidatorBuilder::build).
+    #[inline]
+    pub fn access<'a, T: Archive>(
+        self,
+        bytes: &'a [u8],
+    ) -> Result<&'a T::Archived, CheckTypeError<T::Archived, DefaultValidator<'a>>>
+    where
+        T::Archived: CheckBytes<DefaultValidator<'a>>,
+    {
+        let mut validator = self.build(bytes);
+        check_archived_root_with_context::<T, DefaultValidator>(bytes, &mut validator)
+    }
+}
+
+impl Default for ValidatorBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> Fallible for DefaultValidator<'a> {
@@ -159,6 +301,11 @@ impl<'a> ArchiveContext for DefaultValidator<'a> {
     fn wrap_layout_error(error: LayoutError) -> Self::Error {
         DefaultValidatorError::ArchiveError(ArchiveValidator::wrap_layout_error(error))
     }
+
+    #[inline]
+    fn follows_pointers(&self) -> bool {
+        !self.shallow
+    }
 }
 
 impl<'a> SharedContext for DefaultValidator<'a> {
@@ -236,3 +383,341 @@ where
     let mut validator = DefaultValidator::new(bytes);
     check_archived_root_with_context::<T, DefaultValidator>(bytes, &mut validator)
 }
+
+/// Checks only the bytes of the root of the given archive, without following any relative
+/// pointers.
+///
+/// This validates the inline bytes of the root object, like enum tags, lengths, and scalar fields,
+/// along with the relative pointers themselves. The objects they point to, and the rest of the
+/// buffer, aren't touched. This makes it a cheap way to reject bytes that are obviously not an
+/// archive of the given type before committing to full validation.
+///
+/// **This does not guarantee that the archive can be accessed safely.** It returns `Ok` even if the
+/// data that the root points to is invalid or out of bounds, which is why it doesn't return a
+/// reference to the root. Use [`check_archived_root`] to access the archive.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     ser::{Serializer, serializers::AllocSerializer},
+///     validation::validators::{check_archived_root, check_root_shallow},
+/// };
+///
+/// let value = vec!["a string long enough to be stored out of line".to_string()];
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let mut bytes = serializer.into_serializer().into_inner();
+///
+/// // Corrupt the string, which the root only points to
+/// bytes[0] = 0xff;
+///
+/// assert!(check_root_shallow::<Vec<String>>(&bytes).is_ok());
+/// assert!(check_archived_root::<Vec<String>>(&bytes).is_err());
+///
+/// // Buffers too small to hold the root are still rejected
+/// assert!(check_root_shallow::<Vec<String>>(&bytes[..4]).is_err());
+/// ```
+#[inline]
+pub fn check_root_shallow<'a, T: Archive>(
+    bytes: &'a [u8],
+) -> Result<(), CheckTypeError<T::Archived, DefaultValidator<'a>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    // The shallow validator can't be constructed outside of this function because the root it
+    // checks can't be accessed safely
+    let mut validator = DefaultValidator {
+        archive: ArchiveValidator::new(bytes),
+        shared: SharedValidator::new(),
+        shallow: true,
+    };
+    check_archived_root_with_context::<T, DefaultValidator>(bytes, &mut validator)?;
+    Ok(())
+}
+
+/// Checks the given archive for a root of the given type and returns the number of bytes reachable
+/// from it.
+///
+/// The reachable bytes are the bytes of the root and every object it points to, directly or
+/// indirectly. Shared pointers are only counted once. Padding between objects, leftover data from
+/// previous serializations, and any other bytes that the root doesn't point to are unreachable. See
+/// [`unreachable_bytes`] for the complement.
+///
+/// # Examples
+/// ```
+/// use rkyv::{ser::{Serializer, serializers::AllocSerializer}, validation::validators::reachable_bytes};
+///
+/// let value = vec!["a string long enough to be stored out of line".to_string()];
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// let reachable = reachable_bytes::<Vec<String>>(&bytes).unwrap();
+/// assert!(reachable <= bytes.len());
+/// ```
+#[inline]
+pub fn reachable_bytes<'a, T: Archive>(
+    bytes: &'a [u8],
+) -> Result<usize, CheckTypeError<T::Archived, DefaultValidator<'a>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    let mut validator = DefaultValidator::new(bytes);
+    check_archived_root_with_context::<T, DefaultValidator>(bytes, &mut validator)?;
+    Ok(validator.claimed_bytes())
+}
+
+/// Checks the given archive for a root of the given type and returns the number of bytes that are
+/// not reachable from it.
+///
+/// This is the length of the archive minus the result of [`reachable_bytes`].
+#[inline]
+pub fn unreachable_bytes<'a, T: Archive>(
+    bytes: &'a [u8],
+) -> Result<usize, CheckTypeError<T::Archived, DefaultValidator<'a>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    Ok(bytes.len() - reachable_bytes::<T>(bytes)?)
+}
+
+/// Errors that can occur when checking a candidate archive against a reference archive.
+#[derive(Debug)]
+pub enum PairCheckError<E> {
+    /// The reference archive failed to validate.
+    ReferenceCheckError(E),
+    /// The candidate archive is too small to contain a root of the expected size.
+    RootSizeMismatch {
+        /// The size of the root
+        expected: usize,
+        /// The length of the candidate archive
+        actual: usize,
+    },
+    /// The root of the candidate archive isn't aligned the same way as the root of the reference.
+    RootAlignmentMismatch {
+        /// The alignment of the root
+        align: usize,
+        /// The offset of the reference root from the alignment
+        expected: usize,
+        /// The offset of the candidate root from the alignment
+        actual: usize,
+    },
+    /// The candidate archive failed to validate.
+    CandidateCheckError(E),
+}
+
+impl<E: fmt::Display> fmt::Display for PairCheckError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReferenceCheckError(e) => write!(f, "reference check error: {}", e),
+            Self::RootSizeMismatch { expected, actual } => write!(
+                f,
+                "root size mismatch: expected a root of {} bytes but the archive is only {} bytes",
+                expected, actual
+            ),
+            Self::RootAlignmentMismatch {
+                align,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "root alignment mismatch: expected the root to be {} bytes past a multiple of {} but it was {} bytes past",
+                expected, align, actual
+            ),
+            Self::CandidateCheckError(e) => write!(f, "candidate check error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for PairCheckError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::ReferenceCheckError(e) | Self::CandidateCheckError(e) => {
+                    Some(e as &dyn Error)
+                }
+                Self::RootSizeMismatch { .. } | Self::RootAlignmentMismatch { .. } => None,
+            }
+        }
+    }
+};
+
+/// Checks a candidate archive and a reference archive for a root of type `T`.
+///
+/// The reference should be an archive produced locally, and the candidate is usually an archive
+/// received from somewhere else. Both archives are validated as `T`, and the returned error says
+/// which one failed. A reference that fails to validate means that the local build can't check
+/// its own archives, so errors from the candidate can't be trusted either.
+///
+/// Before the candidate is validated, it must be large enough to hold the root and place it at the
+/// same alignment as the root of the reference. A mismatch in the layout of the root is reported
+/// instead of the less descriptive errors that validation would produce.
+///
+/// Archives don't record the layout they were produced with, so this can only catch differences in
+/// layout that make the candidate invalid or change where its root is. A candidate produced with a
+/// different layout of `T` that happens to be valid under the local layout is accepted.
+///
+/// # Examples
+/// ```
+/// use rkyv::{ser::{Serializer, serializers::AllocSerializer}, validation::validators::check_archived_pair};
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&vec![1u32, 2, 3]).unwrap();
+/// let reference = serializer.into_serializer().into_inner();
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&vec![4u32, 5]).unwrap();
+/// let candidate = serializer.into_serializer().into_inner();
+///
+/// let archived = check_archived_pair::<Vec<u32>>(&reference, &candidate).unwrap();
+/// assert_eq!(archived.as_slice(), &[4, 5]);
+///
+/// assert!(check_archived_pair::<Vec<u32>>(&reference, &candidate[..2]).is_err());
+/// ```
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn check_archived_pair<'a, T: Archive>(
+    reference: &'a [u8],
+    candidate: &'a [u8],
+) -> Result<&'a T::Archived, PairCheckError<CheckTypeError<T::Archived, DefaultValidator<'a>>>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    let size = core::mem::size_of::<T::Archived>();
+    let align = core::mem::align_of::<T::Archived>();
+
+    check_archived_root::<T>(reference).map_err(PairCheckError::ReferenceCheckError)?;
+
+    if candidate.len() < size {
+        return Err(PairCheckError::RootSizeMismatch {
+            expected: size,
+            actual: candidate.len(),
+        });
+    }
+
+    let root_offset = |bytes: &[u8]| (bytes.as_ptr() as usize + bytes.len() - size) % align;
+    let expected = root_offset(reference);
+    let actual = root_offset(candidate);
+    if expected != actual {
+        return Err(PairCheckError::RootAlignmentMismatch {
+            align,
+            expected,
+            actual,
+        });
+    }
+
+    check_archived_root::<T>(candidate).map_err(PairCheckError::CandidateCheckError)
+}
+
+/// Errors that can occur when checking an archive that begins with a header.
+#[derive(Debug)]
+pub enum CheckHeaderError<H, T> {
+    /// The header failed to validate.
+    HeaderCheckError(H),
+    /// The header was valid, but it was rejected by the header check.
+    HeaderMismatch,
+    /// The body failed to validate.
+    BodyCheckError(T),
+}
+
+impl<H: fmt::Display, T: fmt::Display> fmt::Display for CheckHeaderError<H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HeaderCheckError(e) => write!(f, "header check error: {}", e),
+            Self::HeaderMismatch => write!(f, "header mismatch"),
+            Self::BodyCheckError(e) => write!(f, "body check error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<H: Error + 'static, T: Error + 'static> Error for CheckHeaderError<H, T> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                Self::HeaderCheckError(e) => Some(e as &dyn Error),
+                Self::HeaderMismatch => None,
+                Self::BodyCheckError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+/// The error type for [`access_with_header`].
+pub type AccessWithHeaderError<'a, H, T> = CheckHeaderError<
+    CheckTypeError<<H as Archive>::Archived, DefaultValidator<'a>>,
+    CheckTypeError<<T as Archive>::Archived, DefaultValidator<'a>>,
+>;
+
+/// Checks the given archive for a header of type `H` at the start of the bytes followed by a root
+/// of type `T` at the end.
+///
+/// The header is validated first and then passed to `check_header`, which can reject it (for
+/// example, if the magic bytes or format version don't match). If the header is accepted, the root
+/// is validated from the remaining bytes. The header must be located at position 0 and may not
+/// point to any out-of-line data, so it should be made up only of inline types like integers and
+/// arrays. This is the layout produced by serializing the header into an empty serializer and then
+/// serializing the root.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_with_header,
+///     ser::{Serializer, serializers::AllocSerializer},
+///     Archive,
+///     Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Header {
+///     magic: [u8; 4],
+///     version: u32,
+/// }
+///
+/// let header = Header {
+///     magic: *b"RKYV",
+///     version: 1,
+/// };
+/// let value = vec![1, 2, 3, 4];
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&header).unwrap();
+/// serializer.serialize_value(&value).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// let (header, archived) = access_with_header::<Header, Vec<i32>>(&bytes, |header| {
+///     header.magic == *b"RKYV" && header.version == 1
+/// })
+/// .unwrap();
+/// assert_eq!(header.version, 1);
+/// assert_eq!(archived.as_slice(), &[1, 2, 3, 4]);
+/// ```
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn access_with_header<'a, H: Archive, T: Archive>(
+    bytes: &'a [u8],
+    check_header: impl FnOnce(&H::Archived) -> bool,
+) -> Result<(&'a H::Archived, &'a T::Archived), AccessWithHeaderError<'a, H, T>>
+where
+    H::Archived: CheckBytes<DefaultValidator<'a>>,
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    // Checking the header claims its bytes, so the body can't overlap with it.
+    let mut validator = DefaultValidator::new(bytes);
+    let header = check_archived_value_with_context::<H, DefaultValidator>(bytes, 0, &mut validator)
+        .map_err(CheckHeaderError::HeaderCheckError)?;
+    if !check_header(header) {
+        return Err(CheckHeaderError::HeaderMismatch);
+    }
+    let root = check_archived_root_with_context::<T, DefaultValidator>(bytes, &mut validator)
+        .map_err(CheckHeaderError::BodyCheckError)?;
+    Ok((header, root))
+}
@@ -0,0 +1,130 @@
+//! Parallel validation of archived vecs with rayon.
+
+use crate::{
+    validation::{validators::ArchiveValidator, ArchiveContext, CheckArchiveError, CheckTypeError},
+    vec::ArchivedVec,
+    Archive,
+};
+use bytecheck::{CheckBytes, SliceCheckError};
+use core::mem::size_of;
+use rayon::prelude::*;
+
+/// The minimum number of elements that are checked together on one thread.
+const MIN_PAR_CHECK_LEN: usize = 256;
+
+/// The error type for [`par_check_archived_vec`].
+pub type ParCheckError<'a, T> =
+    CheckTypeError<ArchivedVec<<T as Archive>::Archived>, ArchiveValidator<'a>>;
+
+/// Checks the given archive for a root `Vec<T>`, checking its elements in parallel.
+///
+/// The header of the vec is checked first, then the elements are split between the threads of the
+/// current rayon thread pool and checked with separate validators. This can be much faster than
+/// [`check_archived_root`](crate::validation::validators::check_archived_root) for archives that
+/// contain millions of records.
+///
+/// Each validator only checks that the elements on its own thread don't claim the same memory, so
+/// two elements checked on different threads can point to overlapping subtrees without being
+/// rejected. This is fine for shared access, but the archive shouldn't be mutated afterwards.
+///
+/// Because the validators don't share a registry of shared pointers, the elements must be
+/// checkable with only an [`ArchiveValidator`]. Element types that contain shared pointers (like
+/// `Rc` and `Arc`) need a [`SharedContext`](crate::validation::SharedContext) and must be checked
+/// sequentially with `check_archived_root` instead.
+///
+/// If more than one element is invalid, the error may be for any of them.
+///
+/// # Examples
+/// ```
+/// use rkyv::{validation::validators::par_check_archived_vec, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Record {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// let records = (0..10_000)
+///     .map(|id| Record { id, name: format!("record {}", id) })
+///     .collect::<Vec<_>>();
+/// let bytes = rkyv::to_bytes::<_, 4096>(&records).unwrap();
+///
+/// let archived = par_check_archived_vec::<Record>(&bytes).unwrap();
+/// assert_eq!(archived[1234].name, "record 1234");
+/// ```
+pub fn par_check_archived_vec<'a, T: Archive>(
+    bytes: &'a [u8],
+) -> Result<&'a ArchivedVec<T::Archived>, ParCheckError<'a, T>>
+where
+    T::Archived: CheckBytes<ArchiveValidator<'a>>,
+    <T::Archived as CheckBytes<ArchiveValidator<'a>>>::Error: Send,
+{
+    let mut validator = ArchiveValidator::new(bytes);
+    let pos = bytes.len() as isize - size_of::<ArchivedVec<T::Archived>>() as isize;
+
+    unsafe {
+        let ptr = validator
+            .check_subtree_ptr::<ArchivedVec<T::Archived>>(bytes.as_ptr(), pos, ())
+            .map_err(CheckArchiveError::ContextError)?;
+
+        let range = validator
+            .push_prefix_subtree(ptr)
+            .map_err(CheckArchiveError::ContextError)?;
+        let result = ArchivedVec::check_bytes_with(ptr, &mut validator, |elements, _| {
+            par_check_elements::<T::Archived>(
+                bytes,
+                elements.cast::<u8>().offset_from(bytes.as_ptr()) as usize,
+                ptr_meta::metadata(elements),
+            )
+        })
+        .map_err(CheckArchiveError::CheckBytesError)?;
+        validator
+            .pop_prefix_range(range)
+            .map_err(CheckArchiveError::ContextError)?;
+
+        validator
+            .finish()
+            .map_err(CheckArchiveError::ContextError)?;
+        Ok(result)
+    }
+}
+
+/// Checks `len` elements starting at `offset` in parallel.
+///
+/// # Safety
+///
+/// The elements must be in bounds of `bytes` and properly aligned.
+unsafe fn par_check_elements<'a, T>(
+    bytes: &'a [u8],
+    offset: usize,
+    len: usize,
+) -> Result<(), SliceCheckError<T::Error>>
+where
+    T: CheckBytes<ArchiveValidator<'a>>,
+    T::Error: Send,
+{
+    // Pointers aren't `Send`, so each thread recomputes them from the bytes and the offset
+    let elements = move || bytes.as_ptr().add(offset).cast::<T>();
+
+    (0..len)
+        .into_par_iter()
+        .with_min_len(MIN_PAR_CHECK_LEN)
+        .try_for_each_init(
+            || {
+                // The subtrees of the elements must be located before the elements, just like when
+                // checking them sequentially
+                let mut validator = ArchiveValidator::new(bytes);
+                let start = elements().cast::<u8>();
+                let end = elements().add(len).cast::<u8>();
+                // The first range pushed can't exceed the maximum subtree depth
+                let _ = validator.push_prefix_subtree_range(start, end);
+                validator
+            },
+            |validator, index| {
+                T::check_bytes(elements().add(index), validator)
+                    .map(|_| ())
+                    .map_err(|error| SliceCheckError::CheckBytes { index, error })
+            },
+        )
+}
@@ -18,6 +18,11 @@ pub enum SharedError {
         /// The current type that the location is checked as
         current: TypeId,
     },
+    /// The maximum number of distinct shared pointers was exceeded.
+    ExceededMaximumSharedPointers {
+        /// The maximum number of distinct shared pointers that may be validated
+        max_shared_ptrs: usize,
+    },
 }
 
 impl fmt::Display for SharedError {
@@ -29,6 +34,11 @@ impl fmt::Display for SharedError {
                 "the same memory region has been claimed as two different types ({:?} and {:?})",
                 previous, current
             ),
+            SharedError::ExceededMaximumSharedPointers { max_shared_ptrs } => write!(
+                f,
+                "exceeded the maximum number of distinct shared pointers: {}",
+                max_shared_ptrs
+            ),
         }
     }
 }
@@ -40,7 +50,8 @@ const _: () = {
     impl Error for SharedError {
         fn source(&self) -> Option<&(dyn Error + 'static)> {
             match self {
-                SharedError::TypeMismatch { .. } => None,
+                SharedError::TypeMismatch { .. }
+                | SharedError::ExceededMaximumSharedPointers { .. } => None,
             }
         }
     }
@@ -50,6 +61,7 @@ const _: () = {
 #[derive(Debug)]
 pub struct SharedValidator {
     shared: HashMap<*const u8, TypeId>,
+    max_shared_ptrs: usize,
 }
 
 // SAFETY: SharedValidator is safe to send to another thread
@@ -64,9 +76,23 @@ impl SharedValidator {
     /// Wraps the given context and adds shared memory validation.
     #[inline]
     pub fn new() -> Self {
+        Self::with_max_shared_ptrs(usize::MAX)
+    }
+
+    /// Creates a new shared memory validator that tracks at most `max_shared_ptrs` distinct shared
+    /// pointers.
+    ///
+    /// Every distinct shared pointer in an archive is remembered until validation finishes, so an
+    /// untrusted archive can force the validator to allocate memory for each one it declares.
+    /// Registering a shared pointer past the limit fails with
+    /// [`SharedError::ExceededMaximumSharedPointers`]. Pointers to memory that has already been
+    /// registered don't count against the limit.
+    #[inline]
+    pub fn with_max_shared_ptrs(max_shared_ptrs: usize) -> Self {
         Self {
             // TODO: consider deferring this to avoid the overhead of constructing
             shared: HashMap::new(),
+            max_shared_ptrs,
         }
     }
 }
@@ -98,6 +124,10 @@ impl SharedContext for SharedValidator {
             } else {
                 Ok(false)
             }
+        } else if self.shared.len() >= self.max_shared_ptrs {
+            Err(SharedError::ExceededMaximumSharedPointers {
+                max_shared_ptrs: self.max_shared_ptrs,
+            })
         } else {
             self.shared.insert(ptr, type_id);
             Ok(true)
@@ -0,0 +1,189 @@
+//! A validator that checks tags with a predicate.
+
+use crate::{
+    validation::{ArchiveContext, SharedContext, TagContext},
+    Fallible,
+};
+use core::{
+    alloc::{Layout, LayoutError},
+    any::TypeId,
+    fmt,
+    marker::PhantomData,
+};
+
+/// A validator that wraps another validator and checks [`CheckedTag<F>`](crate::with::CheckedTag)
+/// fields with a predicate.
+///
+/// All other validation is forwarded to the wrapped validator.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     ser::{serializers::AllocSerializer, Serializer},
+///     validation::{check_archived_root_with_context, validators::{DefaultValidator, TagValidator}},
+///     with::CheckedTag,
+///     Archive, Serialize,
+/// };
+///
+/// // A marker for the set of plugin IDs
+/// struct PluginId;
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Message {
+///     #[with(CheckedTag<PluginId>)]
+///     plugin: u32,
+///     payload: u32,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Message { plugin: 7, payload: 42 }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+///
+/// // The set of valid plugin IDs is only known at runtime
+/// let registered = vec![3, 7, 12];
+///
+/// let mut validator = TagValidator::<_, PluginId, _>::new(
+///     DefaultValidator::new(&bytes),
+///     |tag| registered.contains(&tag),
+/// );
+/// let archived = check_archived_root_with_context::<Message, _>(&bytes, &mut validator).unwrap();
+/// assert_eq!(archived.plugin, 7);
+///
+/// let mut validator = TagValidator::<_, PluginId, _>::new(
+///     DefaultValidator::new(&bytes),
+///     |tag| tag < 5,
+/// );
+/// assert!(check_archived_root_with_context::<Message, _>(&bytes, &mut validator).is_err());
+/// ```
+pub struct TagValidator<V, F: ?Sized, P> {
+    inner: V,
+    predicate: P,
+    _phantom: PhantomData<fn(&F)>,
+}
+
+impl<V, F: ?Sized, P: FnMut(u64) -> bool> TagValidator<V, F, P> {
+    /// Wraps the given validator and checks tags with the given predicate.
+    #[inline]
+    pub fn new(inner: V, predicate: P) -> Self {
+        Self {
+            inner,
+            predicate,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Consumes the validator and returns the wrapped validator.
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.inner
+    }
+}
+
+impl<V: fmt::Debug, F: ?Sized, P> fmt::Debug for TagValidator<V, F, P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TagValidator")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<V: Fallible, F: ?Sized, P> Fallible for TagValidator<V, F, P> {
+    type Error = V::Error;
+}
+
+impl<V: ArchiveContext, F: ?Sized, P> ArchiveContext for TagValidator<V, F, P> {
+    type PrefixRange = V::PrefixRange;
+    type SuffixRange = V::SuffixRange;
+
+    #[inline]
+    unsafe fn bounds_check_ptr(
+        &mut self,
+        base: *const u8,
+        offset: isize,
+    ) -> Result<*const u8, Self::Error> {
+        self.inner.bounds_check_ptr(base, offset)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.inner.bounds_check_layout(data_address, layout)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_subtree_ptr_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .bounds_check_subtree_ptr_layout(data_address, layout)
+    }
+
+    #[inline]
+    unsafe fn push_prefix_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Self::PrefixRange, Self::Error> {
+        self.inner.push_prefix_subtree_range(root, end)
+    }
+
+    #[inline]
+    fn pop_prefix_range(&mut self, range: Self::PrefixRange) -> Result<(), Self::Error> {
+        self.inner.pop_prefix_range(range)
+    }
+
+    #[inline]
+    unsafe fn push_suffix_subtree_range(
+        &mut self,
+        start: *const u8,
+        root: *const u8,
+    ) -> Result<Self::SuffixRange, Self::Error> {
+        self.inner.push_suffix_subtree_range(start, root)
+    }
+
+    #[inline]
+    fn pop_suffix_range(&mut self, range: Self::SuffixRange) -> Result<(), Self::Error> {
+        self.inner.pop_suffix_range(range)
+    }
+
+    #[inline]
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        self.inner.finish()
+    }
+
+    #[inline]
+    fn wrap_layout_error(error: LayoutError) -> Self::Error {
+        V::wrap_layout_error(error)
+    }
+
+    #[inline]
+    fn follows_pointers(&self) -> bool {
+        self.inner.follows_pointers()
+    }
+}
+
+impl<V: SharedContext, F: ?Sized, P> SharedContext for TagValidator<V, F, P> {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        ptr: *const u8,
+        type_id: TypeId,
+    ) -> Result<bool, Self::Error> {
+        self.inner.register_shared_ptr(ptr, type_id)
+    }
+}
+
+impl<V: Fallible, F: ?Sized, P: FnMut(u64) -> bool> TagContext<F> for TagValidator<V, F, P> {
+    #[inline]
+    fn is_valid_tag(&mut self, tag: u64) -> bool {
+        (self.predicate)(tag)
+    }
+}
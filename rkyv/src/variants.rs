@@ -0,0 +1,229 @@
+//! Archived enums with their variants stored out of line, and access to the variants of archived
+//! enums.
+//!
+//! See [`BoxedVariants`](crate::with::BoxedVariants) and [`ArchivedVariant`] for more details.
+
+use crate::{Archive, Archived, Fallible, RawRelPtr};
+use core::{fmt, marker::PhantomData};
+
+/// A variant of the archived enum `E`.
+///
+/// This is implemented by deriving `Archive` with `#[archive(variant_accessors)]`, which generates a
+/// type for each variant of the enum named after the archived enum and the variant. For variants
+/// with named fields, that type holds the archived fields of the variant. Other variants get a
+/// marker type, and their fields are the archived field of the variant or `()` for unit variants.
+///
+/// The archived enum also gets an `as_variant::<V>()` method that calls [`get`](Self::get), and an
+/// accessor named after each variant, like `as_circle()` for a `Circle` variant.
+///
+/// The accessors match the archived enum on its variants, so they work the same whether the tags
+/// of the variants are assigned in declaration order or pinned with `#[archive(tag = N)]`.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{archived_root, variants::ArchivedVariant, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(variant_accessors)]
+/// enum Shape {
+///     Circle(f32),
+///     Rect { width: f32, height: f32 },
+///     Empty,
+/// }
+///
+/// // Generic code can probe for a variant without matching on the archived enum
+/// fn count<V: ArchivedVariant<ArchivedShape>>(shapes: &[ArchivedShape]) -> usize {
+///     shapes.iter().filter(|shape| shape.as_variant::<V>().is_some()).count()
+/// }
+///
+/// let value = vec![Shape::Rect { width: 2.0, height: 3.0 }, Shape::Circle(1.0), Shape::Empty];
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { archived_root::<Vec<Shape>>(&bytes) };
+///
+/// let rect = archived[0].as_rect().unwrap();
+/// assert_eq!((rect.width, rect.height), (2.0, 3.0));
+/// assert_eq!(archived[1].as_circle(), Some(&1.0));
+/// assert_eq!(archived[1].as_variant::<ArchivedShapeRect>().map(|r| r.width), None);
+/// assert_eq!(count::<ArchivedShapeEmpty>(archived), 1);
+/// ```
+pub trait ArchivedVariant<E: ?Sized> {
+    /// The archived fields of the variant.
+    type Fields: ?Sized;
+
+    /// Returns the archived fields of the variant if it's the active variant of `value`.
+    fn get(value: &E) -> Option<&Self::Fields>;
+}
+
+/// An enum whose variants can be archived out of line.
+///
+/// This is implemented by deriving `Archive` with `#[archive(boxed_variants)]`. Every variant must
+/// either be a unit variant or have a single unnamed field, which is archived as the payload of
+/// the variant.
+pub trait ArchiveVariants {
+    /// Returns the index of the active variant, in declaration order.
+    fn variant_index(&self) -> u32;
+}
+
+/// An enum whose variants can be serialized out of line.
+///
+/// This is implemented by deriving `Serialize` with `#[archive(boxed_variants)]`.
+pub trait SerializeVariants<S: Fallible + ?Sized>: ArchiveVariants {
+    /// Serializes the payload of the active variant and returns its position, or `None` if the
+    /// variant doesn't have a payload.
+    fn serialize_variant(&self, serializer: &mut S) -> Result<Option<usize>, S::Error>;
+}
+
+/// An enum that can be deserialized from out-of-line variants.
+///
+/// This is implemented by deriving `Deserialize` with `#[archive(boxed_variants)]`.
+pub trait DeserializeVariants<D: Fallible + ?Sized>: ArchiveVariants + Sized {
+    /// Deserializes the variant with the given index from its archived payload.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be the index of a variant, and `payload` must point to the archived payload
+    /// of that variant.
+    unsafe fn deserialize_variant(
+        index: u32,
+        payload: *const u8,
+        deserializer: &mut D,
+    ) -> Result<Self, D::Error>;
+}
+
+/// An enum whose variants can be checked out of line.
+///
+/// This is implemented by deriving `Archive` with both `#[archive(boxed_variants)]` and
+/// `#[archive(check_bytes)]`.
+#[cfg(feature = "validation")]
+pub trait CheckVariants<C: ?Sized> {
+    /// Checks that the relative pointer points to a valid payload for the variant with the given
+    /// index.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be aligned and point to enough bytes to represent a `RawRelPtr`.
+    unsafe fn check_variant(
+        index: u32,
+        ptr: *const RawRelPtr,
+        context: &mut C,
+    ) -> Result<(), bytecheck::EnumCheckError<u32>>;
+}
+
+/// An archived enum with the payload of its variant stored out of line.
+///
+/// This is the archived type of enums serialized with [`BoxedVariants`](crate::with::BoxedVariants).
+/// It stores the index of the variant along with a relative pointer to the payload of the variant,
+/// so its size doesn't depend on the size of the largest variant.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedBoxedVariants<T> {
+    index: Archived<u32>,
+    payload: RawRelPtr,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArchivedBoxedVariants<T> {
+    /// Returns the index of the archived variant, in declaration order.
+    #[inline]
+    pub fn variant_index(&self) -> u32 {
+        from_archived!(self.index)
+    }
+
+    /// Returns a reference to the payload of the archived variant.
+    ///
+    /// # Safety
+    ///
+    /// `P` must be the archived type of the field of the archived variant.
+    #[inline]
+    pub unsafe fn payload<P>(&self) -> &P {
+        &*self.payload.as_ptr().cast()
+    }
+
+    /// Resolves an archived enum from its variant index and the position of its payload.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the enum with
+    ///   [`serialize_from_value`](ArchivedBoxedVariants::serialize_from_value)
+    #[inline]
+    pub unsafe fn resolve_from_value(
+        value: &T,
+        pos: usize,
+        resolver: BoxedVariantsResolver,
+        out: *mut Self,
+    ) where
+        T: ArchiveVariants,
+    {
+        let (fp, fo) = out_field!(out.index);
+        value.variant_index().resolve(pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.payload);
+        // Variants without a payload point to themselves
+        RawRelPtr::emplace(pos + fp, resolver.pos.unwrap_or(pos + fp), fo);
+    }
+
+    /// Serializes the payload of the active variant of an enum.
+    #[inline]
+    pub fn serialize_from_value<S: Fallible + ?Sized>(
+        value: &T,
+        serializer: &mut S,
+    ) -> Result<BoxedVariantsResolver, S::Error>
+    where
+        T: SerializeVariants<S>,
+    {
+        Ok(BoxedVariantsResolver {
+            pos: value.serialize_variant(serializer)?,
+        })
+    }
+
+    /// Deserializes the enum from its archived variant.
+    #[inline]
+    pub fn deserialize<D: Fallible + ?Sized>(&self, deserializer: &mut D) -> Result<T, D::Error>
+    where
+        T: DeserializeVariants<D>,
+    {
+        // Safety: The payload was serialized for the archived variant
+        unsafe {
+            T::deserialize_variant(
+                self.variant_index(),
+                self.payload.as_ptr().cast(),
+                deserializer,
+            )
+        }
+    }
+}
+
+impl<T> fmt::Debug for ArchivedBoxedVariants<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedBoxedVariants")
+            .field("variant_index", &self.variant_index())
+            .finish()
+    }
+}
+
+/// The resolver for an [`ArchivedBoxedVariants`].
+pub struct BoxedVariantsResolver {
+    pos: Option<usize>,
+}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use bytecheck::{CheckBytes, EnumCheckError};
+    use core::ptr;
+
+    impl<T: CheckVariants<C>, C: ?Sized> CheckBytes<C> for ArchivedBoxedVariants<T> {
+        type Error = EnumCheckError<u32>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            // Integers are valid for any bit pattern, so only the payload has to be checked
+            let index = from_archived!(*ptr::addr_of!((*value).index));
+            T::check_variant(index, ptr::addr_of!((*value).payload), context)?;
+            Ok(&*value)
+        }
+    }
+};
@@ -0,0 +1,285 @@
+//! Archived variable-length integers.
+//!
+//! See [`VarInt`](crate::with::VarInt) for more details.
+
+use crate::{ser::Serializer, RawRelPtr};
+use core::{fmt, marker::PhantomData};
+
+/// An integer type that can be encoded as a variable-length integer.
+///
+/// Unsigned integers are encoded with [LEB128](https://en.wikipedia.org/wiki/LEB128), seven bits
+/// per byte with the high bit of each byte set if more bytes follow. Signed integers are zigzag
+/// encoded first so that values close to zero are short regardless of their sign.
+pub trait VarIntPrimitive: Copy {
+    /// The number of bits in the integer type.
+    const BITS: u32;
+    /// The maximum number of bytes that an encoded value may take.
+    const MAX_LEN: usize = Self::BITS.div_ceil(7) as usize;
+
+    /// Converts the integer to its unsigned bit representation.
+    fn to_bits(self) -> u128;
+
+    /// Converts an unsigned bit representation back into the integer.
+    ///
+    /// Bits that don't fit in the integer type are discarded.
+    fn from_bits(bits: u128) -> Self;
+}
+
+macro_rules! impl_unsigned {
+    ($ty:ty) => {
+        impl VarIntPrimitive for $ty {
+            const BITS: u32 = <$ty>::BITS;
+
+            #[inline]
+            fn to_bits(self) -> u128 {
+                self as u128
+            }
+
+            #[inline]
+            fn from_bits(bits: u128) -> Self {
+                bits as $ty
+            }
+        }
+    };
+}
+
+impl_unsigned!(u16);
+impl_unsigned!(u32);
+impl_unsigned!(u64);
+impl_unsigned!(u128);
+
+macro_rules! impl_signed {
+    ($ty:ty, $unsigned:ty) => {
+        impl VarIntPrimitive for $ty {
+            const BITS: u32 = <$ty>::BITS;
+
+            #[inline]
+            fn to_bits(self) -> u128 {
+                ((self << 1) ^ (self >> (<$ty>::BITS - 1))) as $unsigned as u128
+            }
+
+            #[inline]
+            fn from_bits(bits: u128) -> Self {
+                let bits = bits as $unsigned;
+                ((bits >> 1) as $ty) ^ -((bits & 1) as $ty)
+            }
+        }
+    };
+}
+
+impl_signed!(i16, u16);
+impl_signed!(i32, u32);
+impl_signed!(i64, u64);
+impl_signed!(i128, u128);
+
+/// Errors that can occur while decoding a variable-length integer.
+#[derive(Debug)]
+pub enum VarIntError {
+    /// The encoded value did not fit in the target integer type.
+    Overflow,
+    /// The encoding did not terminate within the maximum length for the target integer type.
+    Unterminated {
+        /// The maximum length of the encoding
+        max_len: usize,
+    },
+}
+
+impl fmt::Display for VarIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarIntError::Overflow => write!(f, "variable-length integer overflowed"),
+            VarIntError::Unterminated { max_len } => write!(
+                f,
+                "variable-length integer did not terminate within {} bytes",
+                max_len
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl Error for VarIntError {}
+};
+
+/// Decodes a variable-length integer one byte at a time.
+struct Decoder {
+    bits: u128,
+    shift: u32,
+}
+
+impl Decoder {
+    #[inline]
+    fn new() -> Self {
+        Self { bits: 0, shift: 0 }
+    }
+
+    /// Adds the next byte of the encoding and returns whether it was the last byte.
+    #[inline]
+    fn push<T: VarIntPrimitive>(&mut self, byte: u8) -> Result<bool, VarIntError> {
+        let payload = (byte & 0x7f) as u128;
+        if self.shift + 7 > T::BITS && payload >> (T::BITS - self.shift) != 0 {
+            return Err(VarIntError::Overflow);
+        }
+        self.bits |= payload << self.shift;
+        self.shift += 7;
+        Ok(byte & 0x80 == 0)
+    }
+}
+
+/// An archived variable-length integer.
+///
+/// The encoded bytes are stored out-of-line and have no alignment requirements, so each archived
+/// value takes a relative pointer plus one byte for every seven significant bits of the integer.
+/// This is only smaller than the fixed-width encoding for integers that are wider than a relative
+/// pointer.
+#[repr(transparent)]
+pub struct ArchivedVarInt<T> {
+    ptr: RawRelPtr,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: VarIntPrimitive> ArchivedVarInt<T> {
+    /// Decodes the archived integer.
+    #[inline]
+    pub fn get(&self) -> T {
+        let bytes = self.ptr.as_ptr().cast::<u8>();
+        let mut decoder = Decoder::new();
+        for i in 0..T::MAX_LEN {
+            // The encoding is guaranteed to be valid, so this stops at the last byte
+            if let Ok(true) | Err(_) = decoder.push::<T>(unsafe { *bytes.add(i) }) {
+                break;
+            }
+        }
+        T::from_bits(decoder.bits)
+    }
+
+    /// Resolves an archived variable-length integer.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the integer
+    #[inline]
+    pub unsafe fn resolve_from_value(pos: usize, resolver: VarIntResolver, out: *mut Self) {
+        let (fp, fo) = out_field!(out.ptr);
+        RawRelPtr::emplace(pos + fp, resolver.pos, fo);
+    }
+
+    /// Serializes the encoded bytes of an integer.
+    #[inline]
+    pub fn serialize_from_value<S: Serializer + ?Sized>(
+        value: T,
+        serializer: &mut S,
+    ) -> Result<VarIntResolver, S::Error> {
+        let mut buf = [0u8; 19];
+        let mut len = 0;
+        let mut bits = value.to_bits();
+        loop {
+            let byte = (bits & 0x7f) as u8;
+            bits >>= 7;
+            if bits == 0 {
+                buf[len] = byte;
+                len += 1;
+                break;
+            }
+            buf[len] = byte | 0x80;
+            len += 1;
+        }
+        let pos = serializer.pos();
+        serializer.write(&buf[..len])?;
+        Ok(VarIntResolver { pos })
+    }
+}
+
+impl<T: VarIntPrimitive + fmt::Debug> fmt::Debug for ArchivedVarInt<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: VarIntPrimitive + fmt::Display> fmt::Display for ArchivedVarInt<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: VarIntPrimitive + PartialEq> PartialEq<T> for ArchivedVarInt<T> {
+    #[inline]
+    fn eq(&self, other: &T) -> bool {
+        self.get() == *other
+    }
+}
+
+/// The resolver for an [`ArchivedVarInt`].
+pub struct VarIntResolver {
+    pos: usize,
+}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::validation::{owned::OwnedPointerError, ArchiveContext};
+    use bytecheck::{CheckBytes, Error};
+    use core::{alloc::Layout, convert::Infallible};
+
+    impl<T: VarIntPrimitive, C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedVarInt<T>
+    where
+        C::Error: Error,
+    {
+        type Error = OwnedPointerError<Infallible, VarIntError, C::Error>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let raw_ptr = RawRelPtr::manual_check_bytes(value.cast(), context)
+                .map_err(OwnedPointerError::PointerCheckBytesError)?;
+            if !context.follows_pointers() {
+                return Ok(&*value);
+            }
+
+            let ptr = context
+                .bounds_check_ptr(raw_ptr.base(), raw_ptr.offset())
+                .map_err(OwnedPointerError::ContextError)?;
+
+            let mut decoder = Decoder::new();
+            let mut len = 0;
+            loop {
+                if len == T::MAX_LEN {
+                    return Err(OwnedPointerError::ValueCheckBytesError(
+                        VarIntError::Unterminated { max_len: len },
+                    ));
+                }
+                // Each byte must be checked before it's read since the length isn't known ahead of
+                // time
+                len += 1;
+                context
+                    .bounds_check_subtree_ptr_layout(
+                        ptr,
+                        &Layout::from_size_align_unchecked(len, 1),
+                    )
+                    .map_err(OwnedPointerError::ContextError)?;
+                let is_last = decoder
+                    .push::<T>(*ptr.add(len - 1))
+                    .map_err(OwnedPointerError::ValueCheckBytesError)?;
+                if is_last {
+                    break;
+                }
+            }
+
+            let range = context
+                .push_prefix_subtree_range(ptr, ptr.add(len))
+                .map_err(OwnedPointerError::ContextError)?;
+            context
+                .pop_prefix_range(range)
+                .map_err(OwnedPointerError::ContextError)?;
+
+            Ok(&*value)
+        }
+    }
+};
@@ -1,11 +1,16 @@
 //! An archived version of `Vec`.
 
 mod raw;
+mod reserved;
 
 use crate::{
     ser::{ScratchSpace, Serializer},
     Archive, Archived, RelPtr, Serialize, SerializeUnsized,
 };
+#[cfg(feature = "alloc")]
+use crate::{Deserialize, Fallible};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use ::alloc::vec::Vec;
 use core::{
     borrow::Borrow,
     cmp, fmt, hash,
@@ -15,6 +20,24 @@ use core::{
 };
 
 pub use self::raw::*;
+pub use self::reserved::*;
+
+/// A type whose archived representation is identical to its native representation.
+///
+/// Values of a type that implements this trait can be read directly out of an archive without any
+/// conversion. See [`ArchivedVec::as_native_slice`].
+///
+/// `u8`, `i8` and `bool` always implement this trait. Multibyte integers and floats only implement
+/// it when the archive endianness matches the target endianness: either no endianness feature is
+/// enabled, `archive_le` is enabled on a little-endian target, or `archive_be` is enabled on a
+/// big-endian target. Code that relies on this trait for multibyte types won't compile when the
+/// archive endianness differs, and must access the elements one at a time instead.
+///
+/// # Safety
+///
+/// `Self::Archived` must have the same size, alignment and bit validity as `Self`, and every
+/// archived value must represent the same value as the native value with the same bytes.
+pub unsafe trait ArchiveNative: Archive {}
 
 /// An archived [`Vec`].
 ///
@@ -51,6 +74,31 @@ impl<T> ArchivedVec<T> {
         unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
     }
 
+    /// Gets the elements of the archived vec as a slice of their native type.
+    ///
+    /// This is only available when the archived elements have the same representation as their
+    /// native type, so no per-element endianness conversion is needed. For multibyte types like
+    /// `f32`, that's only the case when the archive endianness matches the target endianness. See
+    /// [`ArchiveNative`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::archived_root;
+    ///
+    /// let value = vec![1.0f32, 2.5, -4.0];
+    /// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+    /// let archived = unsafe { archived_root::<Vec<f32>>(&bytes) };
+    ///
+    /// let native: &[f32] = archived.as_native_slice();
+    /// assert_eq!(native, [1.0, 2.5, -4.0]);
+    /// ```
+    #[inline]
+    pub fn as_native_slice<U: ArchiveNative<Archived = T>>(&self) -> &[U] {
+        // SAFETY: `ArchiveNative` guarantees that `T` has the same representation as `U`
+        unsafe { core::slice::from_raw_parts(self.as_ptr().cast::<U>(), self.len()) }
+    }
+
     /// Gets the elements of the archived vec as a pinned mutable slice.
     #[inline]
     pub fn pin_mut_slice(self: Pin<&mut Self>) -> Pin<&mut [T]> {
@@ -59,6 +107,100 @@ impl<T> ArchivedVec<T> {
         }
     }
 
+    /// Returns whether the elements of the archived vec are sorted in ascending order.
+    ///
+    /// Equal elements next to each other are considered sorted. Use
+    /// [`is_sorted_by`](ArchivedVec::is_sorted_by) with `|a, b| a < b` to also check that the
+    /// elements are unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::archived_root;
+    ///
+    /// let bytes = rkyv::to_bytes::<_, 256>(&vec![1, 2, 2, 3]).unwrap();
+    /// let archived = unsafe { archived_root::<Vec<i32>>(&bytes) };
+    /// assert!(archived.is_sorted());
+    /// assert!(!archived.is_sorted_by(|a, b| a < b));
+    /// ```
+    #[inline]
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.is_sorted_by(|a, b| a <= b)
+    }
+
+    /// Returns whether the elements of the archived vec are sorted using the given comparator.
+    ///
+    /// The comparator is called on each pair of adjacent elements and must return whether they are
+    /// in order.
+    #[inline]
+    pub fn is_sorted_by<F>(&self, mut compare: F) -> bool
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        self.as_slice()
+            .windows(2)
+            .all(|pair| compare(&pair[0], &pair[1]))
+    }
+
+    /// Returns whether the archived vec contains an element equal to the given value.
+    ///
+    /// Unlike [`slice::contains`], the value doesn't have to be archived, so archived elements can
+    /// be compared against unarchived values without deserializing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::archived_root;
+    ///
+    /// let value = vec!["a".to_string(), "b".to_string()];
+    /// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+    /// let archived = unsafe { archived_root::<Vec<String>>(&bytes) };
+    /// assert!(archived.contains("b"));
+    /// assert!(!archived.contains("c"));
+    /// ```
+    #[inline]
+    pub fn contains<U: ?Sized>(&self, value: &U) -> bool
+    where
+        T: PartialEq<U>,
+    {
+        self.as_slice().iter().any(|element| element == value)
+    }
+
+    /// Returns the index of the first element of the archived vec that matches a predicate, or
+    /// `None` if no element matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::archived_root;
+    ///
+    /// let bytes = rkyv::to_bytes::<_, 256>(&vec![1, 2, 3, 2]).unwrap();
+    /// let archived = unsafe { archived_root::<Vec<i32>>(&bytes) };
+    /// assert_eq!(archived.position(|x| *x == 2), Some(1));
+    /// assert_eq!(archived.rposition(|x| *x == 2), Some(3));
+    /// assert_eq!(archived.position(|x| *x > 3), None);
+    /// ```
+    #[inline]
+    pub fn position<F>(&self, predicate: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.as_slice().iter().position(predicate)
+    }
+
+    /// Returns the index of the last element of the archived vec that matches a predicate, or
+    /// `None` if no element matches.
+    #[inline]
+    pub fn rposition<F>(&self, predicate: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.as_slice().iter().rposition(predicate)
+    }
+
     // This method can go away once pinned slices have indexing support
     // https://github.com/rust-lang/rust/pull/78370
 
@@ -71,6 +213,45 @@ impl<T> ArchivedVec<T> {
         unsafe { self.pin_mut_slice().map_unchecked_mut(|s| &mut s[index]) }
     }
 
+    /// Deserializes only the elements of the archived vec that match a predicate.
+    ///
+    /// The predicate is called on each archived element in order, and only the elements for which
+    /// it returns `true` are deserialized. Elements that are filtered out are never deserialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::{archived_root, Infallible};
+    ///
+    /// let value = vec![1, 2, 3, 4, 5, 6];
+    /// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+    /// let archived = unsafe { archived_root::<Vec<i32>>(&bytes) };
+    ///
+    /// let even: Vec<i32> = archived
+    ///     .deserialize_filtered(|x| *x % 2 == 0, &mut Infallible)
+    ///     .unwrap();
+    /// assert_eq!(even, vec![2, 4, 6]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn deserialize_filtered<U, D>(
+        &self,
+        mut predicate: impl FnMut(&T) -> bool,
+        deserializer: &mut D,
+    ) -> Result<Vec<U>, D::Error>
+    where
+        T: Deserialize<U, D>,
+        D: Fallible + ?Sized,
+    {
+        let mut result = Vec::new();
+        for item in self.as_slice() {
+            if predicate(item) {
+                result.push(item.deserialize(deserializer)?);
+            }
+        }
+        Ok(result)
+    }
+
     /// Resolves an archived `Vec` from a given slice.
     ///
     /// # Safety
@@ -180,6 +361,55 @@ impl<T> ArchivedVec<T> {
     }
 }
 
+impl<T> ArchivedVec<ArchivedVec<T>> {
+    /// Returns an iterator over the elements of each inner archived vec in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::archived_root;
+    ///
+    /// let value = vec![vec![1, 2], vec![], vec![3]];
+    /// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+    /// let archived = unsafe { archived_root::<Vec<Vec<i32>>>(&bytes) };
+    ///
+    /// assert!(archived.flatten().eq([1, 2, 3].iter()));
+    /// ```
+    #[inline]
+    pub fn flatten(&self) -> impl Iterator<Item = &T> + '_ {
+        self.as_slice().iter().flat_map(|inner| inner.as_slice())
+    }
+
+    /// Deserializes the elements of each inner archived vec into a single flat `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::{archived_root, Infallible};
+    ///
+    /// let value = vec![vec![1, 2], vec![], vec![3]];
+    /// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+    /// let archived = unsafe { archived_root::<Vec<Vec<i32>>>(&bytes) };
+    ///
+    /// let flat: Vec<i32> = archived.concat_to_vec(&mut Infallible).unwrap();
+    /// assert_eq!(flat, vec![1, 2, 3]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn concat_to_vec<U, D>(&self, deserializer: &mut D) -> Result<Vec<U>, D::Error>
+    where
+        T: Deserialize<U, D>,
+        D: Fallible + ?Sized,
+    {
+        let len = self.as_slice().iter().map(|inner| inner.len()).sum();
+        let mut result = Vec::with_capacity(len);
+        for item in self.flatten() {
+            result.push(item.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
 impl<T> AsRef<[T]> for ArchivedVec<T> {
     #[inline]
     fn as_ref(&self) -> &[T] {
@@ -295,6 +525,14 @@ pub struct VecResolver {
     pos: usize,
 }
 
+impl VecResolver {
+    /// Creates a resolver for a vec whose elements were written at the given position.
+    #[inline]
+    pub(crate) fn from_pos(pos: usize) -> Self {
+        Self { pos }
+    }
+}
+
 #[cfg(feature = "validation")]
 const _: () = {
     use crate::validation::{
@@ -321,6 +559,10 @@ const _: () = {
         {
             let rel_ptr = RelPtr::<[T]>::manual_check_bytes(value.cast(), context)
                 .map_err(OwnedPointerError::PointerCheckBytesError)?;
+            if !context.follows_pointers() {
+                return Ok(&*value);
+            }
+
             let ptr = context
                 .check_subtree_rel_ptr(rel_ptr)
                 .map_err(OwnedPointerError::ContextError)?;
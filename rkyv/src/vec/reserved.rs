@@ -0,0 +1,273 @@
+//! An archived `Vec` with reserved capacity for appending elements in place.
+
+use super::{ArchivedVec, VecResolver};
+use crate::{ser::Serializer, Archive, Archived, FixedUsize, Serialize, SerializeUnsized};
+use core::{fmt, mem::size_of, ops::Deref, pin::Pin};
+
+/// An archived `Vec` that has space reserved after its elements for more elements.
+///
+/// This is the archived type of vecs serialized with [`ReservedVec`](crate::with::ReservedVec).
+/// It dereferences to an [`ArchivedVec`] of the elements that are currently in use, and elements
+/// can be appended in place with [`try_push`](ArchivedReservedVec::try_push) until the reserved
+/// capacity runs out. The archive itself never grows, so this is only useful when the archive is
+/// stored in mutable memory (for example, a writable memory map of a file).
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedReservedVec<T> {
+    vec: ArchivedVec<T>,
+    capacity: Archived<usize>,
+}
+
+impl<T> ArchivedReservedVec<T> {
+    /// Returns the total number of elements the archived vec can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        from_archived!(self.capacity) as usize
+    }
+
+    /// Returns the number of elements that can still be pushed onto the archived vec.
+    #[inline]
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity().saturating_sub(self.vec.len())
+    }
+
+    /// Gets the elements of the archived vec as a pinned mutable slice.
+    #[inline]
+    pub fn pin_mut_slice(self: Pin<&mut Self>) -> Pin<&mut [T]> {
+        unsafe { self.map_unchecked_mut(|s| &mut s.vec).pin_mut_slice() }
+    }
+
+    /// Appends an element to the end of the archived vec if there's reserved capacity left.
+    ///
+    /// The element is written into the reserved space directly after the last element and the
+    /// archived length is incremented. If the vec is already at capacity, the element is returned
+    /// in the error and the archive is left unchanged.
+    ///
+    /// Elements must be `Copy` so that they can't contain relative pointers, which would point to
+    /// the wrong place after being moved into the archive. Elements with out-of-line data (like
+    /// strings) can't be pushed.
+    ///
+    /// The reserved capacity lives inside the archive buffer, so pushing only changes bytes that
+    /// the archive already contains. The buffer must still be aligned for the archived type, the
+    /// same as when the archive was first accessed.
+    #[inline]
+    pub fn try_push(self: Pin<&mut Self>, value: T) -> Result<(), ReservedCapacityError<T>>
+    where
+        T: Copy,
+    {
+        // Safety: The archived vec doesn't move, only the element and length are written
+        let this = unsafe { self.get_unchecked_mut() };
+        let len = this.vec.len();
+        let capacity = this.capacity();
+        if len >= capacity {
+            return Err(ReservedCapacityError { value, capacity });
+        }
+
+        // Safety: The reserved capacity was serialized directly after the elements and is aligned
+        // for `T`, so the slot at `len` is in bounds of the archive
+        unsafe {
+            this.vec.ptr.as_mut_ptr().add(len).write(value);
+        }
+        this.vec.len = to_archived!((len + 1) as FixedUsize);
+        Ok(())
+    }
+
+    /// Resolves an archived reserved vec from a given slice.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `slice` with
+    ///   [`serialize_from_slice`](ArchivedReservedVec::serialize_from_slice)
+    #[inline]
+    pub unsafe fn resolve_from_slice<U: Archive<Archived = T>>(
+        slice: &[U],
+        pos: usize,
+        resolver: ReservedVecResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.vec);
+        ArchivedVec::resolve_from_len(slice.len(), pos + fp, resolver.vec, fo);
+        let (fp, fo) = out_field!(out.capacity);
+        resolver.capacity.resolve(pos + fp, (), fo);
+    }
+
+    /// Serializes the elements of a slice followed by space for `extra` more elements.
+    ///
+    /// The reserved space is filled with zeroes.
+    pub fn serialize_from_slice<U: Serialize<S, Archived = T>, S: Serializer + ?Sized>(
+        slice: &[U],
+        extra: usize,
+        serializer: &mut S,
+    ) -> Result<ReservedVecResolver, S::Error>
+    where
+        [U]: SerializeUnsized<S>,
+    {
+        const ZEROES: [u8; 64] = [0; 64];
+
+        let vec = ArchivedVec::<T>::serialize_from_slice(slice, serializer)?;
+        debug_assert_eq!(serializer.pos(), vec.pos + slice.len() * size_of::<T>());
+
+        let mut remaining = extra * size_of::<T>();
+        while remaining > 0 {
+            let len = remaining.min(ZEROES.len());
+            serializer.write(&ZEROES[..len])?;
+            remaining -= len;
+        }
+
+        Ok(ReservedVecResolver {
+            vec,
+            capacity: slice.len() + extra,
+        })
+    }
+}
+
+impl<T> Deref for ArchivedReservedVec<T> {
+    type Target = ArchivedVec<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedReservedVec<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.vec.fmt(f)
+    }
+}
+
+/// The resolver for an [`ArchivedReservedVec`].
+pub struct ReservedVecResolver {
+    vec: VecResolver,
+    capacity: usize,
+}
+
+/// An error that occurs when pushing onto an [`ArchivedReservedVec`] that is at capacity.
+#[derive(Debug)]
+pub struct ReservedCapacityError<T> {
+    /// The element that couldn't be pushed
+    pub value: T,
+    /// The capacity of the archived vec
+    pub capacity: usize,
+}
+
+impl<T> fmt::Display for ReservedCapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archived vec has no reserved capacity left (capacity {})",
+            self.capacity
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> ::std::error::Error for ReservedCapacityError<T> {}
+
+/// Errors that can occur while checking an archived reserved vec.
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum ReservedVecError<E> {
+    /// The length of the vec was greater than its capacity
+    LengthExceedsCapacity {
+        /// The length of the vec
+        len: usize,
+        /// The capacity of the vec
+        capacity: usize,
+    },
+    /// An error occurred while checking the elements or the reserved space
+    CheckVecError(E),
+}
+
+#[cfg(feature = "validation")]
+impl<E: fmt::Display> fmt::Display for ReservedVecError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReservedVecError::LengthExceedsCapacity { len, capacity } => write!(
+                f,
+                "archived vec length {} exceeds its capacity {}",
+                len, capacity
+            ),
+            ReservedVecError::CheckVecError(e) => write!(f, "vec check error: {}", e),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for ReservedVecError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                ReservedVecError::LengthExceedsCapacity { .. } => None,
+                ReservedVecError::CheckVecError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::{
+        validation::{
+            owned::{CheckOwnedPointerError, OwnedPointerError},
+            ArchiveContext,
+        },
+        RelPtr,
+    };
+    use bytecheck::{CheckBytes, Error};
+    use core::ptr;
+
+    impl<T, C> CheckBytes<C> for ArchivedReservedVec<T>
+    where
+        T: CheckBytes<C>,
+        C: ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        type Error = ReservedVecError<CheckOwnedPointerError<[T], C>>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            // An `ArchivedVec<T>` has the same layout as a `RelPtr<[T]>`
+            let rel_ptr = RelPtr::<[T]>::manual_check_bytes(
+                ptr::addr_of!((*value).vec).cast::<RelPtr<[T]>>(),
+                context,
+            )
+            .map_err(|e| {
+                ReservedVecError::CheckVecError(OwnedPointerError::PointerCheckBytesError(e))
+            })?;
+
+            // Integers are valid for any bit pattern
+            let value = &*value;
+            let (len, capacity) = (value.vec.len(), value.capacity());
+            if len > capacity {
+                return Err(ReservedVecError::LengthExceedsCapacity { len, capacity });
+            }
+            if !context.follows_pointers() {
+                return Ok(value);
+            }
+
+            // The reserved space is claimed along with the elements so that nothing else can be
+            // located there, but only the elements in use have to be valid
+            let ptr = context
+                .check_subtree_ptr::<[T]>(rel_ptr.base(), rel_ptr.offset(), capacity)
+                .map_err(|e| ReservedVecError::CheckVecError(OwnedPointerError::ContextError(e)))?;
+            let range = context
+                .push_prefix_subtree(ptr)
+                .map_err(|e| ReservedVecError::CheckVecError(OwnedPointerError::ContextError(e)))?;
+            <[T]>::check_bytes(ptr_meta::from_raw_parts(ptr.cast(), len), context).map_err(
+                |e| ReservedVecError::CheckVecError(OwnedPointerError::ValueCheckBytesError(e)),
+            )?;
+            context
+                .pop_prefix_range(range)
+                .map_err(|e| ReservedVecError::CheckVecError(OwnedPointerError::ContextError(e)))?;
+
+            Ok(value)
+        }
+    }
+};
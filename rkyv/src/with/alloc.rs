@@ -1,30 +1,47 @@
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
-    collections::util::Entry,
-    niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
-    ser::{ScratchSpace, Serializer},
-    string::{ArchivedString, StringResolver},
-    vec::{ArchivedVec, RawArchivedVec, VecResolver},
+    collections::{
+        soa_map::SoAMapResolver, sorted_vec::SortedVecResolver, util::Entry, ArchivedSoAMap,
+        ArchivedSortedVec,
+    },
+    de::SharedDeserializeRegistry,
+    niche::{
+        option_box::{ArchivedOptionBox, OptionBoxResolver},
+        option_rc::{ArchivedOptionRc, OptionRcResolver},
+    },
+    quantize::{ArchivedQuantizedVec, QuantizedVecResolver},
+    rc::ArchiveSharedPointer,
+    ser::{ScratchSpace, Serializer, SharedSerializeRegistry},
+    string::{inline::ArchivedInlineStr, inline::InlineStrError, ArchivedString, StringResolver},
+    vec::{ArchivedReservedVec, ArchivedVec, RawArchivedVec, ReservedVecResolver, VecResolver},
     with::{
-        ArchiveWith, AsOwned, AsVec, CopyOptimize, DeserializeWith, Map, Niche, Raw, RefAsBox,
-        SerializeWith, With,
+        ArchiveWith, AsDisplayString, AsOwned, AsVec, CopyOptimize, DeserializeWith,
+        DisplayStringResolver, InlineStr, Map, MapAsSoA, Niche, Quantize, Raw, RefAsBox,
+        ReservedVec, SerializeWith, SortedVec, With,
     },
     Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized, Fallible,
     MetadataResolver, Serialize, SerializeUnsized,
 };
-use ::core::marker::PhantomData;
+use ::core::{fmt, marker::PhantomData};
+#[cfg(all(not(feature = "std"), has_atomics))]
+use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
 use alloc::{
     borrow::Cow,
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    string::{String, ToString},
     vec::Vec,
 };
+#[cfg(all(feature = "std", has_atomics))]
+use std::sync::Arc;
 #[cfg(feature = "std")]
 use std::{
     borrow::Cow,
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
+    rc::Rc,
 };
 
 // Map for Vecs
@@ -355,6 +372,182 @@ where
     }
 }
 
+// MapAsSoA
+
+impl<K: Archive, V: Archive> ArchiveWith<BTreeMap<K, V>> for MapAsSoA {
+    type Archived = ArchivedSoAMap<K::Archived, V::Archived>;
+    type Resolver = SoAMapResolver;
+
+    unsafe fn resolve_with(
+        field: &BTreeMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSoAMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, V>, S> for MapAsSoA
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(
+        field: &BTreeMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSoAMap::serialize_from_iter(field.iter(), serializer)
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedSoAMap<K::Archived, V::Archived>, BTreeMap<K, V>, D>
+    for MapAsSoA
+where
+    K: Archive + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSoAMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, V>, D::Error> {
+        let mut result = BTreeMap::new();
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// SortedVec
+
+impl<T: Archive> ArchiveWith<Vec<T>> for SortedVec {
+    type Archived = ArchivedSortedVec<T::Archived>;
+    type Resolver = SortedVecResolver;
+
+    unsafe fn resolve_with(
+        _: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSortedVec::resolve_from_resolver(pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for SortedVec
+where
+    T: Serialize<S> + Ord,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedVec::serialize_from_iter(field.iter(), serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedSortedVec<T::Archived>, Vec<T>, D> for SortedVec
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field.as_vec().deserialize(deserializer)
+    }
+}
+
+// Quantize
+
+impl<const BITS: u8> ArchiveWith<Vec<f32>> for Quantize<BITS> {
+    type Archived = ArchivedQuantizedVec<BITS>;
+    type Resolver = QuantizedVecResolver;
+
+    unsafe fn resolve_with(
+        field: &Vec<f32>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedQuantizedVec::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<const BITS: u8, S> SerializeWith<Vec<f32>, S> for Quantize<BITS>
+where
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(field: &Vec<f32>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedQuantizedVec::<BITS>::serialize_from_slice(field, serializer)
+    }
+}
+
+impl<const BITS: u8, D> DeserializeWith<ArchivedQuantizedVec<BITS>, Vec<f32>, D> for Quantize<BITS>
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedQuantizedVec<BITS>,
+        _: &mut D,
+    ) -> Result<Vec<f32>, D::Error> {
+        Ok(field.iter().collect())
+    }
+}
+
+// ReservedVec
+
+impl<T: Archive, const EXTRA: usize> ArchiveWith<Vec<T>> for ReservedVec<EXTRA> {
+    type Archived = ArchivedReservedVec<T::Archived>;
+    type Resolver = ReservedVecResolver;
+
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedReservedVec::resolve_from_slice(field.as_slice(), pos, resolver, out);
+    }
+}
+
+impl<T, S, const EXTRA: usize> SerializeWith<Vec<T>, S> for ReservedVec<EXTRA>
+where
+    T: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedReservedVec::serialize_from_slice(field.as_slice(), EXTRA, serializer)
+    }
+}
+
+impl<T, D, const EXTRA: usize> DeserializeWith<ArchivedReservedVec<T::Archived>, Vec<T>, D>
+    for ReservedVec<EXTRA>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedReservedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        let mut result = Vec::with_capacity(field.len());
+        for item in field.iter() {
+            result.push(item.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
 // Niche
 
 impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Option<Box<T>>> for Niche
@@ -406,6 +599,70 @@ where
     }
 }
 
+// The pointee must be sized so that the impls don't require bounds on the archived metadata, which
+// would overflow when evaluated for recursive types.
+macro_rules! impl_niche_shared_pointer {
+    ($ptr:ident) => {
+        impl<T: Archive> ArchiveWith<Option<$ptr<T>>> for Niche {
+            type Archived =
+                ArchivedOptionRc<T::Archived, <$ptr<T> as ArchiveSharedPointer>::Flavor>;
+            type Resolver = OptionRcResolver<MetadataResolver<T>>;
+
+            unsafe fn resolve_with(
+                field: &Option<$ptr<T>>,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedOptionRc::resolve_from_option(field.as_deref(), pos, resolver, out);
+            }
+        }
+
+        impl<T, S> SerializeWith<Option<$ptr<T>>, S> for Niche
+        where
+            T: Serialize<S> + 'static,
+            S: Serializer + SharedSerializeRegistry + ?Sized,
+        {
+            fn serialize_with(
+                field: &Option<$ptr<T>>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedOptionRc::<
+                    T::Archived,
+                    <$ptr<T> as ArchiveSharedPointer>::Flavor,
+                >::serialize_from_option(field.as_deref(), serializer)
+            }
+        }
+
+        impl<T, D>
+            DeserializeWith<
+                ArchivedOptionRc<T::Archived, <$ptr<T> as ArchiveSharedPointer>::Flavor>,
+                Option<$ptr<T>>,
+                D,
+            > for Niche
+        where
+            T: Archive + 'static,
+            T::Archived: Deserialize<T, D>,
+            D: SharedDeserializeRegistry + ?Sized,
+        {
+            fn deserialize_with(
+                field: &ArchivedOptionRc<T::Archived, <$ptr<T> as ArchiveSharedPointer>::Flavor>,
+                deserializer: &mut D,
+            ) -> Result<Option<$ptr<T>>, D::Error> {
+                if let Some(value) = field.as_ref() {
+                    Ok(Some(value.deserialize(deserializer)?))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    };
+}
+
+impl_niche_shared_pointer!(Rc);
+#[cfg(has_atomics)]
+impl_niche_shared_pointer!(Arc);
+
 // CopyOptimize
 
 impl<T: Archive> ArchiveWith<Vec<T>> for CopyOptimize {
@@ -602,3 +859,66 @@ where
         Ok(result)
     }
 }
+
+// InlineStr
+
+impl<const N: usize> ArchiveWith<String> for InlineStr<N> {
+    type Archived = ArchivedInlineStr<N>;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve_with(field: &String, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedInlineStr::emplace(field, out);
+    }
+}
+
+impl<S: Fallible + ?Sized, const N: usize> SerializeWith<String, S> for InlineStr<N>
+where
+    S::Error: From<InlineStrError>,
+{
+    #[inline]
+    fn serialize_with(field: &String, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedInlineStr::<N>::check_len(field)?;
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized, const N: usize> DeserializeWith<ArchivedInlineStr<N>, String, D>
+    for InlineStr<N>
+{
+    #[inline]
+    fn deserialize_with(field: &ArchivedInlineStr<N>, _: &mut D) -> Result<String, D::Error> {
+        Ok(String::from(field.as_str()))
+    }
+}
+
+// AsDisplayString
+
+impl<F: fmt::Display + ?Sized> ArchiveWith<F> for AsDisplayString {
+    type Archived = ArchivedString;
+    type Resolver = DisplayStringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(_: &F, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedString::resolve_from_str(&resolver.string, pos, resolver.resolver, out);
+    }
+}
+
+impl<F: fmt::Display + ?Sized, S: Fallible + ?Sized> SerializeWith<F, S> for AsDisplayString
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &F, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        let string = field.to_string();
+        let resolver = ArchivedString::serialize_from_str(&string, serializer)?;
+        Ok(DisplayStringResolver { string, resolver })
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, String, D> for AsDisplayString {
+    #[inline]
+    fn deserialize_with(field: &ArchivedString, _: &mut D) -> Result<String, D::Error> {
+        Ok(field.as_str().to_string())
+    }
+}
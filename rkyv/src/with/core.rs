@@ -1,3 +1,5 @@
+#[cfg(feature = "rend")]
+use crate::with::{AsBigEndian, AsLittleEndian};
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
     niche::option_nonzero::{
@@ -7,16 +9,30 @@ use crate::{
         ArchivedOptionNonZeroU8,
     },
     option::ArchivedOption,
+    ser::Serializer,
+    string::{
+        inline::{ArchivedInlineStr, InlineStrError},
+        ArchivedStr, ArchivedString, StrResolver, StringResolver,
+    },
+    tag::{ArchivedCheckedTag, TagPrimitive},
+    variants::{
+        ArchiveVariants, ArchivedBoxedVariants, BoxedVariantsResolver, DeserializeVariants,
+        SerializeVariants,
+    },
+    varint::{ArchivedVarInt, VarIntPrimitive, VarIntResolver},
+    vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsBox, DeserializeWith, Inline, Map, Niche, RefAsBox, SerializeWith, Skip,
-        Unsafe,
+        ArchiveWith, AsBox, AsOwned, AsVec, BoxedVariants, CheckedTag, DeserializeWith, Immutable,
+        Inline, InlineStr, Lock, LockError, Map, NanNiche, NanNicheError, Niche, RefAsBox,
+        SerializeWith, Skip, Unsafe, VarInt,
     },
-    Archive, ArchiveUnsized, Deserialize, Fallible, Serialize, SerializeUnsized,
+    Archive, ArchiveUnsized, Archived, Deserialize, Fallible, Serialize, SerializeUnsized,
 };
 use ::core::{
-    cell::{Cell, UnsafeCell},
+    cell::{Cell, RefCell, UnsafeCell},
     convert::TryInto,
     hint::unreachable_unchecked,
+    mem::{size_of, MaybeUninit},
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
         NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
@@ -104,6 +120,78 @@ struct ArchivedOptionVariantNone(ArchivedOptionTag);
 #[repr(C)]
 struct ArchivedOptionVariantSome<T>(ArchivedOptionTag, T);
 
+// Map for arrays
+
+impl<A, O, const N: usize> ArchiveWith<[O; N]> for Map<A>
+where
+    A: ArchiveWith<O>,
+{
+    type Archived = [<A as ArchiveWith<O>>::Archived; N];
+    type Resolver = [<A as ArchiveWith<O>>::Resolver; N];
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &[O; N],
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        let resolvers = MaybeUninit::new(resolver);
+        let resolvers_ptr = resolvers.as_ptr().cast::<<A as ArchiveWith<O>>::Resolver>();
+        let out_ptr = out.cast::<<A as ArchiveWith<O>>::Archived>();
+        for (i, value) in field.iter().enumerate() {
+            A::resolve_with(
+                value,
+                pos + i * size_of::<<A as ArchiveWith<O>>::Archived>(),
+                resolvers_ptr.add(i).read(),
+                out_ptr.add(i),
+            );
+        }
+    }
+}
+
+impl<A, O, S, const N: usize> SerializeWith<[O; N], S> for Map<A>
+where
+    S: Fallible + ?Sized,
+    A: ArchiveWith<O> + SerializeWith<O, S>,
+{
+    #[inline]
+    fn serialize_with(field: &[O; N], s: &mut S) -> Result<Self::Resolver, S::Error> {
+        let mut result = MaybeUninit::<Self::Resolver>::uninit();
+        let result_ptr = result
+            .as_mut_ptr()
+            .cast::<<A as ArchiveWith<O>>::Resolver>();
+        for (i, value) in field.iter().enumerate() {
+            unsafe {
+                result_ptr.add(i).write(A::serialize_with(value, s)?);
+            }
+        }
+        unsafe { Ok(result.assume_init()) }
+    }
+}
+
+impl<A, O, D, const N: usize> DeserializeWith<[<A as ArchiveWith<O>>::Archived; N], [O; N], D>
+    for Map<A>
+where
+    D: Fallible + ?Sized,
+    A: ArchiveWith<O> + DeserializeWith<<A as ArchiveWith<O>>::Archived, O, D>,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &[<A as ArchiveWith<O>>::Archived; N],
+        d: &mut D,
+    ) -> Result<[O; N], D::Error> {
+        let mut result = MaybeUninit::<[O; N]>::uninit();
+        let result_ptr = result.as_mut_ptr().cast::<O>();
+        for (i, value) in field.iter().enumerate() {
+            unsafe {
+                result_ptr.add(i).write(A::deserialize_with(value, d)?);
+            }
+        }
+        unsafe { Ok(result.assume_init()) }
+    }
+}
+
 // Inline
 
 impl<F: Archive> ArchiveWith<&F> for Inline {
@@ -128,6 +216,60 @@ impl<F: Serialize<S>, S: Fallible + ?Sized> SerializeWith<&F, S> for Inline {
     }
 }
 
+impl ArchiveWith<&str> for Inline {
+    type Archived = ArchivedStr;
+    type Resolver = StrResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &&str,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedStr::resolve_from_str(field, pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<&str, S> for Inline
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &&str, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedStr::serialize_from_str(field, serializer)
+    }
+}
+
+// AsVec
+
+impl<T: Archive> ArchiveWith<&[T]> for AsVec {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &&[T],
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedVec::resolve_from_slice(field, pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<&[T], S> for AsVec
+where
+    T: Serialize<S>,
+    S: Serializer + ?Sized,
+    [T]: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &&[T], serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::<T::Archived>::serialize_from_slice(field, serializer)
+    }
+}
+
 // RefAsBox
 
 impl<F: ArchiveUnsized + ?Sized> ArchiveWith<&F> for RefAsBox {
@@ -189,6 +331,82 @@ where
     }
 }
 
+// AsOwned
+
+impl<F: Archive> ArchiveWith<&F> for AsOwned {
+    type Archived = F::Archived;
+    type Resolver = F::Resolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &&F,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+impl<F: Serialize<S>, S: Fallible + ?Sized> SerializeWith<&F, S> for AsOwned {
+    #[inline]
+    fn serialize_with(field: &&F, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        field.serialize(serializer)
+    }
+}
+
+impl<T: Archive> ArchiveWith<&[T]> for AsOwned {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &&[T],
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedVec::resolve_from_slice(field, pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<&[T], S> for AsOwned
+where
+    T: Serialize<S>,
+    S: Serializer + ?Sized,
+    [T]: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &&[T], serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::<T::Archived>::serialize_from_slice(field, serializer)
+    }
+}
+
+impl ArchiveWith<&str> for AsOwned {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &&str,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedString::resolve_from_str(field, pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<&str, S> for AsOwned
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &&str, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(field, serializer)
+    }
+}
+
 // Niche
 
 macro_rules! impl_nonzero_niche {
@@ -328,6 +546,271 @@ impl<D: Fallible + ?Sized> DeserializeWith<ArchivedOptionNonZeroUsize, Option<No
     }
 }
 
+// NanNiche
+
+macro_rules! impl_nan_niche {
+    ($float:ty) => {
+        impl ArchiveWith<Option<$float>> for NanNiche {
+            type Archived = Archived<$float>;
+            type Resolver = ();
+
+            #[inline]
+            unsafe fn resolve_with(
+                field: &Option<$float>,
+                _: usize,
+                _: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                out.write(to_archived!(field.unwrap_or(<$float>::NAN)));
+            }
+        }
+
+        impl<S: Fallible + ?Sized> SerializeWith<Option<$float>, S> for NanNiche
+        where
+            S::Error: From<NanNicheError>,
+        {
+            #[inline]
+            fn serialize_with(
+                field: &Option<$float>,
+                _: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                if field.is_some_and(|x| x.is_nan()) {
+                    Err(NanNicheError::SomeNan.into())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        impl<D: Fallible + ?Sized> DeserializeWith<Archived<$float>, Option<$float>, D>
+            for NanNiche
+        {
+            #[inline]
+            fn deserialize_with(
+                field: &Archived<$float>,
+                _: &mut D,
+            ) -> Result<Option<$float>, D::Error> {
+                let value = from_archived!(*field);
+                Ok(if value.is_nan() { None } else { Some(value) })
+            }
+        }
+    };
+}
+
+impl_nan_niche!(f32);
+impl_nan_niche!(f64);
+
+// VarInt
+
+impl<F: VarIntPrimitive> ArchiveWith<F> for VarInt {
+    type Archived = ArchivedVarInt<F>;
+    type Resolver = VarIntResolver;
+
+    #[inline]
+    unsafe fn resolve_with(_: &F, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVarInt::resolve_from_value(pos, resolver, out);
+    }
+}
+
+impl<F: VarIntPrimitive, S: Serializer + ?Sized> SerializeWith<F, S> for VarInt {
+    #[inline]
+    fn serialize_with(field: &F, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVarInt::serialize_from_value(*field, serializer)
+    }
+}
+
+impl<F: VarIntPrimitive, D: Fallible + ?Sized> DeserializeWith<ArchivedVarInt<F>, F, D> for VarInt {
+    #[inline]
+    fn deserialize_with(field: &ArchivedVarInt<F>, _: &mut D) -> Result<F, D::Error> {
+        Ok(field.get())
+    }
+}
+
+// AsLittleEndian and AsBigEndian
+
+#[cfg(feature = "rend")]
+macro_rules! impl_with_endian {
+    (@impl $wrapper:ident, $endian:ident; $($ty:ty),* $(,)?) => {
+        $(
+            impl ArchiveWith<$ty> for $wrapper {
+                type Archived = crate::rend::$endian<$ty>;
+                type Resolver = ();
+
+                #[inline]
+                unsafe fn resolve_with(field: &$ty, _: usize, _: (), out: *mut Self::Archived) {
+                    out.write(<crate::rend::$endian<$ty>>::new(*field));
+                }
+            }
+
+            impl<S: Fallible + ?Sized> SerializeWith<$ty, S> for $wrapper {
+                #[inline]
+                fn serialize_with(_: &$ty, _: &mut S) -> Result<Self::Resolver, S::Error> {
+                    Ok(())
+                }
+            }
+
+            impl<D: Fallible + ?Sized> DeserializeWith<crate::rend::$endian<$ty>, $ty, D>
+                for $wrapper
+            {
+                #[inline]
+                fn deserialize_with(
+                    field: &crate::rend::$endian<$ty>,
+                    _: &mut D,
+                ) -> Result<$ty, D::Error> {
+                    Ok(field.value())
+                }
+            }
+        )*
+    };
+    ($wrapper:ident, $endian:ident) => {
+        impl_with_endian!(
+            @impl $wrapper, $endian;
+            i16, i32, i64, i128, u16, u32, u64, u128, f32, f64, char,
+            NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128,
+            NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128,
+        );
+    };
+}
+
+#[cfg(feature = "rend")]
+impl_with_endian!(AsLittleEndian, LittleEndian);
+#[cfg(feature = "rend")]
+impl_with_endian!(AsBigEndian, BigEndian);
+
+// CheckedTag
+
+impl<T: TagPrimitive, F: ?Sized> ArchiveWith<T> for CheckedTag<F> {
+    type Archived = ArchivedCheckedTag<T, F>;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve_with(field: &T, pos: usize, _: (), out: *mut Self::Archived) {
+        ArchivedCheckedTag::resolve_from_value(field, pos, out);
+    }
+}
+
+impl<T: TagPrimitive, F: ?Sized, S: Fallible + ?Sized> SerializeWith<T, S> for CheckedTag<F> {
+    #[inline]
+    fn serialize_with(_: &T, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<T: TagPrimitive, F: ?Sized, D: Fallible + ?Sized>
+    DeserializeWith<ArchivedCheckedTag<T, F>, T, D> for CheckedTag<F>
+{
+    #[inline]
+    fn deserialize_with(field: &ArchivedCheckedTag<T, F>, _: &mut D) -> Result<T, D::Error> {
+        Ok(field.get())
+    }
+}
+
+// BoxedVariants
+
+impl<T: ArchiveVariants> ArchiveWith<T> for BoxedVariants {
+    type Archived = ArchivedBoxedVariants<T>;
+    type Resolver = BoxedVariantsResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &T,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedBoxedVariants::resolve_from_value(field, pos, resolver, out);
+    }
+}
+
+impl<T: SerializeVariants<S>, S: Fallible + ?Sized> SerializeWith<T, S> for BoxedVariants {
+    #[inline]
+    fn serialize_with(field: &T, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedBoxedVariants::serialize_from_value(field, serializer)
+    }
+}
+
+impl<T: DeserializeVariants<D>, D: Fallible + ?Sized>
+    DeserializeWith<ArchivedBoxedVariants<T>, T, D> for BoxedVariants
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedBoxedVariants<T>,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+// InlineStr
+
+impl<const N: usize> ArchiveWith<&str> for InlineStr<N> {
+    type Archived = ArchivedInlineStr<N>;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve_with(field: &&str, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedInlineStr::emplace(field, out);
+    }
+}
+
+impl<S: Fallible + ?Sized, const N: usize> SerializeWith<&str, S> for InlineStr<N>
+where
+    S::Error: From<InlineStrError>,
+{
+    #[inline]
+    fn serialize_with(field: &&str, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedInlineStr::<N>::check_len(field)?;
+        Ok(())
+    }
+}
+
+// Lock
+
+impl<F: Archive> ArchiveWith<RefCell<F>> for Lock {
+    type Archived = Immutable<F::Archived>;
+    type Resolver = F::Resolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &RefCell<F>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        // Resolve must be infallible, so this panics if the value was mutably borrowed after it was
+        // serialized. Like `Mutex`, the value must not be modified between serializing and
+        // resolving.
+        field.borrow().resolve(pos, resolver, out.cast());
+    }
+}
+
+impl<F: Serialize<S>, S: Fallible + ?Sized> SerializeWith<RefCell<F>, S> for Lock
+where
+    S::Error: From<LockError>,
+{
+    #[inline]
+    fn serialize_with(field: &RefCell<F>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        field
+            .try_borrow()
+            .map_err(|_| LockError::Borrowed)?
+            .serialize(serializer)
+    }
+}
+
+impl<F, T, D> DeserializeWith<Immutable<F>, RefCell<T>, D> for Lock
+where
+    F: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &Immutable<F>,
+        deserializer: &mut D,
+    ) -> Result<RefCell<T>, D::Error> {
+        Ok(RefCell::new(field.value().deserialize(deserializer)?))
+    }
+}
+
 // Unsafe
 
 impl<F: Archive> ArchiveWith<UnsafeCell<F>> for Unsafe {
@@ -17,6 +17,8 @@ pub use self::alloc::*;
 pub use self::std::*;
 
 use crate::{Archive, Deserialize, Fallible, Serialize};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use ::alloc::string::String;
 use ::core::{fmt, marker::PhantomData, mem::transmute, ops::Deref};
 
 /// A transparent wrapper for archived fields.
@@ -274,7 +276,8 @@ const _: () = {
     }
 };
 
-/// A generic wrapper that allows wrapping an `Option<T>`.
+/// A generic wrapper that applies a wrapper to the contents of an `Option<T>`, `Vec<T>`, or
+/// `[T; N]`.
 ///
 /// # Example
 ///
@@ -287,6 +290,8 @@ const _: () = {
 ///     option: Option<&'a i32>,
 ///     #[with(Map<RefAsBox>)]
 ///     vec: Vec<&'a i32>,
+///     #[with(Map<RefAsBox>)]
+///     array: [&'a i32; 4],
 /// }
 /// ```
 #[derive(Debug)]
@@ -324,6 +329,9 @@ pub struct Atomic;
 /// References serialized with `Inline` cannot be deserialized because the struct cannot own the
 /// deserialized value.
 ///
+/// String slices are archived as an [`ArchivedStr`](crate::string::ArchivedStr), which points to
+/// the contents of the string.
+///
 /// # Example
 ///
 /// ```
@@ -444,8 +452,97 @@ impl fmt::Display for AsStringError {
 #[cfg(feature = "std")]
 impl ::std::error::Error for AsStringError {}
 
+/// A wrapper that archives a value as the string it displays.
+///
+/// This works for any type that implements `Display`, which is useful for fields that can't be
+/// archived otherwise. The conversion is lossy: only the displayed string is archived, so the
+/// archived value can't be converted back into the original type. The archived string deserializes
+/// into a `String`.
+///
+/// Requires the `alloc` feature, since the value is formatted into a temporary string while it's
+/// serialized.
+///
+/// # Example
+///
+/// ```
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use rkyv::{archived_root, with::AsDisplayString, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[with(AsDisplayString)]
+///     address: IpAddr,
+/// }
+///
+/// let value = Example { address: IpAddr::V4(Ipv4Addr::LOCALHOST) };
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { archived_root::<Example>(&bytes) };
+/// assert_eq!(archived.address, "127.0.0.1");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct AsDisplayString;
+
+/// A wrapper that archives a boxed error as the string it displays.
+///
+/// This supports `Box<dyn Error>` and `Box<dyn Error + Send + Sync>`. Use `Map<ToErrorString>` for
+/// optional errors. Deserializing creates a new boxed error that displays the archived string.
+///
+/// The conversion is lossy: only the message of the error is archived. The type of the error, its
+/// source chain, and any other data it carries are lost, so the deserialized error can't be
+/// downcast to the original error type.
+///
+/// # Example
+///
+/// ```
+/// use std::error::Error;
+/// use rkyv::{archived_root, with::{Map, ToErrorString}, Archive, Deserialize, Infallible, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct TaskResult {
+///     id: u32,
+///     #[with(Map<ToErrorString>)]
+///     error: Option<Box<dyn Error + Send + Sync>>,
+/// }
+///
+/// let value = TaskResult {
+///     id: 1,
+///     error: Some("connection refused".into()),
+/// };
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { archived_root::<TaskResult>(&bytes) };
+/// assert_eq!(archived.error.as_ref().unwrap(), "connection refused");
+///
+/// let deserialized: TaskResult = archived.deserialize(&mut Infallible).unwrap();
+/// assert_eq!(deserialized.error.unwrap().to_string(), "connection refused");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ToErrorString;
+
+/// The resolver for [`AsDisplayString`] and [`ToErrorString`].
+///
+/// This keeps the displayed string so the value only has to be formatted once.
+#[cfg(feature = "alloc")]
+pub struct DisplayStringResolver {
+    string: String,
+    resolver: crate::string::StringResolver,
+}
+
 /// A wrapper that locks a lock and serializes the value immutably.
 ///
+/// This wrapper supports `Mutex` and `RwLock` (with the `std` feature) as well as `RefCell`. The
+/// current value is serialized as a snapshot, and the archived value has no lock or cell overhead.
+/// Serializing a poisoned lock fails with [`LockError::Poisoned`], and serializing a `RefCell` that
+/// is already mutably borrowed fails with [`LockError::Borrowed`].
+///
+/// Neither the lock state nor poisoning is preserved. Deserializing always creates a new value that
+/// is unlocked and not poisoned, even if the original was poisoned when it was serialized.
+///
+/// `Mutex` and `RwLock` intentionally don't implement `Archive` themselves. The lock has to be
+/// taken again in the resolve step, and nothing stops safe code from mutating the value in between,
+/// so the wrapper has to be opted into explicitly as described below.
+///
 /// This wrapper can panic under very specific circumstances when:
 ///
 /// 1. `serialize_with` is called and succeeds in locking the value to serialize it.
@@ -467,6 +564,7 @@ impl ::std::error::Error for AsStringError {}
 /// # Example
 ///
 /// ```
+/// use core::cell::RefCell;
 /// use std::sync::Mutex;
 /// use rkyv::{Archive, with::Lock};
 ///
@@ -474,6 +572,8 @@ impl ::std::error::Error for AsStringError {}
 /// struct Example {
 ///     #[with(Lock)]
 ///     a: Mutex<i32>,
+///     #[with(Lock)]
+///     b: RefCell<Vec<i32>>,
 /// }
 /// ```
 #[derive(Debug)]
@@ -484,30 +584,69 @@ pub struct Lock;
 pub enum LockError {
     /// The mutex was poisoned
     Poisoned,
+    /// The `RefCell` was already mutably borrowed
+    Borrowed,
 }
 
 impl fmt::Display for LockError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "lock poisoned")
+        match self {
+            LockError::Poisoned => write!(f, "lock poisoned"),
+            LockError::Borrowed => write!(f, "already mutably borrowed"),
+        }
     }
 }
 
 #[cfg(feature = "std")]
 impl ::std::error::Error for LockError {}
 
-/// A wrapper that serializes a `Cow` as if it were owned.
+/// A wrapper that serializes a `Cow` or a reference as if it were owned.
+///
+/// Borrowed and owned values are archived the same way:
+///
+/// - `Cow<'a, T>` and `&'a T` archive as `Archived<T>`
+/// - `Cow<'a, [T]>` and `&'a [T]` archive as `ArchivedVec<Archived<T>>`, with each element
+///   archived
+/// - `Cow<'a, str>` and `&'a str` archive as `ArchivedString`
+/// - `Cow<'a, CStr>` archives as `ArchivedCString` (requires `std`)
+///
+/// Deserializing a `Cow` always produces `Cow::Owned`, and references can't be deserialized.
+/// Reference fields without any wrappers are archived with `AsOwned` by default, and fields that
+/// are arrays of references with `Map<AsOwned>`.
 ///
 /// # Example
 ///
 /// ```
 /// use std::borrow::Cow;
-/// use rkyv::{Archive, with::AsOwned};
+/// use rkyv::{Archive, Deserialize, Infallible, Serialize, with::AsOwned};
 ///
-/// #[derive(Archive)]
+/// #[derive(Archive, Serialize, Deserialize, Clone)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// #[derive(Archive, Serialize, Deserialize)]
 /// struct Example<'a> {
 ///     #[with(AsOwned)]
-///     a: Cow<'a, str>,
+///     name: Cow<'a, str>,
+///     #[with(AsOwned)]
+///     points: Cow<'a, [Point]>,
 /// }
+///
+/// let points = [Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }];
+/// let value = Example {
+///     name: Cow::Borrowed("path"),
+///     points: Cow::Borrowed(&points),
+/// };
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { rkyv::archived_root::<Example>(&bytes) };
+/// assert_eq!(archived.name, "path");
+/// assert_eq!(archived.points[1].y, 4.0);
+///
+/// let deserialized: Example = archived.deserialize(&mut Infallible).unwrap();
+/// assert!(matches!(deserialized.points, Cow::Owned(_)));
 /// ```
 #[derive(Debug)]
 pub struct AsOwned;
@@ -517,6 +656,10 @@ pub struct AsOwned;
 /// This provides faster serialization for containers like `HashMap` and `BTreeMap` by serializing
 /// the key-value pairs directly instead of building a data structure in the buffer.
 ///
+/// `AsVec` can also serialize borrowed slices (`&[T]`) as if they were an owned `Vec<T>`. Slices
+/// serialized with `AsVec` cannot be deserialized because the struct cannot own the deserialized
+/// value.
+///
 /// # Example
 ///
 /// ```
@@ -524,19 +667,217 @@ pub struct AsOwned;
 /// use rkyv::{Archive, with::AsVec};
 ///
 /// #[derive(Archive)]
-/// struct Example {
+/// struct Example<'a> {
 ///     #[with(AsVec)]
 ///     values: HashMap<String, u32>,
+///     #[with(AsVec)]
+///     slice: &'a [u32],
 /// }
 /// ```
 #[derive(Debug)]
 pub struct AsVec;
 
+/// A wrapper that serializes associative containers as a `Vec` of key-value pairs sorted by key.
+///
+/// This produces deterministic archives from containers with unspecified iteration order, like
+/// `HashMap`. The archived [`ArchivedSortedVecMap`](crate::collections::ArchivedSortedVecMap)
+/// supports lookups by binary search, and deserializes back into the original container.
+///
+/// Entries are sorted by their unarchived keys, but lookups binary search the archived keys. The
+/// archived keys must have the same order as the unarchived keys, otherwise lookups may miss
+/// entries. Archives with keys that are out of order fail validation.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{Archive, with::AsSortedVec};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AsSortedVec)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsSortedVec;
+
+/// A wrapper that serializes a `Vec` with its elements sorted and duplicates removed.
+///
+/// The archived [`ArchivedSortedVec`](crate::collections::ArchivedSortedVec) checks that its
+/// elements are in strictly ascending order during validation, so binary searches over a checked
+/// archive always give correct results. Elements are sorted by their unarchived order, so the
+/// archived elements must have the same order as the unarchived elements. Otherwise, lookups on
+/// unchecked archives may miss elements. It deserializes into a sorted `Vec` without duplicates.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::SortedVec};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(SortedVec)]
+///     ids: Vec<u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SortedVec;
+
+/// A wrapper that serializes maps as two parallel arrays of keys and values.
+///
+/// The archived [`ArchivedSoAMap`](crate::collections::ArchivedSoAMap) stores all of the keys in
+/// one contiguous array sorted by key, and all of the values in another in the same order. This
+/// struct-of-arrays layout gives bulk scans over the values perfect cache locality, while point
+/// lookups binary search the sorted keys. It deserializes back into the original map.
+///
+/// Keys are sorted by their unarchived order, so the archived keys must have the same order as the
+/// unarchived keys. Otherwise, lookups may miss entries. Archives with keys that are out of order
+/// fail validation.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{Archive, with::MapAsSoA};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(MapAsSoA)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MapAsSoA;
+
+/// A wrapper that serializes a `BTreeMap` as a hash map.
+///
+/// The map is archived as an [`ArchivedHashMap`](crate::collections::ArchivedHashMap), which is
+/// the same archived type as a `HashMap`. This decouples the serialized container from the
+/// deserialized one, so archives that were serialized from a `HashMap` can be read into a
+/// `BTreeMap` after changing the data model. It deserializes back into a `BTreeMap`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::{BTreeMap, HashMap};
+/// use rkyv::{archived_root, with::AsHashMap, Archive, Deserialize, Infallible, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Before {
+///     values: HashMap<String, u32>,
+/// }
+///
+/// #[derive(Archive, Deserialize)]
+/// struct After {
+///     #[with(AsHashMap)]
+///     values: BTreeMap<String, u32>,
+/// }
+///
+/// let mut values = HashMap::new();
+/// values.insert("a".to_string(), 1);
+/// values.insert("b".to_string(), 2);
+/// let bytes = rkyv::to_bytes::<_, 256>(&Before { values }).unwrap();
+///
+/// // Read the archived hash map into a B-tree map
+/// let archived = unsafe { archived_root::<After>(&bytes) };
+/// let after: After = archived.deserialize(&mut Infallible).unwrap();
+/// let keys = after.values.keys().map(|k| k.as_str()).collect::<Vec<_>>();
+/// assert_eq!(keys, ["a", "b"]);
+/// ```
+#[derive(Debug)]
+pub struct AsHashMap;
+
+/// A wrapper that serializes a `HashMap` as a B-tree map.
+///
+/// The map is archived as an [`ArchivedBTreeMap`](crate::collections::ArchivedBTreeMap), which is
+/// the same archived type as a `BTreeMap`. This decouples the serialized container from the
+/// deserialized one, so archives that were serialized from a `BTreeMap` can be read into a
+/// `HashMap` after changing the data model. Keys are sorted while serializing, so they must
+/// implement `Ord`, and the archived keys must have the same order as the unarchived keys.
+/// Otherwise, lookups may miss entries. It deserializes back into a `HashMap`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{Archive, with::AsBTreeMap};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AsBTreeMap)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsBTreeMap;
+
+/// A wrapper that serializes a `HashMap` or `HashSet` with the
+/// [`SeaHash`](crate::collections::hash_index::HashAlgorithm::SeaHash) hash algorithm.
+///
+/// This is the default for hash maps and hash sets, so this wrapper only makes that choice
+/// explicit.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{Archive, with::SeaHash};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(SeaHash)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SeaHash;
+
+/// A wrapper that serializes a `HashMap` or `HashSet` with the
+/// [`SipHash`](crate::collections::hash_index::HashAlgorithm::SipHash) hash algorithm.
+///
+/// SipHash is slower than the default. Its keys are fixed and public so that lookups can reproduce
+/// the hashes, so it doesn't protect against crafted keys that collide: it's only a different hash
+/// function. The archive records which hash algorithm was used, so lookups in the archived map
+/// pick the matching one automatically. It deserializes back into a `HashMap` or `HashSet`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{
+///     archived_root,
+///     collections::hash_index::HashAlgorithm,
+///     with::SipHash,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[with(SipHash)]
+///     values: HashMap<String, u32>,
+/// }
+///
+/// let mut values = HashMap::new();
+/// values.insert("a".to_string(), 1);
+/// values.insert("b".to_string(), 2);
+/// let bytes = rkyv::to_bytes::<_, 256>(&Example { values }).unwrap();
+///
+/// let archived = unsafe { archived_root::<Example>(&bytes) };
+/// assert_eq!(archived.values.hash_algorithm(), HashAlgorithm::SipHash);
+/// assert_eq!(archived.values.get("b"), Some(&2));
+/// ```
+#[derive(Debug)]
+pub struct SipHash;
+
 /// A wrapper that niches some type combinations.
 ///
 /// A common type combination is `Option<Box<T>>`. By using a null pointer, the archived version can
 /// save some space on-disk.
 ///
+/// `Option<Rc<T>>` and `Option<Arc<T>>` are niched the same way for sized `T`. Shared values are
+/// still only serialized once, and `None` pointers don't claim any memory during validation.
+///
 /// # Example
 ///
 /// ```
@@ -559,6 +900,506 @@ pub struct AsVec;
 #[derive(Debug)]
 pub struct Niche;
 
+/// A wrapper that niches `None` into a NaN for `Option<f32>` and `Option<f64>`.
+///
+/// The archived field is a plain archived float instead of an archived option. `None` is archived
+/// as a canonical NaN and `Some(x)` as `x`, which saves the space of the option tag. Combined with
+/// [`Map`], a `Vec<Option<f64>>` archives as a flat `ArchivedVec<f64>` that can be scanned like any
+/// other slice of floats.
+///
+/// Because NaN is reserved for `None`, serializing `Some(NaN)` fails with
+/// [`NanNicheError::SomeNan`], so a custom serializer with an error type satisfying
+/// `<S as Fallible>::Error: From<NanNicheError>` must be provided. Deserializing maps every NaN
+/// back to `None`. Every bit pattern is a valid float, so validation always succeeds.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     archived_root,
+///     ser::{serializers::AlignedSerializer, Serializer},
+///     with::{NanNiche, NanNicheError},
+///     AlignedVec, Archive, Fallible, Serialize,
+/// };
+///
+/// struct MySerializer(AlignedSerializer<AlignedVec>);
+///
+/// impl Fallible for MySerializer {
+///     type Error = NanNicheError;
+/// }
+///
+/// impl Serializer for MySerializer {
+///     fn pos(&self) -> usize {
+///         self.0.pos()
+///     }
+///
+///     fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+///         self.0.write(bytes).map_err(|e| match e {})
+///     }
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// struct Range {
+///     #[with(NanNiche)]
+///     min: Option<f64>,
+///     #[with(NanNiche)]
+///     max: Option<f64>,
+/// }
+///
+/// let mut serializer = MySerializer(AlignedSerializer::new(AlignedVec::new()));
+/// serializer.serialize_value(&Range { min: Some(1.5), max: None }).unwrap();
+/// let bytes = serializer.0.into_inner();
+/// let archived = unsafe { archived_root::<Range>(&bytes) };
+///
+/// assert_eq!(archived.min, 1.5);
+/// assert!(archived.max.is_nan());
+/// assert_eq!(core::mem::size_of::<ArchivedRange>(), 16);
+///
+/// let mut serializer = MySerializer(AlignedSerializer::new(AlignedVec::new()));
+/// assert!(serializer.serialize_value(&Range { min: Some(f64::NAN), max: None }).is_err());
+/// ```
+#[derive(Debug)]
+pub struct NanNiche;
+
+/// Errors that can occur when serializing a [`NanNiche`] wrapper.
+#[derive(Debug)]
+pub enum NanNicheError {
+    /// A `Some` value was NaN, which is reserved for `None`.
+    SomeNan,
+}
+
+impl fmt::Display for NanNicheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Some(NaN) can't be niched because NaN is reserved for None"
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for NanNicheError {}
+
+/// A wrapper that archives an integer as a variable-length integer.
+///
+/// The integer is encoded with [LEB128](https://en.wikipedia.org/wiki/LEB128) (zigzag-encoded first
+/// for signed integers) and the encoded bytes are stored out-of-line. The archived field is an
+/// [`ArchivedVarInt`](crate::varint::ArchivedVarInt), which holds a relative pointer to the encoded
+/// bytes and must be decoded with [`get`](crate::varint::ArchivedVarInt::get) before it can be
+/// used. This trades zero-copy access for size, so it's best suited to large integer types which
+/// usually hold small values, like 64-bit IDs.
+///
+/// Because the relative pointer is part of the archived field, this only saves space for integers
+/// that are wider than a relative pointer. With the default `size_32` feature, the relative
+/// pointer takes four bytes, so a `u64` below 2<sup>21</sup> takes five to seven bytes instead of
+/// eight. 16- and 32-bit integers always take more space than their fixed-width encodings, and
+/// with `size_64` only 128-bit integers can take less.
+///
+/// During validation, the encoding is checked to be in bounds, terminate within the maximum length
+/// for the integer type, and not overflow the integer type.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, Archive, Serialize, with::VarInt};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[with(VarInt)]
+///     id: u64,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Example { id: 300 }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Example>(&bytes) };
+///
+/// assert_eq!(archived.id.get(), 300);
+/// ```
+#[derive(Debug)]
+pub struct VarInt;
+
+/// A wrapper that archives a primitive as little-endian, regardless of the target and the
+/// endianness features.
+///
+/// The `archive_le` and `archive_be` features pick the endianness of every archived primitive at
+/// compile time. This wrapper picks it per field instead, so archives with a fixed byte order can
+/// be written and read on hosts of either endianness. The archived field is a
+/// [`LittleEndian`](crate::rend::LittleEndian) from `rend`, which converts to and from the
+/// native value when it's accessed.
+///
+/// It can be applied to `i16`, `i32`, `i64`, `i128`, `u16`, `u32`, `u64`, `u128`, `f32`, `f64`,
+/// `char`, and the nonzero integers with more than eight bits. Use [`Map`] to apply it to the
+/// elements of containers, and [`AsBigEndian`] for the opposite byte order.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, with::{AsLittleEndian, Map}, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Packet {
+///     #[with(AsLittleEndian)]
+///     id: u32,
+///     #[with(Map<AsLittleEndian>)]
+///     samples: Vec<f32>,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Packet { id: 0x01020304, samples: vec![1.5] }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Packet>(&bytes) };
+///
+/// assert_eq!(archived.id.value(), 0x01020304);
+/// assert_eq!(archived.samples[0].value(), 1.5);
+/// ```
+#[cfg(feature = "rend")]
+#[derive(Debug)]
+pub struct AsLittleEndian;
+
+/// A wrapper that archives a primitive as big-endian, regardless of the target and the
+/// endianness features.
+///
+/// The `archive_le` and `archive_be` features pick the endianness of every archived primitive at
+/// compile time. This wrapper picks it per field instead, so archives with a fixed byte order can
+/// be written and read on hosts of either endianness. The archived field is a
+/// [`BigEndian`](crate::rend::BigEndian) from `rend`, which converts to and from the
+/// native value when it's accessed.
+///
+/// It can be applied to `i16`, `i32`, `i64`, `i128`, `u16`, `u32`, `u64`, `u128`, `f32`, `f64`,
+/// `char`, and the nonzero integers with more than eight bits. Use [`Map`] to apply it to the
+/// elements of containers, and [`AsLittleEndian`] for the opposite byte order.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, with::{AsBigEndian, Map}, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Packet {
+///     #[with(AsBigEndian)]
+///     id: u32,
+///     #[with(Map<AsBigEndian>)]
+///     samples: Vec<f32>,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Packet { id: 0x01020304, samples: vec![1.5] }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Packet>(&bytes) };
+///
+/// assert_eq!(archived.id.value(), 0x01020304);
+/// assert_eq!(archived.samples[0].value(), 1.5);
+/// ```
+#[cfg(feature = "rend")]
+#[derive(Debug)]
+pub struct AsBigEndian;
+
+/// A wrapper that archives a `Vec<f32>` as `BITS`-bit quantized integers.
+///
+/// The archived [`ArchivedQuantizedVec`](crate::quantize::ArchivedQuantizedVec) stores the
+/// minimum and maximum of the values along with each value linearly quantized to an integer between
+/// 0 and `2^BITS - 1`. The quantized integers are packed into bytes, so an 8-bit quantization is a
+/// quarter of the size of the original values. `BITS` must be between 1 and 16.
+///
+/// This is lossy: values are accessed with
+/// [`get_dequantized`](crate::quantize::ArchivedQuantizedVec::get_dequantized) and deserialized as
+/// approximations of the original values. The error for each value is at most half of
+/// `(max - min) / (2^BITS - 1)`. Non-finite values can't be represented, so infinities are clamped
+/// to the range of the finite values and NaNs are quantized to the minimum.
+///
+/// During validation, the minimum and maximum are checked to be a finite range and the length of
+/// the quantized data is checked against the number of values.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, with::Quantize, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Embedding {
+///     #[with(Quantize<8>)]
+///     values: Vec<f32>,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Embedding { values: vec![-1.0, 0.5, 1.0] }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Embedding>(&bytes) };
+///
+/// assert_eq!(archived.values.len(), 3);
+/// assert!((archived.values.get_dequantized(1).unwrap() - 0.5).abs() < 0.01);
+/// ```
+#[derive(Debug)]
+pub struct Quantize<const BITS: u8>;
+
+/// A wrapper that archives a `Vec` with space reserved for `EXTRA` more elements.
+///
+/// The archived [`ArchivedReservedVec`](crate::vec::ArchivedReservedVec) records its capacity
+/// along with its length, and the reserved space is serialized as zeroes directly after the
+/// elements. Elements can then be appended to the archive in place with
+/// [`try_push`](crate::vec::ArchivedReservedVec::try_push), which fails once the reserved space
+/// runs out. This allows bounded growth of archives that are kept in mutable memory, like a log
+/// stored in a writable memory map.
+///
+/// The capacity isn't preserved when deserializing, only the elements in use are deserialized.
+///
+/// During validation, the length is checked to be at most the capacity and the reserved space is
+/// claimed along with the elements, so no other data in the archive can overlap it. Only the
+/// elements in use are checked.
+///
+/// # Example
+///
+/// ```
+/// use core::pin::Pin;
+/// use rkyv::{archived_root_mut, ser::{Serializer, serializers::AllocSerializer}, with::ReservedVec, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Log {
+///     #[with(ReservedVec<2>)]
+///     entries: Vec<u32>,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Log { entries: vec![1, 2] }).unwrap();
+/// let mut bytes = serializer.into_serializer().into_inner();
+/// let mut archived = unsafe { archived_root_mut::<Log>(Pin::new(bytes.as_mut_slice())) };
+///
+/// let mut entries = unsafe { archived.as_mut().map_unchecked_mut(|l| &mut l.entries) };
+/// entries.as_mut().try_push(3).unwrap();
+/// entries.as_mut().try_push(4).unwrap();
+/// assert!(entries.as_mut().try_push(5).is_err());
+/// assert_eq!(archived.entries.as_slice(), &[1, 2, 3, 4]);
+/// ```
+#[derive(Debug)]
+pub struct ReservedVec<const EXTRA: usize>;
+
+/// A wrapper that archives a `HashSet` as a bloom filter for approximate membership checks.
+///
+/// The archived [`ArchivedBloomFilter`](crate::bloom::ArchivedBloomFilter) doesn't store the
+/// elements of the set. Instead, it stores a bit array with about ten bits per element, which is
+/// usually much smaller than the elements themselves. Membership is checked with
+/// [`probably_contains`](crate::bloom::ArchivedBloomFilter::probably_contains), which has no false
+/// negatives and a false positive rate of about 1%. Elements are hashed with their `Hash`
+/// implementation, so the elements passed to `probably_contains` must hash the same way as the
+/// elements of the original set.
+///
+/// This is lossy: the elements can't be recovered from the filter, so **deserializing always
+/// produces an empty set**.
+///
+/// During validation, only the bounds of the bit array are checked.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, with::AsBloomFilter, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Blocklist {
+///     #[with(AsBloomFilter)]
+///     domains: HashSet<String>,
+/// }
+///
+/// let domains = ["example.com", "example.org"].iter().map(|d| d.to_string()).collect();
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Blocklist { domains }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Blocklist>(&bytes) };
+///
+/// assert!(archived.domains.probably_contains("example.com"));
+/// ```
+#[derive(Debug)]
+pub struct AsBloomFilter;
+
+/// A wrapper that checks an integer tag against a set of valid tags provided at runtime.
+///
+/// Archived enums are checked against the variants known at compile time. For formats that can be
+/// extended at runtime (e.g. by plugins), a tag can instead be archived as a plain unsigned integer
+/// with this wrapper. The archived field is an [`ArchivedCheckedTag`](crate::tag::ArchivedCheckedTag),
+/// which has the same layout as the archived integer.
+///
+/// During validation, the tag is checked by a context that implements
+/// [`TagContext<F>`](crate::validation::TagContext). The marker type `F` identifies the set of
+/// valid tags, so different fields can be checked against different sets.
+/// [`TagValidator`](crate::validation::validators::TagValidator) provides that context by wrapping
+/// another validator and checking tags with a predicate. Fields marked with this wrapper can't be
+/// checked with the default validator.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{archived_root, ser::{Serializer, serializers::AllocSerializer}, with::CheckedTag, Archive, Serialize};
+///
+/// struct Kind;
+///
+/// #[derive(Archive, Serialize)]
+/// struct Record {
+///     #[with(CheckedTag<Kind>)]
+///     kind: u16,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Record { kind: 300 }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Record>(&bytes) };
+///
+/// assert_eq!(archived.kind.get(), 300);
+/// ```
+#[derive(Debug)]
+pub struct CheckedTag<F: ?Sized> {
+    _phantom: PhantomData<F>,
+}
+
+/// A wrapper that archives an enum with the payload of its variant stored out of line.
+///
+/// Archived enums are as large as their largest variant, which wastes space when most values are
+/// small variants. With this wrapper, the archived field is an
+/// [`ArchivedBoxedVariants`](crate::variants::ArchivedBoxedVariants) that only stores the index of
+/// the variant and a relative pointer to its payload, and the payload takes up only as much space
+/// as the archived variant needs. This trades an extra pointer indirection for the smaller size.
+///
+/// The enum must derive `Archive`, `Serialize`, and `Deserialize` with
+/// `#[archive(boxed_variants)]`. Every variant must either be a unit variant or have a single
+/// unnamed field. With `#[archive(check_bytes)]`, the payload is validated according to the
+/// archived variant index.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     archived_root,
+///     ser::{Serializer, serializers::AllocSerializer},
+///     with::BoxedVariants,
+///     Archive, Deserialize, Infallible, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// #[archive(boxed_variants)]
+/// enum Message {
+///     Ping,
+///     Reading(u32),
+///     Block([u8; 256]),
+/// }
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Event {
+///     #[with(BoxedVariants)]
+///     message: Message,
+/// }
+///
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&Event { message: Message::Reading(42) }).unwrap();
+/// let bytes = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Event>(&bytes) };
+///
+/// assert_eq!(archived.message.variant_index(), 1);
+/// let event: Event = archived.deserialize(&mut Infallible).unwrap();
+/// assert_eq!(event.message, Message::Reading(42));
+/// // The event doesn't have to make room for the largest variant inline
+/// assert!(core::mem::size_of::<ArchivedEvent>() < core::mem::size_of::<ArchivedMessage>());
+/// ```
+#[derive(Debug)]
+pub struct BoxedVariants;
+
+/// A wrapper that archives a string inline with a fixed capacity of `N` bytes.
+///
+/// The archived field is an [`ArchivedInlineStr`](crate::string::inline::ArchivedInlineStr), which
+/// stores a length byte followed by `N` bytes of contents instead of a relative pointer. Every
+/// archived value has the same size, which keeps records uniformly sized for fixed-layout archives.
+/// Because the length is stored in a single byte, the capacity is limited to 255 bytes.
+///
+/// This wrapper supports `String` and `&str`. Serializing a string longer than the capacity fails
+/// with [`InlineStrError::TooLong`](crate::string::inline::InlineStrError::TooLong), so a custom
+/// serializer with an error type satisfying `<S as Fallible>::Error: From<InlineStrError>` must be
+/// provided. During validation, the length is checked against the capacity and the contents are
+/// checked to be valid UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     archived_root,
+///     ser::{serializers::AlignedSerializer, Serializer},
+///     string::inline::InlineStrError,
+///     with::InlineStr,
+///     AlignedVec, Archive, Fallible, Serialize,
+/// };
+///
+/// struct MySerializer(AlignedSerializer<AlignedVec>);
+///
+/// impl Fallible for MySerializer {
+///     type Error = InlineStrError;
+/// }
+///
+/// impl Serializer for MySerializer {
+///     fn pos(&self) -> usize {
+///         self.0.pos()
+///     }
+///
+///     fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+///         self.0.write(bytes).map_err(|e| match e {})
+///     }
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// struct Record {
+///     #[with(InlineStr<16>)]
+///     name: String,
+/// }
+///
+/// let mut serializer = MySerializer(AlignedSerializer::new(AlignedVec::new()));
+/// serializer.serialize_value(&Record { name: "hello".to_string() }).unwrap();
+/// let bytes = serializer.0.into_inner();
+/// let archived = unsafe { archived_root::<Record>(&bytes) };
+///
+/// assert_eq!(archived.name, "hello");
+/// assert_eq!(core::mem::size_of::<ArchivedRecord>(), 17);
+/// ```
+#[derive(Debug)]
+pub struct InlineStr<const N: usize>;
+
+/// A wrapper that checks the capacity of a fixed-capacity collection during deserialization.
+///
+/// Fixed-capacity collections like `tinyvec::ArrayVec` are archived as an
+/// [`ArchivedVec`](crate::vec::ArchivedVec), which may hold more elements than the collection can
+/// if it was produced by a different type. Deserializing these collections normally panics if there
+/// are too many elements, but with this wrapper deserialization fails with a [`CapacityError`]
+/// instead. Archiving and serializing are the same as without the wrapper.
+///
+/// Regular deserializers don't support the custom error handling needed for this type by default.
+/// To use this wrapper, a custom deserializer with an error type satisfying
+/// `<D as Fallible>::Error: From<CapacityError>` must be provided.
+///
+/// This wrapper supports `tinyvec::ArrayVec` with the `tinyvec` feature and `arrayvec::ArrayString`
+/// with the `arrayvec` feature. The capacity of an `ArrayString` is checked against the length of
+/// the archived string in bytes.
+#[derive(Debug)]
+pub struct CheckCapacity;
+
+/// An error that occurs when deserializing a [`CheckCapacity`] wrapper with too many elements.
+#[derive(Debug)]
+pub struct CapacityError {
+    /// The number of archived elements
+    pub len: usize,
+    /// The capacity of the collection
+    pub capacity: usize,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} elements exceeds the collection capacity of {}",
+            self.len, self.capacity
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for CapacityError {}
+
 /// A wrapper that provides specialized, performant implementations of serialization and
 /// deserialization.
 ///
@@ -670,7 +1511,9 @@ pub struct Raw;
 /// that rkyv is unable to guarantee.
 ///
 /// This wrapper enables serializing these types, and places the burden of verifying that their
-/// access semantics are used safely on the user.
+/// access semantics are used safely on the user. Interior mutability is not preserved across the
+/// archive in any meaningful way: the current value is serialized, and a new cell containing the
+/// deserialized value is created on deserialization. For `RefCell`, [`Lock`] can be used instead.
 ///
 /// # Safety
 ///
@@ -1,22 +1,37 @@
 use crate::{
-    collections::util::Entry,
+    bloom::{ArchivedBloomFilter, BloomFilterResolver},
+    collections::{
+        btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+        hash_index::{HashAlgorithm, HashIndexLenError},
+        hash_map::{ArchivedHashMap, HashMapResolver},
+        hash_set::{ArchivedHashSet, HashSetResolver},
+        soa_map::SoAMapResolver,
+        util::Entry,
+        ArchivedSoAMap, ArchivedSortedVecMap,
+    },
     ser::{ScratchSpace, Serializer},
     string::{ArchivedString, StringResolver},
     time::ArchivedDuration,
     vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsString, AsStringError, AsVec, DeserializeWith, Immutable, Lock, LockError,
-        SerializeWith, UnixTimestamp, UnixTimestampError,
+        ArchiveWith, AsBTreeMap, AsBloomFilter, AsDisplayString, AsHashMap, AsSortedVec, AsString,
+        AsStringError, AsVec, DeserializeWith, DisplayStringResolver, Immutable, Lock, LockError,
+        MapAsSoA, SeaHash, SerializeWith, SipHash, ToErrorString, UnixTimestamp,
+        UnixTimestampError,
     },
     Archive, Deserialize, Fallible, Serialize, SerializeUnsized,
 };
-use core::{hash::Hash, str::FromStr};
+use core::{
+    hash::{BuildHasher, Hash},
+    str::FromStr,
+};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    error::Error,
     ffi::OsString,
     path::PathBuf,
     sync::{Mutex, RwLock},
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 // AsString
@@ -290,6 +305,382 @@ where
     }
 }
 
+// AsSortedVec
+
+impl<K: Archive, V: Archive> ArchiveWith<HashMap<K, V>> for AsSortedVec {
+    type Archived = ArchivedSortedVecMap<K::Archived, V::Archived>;
+    type Resolver = VecResolver;
+
+    unsafe fn resolve_with(
+        field: &HashMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSortedVecMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<HashMap<K, V>, S> for AsSortedVec
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(
+        field: &HashMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedVecMap::serialize_from_iter(field.iter(), serializer)
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedSortedVecMap<K::Archived, V::Archived>, HashMap<K, V>, D>
+    for AsSortedVec
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedVecMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V>, D::Error> {
+        let mut result = HashMap::with_capacity(field.len());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// MapAsSoA
+
+impl<K: Archive, V: Archive> ArchiveWith<HashMap<K, V>> for MapAsSoA {
+    type Archived = ArchivedSoAMap<K::Archived, V::Archived>;
+    type Resolver = SoAMapResolver;
+
+    unsafe fn resolve_with(
+        field: &HashMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSoAMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<HashMap<K, V>, S> for MapAsSoA
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(
+        field: &HashMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSoAMap::serialize_from_iter(field.iter(), serializer)
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedSoAMap<K::Archived, V::Archived>, HashMap<K, V>, D>
+    for MapAsSoA
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSoAMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V>, D::Error> {
+        let mut result = HashMap::with_capacity(field.len());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// AsHashMap
+
+impl<K: Archive, V: Archive> ArchiveWith<BTreeMap<K, V>> for AsHashMap {
+    type Archived = ArchivedHashMap<K::Archived, V::Archived>;
+    type Resolver = HashMapResolver;
+
+    unsafe fn resolve_with(
+        field: &BTreeMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedHashMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, V>, S> for AsHashMap
+where
+    K: Serialize<S> + Hash + Eq,
+    K::Archived: Hash + Eq,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<HashIndexLenError>,
+{
+    fn serialize_with(
+        field: &BTreeMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        // Safety: The keys of a B-tree map are unique
+        unsafe { ArchivedHashMap::serialize_from_iter(field.iter(), serializer) }
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedHashMap<K::Archived, V::Archived>, BTreeMap<K, V>, D>
+    for AsHashMap
+where
+    K: Archive + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedHashMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, V>, D::Error> {
+        let mut result = BTreeMap::new();
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// AsBTreeMap
+
+impl<K: Archive, V: Archive> ArchiveWith<HashMap<K, V>> for AsBTreeMap {
+    type Archived = ArchivedBTreeMap<K::Archived, V::Archived>;
+    type Resolver = BTreeMapResolver;
+
+    unsafe fn resolve_with(
+        field: &HashMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedBTreeMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<HashMap<K, V>, S> for AsBTreeMap
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: Serializer + ?Sized,
+{
+    fn serialize_with(
+        field: &HashMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let mut entries = field.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        // Safety: The keys of a hash map are unique, and are sorted before serializing them
+        unsafe {
+            ArchivedBTreeMap::serialize_from_reverse_iter(entries.into_iter().rev(), serializer)
+        }
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedBTreeMap<K::Archived, V::Archived>, HashMap<K, V>, D>
+    for AsBTreeMap
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedBTreeMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V>, D::Error> {
+        let mut result = HashMap::with_capacity(field.len());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// SeaHash and SipHash
+
+macro_rules! impl_hash_algorithm {
+    ($wrapper:ident, $algorithm:expr) => {
+        impl<K: Archive, V: Archive, H> ArchiveWith<HashMap<K, V, H>> for $wrapper {
+            type Archived = ArchivedHashMap<K::Archived, V::Archived>;
+            type Resolver = HashMapResolver;
+
+            #[inline]
+            unsafe fn resolve_with(
+                field: &HashMap<K, V, H>,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashMap::resolve_from_len(field.len(), pos, resolver, out);
+            }
+        }
+
+        impl<K, V, H, S> SerializeWith<HashMap<K, V, H>, S> for $wrapper
+        where
+            K: Serialize<S> + Hash + Eq,
+            K::Archived: Hash + Eq,
+            V: Serialize<S>,
+            S: ScratchSpace + Serializer + ?Sized,
+            S::Error: From<HashIndexLenError>,
+        {
+            #[inline]
+            fn serialize_with(
+                field: &HashMap<K, V, H>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                // Safety: The keys of a hash map are unique
+                unsafe {
+                    ArchivedHashMap::serialize_from_iter_with(field.iter(), $algorithm, serializer)
+                }
+            }
+        }
+
+        impl<K, V, H, D>
+            DeserializeWith<ArchivedHashMap<K::Archived, V::Archived>, HashMap<K, V, H>, D>
+            for $wrapper
+        where
+            K: Archive + Hash + Eq,
+            V: Archive,
+            K::Archived: Deserialize<K, D> + Hash + Eq,
+            V::Archived: Deserialize<V, D>,
+            H: Default + BuildHasher,
+            D: Fallible + ?Sized,
+        {
+            #[inline]
+            fn deserialize_with(
+                field: &ArchivedHashMap<K::Archived, V::Archived>,
+                deserializer: &mut D,
+            ) -> Result<HashMap<K, V, H>, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+
+        impl<T: Archive, H> ArchiveWith<HashSet<T, H>> for $wrapper {
+            type Archived = ArchivedHashSet<T::Archived>;
+            type Resolver = HashSetResolver;
+
+            #[inline]
+            unsafe fn resolve_with(
+                field: &HashSet<T, H>,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashSet::resolve_from_len(field.len(), pos, resolver, out);
+            }
+        }
+
+        impl<T, H, S> SerializeWith<HashSet<T, H>, S> for $wrapper
+        where
+            T: Serialize<S> + Hash + Eq,
+            T::Archived: Hash + Eq,
+            S: ScratchSpace + Serializer + ?Sized,
+            S::Error: From<HashIndexLenError>,
+        {
+            #[inline]
+            fn serialize_with(
+                field: &HashSet<T, H>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                // Safety: The elements of a hash set are unique
+                unsafe {
+                    ArchivedHashSet::serialize_from_iter_with(field.iter(), $algorithm, serializer)
+                }
+            }
+        }
+
+        impl<T, H, D> DeserializeWith<ArchivedHashSet<T::Archived>, HashSet<T, H>, D> for $wrapper
+        where
+            T: Archive + Hash + Eq,
+            T::Archived: Deserialize<T, D> + Hash + Eq,
+            H: Default + BuildHasher,
+            D: Fallible + ?Sized,
+        {
+            #[inline]
+            fn deserialize_with(
+                field: &ArchivedHashSet<T::Archived>,
+                deserializer: &mut D,
+            ) -> Result<HashSet<T, H>, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+    };
+}
+
+impl_hash_algorithm!(SeaHash, HashAlgorithm::SeaHash);
+impl_hash_algorithm!(SipHash, HashAlgorithm::SipHash);
+
+// AsBloomFilter
+
+impl<T: Hash> ArchiveWith<HashSet<T>> for AsBloomFilter {
+    type Archived = ArchivedBloomFilter<T>;
+    type Resolver = BloomFilterResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        _: &HashSet<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedBloomFilter::resolve_from_resolver(pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<HashSet<T>, S> for AsBloomFilter
+where
+    T: Hash,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &HashSet<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedBloomFilter::<T>::serialize_from_iter(field.iter(), serializer)
+    }
+}
+
+impl<T: Hash, D: Fallible + ?Sized> DeserializeWith<ArchivedBloomFilter<T>, HashSet<T>, D>
+    for AsBloomFilter
+{
+    #[inline]
+    fn deserialize_with(_: &ArchivedBloomFilter<T>, _: &mut D) -> Result<HashSet<T>, D::Error> {
+        // The elements of the set weren't archived
+        Ok(HashSet::new())
+    }
+}
+
 // UnixTimestamp
 
 impl ArchiveWith<SystemTime> for UnixTimestamp {
@@ -323,6 +714,52 @@ where
 
 impl<D: Fallible + ?Sized> DeserializeWith<ArchivedDuration, SystemTime, D> for UnixTimestamp {
     fn deserialize_with(field: &ArchivedDuration, _: &mut D) -> Result<SystemTime, D::Error> {
-        Ok(UNIX_EPOCH + (*field).into())
+        Ok(UNIX_EPOCH + Duration::from(*field))
+    }
+}
+
+// ToErrorString
+
+impl<E: Error + ?Sized> ArchiveWith<Box<E>> for ToErrorString {
+    type Archived = ArchivedString;
+    type Resolver = DisplayStringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Box<E>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        <AsDisplayString as ArchiveWith<E>>::resolve_with(field, pos, resolver, out);
+    }
+}
+
+impl<E: Error + ?Sized, S: Fallible + ?Sized> SerializeWith<Box<E>, S> for ToErrorString
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &Box<E>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        <AsDisplayString as SerializeWith<E, S>>::serialize_with(field, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, Box<dyn Error>, D> for ToErrorString {
+    #[inline]
+    fn deserialize_with(field: &ArchivedString, _: &mut D) -> Result<Box<dyn Error>, D::Error> {
+        Ok(Box::from(field.as_str()))
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, Box<dyn Error + Send + Sync>, D>
+    for ToErrorString
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedString,
+        _: &mut D,
+    ) -> Result<Box<dyn Error + Send + Sync>, D::Error> {
+        Ok(Box::from(field.as_str()))
     }
 }
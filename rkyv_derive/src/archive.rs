@@ -1,14 +1,16 @@
 use crate::{
     attributes::{parse_attributes, Attributes},
     repr::{BaseRepr, IntRepr, Repr},
-    util::{add_bounds, strip_raw},
-    with::{make_with_cast, make_with_ty},
+    util::{add_bounds, boxed_variants, niche_variants, strip_raw, to_snake_case},
+    with::{as_owned_referent, implicit_reference, make_with_cast, make_with_ty},
 };
-use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
 use syn::{
-    parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Error, Field, Fields, Ident,
-    Index, LitStr, Meta, NestedMeta, Type,
+    parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute, Data, DataEnum, DeriveInput,
+    Error, Field, Fields, GenericParam, Generics, Ident, ImplGenerics, Index, Lifetime, Lit,
+    LitInt, LitStr, Meta, NestedMeta, Path, PathArguments, Token, Type, TypeGenerics,
+    TypeParamBound, Variant, Visibility, WhereClause, WherePredicate,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -34,6 +36,256 @@ fn field_archive_attrs(field: &Field) -> impl '_ + Iterator<Item = NestedMeta> {
         .flatten()
 }
 
+/// Gets the tag pinned to a variant with `#[archive(tag = N)]`, if any.
+fn variant_tag(variant: &Variant) -> Result<Option<(LitInt, u128)>, Error> {
+    let mut result = None;
+    for attr in variant.attrs.iter().filter(|a| a.path.is_ident("archive")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "archive may only be a structured list attribute",
+                ))
+            }
+        };
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("tag") => {
+                    let lit = match meta.lit {
+                        Lit::Int(ref lit) => lit,
+                        _ => return Err(Error::new_spanned(meta, "tag must be an integer")),
+                    };
+                    if result.is_some() {
+                        return Err(Error::new_spanned(lit, "tag already specified"));
+                    }
+                    result = Some((lit.clone(), lit.base10_parse::<u128>()?));
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "unrecognized archive argument for variant",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Gets the tags pinned to the variants of an enum, or `None` if they aren't pinned.
+///
+/// If any variant has a pinned tag, every variant must have one and the tags must be unique.
+fn pinned_tags(data: &DataEnum) -> Result<Option<Vec<(LitInt, u128)>>, Error> {
+    let mut tags = Vec::with_capacity(data.variants.len());
+    for variant in data.variants.iter() {
+        tags.push(variant_tag(variant)?);
+    }
+
+    if tags.iter().all(Option::is_none) {
+        return Ok(None);
+    }
+
+    let mut result = Vec::<(LitInt, u128)>::with_capacity(tags.len());
+    for (variant, tag) in data.variants.iter().zip(tags) {
+        let (lit, value) = tag.ok_or_else(|| {
+            Error::new_spanned(
+                &variant.ident,
+                "if any variant has a pinned tag, all variants must have one",
+            )
+        })?;
+        if result.iter().any(|(_, other)| *other == value) {
+            return Err(Error::new_spanned(
+                lit,
+                "tag already used by another variant",
+            ));
+        }
+        result.push((lit, value));
+    }
+    Ok(Some(result))
+}
+
+/// Estimates the alignment of a field's archived type from its syntax.
+///
+/// Only primitives and arrays and tuples of primitives have a known alignment. Integers wider than
+/// a byte may be archived with a different endianness, but that doesn't change their alignment.
+fn estimated_align(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Array(array) => estimated_align(&array.elem),
+        Type::Group(group) => estimated_align(&group.elem),
+        Type::Paren(paren) => estimated_align(&paren.elem),
+        Type::Path(path) if path.qself.is_none() => {
+            let ident = path.path.get_ident()?;
+            match ident.to_string().as_str() {
+                "bool" | "i8" | "u8" => Some(1),
+                "i16" | "u16" => Some(2),
+                "i32" | "u32" | "f32" | "char" => Some(4),
+                "i64" | "u64" | "f64" => Some(8),
+                "i128" | "u128" => Some(16),
+                _ => None,
+            }
+        }
+        Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .try_fold(1, |align, elem| Some(align.max(estimated_align(elem)?))),
+        _ => None,
+    }
+}
+
+/// Returns whether some tokens mention the given lifetime.
+fn mentions_lifetime(tokens: TokenStream, lifetime: &Lifetime) -> bool {
+    let mut after_apostrophe = false;
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                if mentions_lifetime(group.stream(), lifetime) {
+                    return true;
+                }
+                after_apostrophe = false;
+            }
+            TokenTree::Punct(punct) => after_apostrophe = punct.as_char() == '\'',
+            TokenTree::Ident(ident) => {
+                if after_apostrophe && ident == lifetime.ident {
+                    return true;
+                }
+                after_apostrophe = false;
+            }
+            TokenTree::Literal(_) => after_apostrophe = false,
+        }
+    }
+    false
+}
+
+/// Removes the lifetime from the bounds, returning whether any of the remaining bounds mention it.
+fn strip_lifetime_bounds(
+    bounds: &mut Punctuated<TypeParamBound, Token![+]>,
+    lifetime: &Lifetime,
+) -> bool {
+    *bounds = core::mem::take(bounds)
+        .into_pairs()
+        .filter(|pair| !matches!(pair.value(), TypeParamBound::Lifetime(l) if l == lifetime))
+        .collect();
+    mentions_lifetime(bounds.to_token_stream(), lifetime)
+}
+
+/// Returns the generics of the archived type with the lifetime parameter erased, if it can be.
+///
+/// The only lifetime parameter of a struct can be erased if it's only used by reference fields
+/// or arrays of references without wrappers, which are archived as owned values. Bounds requiring
+/// generic parameters to outlive the lifetime are dropped from the archived type along with it.
+fn erase_lifetime(generics: &Generics, data: &Data) -> Option<Generics> {
+    let mut lifetimes = generics.lifetimes();
+    let lifetime = lifetimes.next()?.lifetime.clone();
+    if lifetimes.next().is_some() {
+        return None;
+    }
+
+    let fields = match data {
+        Data::Struct(data) => &data.fields,
+        _ => return None,
+    };
+    for field in fields.iter() {
+        let mentioned = match implicit_reference(field) {
+            Some(reference) => {
+                mentions_lifetime(reference.elem.to_token_stream(), &lifetime)
+                    || matches!(
+                        field.ty,
+                        Type::Array(ref array)
+                            if mentions_lifetime(array.len.to_token_stream(), &lifetime)
+                    )
+            }
+            None => mentions_lifetime(field.to_token_stream(), &lifetime),
+        };
+        if mentioned {
+            return None;
+        }
+    }
+
+    let mut erased = generics.clone();
+    erased.params = core::mem::take(&mut erased.params)
+        .into_pairs()
+        .filter(|pair| !matches!(pair.value(), GenericParam::Lifetime(_)))
+        .collect();
+    for param in erased.params.iter_mut() {
+        let mentioned = match param {
+            GenericParam::Type(param) => {
+                strip_lifetime_bounds(&mut param.bounds, &lifetime)
+                    || mentions_lifetime(param.default.to_token_stream(), &lifetime)
+            }
+            param => mentions_lifetime(param.to_token_stream(), &lifetime),
+        };
+        if mentioned {
+            return None;
+        }
+    }
+    if let Some(ref mut where_clause) = erased.where_clause {
+        let mut predicates = Punctuated::new();
+        for mut predicate in core::mem::take(&mut where_clause.predicates) {
+            if let WherePredicate::Type(ref mut predicate) = predicate {
+                if strip_lifetime_bounds(&mut predicate.bounds, &lifetime) {
+                    return None;
+                }
+                if predicate.bounds.is_empty() {
+                    continue;
+                }
+            }
+            if mentions_lifetime(predicate.to_token_stream(), &lifetime) {
+                return None;
+            }
+            predicates.push(predicate);
+        }
+        where_clause.predicates = predicates;
+    }
+
+    Some(erased)
+}
+
+/// Returns the type of an archived field and the bound it places on the archived type.
+///
+/// Reference fields without wrappers are bounded on the types they reference. If the lifetime of
+/// the archived type was erased, they also name their archived types without the reference.
+fn archived_field(
+    field: &Field,
+    erased: bool,
+    rkyv_path: &Path,
+) -> Result<(Type, Option<WherePredicate>), Error> {
+    let ty = make_with_ty(rkyv_path)(field)?;
+    let reference = match implicit_reference(field) {
+        Some(reference) => reference,
+        None => {
+            return Ok((
+                parse_quote! { #rkyv_path::Archived<#ty> },
+                Some(parse_quote! { #ty: #rkyv_path::Archive }),
+            ))
+        }
+    };
+
+    let referent = as_owned_referent(field);
+    let archived_ty = if erased {
+        let archived_elem: Type = match *reference.elem {
+            Type::Slice(_) => parse_quote! {
+                #rkyv_path::vec::ArchivedVec<#rkyv_path::Archived<#referent>>
+            },
+            _ if referent.is_none() => parse_quote! { #rkyv_path::string::ArchivedString },
+            _ => parse_quote! { #rkyv_path::Archived<#referent> },
+        };
+        match field.ty {
+            Type::Array(ref array) => {
+                let len = &array.len;
+                parse_quote! { [#archived_elem; #len] }
+            }
+            _ => archived_elem,
+        }
+    } else {
+        parse_quote! { #rkyv_path::Archived<#ty> }
+    };
+    Ok((
+        archived_ty,
+        referent.map(|referent| parse_quote! { #referent: #rkyv_path::Archive }),
+    ))
+}
+
 fn derive_archive_impl(
     mut input: DeriveInput,
     attributes: &Attributes,
@@ -76,6 +328,13 @@ fn derive_archive_impl(
             .map::<Attribute, _>(|d| parse_quote! { #[#d] }),
     );
 
+    if let (None, Some(path)) = (&attributes.archive_as, &attributes.check_as) {
+        return Err(Error::new_spanned(
+            path,
+            "check_as may only be used with as = \"...\"",
+        ));
+    }
+
     if let Some(ref archive_as) = attributes.archive_as {
         if let Some(ref ident) = attributes.archived {
             return Err(Error::new_spanned(
@@ -114,14 +373,142 @@ fn derive_archive_impl(
         }
     }
 
+    if let Some(ref path) = attributes.optimize_layout {
+        let is_named_struct = matches!(
+            input.data,
+            Data::Struct(syn::DataStruct {
+                fields: Fields::Named(_),
+                ..
+            })
+        );
+        if attributes.archive_as.is_some() || !is_named_struct {
+            return Err(Error::new_spanned(
+                path,
+                "optimize_layout may only be used on structs with named fields that generate an archived type",
+            ));
+        }
+    }
+
+    if let Some(ref path) = attributes.dump_layout {
+        if attributes.archive_as.is_some() {
+            return Err(Error::new_spanned(
+                path,
+                "dump_layout may not be used with as = \"...\"; implement DumpLayout on the archived type instead",
+            ));
+        }
+    }
+
+    if let Some(ref path) = attributes.reflect {
+        if attributes.archive_as.is_some() {
+            return Err(Error::new_spanned(
+                path,
+                "reflect may not be used with as = \"...\"; implement ReflectArchived on the archived type instead",
+            ));
+        }
+    }
+
+    if let Some(ref path) = attributes.field_offsets {
+        if attributes.archive_as.is_some() || !matches!(input.data, Data::Struct(_)) {
+            return Err(Error::new_spanned(
+                path,
+                "field_offsets may only be used on structs that generate an archived type",
+            ));
+        }
+    }
+
+    if let Some(ref path) = attributes.partial_eq {
+        if attributes.archive_as.is_some() {
+            return Err(Error::new_spanned(
+                path,
+                "partial_eq may not be used with as = \"...\"; implement PartialEq on the archived type instead",
+            ));
+        }
+    }
+
+    if let Some(ref path) = attributes.variant_accessors {
+        if attributes.archive_as.is_some() || !matches!(input.data, Data::Enum(_)) {
+            return Err(Error::new_spanned(
+                path,
+                "variant_accessors may only be used on enums that generate an archived type",
+            ));
+        }
+    }
+
+    if let Some(ref path) = attributes.default {
+        if attributes.archive_as.is_some() || !matches!(input.data, Data::Struct(_)) {
+            return Err(Error::new_spanned(
+                path,
+                "default may only be used on structs that generate an archived type",
+            ));
+        }
+    }
+
+    if let Some(ref path) = attributes.niche {
+        if attributes.archive_as.is_some() || !matches!(input.data, Data::Enum(_)) {
+            return Err(Error::new_spanned(
+                path,
+                "niche may only be used on enums that generate an archived type",
+            ));
+        }
+        let incompatible = [
+            attributes
+                .compares
+                .as_ref()
+                .map(|(path, _)| (path, "compare(...)")),
+            attributes
+                .copy_safe
+                .as_ref()
+                .map(|path| (path, "copy_safe")),
+            attributes
+                .dump_layout
+                .as_ref()
+                .map(|path| (path, "dump_layout")),
+            attributes
+                .partial_eq
+                .as_ref()
+                .map(|path| (path, "partial_eq")),
+            attributes.reflect.as_ref().map(|path| (path, "reflect")),
+            attributes
+                .variant_accessors
+                .as_ref()
+                .map(|path| (path, "variant_accessors")),
+        ];
+        if let Some((path, name)) = incompatible.into_iter().flatten().next() {
+            return Err(Error::new_spanned(
+                path,
+                format!("{} may not be used with niche", name),
+            ));
+        }
+        if let Some(span) = attributes
+            .archived_repr
+            .base_repr
+            .map(|(_, s)| s)
+            .or_else(|| attributes.archived_repr.modifier.as_ref().map(|(_, s)| *s))
+        {
+            return Err(Error::new(
+                span,
+                "repr(...) may not be used with niche because the archived enum has no tag",
+            ));
+        }
+    }
+
     let archived_name = attributes.archived.as_ref().map_or_else(
         || Ident::new(&format!("Archived{}", strip_raw(name)), name.span()),
         |value| value.clone(),
     );
     let archived_doc = format!("An archived [`{}`]", name);
 
+    let erased_generics = if attributes.archive_as.is_none() {
+        erase_lifetime(generics, &input.data)
+    } else {
+        None
+    };
+    let erased = erased_generics.is_some();
+    let archived_generics = erased_generics.as_ref().unwrap_or(generics);
+    let (_, archived_ty_generics, _) = archived_generics.split_for_impl();
+
     let archived_type = attributes.archive_as.as_ref().map_or_else(
-        || Ok(parse_quote! { #archived_name #ty_generics }),
+        || Ok(parse_quote! { #archived_name #archived_ty_generics }),
         |lit| lit.parse::<Type>(),
     )?;
 
@@ -131,6 +518,123 @@ fn derive_archive_impl(
     );
     let resolver_doc = format!("The resolver for an archived [`{}`]", name);
 
+    if let (false, Some(path)) = (cfg!(feature = "debug"), &attributes.dump_layout) {
+        return Err(Error::new_spanned(
+            path,
+            "dump_layout requires the `debug` feature to be enabled",
+        ));
+    }
+
+    let dump_layout_impl = if attributes.dump_layout.is_some() {
+        Some(derive_dump_layout_impl(
+            &input.data,
+            &archived_name,
+            &archived_type,
+            &impl_generics,
+            where_clause,
+            rkyv_path,
+        )?)
+    } else {
+        None
+    };
+
+    if let (false, Some(path)) = (cfg!(feature = "reflect"), &attributes.reflect) {
+        return Err(Error::new_spanned(
+            path,
+            "reflect requires the `reflect` feature to be enabled",
+        ));
+    }
+
+    let reflect_impl = if attributes.reflect.is_some() {
+        Some(derive_reflect_impl(
+            &input.data,
+            &archived_name,
+            &archived_type,
+            &impl_generics,
+            where_clause,
+            rkyv_path,
+        )?)
+    } else {
+        None
+    };
+
+    let archive_as_check = if attributes.check_as.is_some() {
+        Some(derive_archive_as_check(
+            &input.data,
+            &archived_type,
+            &impl_generics,
+            where_clause,
+            rkyv_path,
+        )?)
+    } else {
+        None
+    };
+
+    let field_offsets_impl = if attributes.field_offsets.is_some() {
+        Some(derive_field_offsets_impl(
+            &input.data,
+            &archived_type,
+            &impl_generics,
+            where_clause,
+            rkyv_path,
+        )?)
+    } else {
+        None
+    };
+
+    let default_impl = if attributes.default.is_some() {
+        Some(derive_default_impl(
+            &input.data,
+            &archived_type,
+            &impl_generics,
+            where_clause,
+            rkyv_path,
+        )?)
+    } else {
+        None
+    };
+
+    let partial_eq_impl = if attributes.partial_eq.is_some() {
+        Some(derive_partial_eq_impl(
+            &input.data,
+            &archived_type,
+            &impl_generics,
+            where_clause,
+            rkyv_path,
+        )?)
+    } else {
+        None
+    };
+
+    let max_scratch_impl = if let Some(ref path) = attributes.max_scratch {
+        Some(derive_max_scratch_impl(
+            &input.data,
+            name,
+            &impl_generics,
+            &ty_generics,
+            where_clause,
+            rkyv_path,
+            path,
+        )?)
+    } else {
+        None
+    };
+
+    let boxed_variants_impl = if let Some(ref path) = attributes.boxed_variants {
+        Some(derive_boxed_variants_impl(
+            &input.generics,
+            &input.data,
+            name,
+            &ty_generics,
+            where_clause,
+            attributes.check_bytes.is_some(),
+            rkyv_path,
+            path,
+        )?)
+    } else {
+        None
+    };
+
     let (archive_types, archive_impls) = match input.data {
         Data::Struct(ref data) => {
             let base_repr = if cfg!(feature = "strict") {
@@ -148,6 +652,17 @@ fn derive_archive_impl(
             } else {
                 attributes.archived_repr.base_repr
             };
+            // Reordering fields only has a stable effect on the layout with repr(C)
+            let base_repr = match (&attributes.optimize_layout, base_repr) {
+                (Some(path), None) => Some((BaseRepr::C, path.span())),
+                (Some(_), Some((BaseRepr::Transparent | BaseRepr::Int(_), span))) => {
+                    return Err(Error::new(
+                        span,
+                        "optimize_layout may only be used with repr(C)",
+                    ))
+                }
+                (_, base_repr) => base_repr,
+            };
             let repr = Repr {
                 base_repr,
                 modifier: attributes.archived_repr.modifier.clone(),
@@ -161,10 +676,8 @@ fn derive_archive_impl(
                         .iter()
                         .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
                     {
-                        let ty = with_ty(field)?;
-                        archive_where
-                            .predicates
-                            .push(parse_quote! { #ty: #rkyv_path::Archive });
+                        let (_, bound) = archived_field(field, erased, rkyv_path)?;
+                        archive_where.predicates.extend(bound);
                     }
 
                     let resolver_fields = fields.named.iter().map(|f| {
@@ -174,9 +687,25 @@ fn derive_archive_impl(
                     });
 
                     let archived_def = if attributes.archive_as.is_none() {
-                        let archived_fields = fields.named.iter().map(|f| {
+                        let mut ordered_fields = fields.named.iter().collect::<Vec<_>>();
+                        if attributes.optimize_layout.is_some() {
+                            // Fields with an unknown alignment are placed first since most
+                            // archived types contain relative pointers or other wide fields. The
+                            // sort is stable, so fields with the same alignment keep their order.
+                            ordered_fields.sort_by_key(|f| {
+                                core::cmp::Reverse(
+                                    estimated_align(&with_ty(f).unwrap()).unwrap_or(usize::MAX),
+                                )
+                            });
+                        }
+                        let mut archived_where = archived_generics.where_clause.clone().unwrap();
+                        let mut archived_fields = Vec::new();
+                        for f in ordered_fields {
                             let field_name = f.ident.as_ref();
-                            let ty = with_ty(f).unwrap();
+                            let (ty, bound) = archived_field(f, erased, rkyv_path)?;
+                            if !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")) {
+                                archived_where.predicates.extend(bound);
+                            }
                             let vis = &f.vis;
                             let field_doc = format!(
                                 "The archived counterpart of [`{}::{}`]",
@@ -184,19 +713,19 @@ fn derive_archive_impl(
                                 field_name.unwrap()
                             );
                             let archive_attrs = field_archive_attrs(f);
-                            quote! {
+                            archived_fields.push(quote! {
                                 #[doc = #field_doc]
                                 #(#[#archive_attrs])*
-                                #vis #field_name: #rkyv_path::Archived<#ty>
-                            }
-                        });
+                                #vis #field_name: #ty
+                            });
+                        }
 
                         Some(quote! {
                             #[automatically_derived]
                             #[doc = #archived_doc]
                             #(#archive_attrs)*
                             #repr
-                            #vis struct #archived_name #generics #archive_where {
+                            #vis struct #archived_name #archived_generics #archived_where {
                                 #(#archived_fields,)*
                             }
                         })
@@ -347,10 +876,8 @@ fn derive_archive_impl(
                         .iter()
                         .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
                     {
-                        let ty = with_ty(field)?;
-                        archive_where
-                            .predicates
-                            .push(parse_quote! { #ty: #rkyv_path::Archive });
+                        let (_, bound) = archived_field(field, erased, rkyv_path)?;
+                        archive_where.predicates.extend(bound);
                     }
 
                     let resolver_fields = fields.unnamed.iter().map(|f| {
@@ -359,25 +886,30 @@ fn derive_archive_impl(
                     });
 
                     let archived_def = if attributes.archive_as.is_none() {
-                        let archived_fields = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                            let ty = with_ty(f).unwrap();
+                        let mut archived_where = archived_generics.where_clause.clone().unwrap();
+                        let mut archived_fields = Vec::new();
+                        for (i, f) in fields.unnamed.iter().enumerate() {
+                            let (ty, bound) = archived_field(f, erased, rkyv_path)?;
+                            if !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")) {
+                                archived_where.predicates.extend(bound);
+                            }
                             let vis = &f.vis;
                             let field_doc =
                                 format!("The archived counterpart of [`{}::{}`]", name, i);
                             let archive_attrs = field_archive_attrs(f);
-                            quote! {
+                            archived_fields.push(quote! {
                                 #[doc = #field_doc]
                                 #(#[#archive_attrs])*
-                                #vis #rkyv_path::Archived<#ty>
-                            }
-                        });
+                                #vis #ty
+                            });
+                        }
 
                         Some(quote! {
                             #[automatically_derived]
                             #[doc = #archived_doc]
                             #(#archive_attrs)*
                             #repr
-                            #vis struct #archived_name #generics (#(#archived_fields,)*) #archive_where;
+                            #vis struct #archived_name #archived_generics (#(#archived_fields,)*) #archived_where;
                         })
                     } else {
                         None
@@ -616,6 +1148,21 @@ fn derive_archive_impl(
                 }
             }
         }
+        Data::Enum(_) if attributes.niche.is_some() => derive_niche_impl(
+            &input.data,
+            attributes,
+            name,
+            vis,
+            generics,
+            where_clause,
+            &archived_name,
+            &archived_type,
+            &archived_doc,
+            &resolver,
+            &resolver_doc,
+            archive_attrs.collect(),
+            rkyv_path,
+        )?,
         Data::Enum(ref data) => {
             let mut archive_where = where_clause.clone();
             for variant in data.variants.iter() {
@@ -784,6 +1331,8 @@ fn derive_archive_impl(
                 }
             });
 
+            let pinned_tags = pinned_tags(data)?;
+
             let (int_repr, int_repr_span) = match attributes.archived_repr.base_repr {
                 // The base repr for enums may not be Rust, transparent, or C
                 Some((BaseRepr::Transparent | BaseRepr::C, span)) => {
@@ -794,7 +1343,11 @@ fn derive_archive_impl(
                 // If unspecified, the base repr is set to u* with the smallest unsigned integer
                 // that can represent the number of variants
                 None => {
-                    let int_repr = match data.variants.len() as u128 {
+                    let repr_range = match pinned_tags {
+                        Some(ref tags) => tags.iter().map(|(_, value)| *value).max().unwrap(),
+                        None => data.variants.len() as u128,
+                    };
+                    let int_repr = match repr_range {
                         0..=255 => IntRepr::U8,
                         256..=65_535 => IntRepr::U16,
                         65_536..=4_294_967_295 => IntRepr::U32,
@@ -804,6 +1357,22 @@ fn derive_archive_impl(
                     (int_repr, Span::call_site())
                 }
             };
+            if let Some(ref tags) = pinned_tags {
+                if let Some((lit, _)) = tags
+                    .iter()
+                    .find(|(_, value)| *value > int_repr.max_discriminant())
+                {
+                    return Err(Error::new_spanned(
+                        lit,
+                        "tag is too large for the repr of the archived enum",
+                    ));
+                }
+            }
+            let discriminant = |i: usize| match pinned_tags {
+                Some(ref tags) => int_repr.pinned_discriminant(tags[i].1).into_token_stream(),
+                None => int_repr.enum_discriminant(i).into_token_stream(),
+            };
+
             let repr = Repr {
                 base_repr: Some((BaseRepr::Int(int_repr), int_repr_span)),
                 modifier: attributes.archived_repr.modifier.clone(),
@@ -817,7 +1386,10 @@ fn derive_archive_impl(
                 not(feature = "arbitrary_enum_discriminant"),
                 any(feature = "archive_le", feature = "archive_be")
             ))]
-            if !is_fieldless && !matches!(int_repr, IntRepr::U8 | IntRepr::I8) {
+            if !is_fieldless
+                && pinned_tags.is_none()
+                && !matches!(int_repr, IntRepr::U8 | IntRepr::I8)
+            {
                 return Err(Error::new_spanned(
                     name,
                     "\
@@ -830,12 +1402,14 @@ fn derive_archive_impl(
             let archived_def = if attributes.archive_as.is_none() {
                 let archived_variants = data.variants.iter().enumerate().map(|(i, v)| {
                     let variant = &v.ident;
-                    let discriminant =
-                        if is_fieldless || cfg!(feature = "arbitrary_enum_discriminant") {
-                            Some(int_repr.enum_discriminant(i))
-                        } else {
-                            None
-                        };
+                    let discriminant = if is_fieldless
+                        || pinned_tags.is_some()
+                        || cfg!(feature = "arbitrary_enum_discriminant")
+                    {
+                        Some(discriminant(i))
+                    } else {
+                        None
+                    };
                     match v.fields {
                         Fields::Named(ref fields) => {
                             let fields = fields.named.iter().map(|f| {
@@ -915,7 +1489,7 @@ fn derive_archive_impl(
 
             let archived_variant_tags = data.variants.iter().enumerate().map(|(i, v)| {
                 let variant = &v.ident;
-                let discriminant = int_repr.enum_discriminant(i);
+                let discriminant = discriminant(i);
                 quote! { #variant #discriminant }
             });
 
@@ -1250,9 +1824,28 @@ fn derive_archive_impl(
                 None
             };
 
+            let (variant_accessor_types, variant_accessor_impls) =
+                if attributes.variant_accessors.is_some() {
+                    let (types, impls) = derive_variant_accessors(
+                        data,
+                        name,
+                        &archived_name,
+                        &archived_type,
+                        vis,
+                        generics,
+                        &archive_where,
+                        &int_repr,
+                        rkyv_path,
+                    )?;
+                    (Some(types), Some(impls))
+                } else {
+                    (None, None)
+                };
+
             (
                 quote! {
                     #archived_def
+                    #variant_accessor_types
 
                     #[automatically_derived]
                     #[doc = #resolver_doc]
@@ -1285,6 +1878,7 @@ fn derive_archive_impl(
                     #partial_eq_impl
                     #partial_ord_impl
                     #copy_safe_impl
+                    #variant_accessor_impls
                 },
             )
         }
@@ -1305,6 +1899,1243 @@ fn derive_archive_impl(
             use #rkyv_path::{out_field, Archive, Archived};
 
             #archive_impls
+            #dump_layout_impl
+            #reflect_impl
+            #field_offsets_impl
+            #default_impl
+            #partial_eq_impl
+            #max_scratch_impl
+            #boxed_variants_impl
+            #archive_as_check
+        };
+    })
+}
+
+fn derive_archive_as_check(
+    data: &Data,
+    archived_type: &Type,
+    impl_generics: &ImplGenerics,
+    where_clause: &WhereClause,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+
+    // Generic arguments need a turbofish to be used in patterns
+    let mut path = match archived_type {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path.path.clone(),
+        _ => {
+            return Err(Error::new_spanned(
+                archived_type,
+                "check_as requires as = \"...\" to name a struct or enum type",
+            ))
+        }
+    };
+    for segment in path.segments.iter_mut() {
+        if let PathArguments::AngleBracketed(ref mut args) = segment.arguments {
+            args.colon2_token = Some(Default::default());
+        }
+    }
+
+    let fields = match data {
+        Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    let mut check_where = where_clause.clone();
+    for field in fields
+        .into_iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+    {
+        let ty = with_ty(field)?;
+        check_where
+            .predicates
+            .push(parse_quote! { #ty: #rkyv_path::Archive });
+    }
+
+    // Matches the fields of the archived type exactly and checks that each one has the archived
+    // type of the corresponding field
+    let pattern = |path: &TokenStream, fields: &Fields| -> Result<TokenStream, Error> {
+        let bindings = fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| Ident::new(&format!("field_{}", i), f.span()))
+            .collect::<Vec<_>>();
+        let checks = fields
+            .iter()
+            .zip(bindings.iter())
+            .map(|(f, binding)| {
+                let ty = with_ty(f)?;
+                Ok(quote! { let _: &#rkyv_path::Archived<#ty> = #binding; })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let pattern = match fields {
+            Fields::Named(ref fields) => {
+                let names = fields.named.iter().map(|f| &f.ident);
+                quote! { #path { #(#names: #bindings,)* } }
+            }
+            Fields::Unnamed(_) => quote! { #path(#(#bindings,)*) },
+            Fields::Unit => quote! { #path },
+        };
+        Ok(quote! { #pattern => { #(#checks)* } })
+    };
+
+    let arms = match data {
+        Data::Struct(data) => vec![pattern(&quote! { #path }, &data.fields)?],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|v| {
+                let variant = &v.ident;
+                pattern(&quote! { #path::#variant }, &v.fields)
+            })
+            .collect::<Result<Vec<_>, Error>>()?,
+        Data::Union(ref data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "Archive cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        #[allow(dead_code, unreachable_patterns)]
+        fn check_archive_as #impl_generics (archived: &#archived_type) #check_where {
+            match archived {
+                #(#arms)*
+            }
+        }
+    })
+}
+
+fn derive_field_offsets_impl(
+    data: &Data,
+    archived_type: &Type,
+    impl_generics: &ImplGenerics,
+    where_clause: &WhereClause,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+
+    let fields = match data {
+        Data::Struct(data) => &data.fields,
+        _ => unreachable!("field_offsets is only allowed on structs"),
+    };
+
+    let mut offsets_where = where_clause.clone();
+    for field in fields
+        .iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+    {
+        let ty = with_ty(field)?;
+        offsets_where
+            .predicates
+            .push(parse_quote! { #ty: #rkyv_path::Archive });
+    }
+
+    // Offsets are listed in declaration order, which may differ from their order in memory
+    let offsets = fields.iter().enumerate().map(|(i, f)| {
+        let (member, field_name) = match f.ident {
+            Some(ref ident) => (quote! { #ident }, strip_raw(ident)),
+            None => {
+                let index = Index::from(i);
+                (quote! { #index }, i.to_string())
+            }
         };
+        quote! { (#field_name, ::core::mem::offset_of!(Self, #member)) }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #archived_type #offsets_where {
+            /// The name and byte offset of each field of the archived struct, in declaration order.
+            pub const FIELD_OFFSETS: &'static [(&'static str, usize)] = &[#(#offsets,)*];
+        }
     })
 }
+
+fn derive_partial_eq_impl(
+    data: &Data,
+    archived_type: &Type,
+    impl_generics: &ImplGenerics,
+    where_clause: &WhereClause,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+
+    let fields = match data {
+        Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+
+    let mut eq_where = where_clause.clone();
+    for field in fields
+        .iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+    {
+        let ty = with_ty(field)?;
+        eq_where
+            .predicates
+            .push(parse_quote! { #ty: #rkyv_path::Archive });
+        eq_where
+            .predicates
+            .push(parse_quote! { #rkyv_path::Archived<#ty>: PartialEq });
+    }
+
+    let body = match data {
+        Data::Struct(data) => {
+            let members = data.fields.iter().enumerate().map(|(i, f)| match f.ident {
+                Some(ref ident) => quote! { #ident },
+                None => {
+                    let index = Index::from(i);
+                    quote! { #index }
+                }
+            });
+            quote! { true #(&& self.#members == other.#members)* }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|v| {
+                let variant = &v.ident;
+                let self_bindings = (0..v.fields.len())
+                    .map(|i| Ident::new(&format!("self_{}", i), Span::call_site()))
+                    .collect::<Vec<_>>();
+                let other_bindings = (0..v.fields.len())
+                    .map(|i| Ident::new(&format!("other_{}", i), Span::call_site()))
+                    .collect::<Vec<_>>();
+                let (self_pattern, other_pattern) = match v.fields {
+                    Fields::Named(ref fields) => {
+                        let names = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
+                        (
+                            quote! { Self::#variant { #(#names: #self_bindings,)* } },
+                            quote! { Self::#variant { #(#names: #other_bindings,)* } },
+                        )
+                    }
+                    Fields::Unnamed(_) => (
+                        quote! { Self::#variant(#(#self_bindings,)*) },
+                        quote! { Self::#variant(#(#other_bindings,)*) },
+                    ),
+                    Fields::Unit => (quote! { Self::#variant }, quote! { Self::#variant }),
+                };
+                quote! {
+                    (#self_pattern, #other_pattern) => true #(&& #self_bindings == #other_bindings)*
+                }
+            });
+            quote! {
+                match (self, other) {
+                    #(#arms,)*
+                    #[allow(unreachable_patterns)]
+                    _ => false,
+                }
+            }
+        }
+        Data::Union(_) => unreachable!("Archive cannot be derived for unions"),
+    };
+
+    Ok(quote! {
+        impl #impl_generics PartialEq for #archived_type #eq_where {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                #body
+            }
+        }
+    })
+}
+
+fn derive_max_scratch_impl(
+    data: &Data,
+    name: &Ident,
+    impl_generics: &ImplGenerics,
+    ty_generics: &TypeGenerics,
+    where_clause: &WhereClause,
+    rkyv_path: &Path,
+    path: &Path,
+) -> Result<TokenStream, Error> {
+    let fields = match data {
+        Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+
+    let mut scratch_where = where_clause.clone();
+    for field in fields.iter() {
+        // The scratch space needed by a wrapper isn't known
+        if field.attrs.iter().any(|a| a.path.is_ident("with")) {
+            return Err(Error::new_spanned(
+                path,
+                "max_scratch may not be used with fields that have wrappers",
+            ));
+        }
+        if !field.attrs.iter().any(|a| a.path.is_ident("omit_bounds")) {
+            let ty = &field.ty;
+            scratch_where
+                .predicates
+                .push(parse_quote! { #ty: #rkyv_path::ser::max_scratch::MaxScratch });
+        }
+    }
+
+    // Only one variant of an enum is serialized, so summing all of the fields is conservative
+    let field_scratch = fields.iter().map(|f| {
+        let ty = &f.ty;
+        quote! { <#ty as #rkyv_path::ser::max_scratch::MaxScratch>::MAX_SCRATCH }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::ser::max_scratch::MaxScratch for #name #ty_generics
+        #scratch_where
+        {
+            const MAX_SCRATCH: usize = 0 #(+ #field_scratch)*;
+        }
+    })
+}
+
+fn derive_default_impl(
+    data: &Data,
+    archived_type: &Type,
+    impl_generics: &ImplGenerics,
+    where_clause: &WhereClause,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+
+    let fields = match data {
+        Data::Struct(data) => &data.fields,
+        _ => unreachable!("default is only allowed on structs"),
+    };
+
+    let mut default_where = where_clause.clone();
+    for field in fields
+        .iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+    {
+        let ty = with_ty(field)?;
+        default_where
+            .predicates
+            .push(parse_quote! { #ty: #rkyv_path::Archive });
+        default_where
+            .predicates
+            .push(parse_quote! { #rkyv_path::Archived<#ty>: ::core::default::Default });
+    }
+
+    let body = match fields {
+        Fields::Named(fields) => {
+            let fields = fields.named.iter().map(|f| {
+                let name = &f.ident;
+                quote! { #name: ::core::default::Default::default() }
+            });
+            quote! { Self { #(#fields,)* } }
+        }
+        Fields::Unnamed(fields) => {
+            let fields = fields
+                .unnamed
+                .iter()
+                .map(|_| quote! { ::core::default::Default::default() });
+            quote! { Self(#(#fields,)*) }
+        }
+        Fields::Unit => quote! { Self },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::default::Default for #archived_type #default_where {
+            #[inline]
+            fn default() -> Self {
+                #body
+            }
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn derive_boxed_variants_impl(
+    generics: &Generics,
+    data: &Data,
+    name: &Ident,
+    ty_generics: &TypeGenerics,
+    where_clause: &WhereClause,
+    check_bytes: bool,
+    rkyv_path: &Path,
+    path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+
+    let variants = boxed_variants(data, path)?;
+    let (impl_generics, _, _) = generics.split_for_impl();
+
+    let index_arms = variants.iter().enumerate().map(|(i, (v, field))| {
+        let variant = &v.ident;
+        let index = i as u32;
+        match field {
+            Some(_) => quote! { #name::#variant(_) => #index },
+            None => quote! { #name::#variant => #index },
+        }
+    });
+
+    let check_variants_impl = if check_bytes {
+        let mut check_where = where_clause.clone();
+        for (_, field) in variants.iter() {
+            if let Some(field) = field {
+                if !field.attrs.iter().any(|a| a.path.is_ident("omit_bounds")) {
+                    let ty = with_ty(field)?;
+                    check_where
+                        .predicates
+                        .push(parse_quote! { #ty: #rkyv_path::Archive });
+                    check_where.predicates.push(parse_quote! {
+                        #rkyv_path::boxed::ArchivedBox<#rkyv_path::Archived<#ty>>:
+                            #rkyv_path::bytecheck::CheckBytes<__C>
+                    });
+                }
+            }
+        }
+
+        let check_arms = variants.iter().enumerate().map(|(i, (v, field))| {
+            let index = i as u32;
+            match field {
+                Some(field) => {
+                    let ty = with_ty(field).unwrap();
+                    let variant_name = strip_raw(&v.ident);
+                    quote! {
+                        #index => {
+                            <
+                                #rkyv_path::boxed::ArchivedBox<#rkyv_path::Archived<#ty>>
+                                as #rkyv_path::bytecheck::CheckBytes<__C>
+                            >::check_bytes(ptr.cast(), context).map_err(|e| {
+                                EnumCheckError::InvalidTuple {
+                                    variant_name: #variant_name,
+                                    inner: TupleStructCheckError {
+                                        field_index: 0,
+                                        inner: ErrorBox::new(e),
+                                    },
+                                }
+                            })?;
+                        }
+                    }
+                }
+                None => quote! { #index => () },
+            }
+        });
+
+        let mut check_params = Punctuated::default();
+        check_params.push(parse_quote! { __C: ?Sized });
+        for param in generics.params.iter() {
+            check_params.push(param.clone());
+        }
+        let check_generics = Generics {
+            lt_token: Some(Default::default()),
+            params: check_params,
+            gt_token: Some(Default::default()),
+            where_clause: None,
+        };
+        let (check_impl_generics, _, _) = check_generics.split_for_impl();
+
+        Some(quote! {
+            impl #check_impl_generics #rkyv_path::variants::CheckVariants<__C>
+                for #name #ty_generics #check_where
+            {
+                #[allow(unused_variables)]
+                unsafe fn check_variant(
+                    index: u32,
+                    ptr: *const #rkyv_path::RawRelPtr,
+                    context: &mut __C,
+                ) -> ::core::result::Result<(), #rkyv_path::bytecheck::EnumCheckError<u32>> {
+                    use #rkyv_path::bytecheck::{EnumCheckError, ErrorBox, TupleStructCheckError};
+
+                    match index {
+                        #(#check_arms,)*
+                        _ => return Err(EnumCheckError::InvalidTag(index)),
+                    }
+                    Ok(())
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::variants::ArchiveVariants for #name #ty_generics #where_clause {
+            #[inline]
+            fn variant_index(&self) -> u32 {
+                match self {
+                    #(#index_arms,)*
+                }
+            }
+        }
+
+        #check_variants_impl
+    })
+}
+
+fn derive_dump_layout_impl(
+    data: &Data,
+    archived_name: &Ident,
+    archived_type: &Type,
+    impl_generics: &ImplGenerics,
+    where_clause: &WhereClause,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+
+    let fields = match data {
+        Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    let mut dump_where = where_clause.clone();
+    for field in fields
+        .into_iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+    {
+        let ty = with_ty(field)?;
+        dump_where
+            .predicates
+            .push(parse_quote! { #ty: #rkyv_path::Archive });
+        dump_where
+            .predicates
+            .push(parse_quote! { #rkyv_path::Archived<#ty>: #rkyv_path::dump::DumpLayout });
+    }
+
+    // Fields are dumped in declaration order, which may differ from their order in memory
+    let dumper_param = |fields: &Fields| {
+        if fields.is_empty() {
+            quote! { _ }
+        } else {
+            quote! { dumper }
+        }
+    };
+
+    let body = match data {
+        Data::Struct(data) => {
+            let dumps = data.fields.iter().enumerate().map(|(i, f)| {
+                let (member, field_name) = match f.ident {
+                    Some(ref ident) => (quote! { #ident }, strip_raw(ident)),
+                    None => {
+                        let index = Index::from(i);
+                        (quote! { #index }, i.to_string())
+                    }
+                };
+                quote! { dumper.dump(&#field_name, &self.#member)?; }
+            });
+            let param = dumper_param(&data.fields);
+            quote! {
+                dumper.node(name, self, |#param| {
+                    #(#dumps)*
+                    Ok(())
+                })
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|v| {
+                let variant = &v.ident;
+                let variant_name = strip_raw(variant);
+                let param = dumper_param(&v.fields);
+                let bindings = v
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| Ident::new(&format!("field_{}", i), f.span()))
+                    .collect::<Vec<_>>();
+                let dumps =
+                    v.fields
+                        .iter()
+                        .zip(bindings.iter())
+                        .enumerate()
+                        .map(|(i, (f, binding))| {
+                            let field_name =
+                                f.ident.as_ref().map_or_else(|| i.to_string(), strip_raw);
+                            quote! { dumper.dump(&#field_name, #binding)?; }
+                        });
+                let pattern = match v.fields {
+                    Fields::Named(ref fields) => {
+                        let names = fields.named.iter().map(|f| &f.ident);
+                        quote! { #archived_name::#variant { #(#names: ref #bindings,)* } }
+                    }
+                    Fields::Unnamed(_) => quote! { #archived_name::#variant(#(ref #bindings,)*) },
+                    Fields::Unit => quote! { #archived_name::#variant },
+                };
+                quote! {
+                    #pattern => dumper.variant(name, self, #variant_name, |#param| {
+                        #(#dumps)*
+                        Ok(())
+                    })
+                }
+            });
+            quote! {
+                match *self {
+                    #(#arms,)*
+                }
+            }
+        }
+        Data::Union(ref data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "Archive cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::dump::DumpLayout for #archived_type #dump_where {
+            fn dump_layout(
+                &self,
+                dumper: &mut #rkyv_path::dump::LayoutDumper<'_>,
+                name: &dyn ::core::fmt::Display,
+            ) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+fn derive_reflect_impl(
+    data: &Data,
+    archived_name: &Ident,
+    archived_type: &Type,
+    impl_generics: &ImplGenerics,
+    where_clause: &WhereClause,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+
+    let fields = match data {
+        Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    let mut reflect_where = where_clause.clone();
+    for field in fields
+        .into_iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+    {
+        let ty = with_ty(field)?;
+        reflect_where
+            .predicates
+            .push(parse_quote! { #ty: #rkyv_path::Archive });
+        reflect_where
+            .predicates
+            .push(parse_quote! { #rkyv_path::Archived<#ty>: #rkyv_path::reflect::ReflectValue });
+    }
+
+    let visitor_param = |fields: &Fields| {
+        if fields.is_empty() {
+            quote! { _ }
+        } else {
+            quote! { f }
+        }
+    };
+
+    let (variant_name, for_each_field) =
+        match data {
+            Data::Struct(data) => {
+                let visits = data.fields.iter().enumerate().map(|(i, f)| {
+                    let (member, field_name) = match f.ident {
+                        Some(ref ident) => (quote! { #ident }, strip_raw(ident)),
+                        None => {
+                            let index = Index::from(i);
+                            (quote! { #index }, i.to_string())
+                        }
+                    };
+                    quote! { f(#field_name, &self.#member); }
+                });
+                let param = visitor_param(&data.fields);
+                (
+                    quote! { None },
+                    quote! {
+                        fn for_each_field(
+                            &self,
+                            #param: &mut dyn FnMut(&str, &dyn #rkyv_path::reflect::ReflectValue),
+                        ) {
+                            #(#visits)*
+                        }
+                    },
+                )
+            }
+            Data::Enum(data) => {
+                let mut name_arms = Vec::new();
+                let mut field_arms = Vec::new();
+                for v in data.variants.iter() {
+                    let variant = &v.ident;
+                    let variant_name = strip_raw(variant);
+                    let bindings = v
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, f)| Ident::new(&format!("field_{}", i), f.span()))
+                        .collect::<Vec<_>>();
+                    let visits = v.fields.iter().zip(bindings.iter()).enumerate().map(
+                        |(i, (f, binding))| {
+                            let field_name =
+                                f.ident.as_ref().map_or_else(|| i.to_string(), strip_raw);
+                            quote! { f(#field_name, #binding); }
+                        },
+                    );
+                    let (pattern, wildcard) = match v.fields {
+                        Fields::Named(ref fields) => {
+                            let names = fields.named.iter().map(|f| &f.ident);
+                            (
+                                quote! { #archived_name::#variant { #(#names: ref #bindings,)* } },
+                                quote! { #archived_name::#variant { .. } },
+                            )
+                        }
+                        Fields::Unnamed(_) => (
+                            quote! { #archived_name::#variant(#(ref #bindings,)*) },
+                            quote! { #archived_name::#variant(..) },
+                        ),
+                        Fields::Unit => (
+                            quote! { #archived_name::#variant },
+                            quote! { #archived_name::#variant },
+                        ),
+                    };
+                    name_arms.push(quote! { #wildcard => #variant_name });
+                    field_arms.push(quote! { #pattern => { #(#visits)* } });
+                }
+                let param = if data.variants.iter().all(|v| v.fields.is_empty()) {
+                    quote! { _ }
+                } else {
+                    quote! { f }
+                };
+                (
+                    quote! {
+                        Some(match *self {
+                            #(#name_arms,)*
+                        })
+                    },
+                    quote! {
+                        fn for_each_field(
+                            &self,
+                            #param: &mut dyn FnMut(&str, &dyn #rkyv_path::reflect::ReflectValue),
+                        ) {
+                            match *self {
+                                #(#field_arms,)*
+                            }
+                        }
+                    },
+                )
+            }
+            Data::Union(ref data) => {
+                return Err(Error::new_spanned(
+                    data.union_token,
+                    "Archive cannot be derived for unions",
+                ))
+            }
+        };
+    let type_name = strip_raw(archived_name);
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::reflect::ReflectArchived for #archived_type #reflect_where {
+            fn type_name(&self) -> &'static str {
+                #type_name
+            }
+
+            fn variant_name(&self) -> Option<&'static str> {
+                #variant_name
+            }
+
+            #for_each_field
+        }
+
+        impl #impl_generics #rkyv_path::reflect::ReflectValue for #archived_type #reflect_where {
+            fn reflect(&self) -> #rkyv_path::reflect::Value<'_> {
+                #rkyv_path::reflect::Value::Struct(self)
+            }
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn derive_variant_accessors(
+    data: &DataEnum,
+    name: &Ident,
+    archived_name: &Ident,
+    archived_type: &Type,
+    vis: &Visibility,
+    generics: &Generics,
+    archive_where: &WhereClause,
+    int_repr: &IntRepr,
+    rkyv_path: &Path,
+) -> Result<(TokenStream, TokenStream), Error> {
+    let with_ty = make_with_ty(rkyv_path);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    let mut variant_types = Vec::new();
+    let mut variant_impls = Vec::new();
+    let mut accessors = Vec::new();
+    for v in data.variants.iter() {
+        let variant = &v.ident;
+        let variant_type = Ident::new(
+            &format!("{}{}", strip_raw(archived_name), strip_raw(variant)),
+            v.span(),
+        );
+        let accessor = Ident::new(
+            &format!("as_{}", to_snake_case(&strip_raw(variant))),
+            v.span(),
+        );
+
+        let (fields_type, get_arm) = match v.fields {
+            Fields::Named(ref fields) => {
+                let fields = fields
+                    .named
+                    .iter()
+                    .map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        let ty = with_ty(f)?;
+                        let field_doc = format!(
+                            "The archived counterpart of [`{}::{}::{}`]",
+                            name, variant, field_name,
+                        );
+                        Ok(quote! {
+                            #[doc = #field_doc]
+                            pub #field_name: #rkyv_path::Archived<#ty>
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let variant_doc = format!(
+                    "The archived fields of [`{}::{}`], which can be borrowed from an archived enum \
+                    with that variant.",
+                    name, variant,
+                );
+                // Enums with a primitive representation lay out each variant like a repr(C) struct
+                // that starts with the tag
+                variant_types.push(quote! {
+                    #[doc = #variant_doc]
+                    #[allow(dead_code)]
+                    #[repr(C)]
+                    #vis struct #variant_type #generics #archive_where {
+                        #[allow(dead_code)]
+                        __tag: #int_repr,
+                        #(#fields,)*
+                        #[allow(dead_code)]
+                        __phantom: ::core::marker::PhantomData<#name #ty_generics>,
+                    }
+                });
+                (
+                    quote! { #variant_type #ty_generics },
+                    quote! {
+                        #archived_name::#variant { .. } => unsafe {
+                            Some(&*(value as *const #archived_type).cast::<Self>())
+                        },
+                    },
+                )
+            }
+            Fields::Unnamed(ref fields) => {
+                if fields.unnamed.len() != 1 {
+                    return Err(Error::new_spanned(
+                        v,
+                        "variant_accessors only supports unit variants, variants with named fields, and variants with a single unnamed field",
+                    ));
+                }
+                let ty = with_ty(&fields.unnamed[0])?;
+                (
+                    quote! { #rkyv_path::Archived<#ty> },
+                    quote! { #archived_name::#variant(field) => Some(field), },
+                )
+            }
+            Fields::Unit => (
+                quote! { () },
+                quote! { #archived_name::#variant => Some(&()), },
+            ),
+        };
+
+        // Variants with named fields use their fields type as a marker, the others get a new one
+        let marker_generics = if matches!(v.fields, Fields::Named(_)) {
+            Some(&ty_generics)
+        } else {
+            let marker_doc = format!(
+                "A marker for [`{}::{}`], which can be used to borrow its archived fields from an \
+                archived enum with that variant.",
+                name, variant,
+            );
+            variant_types.push(quote! {
+                #[doc = #marker_doc]
+                #[allow(dead_code)]
+                #vis struct #variant_type;
+            });
+            None
+        };
+
+        variant_impls.push(quote! {
+            impl #impl_generics #rkyv_path::variants::ArchivedVariant<#archived_type> for #variant_type #marker_generics #archive_where {
+                type Fields = #fields_type;
+
+                #[inline]
+                fn get(value: &#archived_type) -> Option<&Self::Fields> {
+                    match value {
+                        #get_arm
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+            }
+        });
+
+        let accessor_doc = format!(
+            "Returns the archived fields of [`{}::{}`] if it's the active variant.",
+            name, variant,
+        );
+        accessors.push(quote! {
+            #[doc = #accessor_doc]
+            #[inline]
+            pub fn #accessor(&self) -> Option<&#fields_type> {
+                <#variant_type #marker_generics as #rkyv_path::variants::ArchivedVariant<Self>>::get(self)
+            }
+        });
+    }
+
+    Ok((
+        quote! { #(#variant_types)* },
+        quote! {
+            #(#variant_impls)*
+
+            impl #impl_generics #archived_type #archive_where {
+                /// Returns the archived fields of the variant `V` if it's the active variant.
+                #[inline]
+                pub fn as_variant<V: #rkyv_path::variants::ArchivedVariant<Self>>(&self) -> Option<&V::Fields> {
+                    V::get(self)
+                }
+
+                #(#accessors)*
+            }
+        },
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn derive_niche_impl(
+    data: &Data,
+    attributes: &Attributes,
+    name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    where_clause: &WhereClause,
+    archived_name: &Ident,
+    archived_type: &Type,
+    archived_doc: &str,
+    resolver: &Ident,
+    resolver_doc: &str,
+    archive_attrs: Vec<Attribute>,
+    rkyv_path: &Path,
+) -> Result<(TokenStream, TokenStream), Error> {
+    let with_ty = make_with_ty(rkyv_path);
+    let with_cast = make_with_cast(rkyv_path);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    let variants = niche_variants(data, attributes.niche.as_ref().unwrap())?;
+    if let Some((lit, _)) = variant_tag(variants.unit)?.or(variant_tag(variants.dataful)?) {
+        return Err(Error::new_spanned(
+            lit,
+            "tags may not be pinned with niche because the archived enum has no tag",
+        ));
+    }
+    let unit = &variants.unit.ident;
+    let dataful = &variants.dataful.ident;
+    let niche_ty = with_ty(variants.niche)?;
+
+    let mut archive_where = where_clause.clone();
+    for field in variants
+        .dataful
+        .fields
+        .iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+    {
+        let ty = with_ty(field)?;
+        archive_where
+            .predicates
+            .push(parse_quote! { #ty: #rkyv_path::Archive });
+    }
+    archive_where.predicates.push(parse_quote! {
+        #rkyv_path::Archived<#niche_ty>: #rkyv_path::niche::ArchivedNiche
+    });
+
+    // The fields of the variant with data are stored in a separate struct, unless it only has a
+    // single unnamed field
+    let (fields_def, fields_type, niche_field) = match variants.dataful.fields {
+        Fields::Named(ref fields) => {
+            let fields_name = Ident::new(
+                &format!("{}{}", strip_raw(archived_name), strip_raw(dataful)),
+                variants.dataful.span(),
+            );
+            let fields = fields
+                .named
+                .iter()
+                .map(|f| {
+                    let field_name = f.ident.as_ref().unwrap();
+                    let ty = with_ty(f)?;
+                    let field_doc = format!(
+                        "The archived counterpart of [`{}::{}::{}`]",
+                        name, dataful, field_name,
+                    );
+                    let field_attrs = field_archive_attrs(f);
+                    Ok(quote! {
+                        #[doc = #field_doc]
+                        #(#[#field_attrs])*
+                        pub #field_name: #rkyv_path::Archived<#ty>
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let fields_doc = format!(
+                "The archived fields of [`{}::{}`], which can be borrowed from an archived enum \
+                with that variant.",
+                name, dataful,
+            );
+            let niche_name = variants.niche.ident.as_ref().unwrap();
+            (
+                Some(quote! {
+                    #[automatically_derived]
+                    #[doc = #fields_doc]
+                    #(#archive_attrs)*
+                    #[repr(C)]
+                    #vis struct #fields_name #generics #archive_where {
+                        #(#fields,)*
+                    }
+                }),
+                quote! { #fields_name #ty_generics },
+                Some(niche_name),
+            )
+        }
+        _ => {
+            if let Some(first) = attributes.attrs.first() {
+                return Err(Error::new_spanned(
+                    first,
+                    "archive_attr(...) may only be used with niche if the variant with data has named fields",
+                ));
+            }
+            (None, quote! { #rkyv_path::Archived<#niche_ty> }, None)
+        }
+    };
+
+    let (niche_ptr, niche_ptr_mut) = match niche_field {
+        Some(niche_name) => (
+            quote! { ::core::ptr::addr_of!((*fields).#niche_name) },
+            quote! { ::core::ptr::addr_of_mut!((*out).#niche_name) },
+        ),
+        None => (quote! { fields }, quote! { out }),
+    };
+
+    let resolver_variants = match variants.dataful.fields {
+        Fields::Named(ref fields) => {
+            let fields = fields.named.iter().map(|f| {
+                let field_name = f.ident.as_ref();
+                let ty = with_ty(f).unwrap();
+                let field_doc = format!(
+                    "The resolver for [`{}::{}::{}`]",
+                    name,
+                    dataful,
+                    field_name.unwrap(),
+                );
+                quote! {
+                    #[doc = #field_doc]
+                    #field_name: #rkyv_path::Resolver<#ty>
+                }
+            });
+            quote! { #dataful { #(#fields,)* } }
+        }
+        _ => quote! { #dataful(#rkyv_path::Resolver<#niche_ty>) },
+    };
+    let unit_resolver_doc = format!("The resolver for [`{}::{}`]", name, unit);
+    let dataful_resolver_doc = format!("The resolver for [`{}::{}`]", name, dataful);
+
+    let resolve_dataful = match variants.dataful.fields {
+        Fields::Named(ref fields) => {
+            let self_bindings = fields.named.iter().map(|f| {
+                let name = &f.ident;
+                let binding = Ident::new(
+                    &format!("self_{}", strip_raw(name.as_ref().unwrap())),
+                    name.span(),
+                );
+                quote! { #name: #binding }
+            });
+            let resolver_bindings = fields.named.iter().map(|f| {
+                let name = &f.ident;
+                let binding = Ident::new(
+                    &format!("resolver_{}", strip_raw(name.as_ref().unwrap())),
+                    name.span(),
+                );
+                quote! { #name: #binding }
+            });
+            let resolves = fields.named.iter().map(|f| {
+                let name = &f.ident;
+                let self_binding = Ident::new(
+                    &format!("self_{}", strip_raw(name.as_ref().unwrap())),
+                    name.span(),
+                );
+                let resolver_binding = Ident::new(
+                    &format!("resolver_{}", strip_raw(name.as_ref().unwrap())),
+                    name.span(),
+                );
+                let value = with_cast(f, parse_quote! { #self_binding }).unwrap();
+                quote! {
+                    let (fp, fo) = out_field!(out.#name);
+                    #rkyv_path::Archive::resolve(#value, pos + fp, #resolver_binding, fo);
+                }
+            });
+            quote! {
+                #resolver::#dataful { #(#resolver_bindings,)* } => match self {
+                    #name::#dataful { #(#self_bindings,)* } => {
+                        #(#resolves)*
+                    }
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::hint::unreachable_unchecked(),
+                }
+            }
+        }
+        _ => {
+            let value = with_cast(variants.niche, parse_quote! { self_0 })?;
+            quote! {
+                #resolver::#dataful(resolver_0) => match self {
+                    #name::#dataful(self_0) => {
+                        #rkyv_path::Archive::resolve(#value, pos, resolver_0, out);
+                    }
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::hint::unreachable_unchecked(),
+                }
+            }
+        }
+    };
+
+    let check_bytes_impl = if attributes.check_bytes.is_some() {
+        let mut check_where = archive_where.clone();
+        check_where
+            .predicates
+            .push(parse_quote! { #fields_type: #rkyv_path::bytecheck::CheckBytes<__C> });
+
+        let mut check_params = Punctuated::default();
+        check_params.push(parse_quote! { __C: ?Sized });
+        for param in generics.params.iter() {
+            check_params.push(param.clone());
+        }
+        let check_generics = Generics {
+            lt_token: Some(Default::default()),
+            params: check_params,
+            gt_token: Some(Default::default()),
+            where_clause: None,
+        };
+        let (check_impl_generics, _, _) = check_generics.split_for_impl();
+
+        Some(quote! {
+            impl #check_impl_generics #rkyv_path::bytecheck::CheckBytes<__C> for #archived_type
+                #check_where
+            {
+                type Error = <#fields_type as #rkyv_path::bytecheck::CheckBytes<__C>>::Error;
+
+                #[inline]
+                unsafe fn check_bytes<'__a>(
+                    value: *const Self,
+                    context: &mut __C,
+                ) -> ::core::result::Result<&'__a Self, Self::Error> {
+                    let fields = value.cast::<#fields_type>();
+                    let niche = #niche_ptr;
+                    if !<
+                        #rkyv_path::Archived<#niche_ty> as #rkyv_path::niche::ArchivedNiche
+                    >::is_niche(niche) {
+                        <
+                            #fields_type as #rkyv_path::bytecheck::CheckBytes<__C>
+                        >::check_bytes(fields, context)?;
+                    }
+                    Ok(&*value)
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let is_unit = Ident::new(
+        &format!("is_{}", to_snake_case(&strip_raw(unit))),
+        unit.span(),
+    );
+    let is_dataful = Ident::new(
+        &format!("is_{}", to_snake_case(&strip_raw(dataful))),
+        dataful.span(),
+    );
+    let as_dataful = Ident::new(
+        &format!("as_{}", to_snake_case(&strip_raw(dataful))),
+        dataful.span(),
+    );
+    let is_unit_doc = format!(
+        "Returns whether the active variant is [`{}::{}`].",
+        name, unit
+    );
+    let is_dataful_doc = format!(
+        "Returns whether the active variant is [`{}::{}`].",
+        name, dataful,
+    );
+    let as_dataful_doc = format!(
+        "Returns the archived fields of [`{}::{}`] if it's the active variant.",
+        name, dataful,
+    );
+
+    Ok((
+        quote! {
+            #fields_def
+
+            #[automatically_derived]
+            #[doc = #archived_doc]
+            #[repr(transparent)]
+            #vis struct #archived_name #generics #archive_where {
+                inner: ::core::mem::MaybeUninit<#fields_type>,
+            }
+
+            #[automatically_derived]
+            #[doc = #resolver_doc]
+            #vis enum #resolver #generics #archive_where {
+                #[doc = #unit_resolver_doc]
+                #[allow(dead_code)]
+                #unit,
+                #[doc = #dataful_resolver_doc]
+                #[allow(dead_code)]
+                #resolver_variants,
+            }
+        },
+        quote! {
+            impl #impl_generics Archive for #name #ty_generics #archive_where {
+                type Archived = #archived_type;
+                type Resolver = #resolver #ty_generics;
+
+                // Some resolvers will be (), this allow is to prevent clippy from complaining
+                #[allow(clippy::unit_arg)]
+                #[inline]
+                unsafe fn resolve(&self, pos: usize, resolver: <Self as Archive>::Resolver, out: *mut <Self as Archive>::Archived) {
+                    let out = out.cast::<#fields_type>();
+                    match resolver {
+                        #resolver::#unit => {
+                            <
+                                #rkyv_path::Archived<#niche_ty> as #rkyv_path::niche::ArchivedNiche
+                            >::resolve_niche(#niche_ptr_mut);
+                        }
+                        #resolve_dataful
+                    }
+                }
+            }
+
+            impl #impl_generics #archived_type #archive_where {
+                #[doc = #is_unit_doc]
+                #[inline]
+                pub fn #is_unit(&self) -> bool {
+                    let fields = self.inner.as_ptr();
+                    // SAFETY: The niche field is always initialized, but it may hold the niche
+                    unsafe {
+                        <
+                            #rkyv_path::Archived<#niche_ty> as #rkyv_path::niche::ArchivedNiche
+                        >::is_niche(#niche_ptr)
+                    }
+                }
+
+                #[doc = #is_dataful_doc]
+                #[inline]
+                pub fn #is_dataful(&self) -> bool {
+                    !self.#is_unit()
+                }
+
+                #[doc = #as_dataful_doc]
+                #[inline]
+                pub fn #as_dataful(&self) -> Option<&#fields_type> {
+                    if self.#is_unit() {
+                        None
+                    } else {
+                        // SAFETY: The fields are initialized unless the niche field holds the niche
+                        Some(unsafe { self.inner.assume_init_ref() })
+                    }
+                }
+            }
+
+            #check_bytes_impl
+        },
+    ))
+}
@@ -13,10 +13,23 @@ pub struct Attributes {
     pub archive_bound: Option<LitStr>,
     pub serialize_bound: Option<LitStr>,
     pub deserialize_bound: Option<LitStr>,
+    pub deserialize_into_builder: Option<LitStr>,
+    pub deserialize_try_into: Option<LitStr>,
+    pub boxed_variants: Option<Path>,
+    pub check_as: Option<Path>,
     pub check_bytes: Option<Path>,
     pub copy_safe: Option<Path>,
+    pub default: Option<Path>,
+    pub dump_layout: Option<Path>,
+    pub field_offsets: Option<Path>,
+    pub max_scratch: Option<Path>,
+    pub niche: Option<Path>,
+    pub optimize_layout: Option<Path>,
+    pub partial_eq: Option<Path>,
+    pub reflect: Option<Path>,
     pub rkyv_path: Option<Path>,
     pub rkyv_path_str: Option<LitStr>,
+    pub variant_accessors: Option<Path>,
 }
 
 fn try_set_attribute<T: ToTokens>(
@@ -38,10 +51,44 @@ fn try_set_attribute<T: ToTokens>(
 fn parse_archive_attributes(attributes: &mut Attributes, meta: &Meta) -> Result<(), Error> {
     match meta {
         Meta::Path(path) => {
-            if path.is_ident("check_bytes") {
+            if path.is_ident("boxed_variants") {
+                try_set_attribute(
+                    &mut attributes.boxed_variants,
+                    path.clone(),
+                    "boxed_variants",
+                )
+            } else if path.is_ident("check_as") {
+                try_set_attribute(&mut attributes.check_as, path.clone(), "check_as")
+            } else if path.is_ident("check_bytes") {
                 try_set_attribute(&mut attributes.check_bytes, path.clone(), "check_bytes")
             } else if path.is_ident("copy_safe") {
                 try_set_attribute(&mut attributes.copy_safe, path.clone(), "copy_safe")
+            } else if path.is_ident("default") {
+                try_set_attribute(&mut attributes.default, path.clone(), "default")
+            } else if path.is_ident("dump_layout") {
+                try_set_attribute(&mut attributes.dump_layout, path.clone(), "dump_layout")
+            } else if path.is_ident("field_offsets") {
+                try_set_attribute(&mut attributes.field_offsets, path.clone(), "field_offsets")
+            } else if path.is_ident("max_scratch") {
+                try_set_attribute(&mut attributes.max_scratch, path.clone(), "max_scratch")
+            } else if path.is_ident("niche") {
+                try_set_attribute(&mut attributes.niche, path.clone(), "niche")
+            } else if path.is_ident("optimize_layout") {
+                try_set_attribute(
+                    &mut attributes.optimize_layout,
+                    path.clone(),
+                    "optimize_layout",
+                )
+            } else if path.is_ident("partial_eq") {
+                try_set_attribute(&mut attributes.partial_eq, path.clone(), "partial_eq")
+            } else if path.is_ident("reflect") {
+                try_set_attribute(&mut attributes.reflect, path.clone(), "reflect")
+            } else if path.is_ident("variant_accessors") {
+                try_set_attribute(
+                    &mut attributes.variant_accessors,
+                    path.clone(),
+                    "variant_accessors",
+                )
             } else {
                 Err(Error::new_spanned(meta, "unrecognized archive argument"))
             }
@@ -90,7 +137,7 @@ fn parse_archive_attributes(attributes: &mut Attributes, meta: &Meta) -> Result<
                             } else {
                                 return Err(Error::new_spanned(
                                     bound,
-                                    "bound must be either serialize or deserialize",
+                                    "bound must be either archive, serialize, or deserialize",
                                 ));
                             }
                         } else {
@@ -102,7 +149,7 @@ fn parse_archive_attributes(attributes: &mut Attributes, meta: &Meta) -> Result<
                     } else {
                         return Err(Error::new_spanned(
                             bound,
-                            "bound arguments must be serialize or deserialize bounds to apply",
+                            "bound arguments must be archive, serialize, or deserialize bounds to apply",
                         ));
                     }
                 }
@@ -144,6 +191,32 @@ fn parse_archive_attributes(attributes: &mut Attributes, meta: &Meta) -> Result<
                 } else {
                     Err(Error::new_spanned(meta, "archive as must be a string"))
                 }
+            } else if meta.path.is_ident("deserialize_into_builder") {
+                if let Lit::Str(ref lit_str) = meta.lit {
+                    try_set_attribute(
+                        &mut attributes.deserialize_into_builder,
+                        lit_str.clone(),
+                        "deserialize into builder",
+                    )
+                } else {
+                    Err(Error::new_spanned(
+                        meta,
+                        "deserialize into builder must be a string",
+                    ))
+                }
+            } else if meta.path.is_ident("deserialize_try_into") {
+                if let Lit::Str(ref lit_str) = meta.lit {
+                    try_set_attribute(
+                        &mut attributes.deserialize_try_into,
+                        lit_str.clone(),
+                        "deserialize try into",
+                    )
+                } else {
+                    Err(Error::new_spanned(
+                        meta,
+                        "deserialize try into must be a string",
+                    ))
+                }
             } else if meta.path.is_ident("crate") {
                 if let Lit::Str(ref lit_str) = meta.lit {
                     let stream = syn::parse_str(&lit_str.value())?;
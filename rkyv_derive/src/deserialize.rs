@@ -1,13 +1,13 @@
 use crate::{
     attributes::{parse_attributes, Attributes},
-    util::add_bounds,
+    util::{add_bounds, boxed_variants, niche_variants, strip_raw, to_snake_case},
     with::{make_with_ty, with_inner},
 };
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
     parse_quote, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error, Fields,
-    Generics, Ident, Index,
+    Generics, Ident, Index, Type,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -48,8 +48,76 @@ fn derive_deserialize_impl(
     let (_, ty_generics, where_clause) = input.generics.split_for_impl();
     let where_clause = where_clause.unwrap();
 
+    if let Some(ref builder) = attributes.deserialize_into_builder {
+        if !matches!(input.data, Data::Struct(ref data) if matches!(data.fields, Fields::Named(_)))
+        {
+            return Err(Error::new_spanned(
+                builder,
+                "deserialize_into_builder is only supported on structs with named fields",
+            ));
+        }
+    }
+
     let deserialize_impl = match input.data {
         Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) if attributes.deserialize_into_builder.is_some() => {
+                let builder = attributes
+                    .deserialize_into_builder
+                    .as_ref()
+                    .unwrap()
+                    .parse::<Type>()?;
+                let builder_trait =
+                    quote! { #rkyv_path::de::DeserializeBuilder<#name #ty_generics> };
+
+                let mut deserialize_where = where_clause.clone();
+                deserialize_where
+                    .predicates
+                    .push(parse_quote! { #builder: Default + #builder_trait });
+                deserialize_where.predicates.push(parse_quote! {
+                    <__D as Fallible>::Error: From<<#builder as #builder_trait>::Error>
+                });
+                for field in fields
+                    .named
+                    .iter()
+                    .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+                {
+                    let ty = with_ty(field)?;
+                    deserialize_where
+                        .predicates
+                        .push(parse_quote! { #ty: Archive });
+                    deserialize_where
+                        .predicates
+                        .push(parse_quote! { Archived<#ty>: Deserialize<#ty, __D> });
+                }
+
+                let set_fields = fields.named.iter().map(|f| {
+                    let name = &f.ident;
+                    let ty = with_ty(f).unwrap();
+                    let value = with_inner(
+                        f,
+                        rkyv_path,
+                        parse_quote! {
+                            Deserialize::<#ty, __D>::deserialize(
+                                &self.#name,
+                                deserializer,
+                            )?
+                        },
+                    )
+                    .unwrap();
+                    quote! { builder.#name(#value); }
+                });
+
+                quote! {
+                    impl #impl_generics Deserialize<#name #ty_generics, __D> for Archived<#name #ty_generics> #deserialize_where {
+                        #[inline]
+                        fn deserialize(&self, deserializer: &mut __D) -> ::core::result::Result<#name #ty_generics, __D::Error> {
+                            let mut builder = <#builder as Default>::default();
+                            #(#set_fields)*
+                            Ok(<#builder as #builder_trait>::build(builder)?)
+                        }
+                    }
+                }
+            }
             Fields::Named(ref fields) => {
                 let mut deserialize_where = where_clause.clone();
                 for field in fields
@@ -71,6 +139,7 @@ fn derive_deserialize_impl(
                     let ty = with_ty(f).unwrap();
                     let value = with_inner(
                         f,
+                        rkyv_path,
                         parse_quote! {
                             Deserialize::<#ty, __D>::deserialize(
                                 &self.#name,
@@ -114,6 +183,7 @@ fn derive_deserialize_impl(
                     let ty = with_ty(f).unwrap();
                     let value = with_inner(
                         f,
+                        rkyv_path,
                         parse_quote! {
                             Deserialize::<#ty, __D>::deserialize(
                                 &self.#index,
@@ -196,6 +266,7 @@ fn derive_deserialize_impl(
                             let ty = with_ty(f).unwrap();
                             let value = with_inner(
                                 f,
+                                rkyv_path,
                                 parse_quote! {
                                     Deserialize::<#ty, __D>::deserialize(
                                         #name,
@@ -220,6 +291,7 @@ fn derive_deserialize_impl(
                             let ty = with_ty(f).unwrap();
                             let value = with_inner(
                                 f,
+                                rkyv_path,
                                 parse_quote! {
                                     Deserialize::<#ty, __D>::deserialize(
                                         #binding,
@@ -240,13 +312,70 @@ fn derive_deserialize_impl(
                 }
             });
 
+            let deserialize_match = if let Some(ref path) = attributes.niche {
+                // Archived enums with a niche are matched with their accessor instead
+                let variants = niche_variants(&input.data, path)?;
+                let niche_ty = with_ty(variants.niche)?;
+                deserialize_where.predicates.push(parse_quote! {
+                    Archived<#niche_ty>: #rkyv_path::niche::ArchivedNiche
+                });
+
+                let unit = &variants.unit.ident;
+                let dataful = &variants.dataful.ident;
+                let as_dataful = Ident::new(
+                    &format!("as_{}", to_snake_case(&strip_raw(dataful))),
+                    dataful.span(),
+                );
+                let value = match variants.dataful.fields {
+                    Fields::Named(ref fields) => {
+                        let fields = fields.named.iter().map(|f| {
+                            let name = &f.ident;
+                            let ty = with_ty(f).unwrap();
+                            let value = with_inner(
+                                f,
+                                rkyv_path,
+                                parse_quote! {
+                                    Deserialize::<#ty, __D>::deserialize(
+                                        &fields.#name,
+                                        deserializer,
+                                    )?
+                                },
+                            )
+                            .unwrap();
+                            quote! { #name: #value }
+                        });
+                        quote! { #name::#dataful { #(#fields,)* } }
+                    }
+                    _ => {
+                        let value = with_inner(
+                            variants.niche,
+                            rkyv_path,
+                            parse_quote! {
+                                Deserialize::<#niche_ty, __D>::deserialize(fields, deserializer)?
+                            },
+                        )?;
+                        quote! { #name::#dataful(#value) }
+                    }
+                };
+                quote! {
+                    match self.#as_dataful() {
+                        Some(fields) => #value,
+                        None => #name::#unit,
+                    }
+                }
+            } else {
+                quote! {
+                    match self {
+                        #(#deserialize_variants,)*
+                    }
+                }
+            };
+
             quote! {
                 impl #impl_generics Deserialize<#name #ty_generics, __D> for Archived<#name #ty_generics> #deserialize_where {
                     #[inline]
                     fn deserialize(&self, deserializer: &mut __D) -> ::core::result::Result<#name #ty_generics, __D::Error> {
-                        Ok(match self {
-                            #(#deserialize_variants,)*
-                        })
+                        Ok(#deserialize_match)
                     }
                 }
             }
@@ -259,11 +388,107 @@ fn derive_deserialize_impl(
         }
     };
 
+    let deserialize_try_into_impl = if let Some(ref target) = attributes.deserialize_try_into {
+        let target = target.parse::<Type>()?;
+        let archived = quote! { Archived<#name #ty_generics> };
+
+        // The error type is a separate parameter so that it isn't tied to the lifetime of the
+        // archived reference
+        let mut try_into_generics = impl_input_generics.clone();
+        try_into_generics.params.insert(1, parse_quote! { __E });
+        let (try_into_impl_generics, _, _) = try_into_generics.split_for_impl();
+
+        let mut try_into_where = where_clause.clone();
+        try_into_where
+            .predicates
+            .push(parse_quote! { #name #ty_generics: Archive });
+        try_into_where.predicates.push(parse_quote! {
+            #target: for<'__a> ::core::convert::TryFrom<&'__a #archived, Error = __E>
+        });
+        try_into_where
+            .predicates
+            .push(parse_quote! { <__D as Fallible>::Error: From<__E> });
+
+        Some(quote! {
+            impl #try_into_impl_generics Deserialize<#target, __D> for #archived #try_into_where {
+                #[inline]
+                fn deserialize(&self, _: &mut __D) -> ::core::result::Result<#target, __D::Error> {
+                    Ok(<#target as ::core::convert::TryFrom<&Self>>::try_from(self)?)
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let deserialize_variants_impl = if let Some(ref path) = attributes.boxed_variants {
+        let variants = boxed_variants(&input.data, path)?;
+
+        let mut deserialize_where = where_clause.clone();
+        for (_, field) in variants.iter() {
+            if let Some(field) = field {
+                if !field.attrs.iter().any(|a| a.path.is_ident("omit_bounds")) {
+                    let ty = with_ty(field)?;
+                    deserialize_where
+                        .predicates
+                        .push(parse_quote! { #ty: Archive });
+                    deserialize_where
+                        .predicates
+                        .push(parse_quote! { Archived<#ty>: Deserialize<#ty, __D> });
+                }
+            }
+        }
+
+        let deserialize_arms = variants.iter().enumerate().map(|(i, (v, field))| {
+            let variant = &v.ident;
+            let index = i as u32;
+            match field {
+                Some(field) => {
+                    let ty = with_ty(field).unwrap();
+                    let value = with_inner(
+                        field,
+                        rkyv_path,
+                        parse_quote! {
+                            Deserialize::<#ty, __D>::deserialize(
+                                &*payload.cast::<Archived<#ty>>(),
+                                deserializer,
+                            )?
+                        },
+                    )
+                    .unwrap();
+                    quote! { #index => #name::#variant(#value) }
+                }
+                None => quote! { #index => #name::#variant },
+            }
+        });
+
+        Some(quote! {
+            impl #impl_generics #rkyv_path::variants::DeserializeVariants<__D> for #name #ty_generics #deserialize_where {
+                #[inline]
+                #[allow(unused_variables)]
+                unsafe fn deserialize_variant(
+                    index: u32,
+                    payload: *const u8,
+                    deserializer: &mut __D,
+                ) -> ::core::result::Result<Self, __D::Error> {
+                    Ok(match index {
+                        #(#deserialize_arms,)*
+                        _ => ::core::hint::unreachable_unchecked(),
+                    })
+                }
+            }
+        })
+    } else {
+        None
+    };
+
     Ok(quote! {
         #[automatically_derived]
         const _: () = {
             use #rkyv_path::{Archive, Archived, Deserialize, Fallible};
             #deserialize_impl
+            #deserialize_try_into_impl
+            #deserialize_variants_impl
         };
     })
 }
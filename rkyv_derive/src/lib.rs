@@ -42,21 +42,125 @@ use syn::{parse_macro_input, DeriveInput};
 ///   when dealing with recursive structures, where bounds may need to be omitted to prevent
 ///   recursive type definitions. Use `archive = "..."` to specify `Archive` bounds,
 ///   `serialize = "..."` to specify `Serialize` bounds, and `deserialize = "..."` to specify
-///   `Deserialize` bounds.
+///   `Deserialize` bounds. To replace the generated bounds instead of adding to them, combine this
+///   with `#[omit_bounds]` on the fields whose bounds should be replaced.
 /// - `check_bytes`: Derive `CheckBytes` on the archived type, in order to enable safe
 ///   deserialization. Requires `validation` feature. Not compatible with `as = "..."`. In that
 ///   case, use `#[derive(CheckBytes)]` on the archived type, and include a `use rkyv::bytecheck`
 ///   statement.
 /// - `copy_safe`: States that the archived type is tightly packed with no padding bytes. This
 ///   qualifies it for copy optimizations. (requires nightly)
+/// - `optimize_layout`: Reorders the fields of the archived type by descending alignment to reduce
+///   padding, and makes it `repr(C)` so the reordered layout is stable. The original type keeps its
+///   field order. Alignments are estimated from field types, so only primitives and arrays and
+///   tuples of primitives are sorted. All other fields are placed first in declaration order. Only
+///   supported on structs with named fields.
+/// - `dump_layout`: Implements `DumpLayout` for the archived type so it can be printed with
+///   `rkyv::dump::dump_layout`. Requires `debug` feature. Not compatible with `as = "..."`.
+/// - `reflect`: Implements `rkyv::reflect::ReflectArchived` and `rkyv::reflect::ReflectValue` for
+///   the archived type, so generic tooling can visit its fields by name. The archived type of every
+///   field must implement `ReflectValue`. Requires `reflect` feature. Not compatible with
+///   `as = "..."`.
+/// - `field_offsets`: Adds an associated constant `FIELD_OFFSETS: &[(&str, usize)]` to the
+///   archived type that lists the name and byte offset of each field in declaration order. Tuple
+///   struct fields are named by their index. Only supported on structs. Not compatible with
+///   `as = "..."`.
+/// - `default`: Implements `Default` for the archived type by defaulting each of its fields. The
+///   archived type of every field must implement `Default`. Only supported on structs. Not
+///   compatible with `as = "..."`.
+/// - `partial_eq`: Implements `PartialEq` between archived values by comparing each of their
+///   fields, so archived values can be compared without deserializing them. Unlike
+///   `#[archive_attr(derive(PartialEq))]`, the generated implementation is bounded on the archived
+///   types of the fields instead of the generic parameters. Not compatible with `as = "..."`.
+/// - `max_scratch`: Implements `rkyv::ser::max_scratch::MaxScratch` for the type, which provides
+///   a compile-time upper bound on the scratch space needed to serialize it. The bound is the sum
+///   of the bounds of every field, so the type of every field must implement `MaxScratch`. Types
+///   with dynamically-sized collections like `Vec` don't have a bound. Not compatible with fields
+///   that have wrappers.
+/// - `boxed_variants`: Implements the traits needed to archive the enum with
+///   `rkyv::with::BoxedVariants`, which stores the payload of the active variant out of line. Every
+///   variant must be a unit variant or have a single unnamed field. Combine with `check_bytes` to
+///   validate the out-of-line payloads. Only supported on enums.
+/// - `variant_accessors`: Generates a type for each variant of the archived enum and implements
+///   `rkyv::variants::ArchivedVariant` for it, along with an `as_variant::<V>()` method and an
+///   accessor for each variant (like `as_circle()` for `Circle`) on the archived enum. Each returns
+///   the archived fields of the variant if it's the active one. Variants must be unit variants,
+///   have named fields, or have a single unnamed field. Only supported on enums. Not compatible
+///   with `as = "..."`.
+/// - `niche`: Stores the unit variant of the enum in the niche of a field in its other variant
+///   instead of storing a separate tag. See [Niches](#niches) for more details. Only supported on
+///   enums with one unit variant and one variant with named fields or a single unnamed field. Not
+///   compatible with `as = "..."`, `repr(...)`, pinned tags, `compare(...)`, `copy_safe`,
+///   `dump_layout`, `partial_eq`, `reflect`, or `variant_accessors`.
 /// - `as = "..."`: Instead of generating a separate archived type, this type will archive as the
-///   named type. This is useful for types which are generic over their parameters.
+///   named type. This is useful for types which are generic over their parameters, or to archive
+///   two types with the same fields as the same archived type so they can be accessed
+///   interchangeably. The named type must be a struct or enum with exactly the same fields (and
+///   variants, in the same order) as this type, and each of its fields must have the archived type
+///   of the corresponding field. Add `check_as` to check this at compile time. The named type must
+///   also have the same layout that a generated archived type would have, which can't be checked:
+///   enums must have the same `repr` and structs must not reorder their fields differently. Using a
+///   named type with a different layout is unsound.
+/// - `check_as`: Checks at compile time that the type named by `as = "..."` has the same fields
+///   and variants as this type, with the archived type of each field. The check destructures the
+///   named type, so it must be a struct or enum whose fields are all visible from this type. Only
+///   compatible with `as = "..."`.
+/// - `deserialize_into_builder = "..."`: Derives `Deserialize` by deserializing each field into
+///   the named builder type and then building it, instead of constructing the type directly. The
+///   builder must implement `Default` and `rkyv::de::DeserializeBuilder`, and have a method named
+///   after each field that takes the deserialized value. Only supported on structs with named
+///   fields.
+/// - `deserialize_try_into = "..."`: Also derives `Deserialize` into the named type by converting
+///   the archived type with `TryFrom<&Archived<Self>>`. If the conversion fails, its error is
+///   converted into the error of the deserializer with `From`. This is useful for deserializing
+///   into a type that checks its invariants, without deserializing into this type first.
 /// - `crate = "..."`: Chooses an alternative crate path to import rkyv from.
 ///
 /// `#[archive_attr(...)]` adds the attributes passed as arguments as attributes to the generated
 /// type. This is commonly used with attributes like `derive(...)` to derive trait implementations
 /// for the archived type.
 ///
+/// # Enum tags
+///
+/// Archived enums are tagged with the smallest unsigned integer type that can represent all of
+/// their variants, so an enum with more than 255 variants uses a `u16` tag. A different tag type
+/// can be chosen with `#[archive_attr(repr(...))]`, for example `#[archive_attr(repr(u32))]`.
+/// Validation rejects any tag that doesn't correspond to a variant.
+///
+/// By default, the tag of each variant is its index in declaration order, so reordering the
+/// variants changes the archived representation. Adding `#[archive(tag = N)]` to every variant pins
+/// its tag to `N` instead, so the variants can be reordered without changing the archived data.
+/// Pinned tags must be unique, and if a variant has a pinned tag then all of them must. Unless a
+/// repr is chosen, the tag type is the smallest unsigned integer that can represent the largest
+/// pinned tag. Validation only accepts the pinned tags.
+///
+/// The accessors generated by `variant_accessors` match on the variants of the archived enum
+/// instead of comparing tags, so they don't depend on whether the tags are pinned.
+///
+/// # Niches
+///
+/// Enums like `Option` that have one unit variant and one variant with fields can be archived
+/// without a tag using `#[archive(niche)]`. The unit variant is stored as an invalid bit pattern
+/// (the niche) of one of the fields in the other variant, which must be marked with
+/// `#[archive(niche)]` if there's more than one. The archived type of the niche field must
+/// implement `rkyv::niche::ArchivedNiche`, which is implemented for archived `NonZero` integers,
+/// `bool`, and `char`. Validation only checks the other fields if the niche field doesn't hold
+/// the niche.
+///
+/// Because the archived enum has no tag, it's a struct that can't be matched on. Instead, it has
+/// an `is_*()` method for each variant and an `as_*()` method that returns the archived fields of
+/// the variant with fields if it's the active one. If the variant has named fields, they're
+/// archived as a separate struct named like `ArchivedHandleOpen` for `Handle::Open`, and
+/// `archive_attr(...)` applies to that struct.
+/// # Comparing archived values
+///
+/// `partial_eq` compares archived values field-by-field, recursing into archived containers like
+/// strings, vectors, and boxes. That can't be replaced with a comparison of the bytes of the
+/// archived values in general, because relative pointers store the distance to their targets. Two
+/// equal values serialized at different positions may have different bytes. A byte comparison is
+/// only equivalent for archived types that don't contain any relative pointers, padding bytes, or
+/// floating-point numbers (where `NaN != NaN` and `0.0 == -0.0`).
+///
 /// # Recursive types
 ///
 /// This derive macro automatically adds a type bound `field: Archive` for each field type. This can
@@ -66,6 +170,24 @@ use syn::{parse_macro_input, DeriveInput};
 /// allow recursive structures. This may be too coarse for some types, in which case additional type
 /// bounds may be required with `bound(...)`.
 ///
+/// # Associated types
+///
+/// Fields may have associated types like `S::Buffer` as their types. Because the bounds are added
+/// per field type, the derived implementations only require `S::Buffer: Archive` and not
+/// `S: Archive`, so the archived type uses the archived version of whichever type `S::Buffer` turns
+/// out to be. The trait that declares the associated type doesn't need to know about rkyv at all.
+///
+/// # References and lifetimes
+///
+/// Shared reference fields without any wrappers are archived as if they were owned with the
+/// `AsOwned` wrapper, so `&'a T` archives as `Archived<T>`, `&'a [T]` archives as
+/// `ArchivedVec<Archived<T>>`, and `&'a str` archives as `ArchivedString`. Arrays of shared
+/// references are archived the same way element by element with `Map<AsOwned>`, so `[&'a str; N]`
+/// archives as `[ArchivedString; N]`. If a struct has a single lifetime parameter that's only used
+/// by those fields, the lifetime is dropped from the archived type. For example, `View<'a, T>`
+/// archives as `ArchivedView<T>`. Bounds like `T: 'a` are dropped along with it. Reference fields
+/// can't be deserialized because the deserialized value can't borrow from the archive.
+///
 /// # Wrappers
 ///
 /// Wrappers transparently customize archived types by providing different implementations of core
@@ -43,35 +43,50 @@ impl IntRepr {
     #[inline]
     #[cfg(feature = "arbitrary_enum_discriminant")]
     pub fn enum_discriminant(&self, index: usize) -> EnumDiscriminant {
-        #[cfg(not(any(
-            all(target_endian = "little", feature = "archive_be"),
-            all(target_endian = "big", feature = "archive_le"),
-        )))]
-        let value = index as u128;
+        self.pinned_discriminant(index as u128)
+    }
+
+    /// Returns the largest discriminant that this repr can represent.
+    pub fn max_discriminant(&self) -> u128 {
+        match self {
+            Self::I8 => i8::MAX as u128,
+            Self::I16 => i16::MAX as u128,
+            Self::I32 => i32::MAX as u128,
+            Self::I64 => i64::MAX as u128,
+            Self::I128 => i128::MAX as u128,
+            Self::U8 => u8::MAX as u128,
+            Self::U16 => u16::MAX as u128,
+            Self::U32 => u32::MAX as u128,
+            Self::U64 => u64::MAX as u128,
+            Self::U128 => u128::MAX,
+        }
+    }
 
+    /// Returns a discriminant with the given archived value, regardless of the position of the
+    /// variant.
+    #[inline]
+    pub fn pinned_discriminant(&self, value: u128) -> EnumDiscriminant {
         #[cfg(any(
             all(target_endian = "little", feature = "archive_be"),
             all(target_endian = "big", feature = "archive_le"),
         ))]
         let value = match self {
-            Self::I8 => (index as i8).swap_bytes() as u128,
-            Self::I16 => (index as i16).swap_bytes() as u128,
-            Self::I32 => (index as i32).swap_bytes() as u128,
-            Self::I64 => (index as i64).swap_bytes() as u128,
-            Self::I128 => (index as i128).swap_bytes() as u128,
-            Self::U8 => (index as u8).swap_bytes() as u128,
-            Self::U16 => (index as u16).swap_bytes() as u128,
-            Self::U32 => (index as u32).swap_bytes() as u128,
-            Self::U64 => (index as u64).swap_bytes() as u128,
-            Self::U128 => (index as u128).swap_bytes(),
+            Self::I8 => (value as i8).swap_bytes() as u128,
+            Self::I16 => (value as i16).swap_bytes() as u128,
+            Self::I32 => (value as i32).swap_bytes() as u128,
+            Self::I64 => (value as i64).swap_bytes() as u128,
+            Self::I128 => (value as i128).swap_bytes() as u128,
+            Self::U8 => (value as u8).swap_bytes() as u128,
+            Self::U16 => (value as u16).swap_bytes() as u128,
+            Self::U32 => (value as u32).swap_bytes() as u128,
+            Self::U64 => (value as u64).swap_bytes() as u128,
+            Self::U128 => value.swap_bytes(),
         };
 
         EnumDiscriminant { repr: *self, value }
     }
 }
 
-// None of these variants are constructed unless the arbitrary_enum_discriminant feature is enabled
-#[allow(dead_code)]
 pub struct EnumDiscriminant {
     repr: IntRepr,
     value: u128,
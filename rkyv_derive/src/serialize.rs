@@ -1,7 +1,7 @@
 use crate::{
     attributes::{parse_attributes, Attributes},
-    util::{add_bounds, strip_raw},
-    with::{make_with_cast, make_with_ty},
+    util::{add_bounds, boxed_variants, niche_variants, strip_raw},
+    with::{as_owned_referent, make_with_cast, make_with_ty},
 };
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -67,6 +67,11 @@ fn derive_serialize_impl(
                     serialize_where
                         .predicates
                         .push(parse_quote! { #ty: Serialize<__S> });
+                    if let Some(referent) = as_owned_referent(field) {
+                        serialize_where
+                            .predicates
+                            .push(parse_quote! { #referent: #rkyv_path::Archive });
+                    }
                 }
 
                 let resolver_values = fields.named.iter().map(|f| {
@@ -97,6 +102,11 @@ fn derive_serialize_impl(
                     serialize_where
                         .predicates
                         .push(parse_quote! { #ty: Serialize<__S> });
+                    if let Some(referent) = as_owned_referent(field) {
+                        serialize_where
+                            .predicates
+                            .push(parse_quote! { #referent: #rkyv_path::Archive });
+                    }
                 }
 
                 let resolver_values = fields.unnamed.iter().enumerate().map(|(i, f)| {
@@ -158,6 +168,12 @@ fn derive_serialize_impl(
                     Fields::Unit => (),
                 }
             }
+            if let Some(ref path) = attributes.niche {
+                let niche_ty = with_ty(niche_variants(&input.data, path)?.niche)?;
+                serialize_where.predicates.push(parse_quote! {
+                    #rkyv_path::Archived<#niche_ty>: #rkyv_path::niche::ArchivedNiche
+                });
+            }
 
             let serialize_arms = data.variants.iter().map(|v| {
                 let variant = &v.ident;
@@ -221,11 +237,59 @@ fn derive_serialize_impl(
         }
     };
 
+    let serialize_variants_impl = if let Some(ref path) = attributes.boxed_variants {
+        let variants = boxed_variants(&input.data, path)?;
+
+        let mut serialize_where = where_clause.clone();
+        serialize_where
+            .predicates
+            .push(parse_quote! { __S: #rkyv_path::ser::Serializer });
+        for (_, field) in variants.iter() {
+            if let Some(field) = field {
+                if !field.attrs.iter().any(|a| a.path.is_ident("omit_bounds")) {
+                    let ty = with_ty(field)?;
+                    serialize_where
+                        .predicates
+                        .push(parse_quote! { #ty: Serialize<__S> });
+                }
+            }
+        }
+
+        let serialize_arms = variants.iter().map(|(v, field)| {
+            let variant = &v.ident;
+            match field {
+                Some(field) => {
+                    let value = with_cast(field, parse_quote! { _0 }).unwrap();
+                    quote! {
+                        Self::#variant(_0) => Ok(Some(
+                            #rkyv_path::ser::Serializer::serialize_value(serializer, #value)?
+                        ))
+                    }
+                }
+                None => quote! { Self::#variant => Ok(None) },
+            }
+        });
+
+        Some(quote! {
+            impl #impl_generics #rkyv_path::variants::SerializeVariants<__S> for #name #ty_generics #serialize_where {
+                #[inline]
+                fn serialize_variant(&self, serializer: &mut __S) -> ::core::result::Result<Option<usize>, __S::Error> {
+                    match self {
+                        #(#serialize_arms,)*
+                    }
+                }
+            }
+        })
+    } else {
+        None
+    };
+
     Ok(quote! {
         #[automatically_derived]
         const _: () = {
             use #rkyv_path::{Archive, Fallible, Serialize};
             #serialize_impl
+            #serialize_variants_impl
         };
     })
 }
@@ -1,5 +1,8 @@
 use proc_macro2::Ident;
-use syn::{punctuated::Punctuated, Error, LitStr, Token, WhereClause, WherePredicate};
+use syn::{
+    punctuated::Punctuated, Data, Error, Field, Fields, LitStr, Meta, NestedMeta, Path, Token,
+    Variant, WhereClause, WherePredicate,
+};
 
 pub fn add_bounds(bounds: &LitStr, where_clause: &mut WhereClause) -> Result<(), Error> {
     let clauses = bounds.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
@@ -16,3 +19,168 @@ pub fn strip_raw(ident: &Ident) -> String {
         .map(ToString::to_string)
         .unwrap_or(as_string)
 }
+
+/// Gets the variants of an enum with `#[archive(boxed_variants)]` along with their payload fields.
+pub fn boxed_variants<'a>(
+    data: &'a Data,
+    path: &Path,
+) -> Result<Vec<(&'a Variant, Option<&'a Field>)>, Error> {
+    let data = match data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                path,
+                "boxed_variants may only be used on enums",
+            ))
+        }
+    };
+
+    data.variants
+        .iter()
+        .map(|variant| match variant.fields {
+            Fields::Unit => Ok((variant, None)),
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                Ok((variant, fields.unnamed.first()))
+            }
+            _ => Err(Error::new_spanned(
+                variant,
+                "boxed_variants may only be used on enums whose variants are unit variants or have a single unnamed field",
+            )),
+        })
+        .collect()
+}
+
+/// The variants of an enum with `#[archive(niche)]`, along with the field whose niche stores the
+/// unit variant.
+pub struct NicheVariants<'a> {
+    pub unit: &'a Variant,
+    pub dataful: &'a Variant,
+    pub niche: &'a Field,
+}
+
+/// Returns whether a field is marked with `#[archive(niche)]`.
+fn is_niche_field(field: &Field) -> Result<bool, Error> {
+    let mut result = false;
+    for attr in field.attrs.iter().filter(|a| a.path.is_ident("archive")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "archive may only be a structured list attribute",
+                ))
+            }
+        };
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("niche") => {
+                    if result {
+                        return Err(Error::new_spanned(path, "niche already specified"));
+                    }
+                    result = true;
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "unrecognized archive argument for field",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Gets the variants of an enum with `#[archive(niche)]` along with its niche field.
+///
+/// The enum must have one unit variant and one variant with named fields or a single unnamed field.
+/// If the variant with fields has more than one field, the niche field must be marked with
+/// `#[archive(niche)]`.
+pub fn niche_variants<'a>(data: &'a Data, path: &Path) -> Result<NicheVariants<'a>, Error> {
+    let data = match data {
+        Data::Enum(data) => data,
+        _ => return Err(Error::new_spanned(path, "niche may only be used on enums")),
+    };
+
+    let mut unit = None;
+    let mut dataful = None;
+    for variant in data.variants.iter() {
+        match variant.fields {
+            Fields::Unit if unit.is_none() => unit = Some(variant),
+            Fields::Named(_) | Fields::Unnamed(_) if dataful.is_none() => dataful = Some(variant),
+            _ => return Err(Error::new_spanned(
+                variant,
+                "niche may only be used on enums with one unit variant and one variant with fields",
+            )),
+        }
+    }
+    let (unit, dataful) =
+        match (unit, dataful) {
+            (Some(unit), Some(dataful)) => (unit, dataful),
+            _ => return Err(Error::new_spanned(
+                path,
+                "niche may only be used on enums with one unit variant and one variant with fields",
+            )),
+        };
+
+    let fields = match dataful.fields {
+        Fields::Named(ref fields) => &fields.named,
+        Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => &fields.unnamed,
+        _ => {
+            return Err(Error::new_spanned(
+                dataful,
+                "niche only supports variants with named fields or a single unnamed field",
+            ))
+        }
+    };
+
+    let mut niche = None;
+    for field in fields.iter() {
+        if is_niche_field(field)? {
+            if niche.is_some() {
+                return Err(Error::new_spanned(
+                    field,
+                    "only one field may be marked with #[archive(niche)]",
+                ));
+            }
+            niche = Some(field);
+        }
+    }
+    let niche = match niche {
+        Some(niche) => niche,
+        None if fields.len() == 1 => fields.first().unwrap(),
+        None => {
+            return Err(Error::new_spanned(
+                dataful,
+                "mark the field whose niche stores the unit variant with #[archive(niche)]",
+            ))
+        }
+    };
+
+    Ok(NicheVariants {
+        unit,
+        dataful,
+        niche,
+    })
+}
+
+/// Converts a variant name like `HttpError` to snake case like `http_error`.
+pub fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut result = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_lower = i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_numeric());
+            let before_lower = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_lower || before_lower {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
@@ -1,8 +1,16 @@
-use syn::{parse_quote, punctuated::Punctuated, token::Comma, Error, Expr, Field, Path, Type};
+use syn::{
+    parse_quote, punctuated::Punctuated, token::Comma, Error, Expr, Field, Path, Type,
+    TypeReference,
+};
 
 #[inline]
-pub fn with<B, F: FnMut(B, &Type) -> B>(field: &Field, init: B, f: F) -> Result<B, Error> {
-    let fields = field
+pub fn with<B, F: FnMut(B, &Type) -> B>(
+    field: &Field,
+    rkyv_path: &Path,
+    init: B,
+    f: F,
+) -> Result<B, Error> {
+    let mut fields = field
         .attrs
         .iter()
         .filter_map(|attr| {
@@ -13,14 +21,53 @@ pub fn with<B, F: FnMut(B, &Type) -> B>(field: &Field, init: B, f: F) -> Result<
             }
         })
         .collect::<Result<Vec<_>, _>>()?;
+    if implicit_reference(field).is_some() {
+        let mut wrappers = Punctuated::new();
+        if matches!(field.ty, Type::Array(_)) {
+            wrappers.push(parse_quote! { #rkyv_path::with::Map<#rkyv_path::with::AsOwned> });
+        } else {
+            wrappers.push(parse_quote! { #rkyv_path::with::AsOwned });
+        }
+        fields.push(wrappers);
+    }
     Ok(fields.iter().flatten().rev().fold(init, f))
 }
 
+/// Returns the reference archived by a field without any wrappers that is a shared reference or an
+/// array of shared references. These are archived as if they were owned with `AsOwned`, and arrays
+/// of them with `Map<AsOwned>`.
+#[inline]
+pub fn implicit_reference(field: &Field) -> Option<&TypeReference> {
+    if field.attrs.iter().any(|a| a.path.is_ident("with")) {
+        return None;
+    }
+    let ty = match field.ty {
+        Type::Array(ref array) => &*array.elem,
+        ref ty => ty,
+    };
+    match ty {
+        Type::Reference(reference) if reference.mutability.is_none() => Some(reference),
+        _ => None,
+    }
+}
+
+/// Returns the type that must be archivable to archive an implicit `AsOwned` reference field, if
+/// any. References to slices archive their elements, and references to strings don't need one.
+#[inline]
+pub fn as_owned_referent(field: &Field) -> Option<&Type> {
+    match *implicit_reference(field)?.elem {
+        Type::Path(ref path) if path.qself.is_none() && path.path.is_ident("str") => None,
+        Type::Slice(ref slice) => Some(&slice.elem),
+        ref elem => Some(elem),
+    }
+}
+
 #[inline]
 pub fn make_with_ty(rkyv_path: &Path) -> impl '_ + Fn(&Field) -> Result<Type, Error> {
     move |field| {
         with(
             field,
+            rkyv_path,
             field.ty.clone(),
             |ty, wrapper| parse_quote! { #rkyv_path::with::With<#ty, #wrapper> },
         )
@@ -32,6 +79,7 @@ pub fn make_with_cast(rkyv_path: &Path) -> impl '_ + Fn(&Field, Expr) -> Result<
     move |field, expr| {
         with(
             field,
+            rkyv_path,
             expr,
             |expr, wrapper| parse_quote! { #rkyv_path::with::With::<_, #wrapper>::cast(#expr) },
         )
@@ -39,6 +87,11 @@ pub fn make_with_cast(rkyv_path: &Path) -> impl '_ + Fn(&Field, Expr) -> Result<
 }
 
 #[inline]
-pub fn with_inner(field: &Field, expr: Expr) -> Result<Expr, Error> {
-    with(field, expr, |expr, _| parse_quote! { #expr.into_inner() })
+pub fn with_inner(field: &Field, rkyv_path: &Path, expr: Expr) -> Result<Expr, Error> {
+    with(
+        field,
+        rkyv_path,
+        expr,
+        |expr, _| parse_quote! { #expr.into_inner() },
+    )
 }
@@ -50,6 +50,47 @@ mod tests {
         test_archive(&Some(42));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_without_alloc() {
+        use core::num::NonZeroU32;
+        use rkyv::{Archive, Deserialize, Serialize};
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Reading {
+            id: u16,
+            value: Option<f32>,
+            flags: [bool; 3],
+        }
+
+        test_archive(&Some(42u8));
+        test_archive(&Option::<u64>::None);
+        test_archive(&Some(Some('x')));
+        test_archive(&NonZeroU32::new(7));
+        test_archive(&[Some(1i32), None, Some(3)]);
+        #[cfg(not(any(feature = "strict", feature = "archive_le", feature = "archive_be")))]
+        test_archive(&(1u8, 'a', [3u32; 2]));
+        test_archive(&Reading {
+            id: 1,
+            value: Some(23.5),
+            flags: [true, false, true],
+        });
+        test_archive(&[
+            Reading {
+                id: 2,
+                value: None,
+                flags: [false; 3],
+            },
+            Reading {
+                id: 3,
+                value: Some(-1.0),
+                flags: [true; 3],
+            },
+        ]);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_refs() {
@@ -100,6 +141,97 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_num_wrappers() {
+        use core::num::{Saturating, Wrapping};
+        use rkyv::{
+            archived_root, num::ArchivedInteger, ser::Serializer, Archive, Deserialize, Infallible,
+            Serialize,
+        };
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        struct Counters {
+            hits: Wrapping<u32>,
+            misses: Saturating<i16>,
+            total: u64,
+        }
+
+        let value = Counters {
+            hits: Wrapping(u32::MAX),
+            misses: Saturating(i16::MIN),
+            total: 12345678901234567890,
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let len = serializer.pos();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Counters>(&buf[0..len]) };
+
+        assert_eq!(archived.hits.value(), u32::MAX);
+        assert_eq!(archived.misses.value(), i16::MIN);
+        assert_eq!(archived.total.value(), 12345678901234567890);
+
+        assert_eq!(archived.hits.checked_add(1), None);
+        assert_eq!(archived.hits.wrapping_add(1), 0);
+        assert_eq!(archived.hits.saturating_add(1), u32::MAX);
+        assert_eq!(archived.misses.checked_sub(1), None);
+        assert_eq!(archived.misses.wrapping_sub(1), i16::MAX);
+        assert_eq!(archived.misses.saturating_mul(2), i16::MIN);
+        assert_eq!(archived.total.checked_mul(2), None);
+        assert_eq!(archived.total.checked_sub(890), Some(12345678901234567000));
+
+        let deserialized: Counters = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn try_archived_root_alignment() {
+        use core::mem::align_of;
+        use rkyv::{
+            archived_alignment, ser::Serializer, try_archived_root, AccessRootError, AlignedBytes,
+            Archive, Archived,
+        };
+
+        #[derive(Archive)]
+        struct Padded {
+            a: u8,
+            b: u64,
+        }
+
+        const ALIGN: usize = archived_alignment::<Padded>();
+        assert_eq!(ALIGN, align_of::<Archived<u64>>());
+        assert_eq!(archived_alignment::<[u8; 3]>(), 1);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&0x12345678u32).unwrap();
+        let len = serializer.pos();
+        let buf = serializer.into_serializer().into_inner();
+        let bytes = &buf[0..len];
+
+        let archived = unsafe { try_archived_root::<u32>(bytes).unwrap() };
+        assert_eq!(*archived, 0x12345678);
+
+        let mut misaligned = AlignedBytes([0u8; 16]);
+        misaligned[2..2 + len].copy_from_slice(bytes);
+        let result = unsafe { try_archived_root::<u32>(&misaligned[2..2 + len]) };
+        assert!(matches!(
+            result,
+            Err(AccessRootError::MisalignedBuffer {
+                expected_align: 4,
+                actual_align: 2,
+            })
+        ));
+
+        let result = unsafe { try_archived_root::<u32>(&bytes[..2]) };
+        assert!(matches!(
+            result,
+            Err(AccessRootError::BufferTooSmall { len: 2, size: 4 })
+        ));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_zst() {
@@ -116,6 +248,253 @@ mod tests {
         test_archive_ref::<[MyZST]>(&[MyZST, MyZST, MyZST, MyZST]);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_optimize_layout() {
+        use core::mem::{offset_of, size_of};
+        use rkyv::{Archive, Deserialize, Serialize};
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug), repr(C))]
+        struct Naive {
+            a: u8,
+            b: u64,
+            c: u8,
+            d: [u16; 2],
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq), optimize_layout)]
+        #[archive_attr(derive(Debug))]
+        struct Optimized {
+            a: u8,
+            b: u64,
+            c: u8,
+            d: [u16; 2],
+        }
+
+        assert_eq!(size_of::<ArchivedNaive>(), 24);
+        assert_eq!(size_of::<ArchivedOptimized>(), 16);
+        assert_eq!(offset_of!(ArchivedOptimized, b), 0);
+        assert_eq!(offset_of!(ArchivedOptimized, d), 8);
+        assert_eq!(offset_of!(ArchivedOptimized, a), 12);
+        assert_eq!(offset_of!(ArchivedOptimized, c), 13);
+
+        test_archive(&Naive {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: [4, 5],
+        });
+        test_archive(&Optimized {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: [4, 5],
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_field_offsets() {
+        use core::mem::offset_of;
+        use rkyv::Archive;
+
+        #[derive(Archive)]
+        #[allow(dead_code)]
+        #[archive(field_offsets)]
+        struct Named {
+            a: u8,
+            b: u64,
+            r#type: [u16; 2],
+        }
+
+        #[derive(Archive)]
+        #[allow(dead_code)]
+        #[archive(field_offsets, optimize_layout)]
+        struct Optimized {
+            a: u8,
+            b: u64,
+            c: u16,
+        }
+
+        #[derive(Archive)]
+        #[allow(dead_code)]
+        #[archive(field_offsets)]
+        struct Tuple<T>(u16, T);
+
+        #[derive(Archive)]
+        #[allow(dead_code)]
+        #[archive(field_offsets)]
+        struct Unit;
+
+        assert_eq!(
+            ArchivedNamed::FIELD_OFFSETS,
+            &[
+                ("a", offset_of!(ArchivedNamed, a)),
+                ("b", offset_of!(ArchivedNamed, b)),
+                ("type", offset_of!(ArchivedNamed, r#type)),
+            ],
+        );
+        // Offsets are listed in declaration order even when fields are reordered
+        assert_eq!(offset_of!(ArchivedOptimized, b), 0);
+        assert_eq!(
+            ArchivedOptimized::FIELD_OFFSETS,
+            &[
+                ("a", offset_of!(ArchivedOptimized, a)),
+                ("b", offset_of!(ArchivedOptimized, b)),
+                ("c", offset_of!(ArchivedOptimized, c)),
+            ],
+        );
+        assert_eq!(
+            ArchivedTuple::<u32>::FIELD_OFFSETS,
+            &[
+                ("0", offset_of!(ArchivedTuple<u32>, 0)),
+                ("1", offset_of!(ArchivedTuple<u32>, 1)),
+            ],
+        );
+        assert!(ArchivedUnit::FIELD_OFFSETS.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_default() {
+        use rkyv::{Archive, Deserialize, Infallible, Serialize};
+
+        #[derive(Archive, Deserialize, Serialize, Debug, Default, PartialEq)]
+        #[archive(compare(PartialEq), default)]
+        #[archive_attr(derive(Debug))]
+        struct Inner {
+            a: u16,
+            b: [bool; 2],
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, Default, PartialEq)]
+        #[archive(compare(PartialEq), default)]
+        #[archive_attr(derive(Debug))]
+        struct Named {
+            a: u32,
+            b: i8,
+            c: char,
+            inner: Inner,
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, Default, PartialEq)]
+        #[archive(compare(PartialEq), default)]
+        #[archive_attr(derive(Debug))]
+        struct Tuple(u64, Inner);
+
+        #[derive(Archive, Deserialize, Serialize, Debug, Default, PartialEq)]
+        #[archive(compare(PartialEq), default)]
+        #[archive_attr(derive(Debug))]
+        struct Unit;
+
+        fn round_trip<T>()
+        where
+            T: Archive + Default + PartialEq + core::fmt::Debug,
+            T::Archived: Default + PartialEq<T> + core::fmt::Debug + Deserialize<T, Infallible>,
+        {
+            let archived = T::Archived::default();
+            assert_eq!(archived, T::default());
+            let deserialized: T = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, T::default());
+        }
+
+        round_trip::<Named>();
+        round_trip::<Tuple>();
+        round_trip::<Unit>();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_deserialize_try_into() {
+        use core::convert::TryFrom;
+        use rkyv::{
+            ser::{serializers::BufferSerializer, Serializer},
+            AlignedBytes, Archive, Deserialize, Fallible, Serialize,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(deserialize_try_into = "Shape")]
+        enum RawShape {
+            Circle { radius: f32 },
+            Rect { width: f32, height: f32 },
+        }
+
+        #[derive(Debug, PartialEq)]
+        enum Shape {
+            Circle(f32),
+            Square(f32),
+            Rect(f32, f32),
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct InvalidShape;
+
+        impl TryFrom<&ArchivedRawShape> for Shape {
+            type Error = InvalidShape;
+
+            fn try_from(raw: &ArchivedRawShape) -> Result<Self, Self::Error> {
+                match *raw {
+                    ArchivedRawShape::Circle { radius } if radius > 0.0 => {
+                        Ok(Shape::Circle(radius))
+                    }
+                    ArchivedRawShape::Rect { width, height } if width > 0.0 && height > 0.0 => {
+                        if width == height {
+                            Ok(Shape::Square(width))
+                        } else {
+                            Ok(Shape::Rect(width, height))
+                        }
+                    }
+                    _ => Err(InvalidShape),
+                }
+            }
+        }
+
+        struct ShapeDeserializer;
+
+        impl Fallible for ShapeDeserializer {
+            type Error = InvalidShape;
+        }
+
+        fn deserialize<T>(value: &RawShape) -> Result<T, InvalidShape>
+        where
+            ArchivedRawShape: Deserialize<T, ShapeDeserializer>,
+        {
+            let mut serializer = BufferSerializer::new(AlignedBytes([0u8; 64]));
+            let pos = serializer.serialize_value(value).unwrap();
+            let buf = serializer.into_inner();
+            let archived = unsafe { rkyv::archived_value::<RawShape>(buf.as_ref(), pos) };
+            archived.deserialize(&mut ShapeDeserializer)
+        }
+
+        let square = RawShape::Rect {
+            width: 2.0,
+            height: 2.0,
+        };
+        assert_eq!(deserialize::<Shape>(&square), Ok(Shape::Square(2.0)));
+        assert_eq!(
+            deserialize::<Shape>(&RawShape::Circle { radius: 1.5 }),
+            Ok(Shape::Circle(1.5)),
+        );
+        // The regular deserialize impl is still derived
+        assert_eq!(deserialize::<RawShape>(&square), Ok(square));
+
+        // The conversion rejects invalid combinations of fields
+        assert_eq!(
+            deserialize::<Shape>(&RawShape::Circle { radius: -1.0 }),
+            Err(InvalidShape),
+        );
+        assert_eq!(
+            deserialize::<Shape>(&RawShape::Rect {
+                width: 0.0,
+                height: 3.0,
+            }),
+            Err(InvalidShape),
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     #[allow(non_camel_case_types)]
@@ -139,4 +518,22 @@ mod tests {
         test_archive::<r#virtual>(&r#virtual { r#virtual: 42 });
         test_archive::<r#try>(&r#try::r#try { r#try: 42 });
     }
+
+    #[cfg(feature = "core_net")]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_core_net() {
+        use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+        test_archive(&Ipv4Addr::new(31, 41, 59, 26));
+        test_archive(&Ipv6Addr::new(31, 41, 59, 26, 53, 58, 97, 93));
+        test_archive(&IpAddr::V4(Ipv4Addr::new(31, 41, 59, 26)));
+        test_archive(&SocketAddrV4::new(Ipv4Addr::new(31, 41, 59, 26), 5358));
+        test_archive(&SocketAddr::V6(SocketAddrV6::new(
+            Ipv6Addr::new(31, 31, 59, 26, 53, 58, 97, 93),
+            2384,
+            0,
+            0,
+        )));
+    }
 }
@@ -45,6 +45,365 @@ mod tests {
         test_archive_container(&vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_string_as_bytes() {
+        for value in ["", "hi", "a string long enough to be stored out of line"] {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value.to_string()).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<String>(buf.as_ref()) };
+
+            assert_eq!(archived.as_bytes(), value.as_bytes());
+            assert_eq!(archived.as_str(), value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_128_bit_integers() {
+        #[cfg(not(any(feature = "archive_le", feature = "archive_be")))]
+        {
+            assert_eq!(
+                core::mem::align_of::<Archived<u128>>(),
+                core::mem::align_of::<u128>()
+            );
+            assert_eq!(
+                core::mem::align_of::<Archived<i128>>(),
+                core::mem::align_of::<i128>()
+            );
+        }
+
+        for value in [i128::MIN, -1, 0, 1, i128::MAX] {
+            test_archive(&value);
+        }
+        for value in [u128::MIN, 1, u128::MAX / 2, u128::MAX] {
+            test_archive(&value);
+        }
+
+        test_archive(&vec![i128::MIN, 0, i128::MAX]);
+        test_archive(&vec![u128::MIN, u128::MAX]);
+
+        let mut map = BTreeMap::new();
+        map.insert(u128::MIN, i128::MAX);
+        map.insert(u128::MAX, i128::MIN);
+        map.insert(1 << 100, 0);
+        test_archive(&map);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn btree_map_mutable_values() {
+        let map = (0..100u32)
+            .map(|i| (i.to_string(), i))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&map).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived_value =
+            unsafe { archived_root_mut::<BTreeMap<String, u32>>(Pin::new(buf.as_mut_slice())) };
+
+        for mut value in archived_value.as_mut().values_pin() {
+            *value += 1;
+        }
+        *archived_value.as_mut().get_pin("42").unwrap() += 100;
+        assert!(archived_value.as_mut().get_pin("missing").is_none());
+
+        assert_eq!(archived_value.len(), map.len());
+        for (key, value) in map.iter() {
+            let expected = if key == "42" { value + 101 } else { value + 1 };
+            assert_eq!(archived_value.get(key.as_str()), Some(&expected));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_max_scratch() {
+        use core::mem;
+        use rkyv::{
+            ser::max_scratch::{slice_max_scratch, MaxScratch},
+            string::StringResolver,
+        };
+
+        #[derive(Archive, Serialize)]
+        struct Chunk(u32);
+
+        impl MaxScratch for Chunk {
+            const MAX_SCRATCH: usize = 8;
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(max_scratch)]
+        struct Tag(u8, char);
+
+        #[derive(Archive, Serialize)]
+        #[archive(max_scratch)]
+        struct Static {
+            a: u32,
+            b: String,
+            c: [Option<Box<str>>; 4],
+            d: Tag,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(max_scratch)]
+        struct Pair<T> {
+            a: T,
+            b: Box<T>,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(max_scratch)]
+        #[allow(dead_code)]
+        enum Either {
+            Left(Chunk),
+            Right(Pair<Chunk>, u32),
+            Neither,
+        }
+
+        assert_eq!(Static::MAX_SCRATCH, 0);
+        #[cfg(not(feature = "strict"))]
+        assert_eq!(<(u8, char)>::MAX_SCRATCH, 0);
+        assert_eq!(Pair::<u32>::MAX_SCRATCH, 0);
+        assert_eq!(Pair::<Chunk>::MAX_SCRATCH, 16);
+        assert_eq!(Either::MAX_SCRATCH, 24);
+
+        assert_eq!(slice_max_scratch::<u32>(16), 0);
+        assert_eq!(
+            slice_max_scratch::<String>(4),
+            4 * mem::size_of::<StringResolver>() + mem::align_of::<StringResolver>() - 1,
+        );
+        assert_eq!(
+            slice_max_scratch::<Pair<Chunk>>(0),
+            Pair::<Chunk>::MAX_SCRATCH
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_variant_accessors() {
+        use rkyv::variants::ArchivedVariant;
+
+        #[derive(Archive, Serialize)]
+        #[archive(variant_accessors)]
+        enum Event<T> {
+            #[archive(tag = 9)]
+            Started,
+            #[archive(tag = 4)]
+            Message(T),
+            // The small first field makes sure the fields are laid out after the tag
+            #[archive(tag = 1)]
+            HTTPError {
+                retry: bool,
+                code: u64,
+                body: String,
+            },
+        }
+
+        fn first<V: ArchivedVariant<ArchivedEvent<String>>>(
+            events: &[ArchivedEvent<String>],
+        ) -> Option<usize> {
+            events
+                .iter()
+                .position(|event| event.as_variant::<V>().is_some())
+        }
+
+        let value = vec![
+            Event::Message("hello".to_string()),
+            Event::HTTPError {
+                retry: true,
+                code: 503,
+                body: "service unavailable".to_string(),
+            },
+            Event::Started,
+            Event::Message("world".to_string()),
+        ];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Event<String>>>(buf.as_ref()) };
+
+        assert_eq!(archived[0].as_message().unwrap(), "hello");
+        assert!(archived[0].as_http_error().is_none());
+        assert!(archived[0].as_started().is_none());
+
+        let error = archived[1].as_http_error().unwrap();
+        assert!(error.retry);
+        assert_eq!(error.code, 503);
+        assert_eq!(error.body, "service unavailable");
+        assert!(archived[1].as_message().is_none());
+
+        assert_eq!(archived[2].as_started(), Some(&()));
+        assert_eq!(
+            archived[3]
+                .as_variant::<ArchivedEventMessage>()
+                .map(|m| m.as_str()),
+            Some("world")
+        );
+
+        assert_eq!(first::<ArchivedEventMessage>(archived), Some(0));
+        assert_eq!(first::<ArchivedEventHTTPError<String>>(archived), Some(1));
+        assert_eq!(first::<ArchivedEventStarted>(archived), Some(2));
+        assert_eq!(first::<ArchivedEventStarted>(&archived[..2]), None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_niche() {
+        use core::{mem::size_of, num::NonZeroU32};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(niche)]
+        enum Handle {
+            Closed,
+            Open {
+                name: String,
+                #[archive(niche)]
+                id: NonZeroU32,
+            },
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        enum TaggedHandle {
+            Closed,
+            Open { name: String, id: NonZeroU32 },
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(niche)]
+        enum Flag {
+            Set(bool),
+            Unset,
+        }
+
+        // The tag is elided
+        assert_eq!(size_of::<ArchivedHandle>(), size_of::<ArchivedHandleOpen>());
+        assert!(size_of::<ArchivedHandle>() < size_of::<ArchivedTaggedHandle>());
+        assert_eq!(size_of::<ArchivedFlag>(), 1);
+
+        for value in [
+            Handle::Closed,
+            Handle::Open {
+                name: "a string long enough to be stored out of line".to_string(),
+                id: NonZeroU32::new(42).unwrap(),
+            },
+        ] {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Handle>(buf.as_ref()) };
+
+            match value {
+                Handle::Closed => {
+                    assert!(archived.is_closed());
+                    assert!(!archived.is_open());
+                    assert!(archived.as_open().is_none());
+                }
+                Handle::Open { ref name, id } => {
+                    assert!(archived.is_open());
+                    let open = archived.as_open().unwrap();
+                    assert_eq!(open.name, *name);
+                    assert_eq!(open.id, id);
+                }
+            }
+
+            let deserialized: Handle = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, value);
+        }
+
+        for value in [Flag::Set(false), Flag::Set(true), Flag::Unset] {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Flag>(buf.as_ref()) };
+
+            assert_eq!(archived.is_unset(), value == Flag::Unset);
+            assert_eq!(
+                archived.as_set().copied(),
+                match value {
+                    Flag::Set(value) => Some(value),
+                    Flag::Unset => None,
+                }
+            );
+            let deserialized: Flag = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_partial_eq() {
+        // Only the archived type can be compared
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(PartialEq))]
+        struct Opaque(u32);
+
+        #[derive(Archive, Serialize)]
+        #[archive(partial_eq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(partial_eq)]
+        enum Shape {
+            Circle { radius: u32 },
+            Polygon(Vec<Point>),
+            Empty,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(partial_eq)]
+        struct Drawing<T> {
+            name: String,
+            shapes: Vec<Shape>,
+            owner: Option<Box<T>>,
+        }
+
+        fn drawing(name: &str, corner: i32, owner: u32) -> Drawing<Opaque> {
+            Drawing {
+                name: name.to_string(),
+                shapes: vec![
+                    Shape::Circle { radius: 3 },
+                    Shape::Polygon(vec![
+                        Point { x: 0, y: 0 },
+                        Point { x: corner, y: 0 },
+                        Point { x: 0, y: corner },
+                    ]),
+                    Shape::Empty,
+                ],
+                owner: Some(Box::new(Opaque(owner))),
+            }
+        }
+
+        fn to_bytes(padding: &str, drawing: &Drawing<Opaque>) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            // Serialize the drawings at different positions so their relative pointers differ
+            serializer.serialize_value(&padding.to_string()).unwrap();
+            serializer.serialize_value(drawing).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let name = "a drawing with a name that doesn't fit inline";
+        let a = to_bytes("", &drawing(name, 4, 1));
+        let b = to_bytes("some padding that isn't inline", &drawing(name, 4, 1));
+        let c = to_bytes("", &drawing(name, 5, 1));
+        let d = to_bytes("", &drawing(name, 4, 2));
+        let a = unsafe { archived_root::<Drawing<Opaque>>(a.as_ref()) };
+        let b = unsafe { archived_root::<Drawing<Opaque>>(b.as_ref()) };
+        let c = unsafe { archived_root::<Drawing<Opaque>>(c.as_ref()) };
+        let d = unsafe { archived_root::<Drawing<Opaque>>(d.as_ref()) };
+
+        assert!(a == b);
+        assert!(a != c);
+        assert!(a != d);
+        assert!(a.shapes[0] == b.shapes[0]);
+        assert!(a.shapes[0] != a.shapes[2]);
+        assert!(a.shapes[1] != c.shapes[1]);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_option() {
@@ -56,6 +415,49 @@ mod tests {
         test_archive(&Some(Box::new(vec![1, 2, 3, 4])));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_option_combinators() {
+        #[derive(Archive, Serialize)]
+        struct Inner {
+            name: Option<String>,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: Option<u32>,
+            b: Option<String>,
+            c: Option<u32>,
+            inner: Option<Inner>,
+            empty: Option<Inner>,
+        }
+
+        let value = Test {
+            a: Some(42),
+            b: Some("hello world".to_string()),
+            c: None,
+            inner: Some(Inner {
+                name: Some("inner".to_string()),
+            }),
+            empty: None,
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        let (a, b) = archived.a.zip(&archived.b).unwrap();
+        assert_eq!(*a, 42);
+        assert_eq!(b, "hello world");
+        assert!(archived.a.zip(&archived.c).is_none());
+        assert!(archived.c.zip(&archived.b).is_none());
+
+        let name = archived.inner.and_then(|inner| &inner.name).unwrap();
+        assert_eq!(name, "inner");
+        assert!(archived.empty.and_then(|inner| &inner.name).is_none());
+    }
+
     #[test]
     fn option_is_copy() {
         #[derive(Clone, Copy, Debug, PartialEq, Archive, Serialize, Deserialize)]
@@ -316,6 +718,108 @@ mod tests {
         ]);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_enum_pinned_tags() {
+        mod v1 {
+            #[cfg(not(feature = "std"))]
+            use alloc::string::String;
+            use rkyv::{Archive, Deserialize, Serialize};
+
+            #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+            #[archive(compare(PartialEq))]
+            #[archive_attr(derive(Debug))]
+            pub enum Message {
+                #[archive(tag = 3)]
+                Ping,
+                #[archive(tag = 7)]
+                Text(String),
+                #[archive(tag = 200)]
+                Move { x: i32, y: i32 },
+            }
+
+            #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+            pub enum Wide {
+                #[archive(tag = 1000)]
+                A(u32),
+                #[archive(tag = 0)]
+                B,
+            }
+        }
+
+        mod v2 {
+            #[cfg(not(feature = "std"))]
+            use alloc::string::String;
+            use rkyv::{Archive, Deserialize, Serialize};
+
+            #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+            #[archive(compare(PartialEq))]
+            #[archive_attr(derive(Debug))]
+            pub enum Message {
+                #[archive(tag = 200)]
+                Move { x: i32, y: i32 },
+                #[archive(tag = 3)]
+                Ping,
+                #[archive(tag = 7)]
+                Text(String),
+            }
+
+            #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+            pub enum Wide {
+                #[archive(tag = 0)]
+                B,
+                #[archive(tag = 1000)]
+                A(u32),
+            }
+        }
+
+        fn archive<T: Serialize<DefaultSerializer>>(value: &T) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(value).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let pairs = [
+            (v1::Message::Ping, v2::Message::Ping, 3),
+            (
+                v1::Message::Text("hello world".to_string()),
+                v2::Message::Text("hello world".to_string()),
+                7,
+            ),
+            (
+                v1::Message::Move { x: -1, y: 2 },
+                v2::Message::Move { x: -1, y: 2 },
+                200,
+            ),
+        ];
+        for (old, new, tag) in pairs.iter() {
+            let buf = archive(old);
+            assert_eq!(buf.as_slice(), archive(new).as_slice());
+
+            let archived = unsafe { archived_root::<v2::Message>(buf.as_ref()) };
+            assert_eq!(archived, new);
+            let tag_ptr = archived as *const v2::ArchivedMessage as *const u8;
+            assert_eq!(unsafe { *tag_ptr }, *tag);
+
+            let deserialized: v2::Message = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(&deserialized, new);
+        }
+
+        assert_eq!(
+            core::mem::size_of::<Archived<v1::Wide>>(),
+            core::mem::size_of::<Archived<v2::Wide>>(),
+        );
+        let buf = archive(&v1::Wide::A(42));
+        assert_eq!(buf.as_slice(), archive(&v2::Wide::A(42)).as_slice());
+        let archived = unsafe { archived_root::<v2::Wide>(buf.as_ref()) };
+        let deserialized: v2::Wide = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, v2::Wide::A(42));
+        assert_eq!(
+            archive(&v1::Wide::B).as_slice(),
+            archive(&v2::Wide::B).as_slice()
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_generic_enum() {
@@ -483,75 +987,379 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn derive_visibility() {
-        mod inner {
-            #[derive(super::Archive, super::Serialize)]
-            pub struct TestTuple(pub i32);
-
-            #[derive(super::Archive, super::Serialize)]
-            pub struct TestStruct {
-                pub value: i32,
-            }
-
-            #[derive(super::Archive, super::Serialize)]
-            pub enum TestEnum {
-                B(i32),
-                C { value: i32 },
-            }
+    fn aligned_vec_reserve_aligned() {
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: u64,
+            b: String,
         }
 
-        use inner::{
-            ArchivedTestEnum, ArchivedTestStruct, ArchivedTestTuple, TestEnum, TestStruct,
-            TestTuple,
+        let value = Test {
+            a: 42,
+            b: "a string long enough to be stored out of line".to_string(),
         };
 
-        TestTuple(42.into());
-        ArchivedTestTuple(42.into());
-        TestStruct { value: 42.into() };
-        ArchivedTestStruct { value: 42.into() };
-        TestEnum::B(42.into());
-        TestEnum::C { value: 42.into() };
-        ArchivedTestEnum::B(42.into());
-        ArchivedTestEnum::C { value: 42.into() };
+        // Measure the archive
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let len = serializer.into_inner().len();
+
+        // Reuse a buffer that already has a byte in it
+        let mut buf = AlignedVec::new();
+        buf.push(0xff);
+        buf.reserve_exact_aligned(len, 16);
+        assert_eq!(buf.capacity(), 16 + len);
+        let ptr = buf.as_ptr();
+
+        let mut serializer = AlignedSerializer::new(&mut buf);
+        serializer.pad(15).unwrap();
+        serializer.serialize_value(&value).unwrap();
+        assert_eq!(buf.len(), 16 + len);
+        assert_eq!(buf.as_ptr(), ptr);
+        assert_eq!(buf.capacity(), 16 + len);
+
+        let archived = unsafe { archived_root::<Test>(&buf[16..]) };
+        assert_eq!(archived.a, 42);
+        assert_eq!(archived.b, value.b);
+
+        // Reserving again with enough capacity doesn't reallocate
+        buf.clear();
+        buf.reserve_aligned(len, 8);
+        assert_eq!(buf.as_ptr(), ptr);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn basic_mutable_refs() {
-        let mut serializer = AlignedSerializer::new(AlignedVec::new());
-        serializer.serialize_value(&42i32).unwrap();
-        let mut buf = serializer.into_inner();
-        let mut value = unsafe { archived_root_mut::<i32>(Pin::new(buf.as_mut())) };
-        assert_eq!(*value, 42);
-        *value = 11.into();
-        assert_eq!(*value, 11);
+    fn aligned_vec_growth() {
+        use rkyv::{ser::serializers::AllocSerializer, AlignedVecGrowth};
+
+        let value = (0..2_000u32).collect::<Vec<_>>();
+
+        // Measure the archive
+        let len = rkyv::to_bytes::<_, 256>(&value).unwrap().len();
+
+        // An exact hint doesn't reallocate or over-allocate
+        let mut serializer = AllocSerializer::<256>::with_capacity(len);
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert_eq!(buf.len(), len);
+        assert_eq!(buf.capacity(), len);
+
+        // An underestimated hint grows by at most the bound
+        const MAX_STEP: usize = 1024;
+        let mut vec = AlignedVec::with_capacity(len / 2);
+        vec.set_growth(AlignedVecGrowth::Bounded(MAX_STEP));
+        let mut serializer = AllocSerializer::<256>::new(
+            AlignedSerializer::new(vec),
+            Default::default(),
+            Default::default(),
+        );
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert_eq!(buf.len(), len);
+        assert!(buf.capacity() >= len);
+        assert!(buf.capacity() < len + MAX_STEP);
+        assert_eq!(buf.growth(), AlignedVecGrowth::Bounded(MAX_STEP));
+
+        let archived = unsafe { archived_root::<Vec<u32>>(buf.as_ref()) };
+        assert_eq!(archived.as_slice(), value.as_slice());
+
+        // Doubling over-allocates by up to the length of the archive
+        let mut serializer = AllocSerializer::<256>::with_capacity(len / 2);
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert_eq!(buf.capacity(), len.next_power_of_two());
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn struct_mutable_refs() {
+    fn serialize_value_padded() {
+        use rkyv::ser::PadToError;
+
         #[derive(Archive, Serialize)]
         struct Test {
-            a: Box<i32>,
-            b: Vec<String>,
-        }
-
-        impl ArchivedTest {
-            fn a(self: Pin<&mut Self>) -> Pin<&mut Archived<Box<i32>>> {
-                unsafe { self.map_unchecked_mut(|s| &mut s.a) }
-            }
-
-            fn b(self: Pin<&mut Self>) -> Pin<&mut Archived<Vec<String>>> {
-                unsafe { self.map_unchecked_mut(|s| &mut s.b) }
-            }
+            a: u64,
+            b: String,
         }
 
         let value = Test {
-            a: Box::new(10),
-            b: vec!["hello".to_string(), "world".to_string()],
+            a: 42,
+            b: "a string long enough to be stored out of line".to_string(),
         };
 
-        let mut serializer = DefaultSerializer::default();
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let len = serializer.into_inner().len();
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        let pos = serializer.serialize_value_padded(&value, 256).unwrap();
+        let buf = serializer.into_inner();
+        assert_eq!(buf.len(), 256);
+        assert_eq!(pos, 256 - core::mem::size_of::<Archived<Test>>());
+
+        let archived = unsafe { archived_root::<Test>(&buf) };
+        assert_eq!(archived.a, 42);
+        assert_eq!(archived.b, value.b);
+
+        // Padding to the exact size of the archive doesn't add any padding
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value_padded(&value, len).unwrap();
+        assert_eq!(serializer.into_inner().len(), len);
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        assert!(matches!(
+            serializer.serialize_value_padded(&value, len - 8),
+            Err(PadToError::Overflow { size, total }) if size == len && total == len - 8,
+        ));
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        assert!(matches!(
+            serializer.serialize_value_padded(&value, 257),
+            Err(PadToError::Unaligned {
+                total: 257,
+                align: 8
+            }),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn offset_serializer() {
+        use rkyv::{archived_value, ser::serializers::OffsetSerializer};
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: u64,
+            b: String,
+        }
+
+        let value = Test {
+            a: 42,
+            b: "a string long enough to be stored out of line".to_string(),
+        };
+
+        let mut buf = AlignedVec::new();
+        buf.resize(128, 0xaa);
+
+        // Padding is computed from the start of the buffer, not the starting position
+        let mut serializer = OffsetSerializer::new(&mut buf, 3);
+        let pos = serializer.serialize_value(&value).unwrap();
+        let end = serializer.pos();
+        assert_eq!(pos % core::mem::align_of::<Archived<Test>>(), 0);
+        assert!(end <= 128);
+        assert_eq!(buf.len(), 128);
+        assert!(buf[..3].iter().all(|&b| b == 0xaa));
+        assert!(buf[end..].iter().all(|&b| b == 0xaa));
+
+        let archived = unsafe { archived_value::<Test>(buf.as_ref(), pos) };
+        assert_eq!(archived.a, 42);
+        assert_eq!(archived.b, value.b);
+
+        // Writing past the end of the buffer grows it, after padding it up to the starting position
+        let mut serializer = OffsetSerializer::new(&mut buf, 200);
+        let second = serializer.serialize_value(&value).unwrap();
+        assert_eq!(buf.len(), second + core::mem::size_of::<Archived<Test>>());
+        assert!(buf[128..200].iter().all(|&b| b == 0));
+
+        let archived = unsafe { archived_value::<Test>(buf.as_ref(), pos) };
+        assert_eq!(archived.b, value.b);
+        let archived = unsafe { archived_value::<Test>(buf.as_ref(), second) };
+        assert_eq!(archived.a, 42);
+        assert_eq!(archived.b, value.b);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn limited_serializer() {
+        use rkyv::ser::serializers::{
+            AllocScratch, CompositeSerializer, CompositeSerializerError, LimitedSerializer,
+            LimitedSerializerError,
+        };
+
+        fn limited(limit: usize) -> LimitedSerializer<AlignedSerializer<AlignedVec>> {
+            LimitedSerializer::new(AlignedSerializer::new(AlignedVec::new()), limit)
+        }
+
+        // Writing exactly up to the limit succeeds
+        let mut serializer = limited(16);
+        serializer.serialize_value(&[1u32, 2, 3, 4]).unwrap();
+        assert_eq!(serializer.bytes_written(), 16);
+        assert_eq!(serializer.into_inner().into_inner().len(), 16);
+
+        // One byte over the limit fails without writing anything
+        let mut serializer = limited(15);
+        match serializer.serialize_value(&[1u32, 2, 3, 4]) {
+            Err(LimitedSerializerError::SizeLimitExceeded { limit }) => assert_eq!(limit, 15),
+            _ => panic!("expected the size limit to be exceeded"),
+        }
+        assert_eq!(serializer.bytes_written(), 0);
+        assert_eq!(serializer.pos(), 0);
+
+        // Padding counts towards the limit
+        let mut serializer = limited(8);
+        serializer.write(&[0xff]).unwrap();
+        serializer.serialize_value(&42u32).unwrap();
+        assert_eq!(serializer.bytes_written(), 8);
+
+        let mut serializer = limited(7);
+        serializer.write(&[0xff]).unwrap();
+        assert!(matches!(
+            serializer.serialize_value(&42u32),
+            Err(LimitedSerializerError::SizeLimitExceeded { limit: 7 }),
+        ));
+        assert_eq!(serializer.bytes_written(), 4);
+
+        // Padding alone can push past the limit
+        let mut serializer = limited(3);
+        serializer.write(&[0xff]).unwrap();
+        assert!(matches!(
+            serializer.align_for::<u32>(),
+            Err(LimitedSerializerError::SizeLimitExceeded { limit: 3 }),
+        ));
+        assert_eq!(serializer.bytes_written(), 1);
+        assert_eq!(serializer.pos(), 1);
+
+        // The limit also applies when composed with scratch space
+        let value = vec![String::from("a string long enough to be stored out of line"); 16];
+        let mut serializer =
+            CompositeSerializer::new(limited(256), AllocScratch::default(), rkyv::Infallible);
+        assert!(matches!(
+            serializer.serialize_value(&value),
+            Err(CompositeSerializerError::SerializerError(
+                LimitedSerializerError::SizeLimitExceeded { limit: 256 }
+            )),
+        ));
+        assert!(serializer.into_serializer().bytes_written() <= 256);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn reinterpret_archived_layouts() {
+        use core::alloc::Layout;
+        use rkyv::{reinterpret_archived, LayoutMismatch};
+
+        #[derive(Archive, Serialize)]
+        struct V1 {
+            id: u32,
+            name: String,
+        }
+
+        #[derive(Archive)]
+        struct V2 {
+            key: u32,
+            label: String,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Halves {
+            low: u32,
+            high: u32,
+        }
+
+        #[derive(Archive)]
+        #[archive_attr(derive(Debug))]
+        struct Wide {
+            value: u64,
+        }
+
+        let buf = rkyv::to_bytes::<_, 256>(&V1 {
+            id: 7,
+            name: "seven".to_string(),
+        })
+        .unwrap();
+        let v1 = unsafe { archived_root::<V1>(buf.as_ref()) };
+        let v2 = unsafe { reinterpret_archived::<V1, V2>(v1).unwrap() };
+        assert_eq!(v2.key, 7);
+        assert_eq!(v2.label, "seven");
+
+        // Same size, different alignment
+        let buf = rkyv::to_bytes::<_, 256>(&Halves { low: 1, high: 2 }).unwrap();
+        let halves = unsafe { archived_root::<Halves>(buf.as_ref()) };
+        let error = unsafe { reinterpret_archived::<Halves, Wide>(halves) }.unwrap_err();
+        assert_eq!(
+            error,
+            LayoutMismatch {
+                from: Layout::new::<ArchivedHalves>(),
+                to: Layout::new::<ArchivedWide>(),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "archived layouts don't match: size 8 and alignment 4 can't be reinterpreted as size 8 and alignment 8",
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_visibility() {
+        mod inner {
+            #[derive(super::Archive, super::Serialize)]
+            pub struct TestTuple(pub i32);
+
+            #[derive(super::Archive, super::Serialize)]
+            pub struct TestStruct {
+                pub value: i32,
+            }
+
+            #[derive(super::Archive, super::Serialize)]
+            pub enum TestEnum {
+                B(i32),
+                C { value: i32 },
+            }
+        }
+
+        use inner::{
+            ArchivedTestEnum, ArchivedTestStruct, ArchivedTestTuple, TestEnum, TestStruct,
+            TestTuple,
+        };
+
+        TestTuple(42.into());
+        ArchivedTestTuple(42.into());
+        TestStruct { value: 42.into() };
+        ArchivedTestStruct { value: 42.into() };
+        TestEnum::B(42.into());
+        TestEnum::C { value: 42.into() };
+        ArchivedTestEnum::B(42.into());
+        ArchivedTestEnum::C { value: 42.into() };
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn basic_mutable_refs() {
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&42i32).unwrap();
+        let mut buf = serializer.into_inner();
+        let mut value = unsafe { archived_root_mut::<i32>(Pin::new(buf.as_mut())) };
+        assert_eq!(*value, 42);
+        *value = 11.into();
+        assert_eq!(*value, 11);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn struct_mutable_refs() {
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: Box<i32>,
+            b: Vec<String>,
+        }
+
+        impl ArchivedTest {
+            fn a(self: Pin<&mut Self>) -> Pin<&mut Archived<Box<i32>>> {
+                unsafe { self.map_unchecked_mut(|s| &mut s.a) }
+            }
+
+            fn b(self: Pin<&mut Self>) -> Pin<&mut Archived<Vec<String>>> {
+                unsafe { self.map_unchecked_mut(|s| &mut s.b) }
+            }
+        }
+
+        let value = Test {
+            a: Box::new(10),
+            b: vec!["hello".to_string(), "world".to_string()],
+        };
+
+        let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
         let mut buf = serializer.into_serializer().into_inner();
         let mut value = unsafe { archived_root_mut::<Test>(Pin::new(buf.as_mut())) };
@@ -580,6 +1388,81 @@ mod tests {
         assert_eq!(value.b[1], "WORLD");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    // The conversions are only needed with the endianness features
+    #[allow(clippy::useless_conversion)]
+    fn with_reserved_vec() {
+        use rkyv::{vec::ArchivedReservedVec, with::ReservedVec};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+        #[archive_attr(derive(Debug, PartialEq, Clone, Copy))]
+        struct Record {
+            id: u32,
+            value: f32,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Log {
+            name: String,
+            #[with(ReservedVec<3>)]
+            records: Vec<Record>,
+        }
+
+        impl ArchivedLog {
+            fn records(self: Pin<&mut Self>) -> Pin<&mut ArchivedReservedVec<ArchivedRecord>> {
+                unsafe { self.map_unchecked_mut(|s| &mut s.records) }
+            }
+        }
+
+        let value = Log {
+            name: "log".to_string(),
+            records: vec![Record { id: 1, value: 1.5 }],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let len = buf.len();
+        let mut archived = unsafe { archived_root_mut::<Log>(Pin::new(buf.as_mut())) };
+
+        assert_eq!(archived.records.len(), 1);
+        assert_eq!(archived.records.capacity(), 4);
+        assert_eq!(archived.records.remaining_capacity(), 3);
+
+        let record = |id: u32| ArchivedRecord {
+            id: id.into(),
+            value: (id as f32).into(),
+        };
+        for id in 2..5 {
+            archived.as_mut().records().try_push(record(id)).unwrap();
+        }
+        let error = archived.as_mut().records().try_push(record(5)).unwrap_err();
+        assert_eq!(error.value, record(5));
+        assert_eq!(error.capacity, 4);
+
+        assert_eq!(archived.records.len(), 4);
+        assert_eq!(archived.records.remaining_capacity(), 0);
+        assert_eq!(archived.records[3], record(4));
+        assert_eq!(archived.name, "log");
+
+        archived.as_mut().records().pin_mut_slice()[0].id = 10u32.into();
+
+        // The archive doesn't grow
+        assert_eq!(buf.len(), len);
+        let archived = unsafe { archived_root::<Log>(buf.as_ref()) };
+        let deserialized: Log = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(
+            deserialized.records,
+            vec![
+                Record { id: 10, value: 1.5 },
+                Record { id: 2, value: 2.0 },
+                Record { id: 3, value: 3.0 },
+                Record { id: 4, value: 4.0 },
+            ]
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn enum_mutable_ref() {
@@ -748,6 +1631,101 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_open_ranges() {
+        use core::{
+            mem::size_of,
+            ops::{RangeFrom, RangeFull, RangeTo},
+        };
+
+        test_archive(&RangeFull);
+        test_archive(&(10u64..));
+        test_archive(&(..-5i16));
+        test_archive(&("a".to_string()..));
+        test_archive(&(.."z".to_string()));
+        assert_eq!(size_of::<Archived<RangeFull>>(), 0);
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Slicing {
+            all: RangeFull,
+            tail: RangeFrom<u32>,
+            head: RangeTo<u32>,
+            names: Vec<RangeFrom<String>>,
+        }
+
+        let value = Slicing {
+            all: ..,
+            tail: 4..,
+            head: ..16,
+            names: vec!["m".to_string().., "x".to_string()..],
+        };
+        test_archive(&value);
+
+        let buf = rkyv::to_bytes::<_, 256>(&value).unwrap();
+        let archived = unsafe { archived_root::<Slicing>(buf.as_ref()) };
+        assert!(archived.tail.contains(&4));
+        assert!(!archived.tail.contains(&3));
+        assert!(archived.head.contains(&15));
+        assert!(!archived.head.contains(&16));
+        assert_eq!(archived.names[1].start, "x");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_bound() {
+        use core::ops::Bound;
+
+        test_archive(&Bound::Included(42));
+        test_archive(&Bound::Excluded("hello world".to_string()));
+        test_archive(&Bound::<Vec<i32>>::Unbounded);
+
+        #[derive(Archive, Serialize)]
+        struct Span {
+            start: Bound<i32>,
+            end: Bound<i32>,
+        }
+
+        let span = Span {
+            start: Bound::Excluded(10),
+            end: Bound::Included(20),
+        };
+        let buf = rkyv::to_bytes::<_, 256>(&span).unwrap();
+        let archived = unsafe { archived_root::<Span>(buf.as_ref()) };
+        assert_eq!(archived.start.as_ref(), Bound::Excluded(&10));
+        assert_eq!(archived.end.as_ref(), Bound::Included(&20));
+
+        let map = (0..30).map(|i| (i, i)).collect::<BTreeMap<_, _>>();
+        let range = map
+            .range((archived.start.as_ref(), archived.end.as_ref()))
+            .map(|(k, _)| *k)
+            .collect::<Vec<_>>();
+        assert_eq!(range, (11..=20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_control_flow() {
+        use core::ops::ControlFlow;
+
+        test_archive(&ControlFlow::<String, Vec<i32>>::Continue(vec![1, 2, 3]));
+        test_archive(&ControlFlow::<String, Vec<i32>>::Break(
+            "hello world".to_string(),
+        ));
+        test_archive(&ControlFlow::<u8>::Continue(()));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_poll() {
+        use core::task::Poll;
+
+        test_archive(&Poll::Ready("hello world".to_string()));
+        test_archive(&Poll::<Vec<i32>>::Pending);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_atomic() {
@@ -901,6 +1879,110 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_custom_shared_ptr() {
+        use rkyv::{
+            de::{DeserializeSharedPointer, SharedPointer},
+            rc::{ArchiveSharedPointer, ArchivedSharedPointer, RcResolver},
+            ser::SharedSerializeRegistry,
+            ArchiveUnsized, MetadataResolver, SerializeUnsized,
+        };
+
+        #[derive(Debug)]
+        struct MyRc<T: ?Sized>(Rc<T>);
+
+        impl<T: ?Sized> Clone for MyRc<T> {
+            fn clone(&self) -> Self {
+                Self(self.0.clone())
+            }
+        }
+
+        struct MyRcFlavor;
+
+        impl<T: ?Sized> SharedPointer for MyRc<T> {
+            fn data_address(&self) -> *const () {
+                Rc::as_ptr(&self.0) as *const ()
+            }
+        }
+
+        impl<T: ArchiveUnsized + ?Sized> ArchiveSharedPointer for MyRc<T> {
+            type Target = T;
+            type Flavor = MyRcFlavor;
+
+            fn as_target(&self) -> &T {
+                &self.0
+            }
+        }
+
+        // SAFETY: `MyRc` is the only shared pointer with the `MyRcFlavor` flavor, and it wraps an
+        // `Rc`.
+        unsafe impl<T: ArchiveUnsized + ?Sized + 'static> DeserializeSharedPointer for MyRc<T> {
+            fn from_box(boxed: Box<T>) -> Self {
+                Self(Rc::from(boxed))
+            }
+
+            unsafe fn from_raw(ptr: *const T) -> Self {
+                Self(Rc::from_raw(ptr))
+            }
+        }
+
+        impl<T: ArchiveUnsized + ?Sized> Archive for MyRc<T> {
+            type Archived = ArchivedSharedPointer<Self>;
+            type Resolver = RcResolver<MetadataResolver<T>>;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                self.resolve_shared(pos, resolver, out);
+            }
+        }
+
+        impl<T, S> Serialize<S> for MyRc<T>
+        where
+            T: SerializeUnsized<S> + ?Sized,
+            S: Serializer + SharedSerializeRegistry + ?Sized,
+        {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                self.serialize_shared(serializer)
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            a: MyRc<u32>,
+            b: MyRc<u32>,
+            c: MyRc<[String]>,
+        }
+
+        let shared = MyRc(Rc::new(10));
+        let value = Test {
+            a: shared.clone(),
+            b: shared,
+            c: MyRc(Rc::from(vec!["hello".to_string()].into_boxed_slice())),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert_eq!(*archived.a, 10);
+        assert!(core::ptr::eq(archived.a.get(), archived.b.get()));
+        assert_eq!(archived.c[0], "hello");
+
+        let deserialized: Test = archived
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        assert_eq!(*deserialized.a.0, 10);
+        assert!(Rc::ptr_eq(&deserialized.a.0, &deserialized.b.0));
+        assert_eq!(Rc::strong_count(&deserialized.a.0), 2);
+        assert_eq!(&*deserialized.c.0, &["hello".to_string()]);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_weak_ptr() {
@@ -994,14 +2076,52 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn derive_attributes() {
-        #[derive(Archive, Debug, PartialEq)]
-        #[archive(archived = "ATest", resolver = "RTest", compare(PartialEq))]
-        #[archive_attr(derive(Debug))]
+    fn archive_expired_weak_ptr() {
+        #[derive(Archive, Serialize, Deserialize)]
         struct Test {
-            a: i32,
-            b: Option<u32>,
-            c: String,
+            live: Weak<u32>,
+            expired: Weak<String>,
+            null: Weak<u32>,
+        }
+
+        let shared = Rc::new(10);
+        let dropped = Rc::new("dropped before serializing".to_string());
+        let value = Test {
+            live: Rc::downgrade(&shared),
+            expired: Rc::downgrade(&dropped),
+            null: Weak::new(),
+        };
+        core::mem::drop(dropped);
+        assert!(value.expired.upgrade().is_none());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert_eq!(**archived.live.upgrade().unwrap(), 10);
+        assert!(archived.expired.upgrade().is_none());
+        assert!(archived.null.upgrade().is_none());
+
+        let mut deserializer = DefaultDeserializer::default();
+        let deserialized: Test = archived.deserialize(&mut deserializer).unwrap();
+        // The live target is only kept alive by the deserializer
+        assert_eq!(*deserialized.live.upgrade().unwrap(), 10);
+        assert!(deserialized.expired.upgrade().is_none());
+        assert_eq!(deserialized.expired.strong_count(), 0);
+        assert!(deserialized.null.upgrade().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_attributes() {
+        #[derive(Archive, Debug, PartialEq)]
+        #[archive(archived = "ATest", resolver = "RTest", compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            a: i32,
+            b: Option<u32>,
+            c: String,
             d: Vec<i32>,
         }
 
@@ -1392,6 +2512,182 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_as_other_type() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Point {
+            x: i32,
+            y: i32,
+            label: String,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(as = "ArchivedPoint", check_as, compare(PartialEq))]
+        struct Coordinate {
+            x: i32,
+            y: i32,
+            label: String,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum Shape {
+            Circle(i32),
+            Rect { w: i32, h: i32 },
+            Empty,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(as = "ArchivedShape", check_as)]
+        enum Figure {
+            Circle(i32),
+            Rect { w: i32, h: i32 },
+            Empty,
+        }
+
+        let coordinate = Coordinate {
+            x: 1,
+            y: -2,
+            label: "a label long enough to be stored out of line".to_string(),
+        };
+        test_archive(&coordinate);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&coordinate).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Point>(buf.as_ref()) };
+
+        let point = Point {
+            x: 1,
+            y: -2,
+            label: coordinate.label.clone(),
+        };
+        assert_eq!(archived, &point);
+        let deserialized: Point = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, point);
+
+        for (figure, shape) in [
+            (Figure::Circle(3), Shape::Circle(3)),
+            (Figure::Rect { w: 4, h: 5 }, Shape::Rect { w: 4, h: 5 }),
+            (Figure::Empty, Shape::Empty),
+        ] {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&figure).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Shape>(buf.as_ref()) };
+            assert_eq!(archived, &shape);
+
+            let deserialized: Figure = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, figure);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_into_builder() {
+        mod user {
+            #[cfg(not(feature = "std"))]
+            use alloc::{string::String, vec::Vec};
+            use rkyv::{de::DeserializeBuilder, Archive, Deserialize, Serialize};
+
+            #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+            #[archive(deserialize_into_builder = "UserBuilder<T>")]
+            pub struct User<T> {
+                name: String,
+                tags: Vec<T>,
+            }
+
+            impl<T> User<T> {
+                pub fn new(name: String, tags: Vec<T>) -> Self {
+                    Self { name, tags }
+                }
+
+                pub fn name(&self) -> &str {
+                    &self.name
+                }
+            }
+
+            pub struct UserBuilder<T> {
+                name: Option<String>,
+                tags: Vec<T>,
+            }
+
+            impl<T> Default for UserBuilder<T> {
+                fn default() -> Self {
+                    Self {
+                        name: None,
+                        tags: Vec::new(),
+                    }
+                }
+            }
+
+            impl<T> UserBuilder<T> {
+                pub fn name(&mut self, name: String) -> &mut Self {
+                    self.name = Some(name);
+                    self
+                }
+
+                pub fn tags(&mut self, tags: Vec<T>) -> &mut Self {
+                    self.tags = tags;
+                    self
+                }
+            }
+
+            #[derive(Debug, PartialEq)]
+            pub enum UserError {
+                MissingName,
+                EmptyName,
+            }
+
+            impl<T> DeserializeBuilder<User<T>> for UserBuilder<T> {
+                type Error = UserError;
+
+                fn build(self) -> Result<User<T>, UserError> {
+                    match self.name {
+                        None => Err(UserError::MissingName),
+                        Some(name) if name.is_empty() => Err(UserError::EmptyName),
+                        Some(name) => Ok(User {
+                            name,
+                            tags: self.tags,
+                        }),
+                    }
+                }
+            }
+        }
+
+        use user::{User, UserError};
+
+        struct UserDeserializer;
+
+        impl Fallible for UserDeserializer {
+            type Error = UserError;
+        }
+
+        let value = User::new("alice".to_string(), vec![1u32, 2, 3]);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<User<u32>>(buf.as_ref()) };
+
+        let deserialized: User<u32> = archived.deserialize(&mut UserDeserializer).unwrap();
+        assert_eq!(deserialized, value);
+        assert_eq!(deserialized.name(), "alice");
+
+        // The builder enforces its invariants when the archived value doesn't satisfy them
+        let value = User::new(String::new(), vec![4u32]);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<User<u32>>(buf.as_ref()) };
+
+        let result: Result<User<u32>, _> = archived.deserialize(&mut UserDeserializer);
+        assert_eq!(result, Err(UserError::EmptyName));
+    }
+
     mod with {
         #[cfg(not(feature = "std"))]
         use alloc::string::{String, ToString};
@@ -1661,6 +2957,117 @@ mod tests {
         assert_eq!(archived.c, "hello world");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_references_as_owned() {
+        #[derive(Archive, Serialize)]
+        struct View<'a> {
+            name: &'a str,
+            data: &'a [u8],
+            value: &'a u32,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Items<'a, T: 'a>(&'a [T], u32);
+
+        // The archived types don't have a lifetime parameter
+        fn archived_name(view: &ArchivedView) -> &str {
+            view.name.as_str()
+        }
+
+        fn archived_items<T: Archive>(items: &ArchivedItems<T>) -> &[T::Archived] {
+            items.0.as_slice()
+        }
+
+        let data = [1, 2, 3, 4, 5, 6];
+        let value = View {
+            name: "a name that is too long to be inlined",
+            data: &data,
+            value: &42,
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<View>(result.as_slice()) };
+
+        assert_eq!(archived_name(archived), value.name);
+        assert_eq!(archived.data.as_slice(), value.data);
+        assert_eq!(archived.value, 42);
+
+        let strings = ["hello".to_string(), "world".to_string()];
+        let value = Items(&strings, 7);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Items<String>>(result.as_slice()) };
+
+        assert_eq!(archived_items(archived), ["hello", "world"]);
+        assert_eq!(archived.1, 7);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_reference_arrays_as_owned() {
+        use rkyv::{
+            string::ArchivedString,
+            vec::ArchivedVec,
+            with::{AsOwned, Map},
+        };
+
+        const NAMES: [&str; 8] = [
+            "zero",
+            "one",
+            "two",
+            "three",
+            "four",
+            "five",
+            "six",
+            "a name that is too long to be inlined",
+        ];
+
+        #[derive(Archive, Serialize)]
+        struct Config<'a> {
+            names: [&'a str; 8],
+            blobs: [&'a [u8]; 2],
+        }
+
+        let value = Config {
+            names: NAMES,
+            blobs: [b"hello", &[]],
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Config>(result.as_slice()) };
+
+        let names: &[ArchivedString; 8] = &archived.names;
+        for (name, expected) in names.iter().zip(NAMES.iter()) {
+            assert_eq!(name.as_str(), *expected);
+        }
+        let blobs: &[ArchivedVec<u8>; 2] = &archived.blobs;
+        assert_eq!(blobs[0].as_slice(), b"hello");
+        assert!(blobs[1].is_empty());
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Owned<'a> {
+            #[with(Map<AsOwned>)]
+            names: [Cow<'a, str>; 2],
+        }
+
+        let value = Owned {
+            names: [Cow::Borrowed(NAMES[0]), Cow::Borrowed(NAMES[7])],
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Owned>(result.as_slice()) };
+        assert_eq!(archived.names[1], NAMES[7]);
+
+        let deserialized: Owned = archived.deserialize(&mut Infallible).unwrap();
+        assert!(matches!(deserialized.names[0], Cow::Owned(ref name) if name == NAMES[0]));
+        assert!(matches!(deserialized.names[1], Cow::Owned(ref name) if name == NAMES[7]));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_as_vec() {
@@ -1738,117 +3145,741 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_niche() {
-        use ::core::mem::size_of;
-        use rkyv::with::Niche;
+    fn with_as_vec_slice() {
+        use rkyv::with::AsVec;
 
-        #[derive(Archive, Serialize, Deserialize)]
-        struct Test {
-            #[with(Niche)]
-            inner: Option<Box<String>>,
+        #[derive(Archive, Serialize)]
+        struct Borrowed<'a> {
+            #[with(AsVec)]
+            values: &'a [String],
         }
 
-        #[derive(Archive, Serialize, Deserialize)]
-        struct TestNoNiching {
-            inner: Option<Box<String>>,
+        #[derive(Archive, Serialize)]
+        struct Owned {
+            values: Vec<String>,
         }
 
-        let value = Test {
-            inner: Some(Box::new("hello world".to_string())),
-        };
+        let values = vec!["hello".to_string(), "world".to_string()];
+
         let mut serializer = DefaultSerializer::default();
-        serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_serializer().into_inner();
-        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+        serializer
+            .serialize_value(&Borrowed { values: &values })
+            .unwrap();
+        let borrowed = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Borrowed>(borrowed.as_slice()) };
+        assert_eq!(archived.values, values);
 
-        assert!(archived.inner.is_some());
-        assert_eq!(&**archived.inner.as_ref().unwrap(), "hello world");
-        assert_eq!(archived.inner, value.inner);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Owned { values }).unwrap();
+        let owned = serializer.into_serializer().into_inner();
+        assert_eq!(borrowed.as_slice(), owned.as_slice());
+    }
 
-        let value = Test { inner: None };
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_filtered() {
+        use crate::util::counted::{count_deserialized, Counted};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        struct Item {
+            #[with(Counted)]
+            value: u32,
+        }
+
+        let value = (0..10).map(|value| Item { value }).collect::<Vec<_>>();
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_serializer().into_inner();
-        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
-
-        assert!(archived.inner.is_none());
-        assert_eq!(archived.inner, value.inner);
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Item>>(buf.as_ref()) };
 
-        assert!(size_of::<Archived<Test>>() < size_of::<Archived<TestNoNiching>>());
+        let (filtered, count) = count_deserialized(|deserializer| {
+            archived.deserialize_filtered::<Item, _>(|item| item.value >= 7, deserializer)
+        });
+        assert_eq!(filtered.unwrap(), &value[7..]);
+        assert_eq!(count, 3);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_niche_nonzero() {
-        use ::core::{
-            mem::size_of,
-            num::{NonZeroI32, NonZeroI8, NonZeroIsize, NonZeroU32, NonZeroU8, NonZeroUsize},
-        };
-        use rkyv::{from_archived, with::Niche};
+    fn derive_associated_type_field() {
+        trait Storage {
+            type Buffer;
+        }
 
-        #[derive(Archive, Serialize, Deserialize)]
-        struct Test {
-            #[with(Niche)]
-            a: Option<NonZeroI8>,
-            #[with(Niche)]
-            b: Option<NonZeroI32>,
-            #[with(Niche)]
-            c: Option<NonZeroIsize>,
-            #[with(Niche)]
-            d: Option<NonZeroU8>,
-            #[with(Niche)]
-            e: Option<NonZeroU32>,
-            #[with(Niche)]
-            f: Option<NonZeroUsize>,
+        struct VecStorage;
+
+        impl Storage for VecStorage {
+            type Buffer = Vec<u32>;
+        }
+
+        struct StringStorage;
+
+        impl Storage for StringStorage {
+            type Buffer = String;
         }
 
         #[derive(Archive, Serialize, Deserialize)]
-        struct TestNoNiching {
-            a: Option<NonZeroI8>,
-            b: Option<NonZeroI32>,
-            c: Option<NonZeroIsize>,
-            d: Option<NonZeroU8>,
-            e: Option<NonZeroU32>,
-            f: Option<NonZeroUsize>,
+        struct Backed<S: Storage> {
+            buffer: S::Buffer,
+            len: u32,
         }
 
-        let value = Test {
-            a: Some(NonZeroI8::new(10).unwrap()),
-            b: Some(NonZeroI32::new(10).unwrap()),
-            c: Some(NonZeroIsize::new(10).unwrap()),
-            d: Some(NonZeroU8::new(10).unwrap()),
-            e: Some(NonZeroU32::new(10).unwrap()),
-            f: Some(NonZeroUsize::new(10).unwrap()),
+        let value = Backed::<VecStorage> {
+            buffer: vec![1, 2, 3],
+            len: 3,
         };
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_serializer().into_inner();
-        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
-
-        assert!(archived.a.is_some());
-        assert_eq!(archived.a.as_ref().unwrap().get(), 10);
-        assert!(archived.b.is_some());
-        assert_eq!(from_archived!(*archived.b.as_ref().unwrap()).get(), 10);
-        assert!(archived.c.is_some());
-        assert_eq!(from_archived!(*archived.c.as_ref().unwrap()).get(), 10);
-        assert!(archived.d.is_some());
-        assert_eq!(archived.d.as_ref().unwrap().get(), 10);
-        assert!(archived.e.is_some());
-        assert_eq!(from_archived!(*archived.e.as_ref().unwrap()).get(), 10);
-        assert!(archived.f.is_some());
-        assert_eq!(from_archived!(*archived.f.as_ref().unwrap()).get(), 10);
-
-        let value = Test {
-            a: None,
-            b: None,
-            c: None,
-            d: None,
-            e: None,
-            f: None,
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Backed<VecStorage>>(buf.as_ref()) };
+        assert_eq!(archived.buffer, [1, 2, 3]);
+        assert_eq!(archived.len, 3);
+        let deserialized: Backed<VecStorage> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.buffer, value.buffer);
+
+        let value = Backed::<StringStorage> {
+            buffer: "hello".to_string(),
+            len: 5,
         };
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_serializer().into_inner();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Backed<StringStorage>>(buf.as_ref()) };
+        assert_eq!(archived.buffer, "hello");
+        let deserialized: Backed<StringStorage> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.buffer, value.buffer);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn flatten_nested_vec() {
+        let value = vec![
+            vec![],
+            vec!["a".to_string(), "b".to_string()],
+            vec![],
+            vec![],
+            vec!["c".to_string()],
+            vec![],
+        ];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Vec<String>>>(buf.as_ref()) };
+
+        assert!(archived.flatten().eq(["a", "b", "c"].iter()));
+        let flat: Vec<String> = archived.concat_to_vec(&mut Infallible).unwrap();
+        assert_eq!(flat, value.concat());
+
+        let empty: Vec<Vec<String>> = vec![vec![], vec![]];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&empty).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Vec<String>>>(buf.as_ref()) };
+
+        assert_eq!(archived.flatten().count(), 0);
+        let flat: Vec<String> = archived.concat_to_vec(&mut Infallible).unwrap();
+        assert!(flat.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_with_context() {
+        use rkyv::{
+            de::{deserializers::ContextDeserializer, DeserializeContext},
+            string::{ArchivedString, StringResolver},
+            with::{ArchiveWith, DeserializeWith, SerializeWith},
+            SerializeUnsized,
+        };
+
+        #[derive(Default)]
+        struct Interner {
+            strings: BTreeMap<String, Rc<str>>,
+        }
+
+        struct Interned;
+
+        impl ArchiveWith<Rc<str>> for Interned {
+            type Archived = ArchivedString;
+            type Resolver = StringResolver;
+
+            unsafe fn resolve_with(
+                field: &Rc<str>,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedString::resolve_from_str(field, pos, resolver, out);
+            }
+        }
+
+        impl<S: Fallible + ?Sized> SerializeWith<Rc<str>, S> for Interned
+        where
+            str: SerializeUnsized<S>,
+        {
+            fn serialize_with(
+                field: &Rc<str>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedString::serialize_from_str(field, serializer)
+            }
+        }
+
+        impl<D: DeserializeContext<Interner> + ?Sized> DeserializeWith<ArchivedString, Rc<str>, D>
+            for Interned
+        {
+            fn deserialize_with(
+                field: &ArchivedString,
+                deserializer: &mut D,
+            ) -> Result<Rc<str>, D::Error> {
+                Ok(deserializer
+                    .context()
+                    .strings
+                    .entry(field.as_str().to_string())
+                    .or_insert_with(|| Rc::from(field.as_str()))
+                    .clone())
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Interned)]
+            a: Rc<str>,
+            #[with(Interned)]
+            b: Rc<str>,
+            shared: Rc<u32>,
+            other_shared: Rc<u32>,
+        }
+
+        let shared = Rc::new(42);
+        let value = Test {
+            a: Rc::from("hello world, this string is out-of-line"),
+            b: Rc::from("hello world, this string is out-of-line"),
+            shared: shared.clone(),
+            other_shared: shared,
+        };
+        assert!(!Rc::ptr_eq(&value.a, &value.b));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert_eq!(archived.a, "hello world, this string is out-of-line");
+
+        let mut deserializer =
+            ContextDeserializer::new(Interner::default(), DefaultDeserializer::default());
+        let deserialized: Test = archived.deserialize(&mut deserializer).unwrap();
+        assert!(Rc::ptr_eq(&deserialized.a, &deserialized.b));
+        assert!(Rc::ptr_eq(&deserialized.shared, &deserialized.other_shared));
+        assert_eq!(*deserialized.shared, 42);
+
+        let interner = deserializer.into_context();
+        assert_eq!(interner.strings.len(), 1);
+        assert!(Rc::ptr_eq(
+            &interner.strings["hello world, this string is out-of-line"],
+            &deserialized.a
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_niche() {
+        use ::core::mem::size_of;
+        use rkyv::with::Niche;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Niche)]
+            inner: Option<Box<String>>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct TestNoNiching {
+            inner: Option<Box<String>>,
+        }
+
+        let value = Test {
+            inner: Some(Box::new("hello world".to_string())),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert!(archived.inner.is_some());
+        assert_eq!(&**archived.inner.as_ref().unwrap(), "hello world");
+        assert_eq!(archived.inner, value.inner);
+
+        let value = Test { inner: None };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert!(archived.inner.is_none());
+        assert_eq!(archived.inner, value.inner);
+
+        assert!(size_of::<Archived<Test>>() < size_of::<Archived<TestNoNiching>>());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_vec_as_native_slice() {
+        let bytes = vec![1u8, 2, 3];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&bytes).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u8>>(buf.as_ref()) };
+        assert_eq!(archived.as_native_slice::<u8>(), bytes.as_slice());
+
+        #[cfg(any(
+            not(any(feature = "archive_le", feature = "archive_be")),
+            all(target_endian = "little", feature = "archive_le"),
+            all(target_endian = "big", feature = "archive_be"),
+        ))]
+        {
+            let floats = vec![1.0f32, -2.5, 0.125, f32::MAX];
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&floats).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Vec<f32>>(buf.as_ref()) };
+
+            let native: &[f32] = archived.as_native_slice();
+            assert_eq!(native, floats.as_slice());
+            assert_eq!(native.as_ptr() as *const u8, archived.as_ptr() as *const u8);
+
+            let empty = Vec::<u64>::new();
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&empty).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Vec<u64>>(buf.as_ref()) };
+            assert!(archived.as_native_slice::<u64>().is_empty());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_vec_is_sorted() {
+        fn archive(value: &Vec<u32>) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(value).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let sorted = archive(&vec![1, 2, 3, 5, 8]);
+        let archived = unsafe { archived_root::<Vec<u32>>(sorted.as_ref()) };
+        assert!(archived.is_sorted());
+        assert!(archived.is_sorted_by(|a, b| a < b));
+        assert!(!archived.is_sorted_by(|a, b| a > b));
+
+        let unsorted = archive(&vec![1, 3, 2]);
+        let archived = unsafe { archived_root::<Vec<u32>>(unsorted.as_ref()) };
+        assert!(!archived.is_sorted());
+
+        let duplicates = archive(&vec![1, 2, 2, 3]);
+        let archived = unsafe { archived_root::<Vec<u32>>(duplicates.as_ref()) };
+        assert!(archived.is_sorted());
+        assert!(!archived.is_sorted_by(|a, b| a < b));
+
+        let empty = archive(&Vec::new());
+        let archived = unsafe { archived_root::<Vec<u32>>(empty.as_ref()) };
+        assert!(archived.is_sorted());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_vec_search() {
+        let value = vec![
+            "first".to_string(),
+            "a string long enough to be stored out of line".to_string(),
+            "first".to_string(),
+            "last".to_string(),
+        ];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<String>>(buf.as_ref()) };
+
+        assert!(archived.contains("last"));
+        assert!(archived.contains(&value[1]));
+        assert!(!archived.contains("missing"));
+
+        // Duplicates are found from the matching end
+        assert_eq!(archived.position(|s| s == "first"), Some(0));
+        assert_eq!(archived.rposition(|s| s == "first"), Some(2));
+        assert_eq!(archived.position(|s| s.len() > 10), Some(1));
+        assert_eq!(archived.rposition(|s| s.len() > 10), Some(1));
+
+        assert_eq!(archived.position(|s| s == "missing"), None);
+        assert_eq!(archived.rposition(|s| s == "missing"), None);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Vec::<u32>::new()).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<u32>>(buf.as_ref()) };
+        assert!(!archived.contains(&0));
+        assert_eq!(archived.position(|_| true), None);
+        assert_eq!(archived.rposition(|_| true), None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_sorted_vec() {
+        use rkyv::with::SortedVec;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(SortedVec)]
+            ids: Vec<u32>,
+            #[with(SortedVec)]
+            names: Vec<String>,
+        }
+
+        let value = Test {
+            ids: vec![5, 3, 8, 1, 3, 5, 2],
+            names: vec!["b".to_string(), "a".to_string(), "b".to_string()],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        // The elements are sorted and duplicates are removed
+        assert_eq!(archived.ids.as_slice(), &[1, 2, 3, 5, 8]);
+        assert!(archived.ids.as_vec().is_sorted_by(|a, b| a < b));
+        assert_eq!(archived.ids.binary_search(&5), Ok(3));
+        assert_eq!(archived.ids.binary_search(&4), Err(3));
+        assert!(archived.ids.contains(&8));
+        assert!(!archived.ids.contains(&0));
+        assert_eq!(archived.names.len(), 2);
+        assert_eq!(archived.names.get("b").map(|s| s.as_str()), Some("b"));
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.ids, vec![1, 2, 3, 5, 8]);
+        assert_eq!(deserialized.names, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_quantize() {
+        use rkyv::with::Quantize;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Quantize<8>)]
+            bytes: Vec<f32>,
+            #[with(Quantize<12>)]
+            unaligned: Vec<f32>,
+            #[with(Quantize<1>)]
+            bits: Vec<f32>,
+            #[with(Quantize<16>)]
+            empty: Vec<f32>,
+        }
+
+        let values = (0..100)
+            .map(|i| (i as f32 / 49.5) - 1.0)
+            .collect::<Vec<_>>();
+        let value = Test {
+            bytes: values.clone(),
+            unaligned: values.clone(),
+            bits: vec![0.0, 1.0, f32::INFINITY, f32::NAN, 1.0],
+            empty: Vec::new(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.bytes.len(), 100);
+        assert_eq!(archived.bytes.min(), -1.0);
+        assert_eq!(archived.bytes.max(), 1.0);
+        assert_eq!(archived.bytes.get_quantized(0), Some(0));
+        assert_eq!(archived.bytes.get_quantized(99), Some(255));
+        assert_eq!(archived.bytes.get_dequantized(100), None);
+        for (i, value) in values.iter().enumerate() {
+            let error = (archived.bytes.get_dequantized(i).unwrap() - value).abs();
+            assert!(error <= 1.0 / 255.0);
+            let error = (archived.unaligned.get_dequantized(i).unwrap() - value).abs();
+            assert!(error <= 1.0 / 4095.0);
+        }
+        assert_eq!(archived.unaligned.get_quantized(99), Some(4095));
+
+        // Infinities are clamped and NaNs are quantized to the minimum
+        assert_eq!(
+            archived.bits.iter().collect::<Vec<_>>(),
+            vec![0.0, 1.0, 1.0, 0.0, 1.0],
+        );
+        assert!(archived.empty.is_empty());
+        assert_eq!(archived.empty.get_dequantized(0), None);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(
+            deserialized.bytes,
+            archived.bytes.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(deserialized.bits, vec![0.0, 1.0, 1.0, 0.0, 1.0]);
+        assert!(deserialized.empty.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_boxed_variants() {
+        use core::mem::{align_of, size_of};
+        use rkyv::with::{BoxedVariants, Map, VarInt};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(boxed_variants)]
+        #[allow(clippy::large_enum_variant)]
+        enum Message {
+            Ping,
+            Reading(u16),
+            Name(String),
+            Block([u64; 32]),
+            Code(#[with(VarInt)] u64),
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(BoxedVariants)]
+            first: Message,
+            #[with(Map<BoxedVariants>)]
+            messages: Vec<Message>,
+        }
+
+        assert!(size_of::<Archived<Message>>() > 256);
+        // The index and the relative pointer are padded to the alignment of the larger one
+        let align = align_of::<Archived<u32>>().max(align_of::<rkyv::RawRelPtr>());
+        assert_eq!(
+            size_of::<rkyv::variants::ArchivedBoxedVariants<Message>>(),
+            (size_of::<Archived<u32>>() + size_of::<rkyv::RawRelPtr>()).next_multiple_of(align),
+        );
+
+        let value = Test {
+            first: Message::Ping,
+            messages: vec![
+                Message::Ping,
+                Message::Reading(7),
+                Message::Name("hello world".to_string()),
+                Message::Block([3; 32]),
+                Message::Code(300),
+            ],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        // Only the large variant takes up as much space as an inline archived enum
+        assert!(buf.len() < 2 * size_of::<Archived<Message>>());
+
+        let indices = archived
+            .messages
+            .iter()
+            .map(|m| m.variant_index())
+            .collect::<Vec<_>>();
+        assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+        assert_eq!(archived.first.variant_index(), 0);
+        assert_eq!(
+            unsafe { *archived.messages[1].payload::<Archived<u16>>() },
+            7
+        );
+        assert_eq!(
+            unsafe { archived.messages[2].payload::<Archived<String>>() },
+            "hello world"
+        );
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_niche_rc() {
+        use ::core::mem::size_of;
+        use rkyv::{
+            de::SharedDeserializeRegistry,
+            ser::{Serializer, SharedSerializeRegistry},
+            with::Niche,
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(bound(
+            serialize = "__S: Serializer + SharedSerializeRegistry",
+            deserialize = "__D: SharedDeserializeRegistry"
+        ))]
+        struct Node {
+            value: u32,
+            #[with(Niche)]
+            #[omit_bounds]
+            left: Option<Rc<Node>>,
+            #[with(Niche)]
+            #[omit_bounds]
+            right: Option<Rc<Node>>,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(bound(serialize = "__S: Serializer + SharedSerializeRegistry"))]
+        struct NodeNoNiching {
+            value: u32,
+            #[omit_bounds]
+            left: Option<Rc<NodeNoNiching>>,
+            #[omit_bounds]
+            right: Option<Rc<NodeNoNiching>>,
+        }
+
+        // A chain of nodes where every node shares the same leaf
+        let leaf = Rc::new(Node {
+            value: 0,
+            left: None,
+            right: None,
+        });
+        let mut root = Rc::new(Node {
+            value: 1,
+            left: Some(leaf.clone()),
+            right: None,
+        });
+        for value in 2..10 {
+            root = Rc::new(Node {
+                value,
+                left: Some(leaf.clone()),
+                right: Some(root),
+            });
+        }
+
+        let no_niching_leaf = Rc::new(NodeNoNiching {
+            value: 0,
+            left: None,
+            right: None,
+        });
+        let mut no_niching_root = Rc::new(NodeNoNiching {
+            value: 1,
+            left: Some(no_niching_leaf.clone()),
+            right: None,
+        });
+        for value in 2..10 {
+            no_niching_root = Rc::new(NodeNoNiching {
+                value,
+                left: Some(no_niching_leaf.clone()),
+                right: Some(no_niching_root),
+            });
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&root).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Rc<Node>>(result.as_slice()) };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&no_niching_root).unwrap();
+        let no_niching_result = serializer.into_serializer().into_inner();
+
+        assert!(size_of::<Archived<Node>>() < size_of::<Archived<NodeNoNiching>>());
+        assert!(result.len() < no_niching_result.len());
+
+        // Walk the chain and check that the leaf was only archived once
+        let archived_leaf = archived.left.as_ref().unwrap();
+        assert!(archived_leaf.left.is_none());
+        assert!(archived_leaf.right.is_none());
+        let mut node = &**archived;
+        for value in (1..10).rev() {
+            assert_eq!(node.value, value);
+            assert!(::core::ptr::eq(
+                node.left.as_deref().unwrap(),
+                archived_leaf.get()
+            ));
+            match node.right.as_deref() {
+                Some(next) => node = next,
+                None => assert_eq!(value, 1),
+            }
+        }
+
+        let mut deserializer = DefaultDeserializer::default();
+        let deserialized: Rc<Node> = archived.deserialize(&mut deserializer).unwrap();
+        let deserialized_leaf = deserialized.left.as_ref().unwrap();
+        let mut node = &deserialized;
+        while let Some(next) = node.right.as_ref() {
+            assert!(Rc::ptr_eq(node.left.as_ref().unwrap(), deserialized_leaf));
+            node = next;
+        }
+        assert_eq!(node.value, 1);
+        assert!(Rc::ptr_eq(node.left.as_ref().unwrap(), deserialized_leaf));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_niche_nonzero() {
+        use ::core::{
+            mem::size_of,
+            num::{NonZeroI32, NonZeroI8, NonZeroIsize, NonZeroU32, NonZeroU8, NonZeroUsize},
+        };
+        use rkyv::{from_archived, with::Niche};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Niche)]
+            a: Option<NonZeroI8>,
+            #[with(Niche)]
+            b: Option<NonZeroI32>,
+            #[with(Niche)]
+            c: Option<NonZeroIsize>,
+            #[with(Niche)]
+            d: Option<NonZeroU8>,
+            #[with(Niche)]
+            e: Option<NonZeroU32>,
+            #[with(Niche)]
+            f: Option<NonZeroUsize>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct TestNoNiching {
+            a: Option<NonZeroI8>,
+            b: Option<NonZeroI32>,
+            c: Option<NonZeroIsize>,
+            d: Option<NonZeroU8>,
+            e: Option<NonZeroU32>,
+            f: Option<NonZeroUsize>,
+        }
+
+        let value = Test {
+            a: Some(NonZeroI8::new(10).unwrap()),
+            b: Some(NonZeroI32::new(10).unwrap()),
+            c: Some(NonZeroIsize::new(10).unwrap()),
+            d: Some(NonZeroU8::new(10).unwrap()),
+            e: Some(NonZeroU32::new(10).unwrap()),
+            f: Some(NonZeroUsize::new(10).unwrap()),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert!(archived.a.is_some());
+        assert_eq!(archived.a.as_ref().unwrap().get(), 10);
+        assert!(archived.b.is_some());
+        assert_eq!(from_archived!(*archived.b.as_ref().unwrap()).get(), 10);
+        assert!(archived.c.is_some());
+        assert_eq!(from_archived!(*archived.c.as_ref().unwrap()).get(), 10);
+        assert!(archived.d.is_some());
+        assert_eq!(archived.d.as_ref().unwrap().get(), 10);
+        assert!(archived.e.is_some());
+        assert_eq!(from_archived!(*archived.e.as_ref().unwrap()).get(), 10);
+        assert!(archived.f.is_some());
+        assert_eq!(from_archived!(*archived.f.as_ref().unwrap()).get(), 10);
+
+        let value = Test {
+            a: None,
+            b: None,
+            c: None,
+            d: None,
+            e: None,
+            f: None,
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
         let archived = unsafe { archived_root::<Test>(result.as_slice()) };
 
         assert!(archived.a.is_none());
@@ -1861,6 +3892,113 @@ mod tests {
         assert!(size_of::<Archived<Test>>() < size_of::<Archived<TestNoNiching>>());
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_nan_niche() {
+        use ::core::{alloc::Layout, mem::size_of, ptr::NonNull};
+        use rkyv::{
+            from_archived,
+            ser::{
+                serializers::{AllocScratch, CompositeSerializer},
+                ScratchSpace,
+            },
+            with::{Map, NanNiche, NanNicheError},
+        };
+
+        #[derive(Default)]
+        struct NanNicheSerializer {
+            inner: CompositeSerializer<AlignedSerializer<AlignedVec>, AllocScratch>,
+        }
+
+        impl Fallible for NanNicheSerializer {
+            type Error = NanNicheError;
+        }
+
+        impl Serializer for NanNicheSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).map_err(|_| unreachable!())
+            }
+        }
+
+        impl ScratchSpace for NanNicheSerializer {
+            unsafe fn push_scratch(
+                &mut self,
+                layout: Layout,
+            ) -> Result<NonNull<[u8]>, NanNicheError> {
+                Ok(self.inner.push_scratch(layout).unwrap())
+            }
+
+            unsafe fn pop_scratch(
+                &mut self,
+                ptr: NonNull<u8>,
+                layout: Layout,
+            ) -> Result<(), NanNicheError> {
+                self.inner.pop_scratch(ptr, layout).unwrap();
+                Ok(())
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(NanNiche)]
+            a: Option<f32>,
+            #[with(NanNiche)]
+            b: Option<f64>,
+            #[with(Map<NanNiche>)]
+            column: Vec<Option<f64>>,
+        }
+
+        #[derive(Archive)]
+        struct TestNoNiching {
+            _a: Option<f32>,
+            _b: Option<f64>,
+            _column: Vec<Option<f64>>,
+        }
+
+        let value = Test {
+            a: None,
+            b: Some(-0.5),
+            column: vec![Some(1.0), None, Some(f64::INFINITY), None],
+        };
+        let mut serializer = NanNicheSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.inner.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert!(from_archived!(archived.a).is_nan());
+        assert_eq!(from_archived!(archived.b), -0.5);
+        let column = &archived.column;
+        assert_eq!(from_archived!(column[0]), 1.0);
+        assert!(from_archived!(column[1]).is_nan());
+        assert_eq!(from_archived!(column[2]), f64::INFINITY);
+        assert!(from_archived!(column[3]).is_nan());
+        assert!(size_of::<Archived<Test>>() < size_of::<Archived<TestNoNiching>>());
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        // NaN is reserved for None
+        let mut serializer = NanNicheSerializer::default();
+        let result = serializer.serialize_value(&Test {
+            a: Some(f32::NAN),
+            b: None,
+            column: Vec::new(),
+        });
+        assert!(matches!(result, Err(NanNicheError::SomeNan)));
+
+        let mut serializer = NanNicheSerializer::default();
+        let result = serializer.serialize_value(&Test {
+            a: None,
+            b: None,
+            column: vec![Some(2.0), Some(-f64::NAN)],
+        });
+        assert!(matches!(result, Err(NanNicheError::SomeNan)));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_copy_optimize() {
@@ -1880,95 +4018,370 @@ mod tests {
         };
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_serializer().into_inner();
-        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert_eq!(archived.bytes, value.bytes);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.bytes, value.bytes);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_ref_as_box_copy_optimize() {
+        use rkyv::with::{CopyOptimize, RefAsBox};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test<'a> {
+            #[with(CopyOptimize, RefAsBox)]
+            bytes: &'a [u8],
+        }
+
+        let bytes = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let value = Test {
+            bytes: bytes.as_ref(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert_eq!(&*archived.bytes, value.bytes);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_raw() {
+        use rkyv::with::Raw;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Raw)]
+            bytes: Vec<u8>,
+        }
+
+        let value = Test {
+            bytes: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert_eq!(&*archived.bytes, value.bytes);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_unsafe() {
+        use core::cell::UnsafeCell;
+        use rkyv::{to_archived, with::Unsafe};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Unsafe)]
+            inner: UnsafeCell<u32>,
+        }
+
+        let value = Test {
+            inner: UnsafeCell::new(100),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut result = serializer.into_serializer().into_inner();
+        let bytes = unsafe { Pin::new_unchecked(result.as_mut_slice()) };
+        let archived = unsafe { archived_root_mut::<Test>(bytes) };
+
+        unsafe {
+            assert_eq!(*archived.inner.get(), 100);
+            *archived.inner.get() = to_archived!(42u32);
+            assert_eq!(*archived.inner.get(), 42);
+        }
+
+        let deserialized: Test = (&*archived)
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        unsafe {
+            assert_eq!(*deserialized.inner.get(), 42);
+            *deserialized.inner.get() = 88;
+            assert_eq!(*deserialized.inner.get(), 88);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_lock_ref_cell() {
+        use core::cell::RefCell;
+        use rkyv::with::{Lock, LockError};
+
+        struct LockSerializer(AlignedSerializer<AlignedVec>);
+
+        impl Fallible for LockSerializer {
+            type Error = LockError;
+        }
+
+        impl Serializer for LockSerializer {
+            fn pos(&self) -> usize {
+                self.0.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.0.write(bytes).map_err(|e| match e {})
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Lock)]
+            a: RefCell<u32>,
+            #[with(Lock)]
+            b: RefCell<[u32; 4]>,
+        }
+
+        let value = Test {
+            a: RefCell::new(10),
+            b: RefCell::new([1, 2, 3, 4]),
+        };
+        *value.a.borrow_mut() += 1;
+
+        let mut serializer = LockSerializer(AlignedSerializer::new(AlignedVec::new()));
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.0.into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert_eq!(*archived.a, 11);
+        assert_eq!(*archived.b, [1, 2, 3, 4]);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(*deserialized.a.borrow(), 11);
+        deserialized.b.borrow_mut()[0] = 5;
+        assert_eq!(*deserialized.b.borrow(), [5, 2, 3, 4]);
+
+        let _guard = value.b.borrow_mut();
+        let mut serializer = LockSerializer(AlignedSerializer::new(AlignedVec::new()));
+        assert!(matches!(
+            serializer.serialize_value(&value),
+            Err(LockError::Borrowed)
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_inline_str() {
+        use rkyv::{string::inline::InlineStrError, with::InlineStr};
+
+        struct InlineStrSerializer(AlignedSerializer<AlignedVec>);
+
+        impl Fallible for InlineStrSerializer {
+            type Error = InlineStrError;
+        }
+
+        impl Serializer for InlineStrSerializer {
+            fn pos(&self) -> usize {
+                self.0.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.0.write(bytes).map_err(|e| match e {})
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Record {
+            id: u8,
+            #[with(InlineStr<8>)]
+            name: String,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Borrowed<'a> {
+            #[with(InlineStr<8>)]
+            name: &'a str,
+        }
+
+        assert_eq!(core::mem::size_of::<ArchivedRecord>(), 10);
+
+        let value = [
+            Record {
+                id: 1,
+                name: String::new(),
+            },
+            Record {
+                id: 2,
+                name: "hello".to_string(),
+            },
+            Record {
+                id: 3,
+                name: "12345678".to_string(),
+            },
+        ];
+        let mut serializer = InlineStrSerializer(AlignedSerializer::new(AlignedVec::new()));
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.0.into_inner();
+        assert_eq!(buf.len(), 30);
+        let archived = unsafe { archived_root::<[Record; 3]>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+        assert_eq!(archived[1].name, "hello");
+        assert_eq!(archived[2].name.len(), 8);
 
-        assert_eq!(archived.bytes, value.bytes);
+        let deserialized: [Record; 3] = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
 
-        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
-        assert_eq!(deserialized.bytes, value.bytes);
+        let mut serializer = InlineStrSerializer(AlignedSerializer::new(AlignedVec::new()));
+        serializer
+            .serialize_value(&Borrowed { name: "world" })
+            .unwrap();
+        let buf = serializer.0.into_inner();
+        let archived = unsafe { archived_root::<Borrowed>(buf.as_ref()) };
+        assert_eq!(archived.name, "world");
+
+        let mut serializer = InlineStrSerializer(AlignedSerializer::new(AlignedVec::new()));
+        assert!(matches!(
+            serializer.serialize_value(&Record {
+                id: 4,
+                name: "123456789".to_string(),
+            }),
+            Err(InlineStrError::TooLong {
+                len: 9,
+                capacity: 8,
+            })
+        ));
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_ref_as_box_copy_optimize() {
-        use rkyv::with::{CopyOptimize, RefAsBox};
+    fn with_var_int() {
+        use rkyv::with::VarInt;
 
-        #[derive(Archive, Serialize, Deserialize)]
-        struct Test<'a> {
-            #[with(CopyOptimize, RefAsBox)]
-            bytes: &'a [u8],
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(VarInt)]
+            a: u16,
+            #[with(VarInt)]
+            b: u64,
+            #[with(VarInt)]
+            c: i32,
+            #[with(VarInt)]
+            d: u128,
+            #[with(VarInt)]
+            e: i128,
         }
 
-        let bytes = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
-        let value = Test {
-            bytes: bytes.as_ref(),
-        };
-        let mut serializer = DefaultSerializer::default();
-        serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_serializer().into_inner();
-        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+        let values = [
+            Test {
+                a: 0,
+                b: 0,
+                c: 0,
+                d: 0,
+                e: 0,
+            },
+            Test {
+                a: 300,
+                b: 1 << 35,
+                c: -1,
+                d: 1 << 100,
+                e: -(1 << 100),
+            },
+            Test {
+                a: u16::MAX,
+                b: u64::MAX,
+                c: i32::MIN,
+                d: u128::MAX,
+                e: i128::MIN,
+            },
+            Test {
+                a: u16::MAX,
+                b: u64::MAX,
+                c: i32::MAX,
+                d: u128::MAX,
+                e: i128::MAX,
+            },
+        ];
 
-        assert_eq!(&*archived.bytes, value.bytes);
-    }
+        for value in values {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+            assert_eq!(archived.a.get(), value.a);
+            assert_eq!(archived.b.get(), value.b);
+            assert_eq!(archived.c, value.c);
+            assert_eq!(archived.d, value.d);
+            assert_eq!(archived.e, value.e);
 
-    #[test]
-    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_raw() {
-        use rkyv::with::Raw;
+            let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, value);
+        }
 
-        #[derive(Archive, Serialize, Deserialize)]
-        struct Test {
-            #[with(Raw)]
-            bytes: Vec<u8>,
+        // Small values only take a single byte
+        #[derive(Archive, Serialize)]
+        struct Small {
+            #[with(VarInt)]
+            value: u128,
         }
 
-        let value = Test {
-            bytes: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
-        };
         let mut serializer = DefaultSerializer::default();
-        serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_serializer().into_inner();
-        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
-
-        assert_eq!(&*archived.bytes, value.bytes);
+        serializer.serialize_value(&Small { value: 127 }).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert_eq!(buf[0], 127);
+        assert_eq!(
+            buf.len(),
+            core::mem::size_of::<ArchivedSmall>() + core::mem::align_of::<ArchivedSmall>()
+        );
     }
 
     #[test]
+    #[cfg(feature = "rend")]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_unsafe() {
-        use core::cell::UnsafeCell;
-        use rkyv::{to_archived, with::Unsafe};
+    fn with_endian() {
+        use core::num::NonZeroU16;
+        use rkyv::with::{AsBigEndian, AsLittleEndian, Map};
 
-        #[derive(Archive, Serialize, Deserialize)]
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
         struct Test {
-            #[with(Unsafe)]
-            inner: UnsafeCell<u32>,
+            #[with(AsLittleEndian)]
+            le: u32,
+            #[with(AsBigEndian)]
+            be: u32,
+            #[with(AsBigEndian)]
+            nonzero: NonZeroU16,
+            #[with(Map<AsLittleEndian>)]
+            values: Vec<i64>,
         }
 
         let value = Test {
-            inner: UnsafeCell::new(100),
+            le: 0x01020304,
+            be: 0x01020304,
+            nonzero: NonZeroU16::new(0x0506).unwrap(),
+            values: vec![-1, 0x0708],
         };
+
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
-        let mut result = serializer.into_serializer().into_inner();
-        let bytes = unsafe { Pin::new_unchecked(result.as_mut_slice()) };
-        let archived = unsafe { archived_root_mut::<Test>(bytes) };
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
 
-        unsafe {
-            assert_eq!(*archived.inner.get(), 100);
-            *archived.inner.get() = to_archived!(42u32);
-            assert_eq!(*archived.inner.get(), 42);
+        // The byte order doesn't depend on the target or the endianness features
+        fn bytes<T>(value: &T) -> &[u8] {
+            unsafe {
+                core::slice::from_raw_parts((value as *const T).cast(), core::mem::size_of::<T>())
+            }
         }
+        assert_eq!(bytes(&archived.le), &[0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(bytes(&archived.be), &[0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(bytes(&archived.nonzero), &[0x05, 0x06]);
+        assert_eq!(bytes(&archived.values[0]), &[0xff; 8]);
+        assert_eq!(bytes(&archived.values[1]), &[0x08, 0x07, 0, 0, 0, 0, 0, 0]);
 
-        let deserialized: Test = (&*archived)
-            .deserialize(&mut DefaultDeserializer::default())
-            .unwrap();
-        unsafe {
-            assert_eq!(*deserialized.inner.get(), 42);
-            *deserialized.inner.get() = 88;
-            assert_eq!(*deserialized.inner.get(), 88);
-        }
+        assert_eq!(archived.le.value(), value.le);
+        assert_eq!(archived.be.value(), value.be);
+        assert_eq!(archived.nonzero.value(), value.nonzero);
+        assert_eq!(archived.values[1].value(), 0x0708);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
@@ -2096,6 +4509,49 @@ mod tests {
         assert_eq!(value, deserialized);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_map_custom_order() {
+        use core::cmp::Ordering;
+
+        #[derive(Archive, Serialize, Debug, PartialEq, Eq)]
+        #[archive_attr(derive(Debug, PartialEq, Eq, PartialOrd, Ord))]
+        struct Reversed(u32);
+
+        impl PartialOrd for Reversed {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for Reversed {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other.0.cmp(&self.0)
+            }
+        }
+
+        let mut value = BTreeMap::new();
+        for i in 0..1000 {
+            value.insert(Reversed(i * 2), i);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<BTreeMap<Reversed, u32>>(buf.as_ref()) };
+
+        // Entries are stored in the order of the unarchived keys
+        assert_eq!(archived.iter().next().unwrap().0, &ArchivedReversed(1998));
+
+        for i in 0..1000 {
+            let key = i * 2;
+            let found = archived.get_key_value_by(|probe| key.cmp(&probe.0));
+            assert_eq!(found, Some((&ArchivedReversed(key), &i)));
+            assert!(!archived.contains_key_by(|probe| (key + 1).cmp(&probe.0)));
+        }
+        assert_eq!(archived.get_by(|probe| 10.cmp(&probe.0)), Some(&5));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_zst_containers() {
@@ -2191,6 +4647,211 @@ mod tests {
         assert_ne!(tracker.min_buffer_size(), 0);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn scratch_tracker_finish() {
+        use core::alloc::Layout;
+        use rkyv::ser::{
+            serializers::{AllocScratch, CompositeSerializer, ScratchTracker},
+            ScratchSpace,
+        };
+
+        // Allocates scratch space without ever returning it
+        struct Leaky;
+
+        impl Archive for Leaky {
+            type Archived = ();
+            type Resolver = ();
+
+            unsafe fn resolve(&self, _: usize, _: Self::Resolver, _: *mut Self::Archived) {}
+        }
+
+        impl<S: ScratchSpace + ?Sized> Serialize<S> for Leaky {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    serializer.push_scratch(Layout::new::<[u64; 2]>())?;
+                }
+                Ok(())
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Test<T> {
+            values: Vec<Vec<u32>>,
+            inner: T,
+        }
+
+        fn serialize<T>(value: &Test<T>) -> ScratchTracker<AllocScratch>
+        where
+            Test<T>: Serialize<
+                CompositeSerializer<
+                    AlignedSerializer<AlignedVec>,
+                    ScratchTracker<AllocScratch>,
+                    Infallible,
+                >,
+            >,
+        {
+            let mut serializer = CompositeSerializer::new(
+                AlignedSerializer::<AlignedVec>::default(),
+                ScratchTracker::new(AllocScratch::default()),
+                Infallible,
+            );
+            serializer
+                .serialize_value(value)
+                .expect("failed to serialize value");
+            serializer.into_components().1
+        }
+
+        // Balanced scratch usage is accepted
+        let tracker = serialize(&Test {
+            values: vec![vec![1, 2], vec![3, 4]],
+            inner: (),
+        });
+        assert_ne!(tracker.max_allocations(), 0);
+        assert_eq!(tracker.allocations(), 0);
+        assert_eq!(tracker.bytes_allocated(), 0);
+        tracker.finish().unwrap();
+
+        // Leaked scratch space is reported
+        let tracker = serialize(&Test {
+            values: vec![vec![1, 2], vec![3, 4]],
+            inner: Leaky,
+        });
+        assert_eq!(tracker.allocations(), 1);
+        assert_eq!(tracker.bytes_allocated(), 16);
+        let error = tracker.finish().unwrap_err();
+        assert_eq!(error.bytes, 16);
+        assert_eq!(error.allocations, 1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn reserved_scratch() {
+        use rkyv::ser::serializers::{
+            AllocScratch, CompositeSerializer, DynamicHeapScratch, FallbackScratch, ScratchTracker,
+        };
+
+        let value = vec![vec![1, 2], vec![3, 4], vec![5, 6, 7]];
+
+        fn serialize_with(
+            value: &Vec<Vec<i32>>,
+            scratch: DynamicHeapScratch,
+        ) -> ScratchTracker<AllocScratch> {
+            let mut serializer = CompositeSerializer::new(
+                AlignedSerializer::<AlignedVec>::default(),
+                FallbackScratch::new(scratch, ScratchTracker::new(AllocScratch::default())),
+                Infallible,
+            );
+            serializer
+                .serialize_value(value)
+                .expect("failed to serialize value");
+            serializer.into_components().1.into_components().1
+        }
+
+        // Without any capacity, all scratch space falls back to the heap
+        let fallback = serialize_with(&value, DynamicHeapScratch::new());
+        assert_ne!(fallback.max_allocations(), 0);
+
+        // Measure the required scratch space, then reserve it up front
+        let mut serializer = CompositeSerializer::new(
+            AlignedSerializer::<AlignedVec>::default(),
+            ScratchTracker::new(AllocScratch::default()),
+            Infallible,
+        );
+        serializer
+            .serialize_value(&value)
+            .expect("failed to serialize value");
+        let tracker = serializer.into_components().1;
+        let mut scratch = DynamicHeapScratch::new();
+        scratch.reserve(tracker.min_buffer_size());
+        assert!(scratch.capacity() >= tracker.min_buffer_size());
+
+        let fallback = serialize_with(&value, scratch);
+        assert_eq!(fallback.max_allocations(), 0);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn composite_serializer_errors() {
+        use rkyv::ser::{
+            serializers::{
+                AllocScratch, AllocScratchError, BufferScratch, BufferSerializerError,
+                CompositeSerializer, CompositeSerializerError, FixedSizeScratchError,
+                SharedSerializeMap, SharedSerializeMapError,
+            },
+            SharedSerializeRegistry,
+        };
+
+        let value = vec!["hello world".to_string(); 16];
+
+        // Out of scratch space with an allocation limit
+        let mut serializer = CompositeSerializer::new(
+            AlignedSerializer::new(AlignedVec::new()),
+            AllocScratch::with_limit(16),
+            Infallible,
+        );
+        match serializer.serialize_value(&value) {
+            Err(CompositeSerializerError::ScratchSpaceError(
+                AllocScratchError::ExceededLimit {
+                    requested,
+                    remaining,
+                },
+            )) => {
+                assert!(requested > remaining);
+                assert_eq!(remaining, 16);
+            }
+            result => panic!("expected scratch space to be exhausted, got {:?}", result),
+        }
+
+        // Out of scratch space with a fixed-size buffer
+        let mut serializer = CompositeSerializer::new(
+            AlignedSerializer::new(AlignedVec::new()),
+            BufferScratch::new(AlignedBytes([0u8; 16])),
+            Infallible,
+        );
+        match serializer.serialize_value(&value) {
+            Err(CompositeSerializerError::ScratchSpaceError(
+                FixedSizeScratchError::OutOfScratch(layout),
+            )) => {
+                assert!(layout.size() > 16);
+            }
+            result => panic!("expected scratch space to be exhausted, got {:?}", result),
+        }
+
+        // The underlying serializer ran out of room
+        let mut serializer = CompositeSerializer::new(
+            BufferSerializer::new(AlignedBytes([0u8; 16])),
+            AllocScratch::new(),
+            Infallible,
+        );
+        match serializer.serialize_value(&value) {
+            Err(CompositeSerializerError::SerializerError(BufferSerializerError::Overflow {
+                archive_len,
+                ..
+            })) => {
+                assert_eq!(archive_len, 16);
+            }
+            result => panic!("expected serializer to overflow, got {:?}", result),
+        }
+
+        // A shared pointer was registered twice
+        let mut serializer = CompositeSerializer::new(
+            AlignedSerializer::new(AlignedVec::new()),
+            Infallible,
+            SharedSerializeMap::new(),
+        );
+        let ptr = &value as *const Vec<String> as *const u8;
+        serializer.add_shared_ptr(ptr, 0).unwrap();
+        match serializer.add_shared_ptr(ptr, 0) {
+            Err(CompositeSerializerError::SharedError(
+                SharedSerializeMapError::DuplicateSharedPointer(p),
+            )) => {
+                assert_eq!(p, ptr);
+            }
+            result => panic!("expected duplicate shared pointer error, got {:?}", result),
+        }
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_manually_drop() {
@@ -2210,4 +4871,199 @@ mod tests {
 
         drop(ManuallyDrop::into_inner(vec));
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(feature = "debug")]
+    fn dump_archive_layout() {
+        use rkyv::dump::dump_layout;
+
+        #[derive(Archive, Serialize)]
+        #[archive(dump_layout)]
+        enum Shape {
+            Point,
+            Circle { radius: f32 },
+            Polygon(Vec<(i16, i16)>),
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(dump_layout)]
+        struct Id(u32);
+
+        #[derive(Archive, Serialize)]
+        #[archive(dump_layout)]
+        struct Drawing<T> {
+            id: Id,
+            name: String,
+            shapes: Vec<Shape>,
+            parent: Option<Box<T>>,
+        }
+
+        let value = Drawing {
+            id: Id(42),
+            name: "a drawing with a long name".to_string(),
+            shapes: vec![Shape::Point, Shape::Circle { radius: 1.5 }],
+            parent: Some(Box::new(7u8)),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Drawing<u8>>(buf.as_ref()) };
+
+        let mut dump = String::new();
+        dump_layout(buf.as_ref(), archived, &mut dump).unwrap();
+
+        let offset = |value: *const u8| value as usize - buf.as_ptr() as usize;
+        let lines = dump.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            format!(
+                "root: ArchivedDrawing<u8> @ {:#x} ({} bytes)",
+                offset((archived as *const ArchivedDrawing<u8>).cast()),
+                core::mem::size_of::<ArchivedDrawing<u8>>(),
+            ),
+        );
+        assert_eq!(
+            lines[1],
+            format!(
+                "  id: ArchivedId @ {:#x} (4 bytes)",
+                offset((&archived.id as *const ArchivedId).cast()),
+            ),
+        );
+        assert_eq!(
+            lines[2],
+            format!(
+                "    0: u32 @ {:#x} (4 bytes) = 42",
+                offset((&archived.id as *const ArchivedId).cast()),
+            ),
+        );
+        assert!(lines[4].ends_with("= \"a drawing with a long name\""));
+        assert!(lines[7].starts_with("      [0]: ArchivedShape::Point @ "));
+        assert!(lines[8].starts_with("      [1]: ArchivedShape::Circle @ "));
+        assert!(lines[9].starts_with("        radius: f32 @ "));
+        assert!(lines[9].ends_with("(4 bytes) = 1.5"));
+        assert!(lines[10].starts_with("  parent: ArchivedOption<ArchivedBox<u8>>::Some @ "));
+        assert_eq!(
+            lines[12],
+            format!(
+                "      *: u8 @ {:#x} (1 bytes) = 7",
+                offset(archived.parent.as_ref().unwrap().get()),
+            ),
+        );
+        assert_eq!(lines.len(), 13);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(feature = "reflect")]
+    fn reflect_archived_fields() {
+        use core::fmt::Write;
+        use rkyv::reflect::{ReflectArchived, ReflectValue, Value};
+
+        #[derive(Archive, Serialize)]
+        #[archive(reflect)]
+        enum Shape {
+            Point,
+            Circle { radius: f32 },
+            Polygon(Vec<i16>),
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(reflect)]
+        struct Id(u32);
+
+        #[derive(Archive, Serialize)]
+        #[archive(reflect)]
+        struct Drawing<T> {
+            id: Id,
+            name: String,
+            visible: bool,
+            offset: i64,
+            initial: char,
+            shapes: Vec<Shape>,
+            parent: Option<Box<T>>,
+            origin: [u8; 2],
+        }
+
+        fn write_value(value: &dyn ReflectValue, out: &mut String) {
+            match value.reflect() {
+                Value::Unit => out.push_str("()"),
+                Value::Bool(b) => write!(out, "{}", b).unwrap(),
+                Value::Int(i) => write!(out, "{}", i).unwrap(),
+                Value::Uint(u) => write!(out, "{}", u).unwrap(),
+                Value::Float(x) => write!(out, "{}", x).unwrap(),
+                Value::Char(c) => write!(out, "{:?}", c).unwrap(),
+                Value::Str(s) => write!(out, "{:?}", s).unwrap(),
+                Value::Seq(seq) => {
+                    out.push('[');
+                    for i in 0..seq.len() {
+                        if i != 0 {
+                            out.push_str(", ");
+                        }
+                        write_value(seq.get(i).unwrap(), out);
+                    }
+                    out.push(']');
+                    assert!(seq.get(seq.len()).is_none());
+                }
+                Value::Option(None) => out.push_str("None"),
+                Value::Option(Some(value)) => {
+                    out.push_str("Some(");
+                    write_value(value, out);
+                    out.push(')');
+                }
+                Value::Struct(value) => {
+                    out.push_str(value.type_name());
+                    if let Some(variant) = value.variant_name() {
+                        write!(out, "::{}", variant).unwrap();
+                    }
+                    out.push_str(" {");
+                    let mut first = true;
+                    value.for_each_field(&mut |name, field| {
+                        out.push_str(if first { " " } else { ", " });
+                        first = false;
+                        write!(out, "{}: ", name).unwrap();
+                        write_value(field, out);
+                    });
+                    out.push_str(" }");
+                }
+            }
+        }
+
+        let value = Drawing {
+            id: Id(42),
+            name: "sketch".to_string(),
+            visible: true,
+            offset: -7,
+            initial: 's',
+            shapes: vec![
+                Shape::Point,
+                Shape::Circle { radius: 1.5 },
+                Shape::Polygon(vec![0, 1, -1]),
+            ],
+            parent: Some(Box::new(())),
+            origin: [3, 4],
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Drawing<()>>(buf.as_ref()) };
+
+        let mut names = Vec::new();
+        archived.for_each_field(&mut |name, _| names.push(name.to_string()));
+        assert_eq!(
+            names,
+            ["id", "name", "visible", "offset", "initial", "shapes", "parent", "origin"],
+        );
+
+        let mut out = String::new();
+        write_value(archived, &mut out);
+        assert_eq!(
+            out,
+            "ArchivedDrawing { id: ArchivedId { 0: 42 }, name: \"sketch\", visible: true, \
+             offset: -7, initial: 's', shapes: [ArchivedShape::Point { }, \
+             ArchivedShape::Circle { radius: 1.5 }, \
+             ArchivedShape::Polygon { 0: [0, 1, -1] }], parent: Some(()), \
+             origin: [3, 4] }",
+        );
+    }
 }
@@ -4,7 +4,7 @@ mod tests {
     use rkyv::{
         archived_root,
         ser::{serializers::WriteSerializer, Serializer},
-        AlignedBytes, Archive, Deserialize, Serialize,
+        AlignedBytes, Archive, Deserialize, Infallible, Serialize,
     };
     use std::collections::{HashMap, HashSet};
 
@@ -66,6 +66,68 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_keys_values() {
+        let mut hash_map = HashMap::new();
+        hash_map.insert("hello".to_string(), 1u32);
+        hash_map.insert("foo".to_string(), 2u32);
+        hash_map.insert("baz".to_string(), 3u32);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived_value = unsafe { archived_root::<HashMap<String, u32>>(buf.as_ref()) };
+
+        assert_eq!(archived_value.keys().len(), hash_map.len());
+        assert_eq!(archived_value.values().len(), hash_map.len());
+        assert!(archived_value
+            .keys()
+            .zip(archived_value.values())
+            .eq(archived_value.iter()));
+
+        let mut keys = archived_value
+            .keys()
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(keys, ["baz", "foo", "hello"]);
+
+        for value in archived_value.values() {
+            assert!(hash_map.values().any(|v| value == v));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_map_mutable_values() {
+        use core::pin::Pin;
+        use rkyv::archived_root_mut;
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert("hello".to_string(), 1u32);
+        hash_map.insert("foo".to_string(), 2u32);
+        hash_map.insert("baz".to_string(), 3u32);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let mut archived_value =
+            unsafe { archived_root_mut::<HashMap<String, u32>>(Pin::new(buf.as_mut_slice())) };
+
+        for mut value in archived_value.as_mut().values_pin() {
+            *value += 1;
+        }
+        for (key, mut value) in archived_value.as_mut().iter_pin() {
+            *value += key.len() as u32;
+        }
+
+        assert_eq!(archived_value.len(), hash_map.len());
+        for (key, value) in hash_map.iter() {
+            assert_eq!(archived_value[key.as_str()], value + 1 + key.len() as u32);
+        }
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     #[allow(deprecated)]
@@ -106,6 +168,318 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_sorted_vec() {
+        use rkyv::with::AsSortedVec;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(AsSortedVec)]
+            values: HashMap<String, u32>,
+        }
+
+        let names = ["hello", "world", "foo", "bar", "baz", "bat"];
+
+        let forward = Test {
+            values: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.to_string(), i as u32))
+                .collect(),
+        };
+        let mut backward = Test {
+            values: HashMap::with_capacity(64),
+        };
+        for (i, name) in names.iter().enumerate().rev() {
+            backward.values.insert(name.to_string(), i as u32);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&forward).unwrap();
+        let forward_bytes = serializer.into_serializer().into_inner();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&backward).unwrap();
+        let backward_bytes = serializer.into_serializer().into_inner();
+
+        assert_eq!(forward_bytes.as_slice(), backward_bytes.as_slice());
+
+        let archived = unsafe { archived_root::<Test>(forward_bytes.as_ref()) };
+        assert_eq!(archived.values.len(), names.len());
+        for (i, name) in names.iter().enumerate() {
+            assert_eq!(archived.values.get(*name), Some(&(i as u32)));
+        }
+        assert_eq!(archived.values.get("missing"), None);
+
+        let keys = archived
+            .values
+            .iter()
+            .map(|(k, _)| k.as_str())
+            .collect::<Vec<_>>();
+        let mut sorted = names.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(keys, sorted);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, forward);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_map_as_soa() {
+        use rkyv::with::MapAsSoA;
+        use std::collections::BTreeMap;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(MapAsSoA)]
+            hash_map: HashMap<String, u32>,
+            #[with(MapAsSoA)]
+            btree_map: BTreeMap<u32, f32>,
+        }
+
+        let names = ["hello", "world", "foo", "bar", "baz", "bat"];
+
+        let value = Test {
+            hash_map: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.to_string(), i as u32))
+                .collect(),
+            btree_map: (0..10).map(|i| (i * 3, i as f32 / 2.0)).collect(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.hash_map.len(), names.len());
+        for (i, name) in names.iter().enumerate() {
+            assert_eq!(archived.hash_map.get(*name), Some(&(i as u32)));
+        }
+        assert_eq!(archived.hash_map.get("missing"), None);
+
+        let mut sorted = names.to_vec();
+        sorted.sort_unstable();
+        let keys = archived
+            .hash_map
+            .keys_slice()
+            .iter()
+            .map(|k| k.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, sorted);
+        for (key, index) in archived.hash_map.iter() {
+            assert_eq!(names[*index as usize], key.as_str());
+        }
+
+        assert_eq!(archived.btree_map.get(&9), Some(&1.5));
+        assert!(!archived.btree_map.contains_key(&10));
+        let sum = archived.btree_map.values_slice().iter().sum::<f32>();
+        assert_eq!(sum, value.btree_map.values().sum::<f32>());
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_hash_map_and_btree_map() {
+        use rkyv::with::{AsBTreeMap, AsHashMap};
+        use std::collections::BTreeMap;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Old {
+            by_name: HashMap<String, u32>,
+            by_id: BTreeMap<u32, String>,
+        }
+
+        // The containers are swapped, but the archived types stay the same
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct New {
+            #[with(AsHashMap)]
+            by_name: BTreeMap<String, u32>,
+            #[with(AsBTreeMap)]
+            by_id: HashMap<u32, String>,
+        }
+
+        let old = Old {
+            by_name: (0..50).map(|i| (format!("name-{}", i), i)).collect(),
+            by_id: (0..50).map(|i| (i, format!("name-{}", i))).collect(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&old).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        // Archives of the old model can be read into the new model
+        let archived = unsafe { archived_root::<New>(buf.as_ref()) };
+        assert_eq!(archived.by_name.get("name-7"), Some(&7));
+        assert_eq!(archived.by_id.get(&7).map(|s| s.as_str()), Some("name-7"));
+        let new: New = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(new.by_name.len(), old.by_name.len());
+        for (name, id) in old.by_name.iter() {
+            assert_eq!(new.by_name.get(name), Some(id));
+        }
+        assert_eq!(new.by_id.len(), old.by_id.len());
+        for (id, name) in old.by_id.iter() {
+            assert_eq!(new.by_id.get(id), Some(name));
+        }
+
+        // And archives of the new model can be read back into the old model
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&new).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Old>(buf.as_ref()) };
+        let deserialized: Old = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, old);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_hash_algorithms() {
+        use core::{
+            hash::{Hash, Hasher},
+            mem::size_of,
+        };
+        use rkyv::{
+            collections::hash_index::HashAlgorithm,
+            with::{SeaHash, SipHash},
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            default: HashMap<String, u32>,
+            #[with(SeaHash)]
+            sea: HashMap<String, u32>,
+            #[with(SipHash)]
+            sip: HashMap<String, u32>,
+            #[with(SipHash)]
+            sip_set: HashSet<u32>,
+            #[with(SipHash)]
+            empty: HashMap<String, u32>,
+        }
+
+        let values = (0..100)
+            .map(|i| (format!("key-{}", i), i))
+            .collect::<HashMap<_, _>>();
+        let value = Test {
+            default: values.clone(),
+            sea: values.clone(),
+            sip: values,
+            sip_set: (0..100).collect(),
+            empty: HashMap::new(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.default.hash_algorithm(), HashAlgorithm::SeaHash);
+        assert_eq!(archived.sea.hash_algorithm(), HashAlgorithm::SeaHash);
+        assert_eq!(archived.sip.hash_algorithm(), HashAlgorithm::SipHash);
+        assert_eq!(archived.sip_set.hash_algorithm(), HashAlgorithm::SipHash);
+        assert_eq!(archived.empty.hash_algorithm(), HashAlgorithm::SipHash);
+
+        // The maps use different hash functions
+        let hash = |algorithm: HashAlgorithm| {
+            let mut hasher = algorithm.hasher();
+            "key-0".hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_ne!(hash(HashAlgorithm::SeaHash), hash(HashAlgorithm::SipHash));
+
+        // Recording the hash algorithm doesn't take up any space
+        assert_eq!(
+            size_of::<rkyv::collections::hash_index::ArchivedHashIndex>(),
+            size_of::<rkyv::Archived<usize>>() + size_of::<rkyv::RawRelPtr>(),
+        );
+
+        // But lookups pick the matching one
+        for (key, value) in value.sip.iter() {
+            assert_eq!(archived.default.get(key.as_str()), Some(value));
+            assert_eq!(archived.sea.get(key.as_str()), Some(value));
+            assert_eq!(archived.sip.get(key.as_str()), Some(value));
+            assert!(archived.sip_set.contains(value));
+        }
+        assert_eq!(archived.sip.get("key-100"), None);
+        assert!(!archived.sip_set.contains(&100));
+        assert_eq!(archived.empty.get("key-0"), None);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg(feature = "size_16")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_index_max_len() {
+        use rkyv::{
+            collections::hash_index::{ArchivedHashIndex, HashIndexLenError},
+            ser::serializers::CompositeSerializerError,
+        };
+
+        // The highest bit of the length records the hash algorithm
+        assert_eq!(ArchivedHashIndex::MAX_LEN, 0x7f_ff);
+
+        let value = (0..=ArchivedHashIndex::MAX_LEN as u16).collect::<HashSet<_>>();
+        let mut serializer = DefaultSerializer::default();
+        match serializer.serialize_value(&value) {
+            Err(CompositeSerializerError::HashIndexError(HashIndexLenError { len })) => {
+                assert_eq!(len, ArchivedHashIndex::MAX_LEN + 1);
+            }
+            _ => panic!("expected the hash set to be too long"),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_bloom_filter() {
+        use rkyv::with::AsBloomFilter;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(AsBloomFilter)]
+            seen: HashSet<String>,
+            #[with(AsBloomFilter)]
+            empty: HashSet<u32>,
+        }
+
+        let value = Test {
+            seen: (0..1000).map(|i| format!("seen-{}", i)).collect(),
+            empty: HashSet::new(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        // Ten bits per element
+        assert_eq!(archived.seen.num_bits(), 10_000);
+        for key in value.seen.iter() {
+            assert!(archived.seen.probably_contains(key.as_str()));
+        }
+        let false_positives = (0..1000)
+            .filter(|i| {
+                archived
+                    .seen
+                    .probably_contains(format!("unseen-{}", i).as_str())
+            })
+            .count();
+        assert!(false_positives < 50);
+
+        assert!(archived.empty.is_empty());
+        assert!(!archived.empty.probably_contains(&0));
+
+        // The elements can't be recovered
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert!(deserialized.seen.is_empty());
+        assert!(deserialized.empty.is_empty());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_hash_set() {
@@ -222,61 +596,228 @@ mod tests {
         test_archive(&value);
     }
 
-    // TODO: figure out errors
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn format_archived() {
+        use std::net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6};
 
-    // #[test]
-    // #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    // fn mutex() {
-    //     use rkyv::with::Lock;
-    //     use std::sync::Mutex;
+        #[derive(Archive, Serialize)]
+        struct Test {
+            name: String,
+            tags: Vec<String>,
+            scores: Vec<Option<u32>>,
+            boxed: Box<str>,
+            ip: IpAddr,
+            sock: SocketAddr,
+        }
 
-    //     #[derive(Archive, Serialize, Deserialize)]
-    //     struct Test {
-    //         #[with(Lock)]
-    //         value: Mutex<i32>,
-    //     }
+        let value = Test {
+            name: "a string that's \"quoted\"".to_string(),
+            tags: vec!["hello".to_string(), "world".to_string()],
+            scores: vec![Some(1), None, Some(3)],
+            boxed: "boxed".into(),
+            ip: IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+            sock: SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0)),
+        };
 
-    //     let value = Test {
-    //         value: Mutex::new(10),
-    //     };
-    //     let mut serializer = AlignedSerializer::new(AlignedVec::new());
-    //     serializer.serialize_value(&value).unwrap();
-    //     let result = serializer.into_inner();
-    //     let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        assert_eq!(format!("{}", archived.name), value.name);
+        assert_eq!(format!("{:?}", archived.name), format!("{:?}", value.name));
+        assert_eq!(format!("{:?}", archived.tags), format!("{:?}", value.tags));
+        assert_eq!(
+            format!("{:?}", archived.scores),
+            format!("{:?}", value.scores)
+        );
+        assert_eq!(format!("{}", archived.boxed), "boxed");
+        assert_eq!(format!("{}", archived.ip), value.ip.to_string());
+        assert_eq!(format!("{}", archived.sock), "[::1]:8080");
+    }
 
-    //     assert_eq!(*archived.value, 10);
+    mod lock {
+        use rkyv::{
+            ser::{
+                serializers::{AlignedSerializer, AllocScratch, CompositeSerializer},
+                ScratchSpace, Serializer,
+            },
+            with::LockError,
+            AlignedVec, Fallible,
+        };
+        use std::{alloc::Layout, ptr::NonNull};
 
-    //     let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        #[derive(Default)]
+        pub struct LockSerializer {
+            inner: CompositeSerializer<AlignedSerializer<AlignedVec>, AllocScratch>,
+        }
 
-    //     assert_eq!(*deserialized.value.lock().unwrap(), 10);
-    // }
+        impl LockSerializer {
+            pub fn into_inner(self) -> AlignedVec {
+                self.inner.into_serializer().into_inner()
+            }
+        }
 
-    // #[test]
-    // #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    // fn rwlock() {
-    //     use rkyv::with::Lock;
-    //     use std::sync::RwLock;
+        impl Fallible for LockSerializer {
+            type Error = LockError;
+        }
 
-    //     #[derive(Archive, Serialize, Deserialize)]
-    //     struct Test {
-    //         #[with(Lock)]
-    //         value: RwLock<i32>,
-    //     }
+        impl Serializer for LockSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
 
-    //     let value = Test {
-    //         value: RwLock::new(10),
-    //     };
-    //     let mut serializer = AlignedSerializer::new(AlignedVec::new());
-    //     serializer.serialize_value(&value).unwrap();
-    //     let result = serializer.into_inner();
-    //     let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).map_err(|_| unreachable!())
+            }
+        }
 
-    //     assert_eq!(*archived.value, 10);
+        impl ScratchSpace for LockSerializer {
+            unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, LockError> {
+                Ok(self.inner.push_scratch(layout).unwrap())
+            }
+
+            unsafe fn pop_scratch(
+                &mut self,
+                ptr: NonNull<u8>,
+                layout: Layout,
+            ) -> Result<(), LockError> {
+                self.inner.pop_scratch(ptr, layout).unwrap();
+                Ok(())
+            }
+        }
+    }
 
-    //     let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_mutex() {
+        use lock::LockSerializer;
+        use rkyv::with::Lock;
+        use std::sync::Mutex;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Lock)]
+            value: Mutex<i32>,
+            #[with(Lock)]
+            names: Mutex<Vec<String>>,
+        }
 
-    //     assert_eq!(*deserialized.value.read().unwrap(), 10);
-    // }
+        let value = Test {
+            value: Mutex::new(10),
+            names: Mutex::new(vec!["a".to_string(), "b".to_string()]),
+        };
+        *value.value.lock().unwrap() += 1;
+
+        let mut serializer = LockSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        // The archived value is just the inner archived value
+        assert_eq!(*archived.value, 11);
+        assert_eq!(*archived.names, ["a", "b"]);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(*deserialized.value.lock().unwrap(), 11);
+        assert_eq!(*deserialized.names.lock().unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_rw_lock() {
+        use lock::LockSerializer;
+        use rkyv::with::Lock;
+        use std::sync::RwLock;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Lock)]
+            value: RwLock<i32>,
+            #[with(Lock)]
+            names: RwLock<Vec<String>>,
+        }
+
+        let value = Test {
+            value: RwLock::new(10),
+            names: RwLock::new(vec!["a".to_string(), "b".to_string()]),
+        };
+
+        // Serializing only needs shared access
+        let _guard = value.names.read().unwrap();
+        let mut serializer = LockSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        assert_eq!(*archived.value, 10);
+        assert_eq!(*archived.names, ["a", "b"]);
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(*deserialized.value.read().unwrap(), 10);
+        assert_eq!(*deserialized.names.read().unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_poisoned_lock() {
+        use lock::LockSerializer;
+        use rkyv::with::{Lock, LockError};
+        use std::{
+            panic,
+            sync::{Mutex, RwLock},
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Lock)]
+            mutex: Mutex<i32>,
+            #[with(Lock)]
+            rw_lock: RwLock<i32>,
+        }
+
+        let value = Test {
+            mutex: Mutex::new(10),
+            rw_lock: RwLock::new(10),
+        };
+        let _ = panic::catch_unwind(|| {
+            let _mutex_guard = value.mutex.lock().unwrap();
+            panic!("poison the mutex");
+        });
+        assert!(value.mutex.is_poisoned());
+
+        let mut serializer = LockSerializer::default();
+        assert!(matches!(
+            serializer.serialize_value(&value),
+            Err(LockError::Poisoned)
+        ));
+
+        value.mutex.clear_poison();
+        let _ = panic::catch_unwind(|| {
+            let _rw_lock_guard = value.rw_lock.write().unwrap();
+            panic!("poison the rw lock");
+        });
+        assert!(value.rw_lock.is_poisoned());
+
+        let mut serializer = LockSerializer::default();
+        assert!(matches!(
+            serializer.serialize_value(&value),
+            Err(LockError::Poisoned)
+        ));
+
+        // Deserializing creates new locks that aren't poisoned
+        value.rw_lock.clear_poison();
+        let mut serializer = LockSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert!(!deserialized.mutex.is_poisoned());
+        assert!(!deserialized.rw_lock.is_poisoned());
+        assert_eq!(*deserialized.mutex.lock().unwrap(), 10);
+        assert_eq!(*deserialized.rw_lock.read().unwrap(), 10);
+    }
 
     // #[test]
     // #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
@@ -364,4 +905,183 @@ mod tests {
         value.insert(());
         test_archive(&value);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_shared_arc_unsized() {
+        use std::sync::Arc;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            names: Vec<Arc<str>>,
+            values: Vec<Arc<[u32]>>,
+        }
+
+        let name = Arc::<str>::from("a shared string that is only stored once");
+        let values = Arc::<[u32]>::from(vec![31, 41, 59, 26]);
+        let value = Test {
+            names: vec![name.clone(); 4],
+            values: vec![values.clone(), values],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+
+        // Every pointer shares a single copy of the payload
+        let bytes = name.as_bytes();
+        assert_eq!(buf.windows(bytes.len()).filter(|w| *w == bytes).count(), 1);
+        for archived_name in archived.names.iter() {
+            assert_eq!(archived_name.len(), name.len());
+            assert_eq!(archived_name.as_ptr(), archived.names[0].as_ptr());
+        }
+        assert_eq!(archived.values[0].as_ptr(), archived.values[1].as_ptr());
+
+        let deserialized: Test = archived
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        assert_eq!(deserialized, value);
+        assert!(Arc::ptr_eq(&deserialized.names[0], &deserialized.names[3]));
+        assert!(Arc::ptr_eq(
+            &deserialized.values[0],
+            &deserialized.values[1]
+        ));
+        assert_eq!(Arc::strong_count(&deserialized.names[0]), 4);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_vec_of_shared_arcs() {
+        use std::{collections::HashSet, sync::Arc};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Node {
+            id: u32,
+            name: String,
+        }
+
+        let nodes = (0..8)
+            .map(|id| {
+                Arc::new(Node {
+                    id,
+                    name: format!("node number {} with a long name", id),
+                })
+            })
+            .collect::<Vec<_>>();
+        let value = (0..256)
+            .map(|i| nodes[(i * 5) % nodes.len()].clone())
+            .collect::<Vec<_>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Arc<Node>>>(buf.as_ref()) };
+        assert_eq!(archived.len(), value.len());
+
+        // Each unique node is stored once and every element points at that single copy
+        for node in nodes.iter() {
+            let bytes = node.name.as_bytes();
+            assert_eq!(buf.windows(bytes.len()).filter(|w| *w == bytes).count(), 1);
+        }
+        for (archived_node, node) in archived.iter().zip(value.iter()) {
+            assert_eq!(&**archived_node, &**node);
+            let first = archived.iter().find(|n| n.id == node.id).unwrap();
+            assert_eq!(&**archived_node as *const _, &**first as *const _);
+        }
+        let unique = archived
+            .iter()
+            .map(|n| &**n as *const ArchivedNode)
+            .collect::<HashSet<_>>();
+        assert_eq!(unique.len(), nodes.len());
+
+        let deserialized: Vec<Arc<Node>> = archived
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        assert_eq!(deserialized, value);
+        for (i, node) in deserialized.iter().enumerate() {
+            assert!(Arc::ptr_eq(node, &deserialized[i % nodes.len()]));
+        }
+        assert_eq!(
+            Arc::strong_count(&deserialized[0]),
+            value.len() / nodes.len()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_to_error_string() {
+        use core::fmt;
+        use rkyv::with::{AsDisplayString, Map, ToErrorString};
+        use std::error::Error;
+
+        #[derive(Debug)]
+        struct Timeout {
+            seconds: u32,
+        }
+
+        impl fmt::Display for Timeout {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "timed out after {} seconds", self.seconds)
+            }
+        }
+
+        impl Error for Timeout {}
+
+        // Values that display as strings can only be deserialized as strings
+        #[derive(Archive, Serialize)]
+        struct Summary {
+            #[with(AsDisplayString)]
+            count: u64,
+            #[with(AsDisplayString)]
+            slowest: Timeout,
+        }
+
+        let value = Summary {
+            count: 12345,
+            slowest: Timeout { seconds: 300 },
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Summary>(buf.as_ref()) };
+        assert_eq!(archived.count, "12345");
+        assert_eq!(archived.slowest, "timed out after 300 seconds");
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Report {
+            #[with(ToErrorString)]
+            cause: Box<dyn Error>,
+            #[with(Map<ToErrorString>)]
+            retry_error: Option<Box<dyn Error + Send + Sync>>,
+            #[with(Map<ToErrorString>)]
+            cleanup_error: Option<Box<dyn Error + Send + Sync>>,
+        }
+
+        let value = Report {
+            cause: Box::new(Timeout { seconds: 30 }),
+            retry_error: Some("refused".into()),
+            cleanup_error: None,
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Report>(buf.as_ref()) };
+
+        assert_eq!(archived.cause, "timed out after 30 seconds");
+        assert_eq!(archived.retry_error.as_ref().unwrap(), "refused");
+        assert!(archived.cleanup_error.is_none());
+
+        // Only the messages of the errors survive the round trip
+        let deserialized: Report = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.cause.to_string(), "timed out after 30 seconds");
+        assert!(deserialized.cause.downcast_ref::<Timeout>().is_none());
+        assert_eq!(deserialized.retry_error.unwrap().to_string(), "refused");
+        assert!(deserialized.cleanup_error.is_none());
+    }
 }
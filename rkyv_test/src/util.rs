@@ -76,6 +76,51 @@ pub mod core {
     impl_test_archive!(DefaultSerializer, DefaultDeserializer);
 }
 
+/// A wrapper that counts how many times its fields are deserialized.
+pub mod counted {
+    use rkyv::{
+        de::{deserializers::ContextDeserializer, DeserializeContext},
+        with::{ArchiveWith, DeserializeWith, SerializeWith},
+        Archive, Archived, Deserialize, Fallible, Infallible, Resolver, Serialize,
+    };
+
+    /// Archives a `u32` as-is, and increments the `usize` context of the deserializer whenever
+    /// it's deserialized.
+    pub struct Counted;
+
+    impl ArchiveWith<u32> for Counted {
+        type Archived = Archived<u32>;
+        type Resolver = Resolver<u32>;
+
+        unsafe fn resolve_with(field: &u32, pos: usize, _: (), out: *mut Self::Archived) {
+            field.resolve(pos, (), out);
+        }
+    }
+
+    impl<S: Fallible + ?Sized> SerializeWith<u32, S> for Counted {
+        fn serialize_with(field: &u32, serializer: &mut S) -> Result<(), S::Error> {
+            field.serialize(serializer)
+        }
+    }
+
+    impl<D: DeserializeContext<usize> + ?Sized> DeserializeWith<Archived<u32>, u32, D> for Counted {
+        fn deserialize_with(field: &Archived<u32>, deserializer: &mut D) -> Result<u32, D::Error> {
+            *deserializer.context() += 1;
+            field.deserialize(deserializer)
+        }
+    }
+
+    /// Deserializes with a counting deserializer, and returns the result along with how many
+    /// [`Counted`] fields were deserialized.
+    pub fn count_deserialized<T, E>(
+        deserialize: impl FnOnce(&mut ContextDeserializer<usize, Infallible>) -> Result<T, E>,
+    ) -> (Result<T, E>, usize) {
+        let mut deserializer = ContextDeserializer::new(0, Infallible);
+        let result = deserialize(&mut deserializer);
+        (result, deserializer.into_context())
+    }
+}
+
 #[cfg(feature = "alloc")]
 pub mod alloc {
     const SCRATCH_SIZE: usize = 256;
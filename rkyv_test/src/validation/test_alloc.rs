@@ -11,8 +11,8 @@ mod tests {
         vec::Vec,
     };
     use rkyv::{
-        check_archived_root, check_archived_value, ser::Serializer, AlignedBytes, Archive,
-        CheckBytes, Deserialize, Infallible, Serialize,
+        check_archived_root, check_archived_value, ser::Serializer, AlignedBytes, AlignedVec,
+        Archive, CheckBytes, Deserialize, Infallible, Serialize,
     };
     #[cfg(feature = "std")]
     use std::{
@@ -359,6 +359,408 @@ mod tests {
         ])));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_enum_pinned_tags() {
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        enum Test {
+            #[archive(tag = 7)]
+            A(u8),
+            #[archive(tag = 3)]
+            B,
+        }
+
+        serialize_and_check(&Test::A(42));
+        serialize_and_check(&Test::B);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Test::A(0)).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let tag = buf.len() - core::mem::size_of::<ArchivedTest>();
+        assert_eq!(buf[tag], 7);
+
+        // Only the pinned tags are valid
+        for (value, valid) in [(0, false), (1, false), (3, true), (7, true), (8, false)] {
+            buf[tag] = value;
+            assert_eq!(check_archived_root::<Test>(buf.as_ref()).is_ok(), valid);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_niche_enum() {
+        use core::num::NonZeroU32;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes, niche)]
+        enum Handle {
+            Closed,
+            Open {
+                name: String,
+                #[archive(niche)]
+                id: NonZeroU32,
+            },
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes, niche)]
+        enum Flag {
+            Unset,
+            Set(bool),
+        }
+
+        serialize_and_check(&Handle::Closed);
+        serialize_and_check(&Handle::Open {
+            name: "a string long enough to be stored out of line".to_string(),
+            id: NonZeroU32::new(42).unwrap(),
+        });
+        serialize_and_check(&Flag::Unset);
+        serialize_and_check(&Flag::Set(false));
+        serialize_and_check(&Flag::Set(true));
+
+        // Only valid bools and the niche are accepted
+        let mut buf = AlignedVec::new();
+        for (value, valid) in [(0, true), (1, true), (2, true), (3, false), (255, false)] {
+            buf.clear();
+            buf.push(value);
+            let archived = check_archived_root::<Flag>(buf.as_ref());
+            assert_eq!(archived.is_ok(), valid);
+            if value == 2 {
+                assert!(archived.unwrap().is_unset());
+            }
+        }
+
+        // The other fields aren't checked if the niche field holds the niche
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Handle::Open {
+                name: "a string long enough to be stored out of line".to_string(),
+                id: NonZeroU32::new(42).unwrap(),
+            })
+            .unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let root = buf.len() - core::mem::size_of::<ArchivedHandle>();
+        let id = root + core::mem::offset_of!(ArchivedHandleOpen, id);
+        let name = root + core::mem::offset_of!(ArchivedHandleOpen, name);
+
+        // Point the string out of bounds
+        for byte in &mut buf[name..id] {
+            *byte = 0xff;
+        }
+        assert!(check_archived_root::<Handle>(buf.as_ref()).is_err());
+
+        for byte in &mut buf[id..id + 4] {
+            *byte = 0;
+        }
+        assert!(check_archived_root::<Handle>(buf.as_ref())
+            .unwrap()
+            .is_closed());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_large_enum() {
+        macro_rules! define_large_enum {
+            ($($variant:ident)*) => {
+                #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+                #[archive(check_bytes, compare(PartialEq))]
+                #[archive_attr(derive(Debug))]
+                enum Large {
+                    $($variant,)*
+                }
+            };
+        }
+
+        define_large_enum! {
+            V0 V1 V2 V3 V4 V5 V6 V7 V8 V9 V10 V11 V12 V13 V14 V15 V16 V17 V18 V19 V20 V21 V22 V23
+            V24 V25 V26 V27 V28 V29 V30 V31 V32 V33 V34 V35 V36 V37 V38 V39 V40 V41 V42 V43 V44 V45
+            V46 V47 V48 V49 V50 V51 V52 V53 V54 V55 V56 V57 V58 V59 V60 V61 V62 V63 V64 V65 V66 V67
+            V68 V69 V70 V71 V72 V73 V74 V75 V76 V77 V78 V79 V80 V81 V82 V83 V84 V85 V86 V87 V88 V89
+            V90 V91 V92 V93 V94 V95 V96 V97 V98 V99 V100 V101 V102 V103 V104 V105 V106 V107 V108
+            V109 V110 V111 V112 V113 V114 V115 V116 V117 V118 V119 V120 V121 V122 V123 V124 V125
+            V126 V127 V128 V129 V130 V131 V132 V133 V134 V135 V136 V137 V138 V139 V140 V141 V142
+            V143 V144 V145 V146 V147 V148 V149 V150 V151 V152 V153 V154 V155 V156 V157 V158 V159
+            V160 V161 V162 V163 V164 V165 V166 V167 V168 V169 V170 V171 V172 V173 V174 V175 V176
+            V177 V178 V179 V180 V181 V182 V183 V184 V185 V186 V187 V188 V189 V190 V191 V192 V193
+            V194 V195 V196 V197 V198 V199 V200 V201 V202 V203 V204 V205 V206 V207 V208 V209 V210
+            V211 V212 V213 V214 V215 V216 V217 V218 V219 V220 V221 V222 V223 V224 V225 V226 V227
+            V228 V229 V230 V231 V232 V233 V234 V235 V236 V237 V238 V239 V240 V241 V242 V243 V244
+            V245 V246 V247 V248 V249 V250 V251 V252 V253 V254 V255 V256 V257 V258 V259 V260 V261
+            V262 V263 V264 V265 V266 V267 V268 V269 V270 V271 V272 V273 V274 V275 V276 V277 V278
+            V279 V280 V281 V282 V283 V284 V285 V286 V287 V288 V289 V290 V291 V292 V293 V294 V295
+            V296 V297 V298 V299
+        }
+
+        // 300 variants don't fit in a u8 tag
+        assert_eq!(core::mem::size_of::<ArchivedLarge>(), 2);
+
+        for value in [Large::V0, Large::V255, Large::V256, Large::V299] {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = check_archived_root::<Large>(buf.as_ref()).unwrap();
+            assert_eq!(archived, &value);
+            let deserialized: Large = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, value);
+        }
+
+        // Tags past the last variant are invalid
+        let data = AlignedBytes(300u16.to_ne_bytes());
+        check_archived_root::<Large>(data.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_archived_str() {
+        use rkyv::{string::ArchivedStr, with::Inline};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test<'a> {
+            #[with(Inline)]
+            short: &'a str,
+            #[with(Inline)]
+            long: &'a str,
+        }
+
+        let value = Test {
+            short: "hi",
+            long: "hello world, this string is too long to inline",
+        };
+        serialize_and_check(&value);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        let short: &ArchivedStr = &archived.short;
+        assert_eq!(short, "hi");
+        assert_eq!(archived.long.len(), value.long.len());
+        assert!(archived.long.starts_with("hello world"));
+        assert_eq!(&archived.long[..5], "hello");
+
+        // Invalid UTF-8
+        let start = buf.as_slice().windows(2).position(|w| w == b"hi").unwrap();
+        buf[start] = 0xff;
+        assert!(check_archived_root::<Test>(buf.as_ref()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_references_as_owned() {
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct View<'a, T: 'a> {
+            name: &'a str,
+            values: &'a [T],
+        }
+
+        let values = [1u32, 2, 3, 4];
+        let value = View {
+            name: "a name that is too long to be inlined",
+            values: &values,
+        };
+        serialize_and_check(&value);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let archived: &ArchivedView<u32> = check_archived_root::<View<u32>>(buf.as_ref()).unwrap();
+        assert_eq!(archived.name, value.name);
+        assert_eq!(archived.values.as_slice(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_inline_str() {
+        use rkyv::{string::inline::CheckInlineStrError, with::InlineStr};
+
+        #[derive(Archive)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug))]
+        struct Record {
+            #[with(InlineStr<4>)]
+            name: String,
+        }
+
+        let data = AlignedBytes([2u8, b'h', b'i', 0, 0]);
+        let archived = check_archived_root::<Record>(data.as_ref()).unwrap();
+        assert_eq!(archived.name, "hi");
+
+        // Length greater than the capacity
+        let data = AlignedBytes([5u8, b'h', b'e', b'l', b'l']);
+        let result =
+            check_archived_value::<rkyv::with::With<String, InlineStr<4>>>(data.as_ref(), 0);
+        assert!(matches!(
+            result,
+            Err(rkyv::validation::CheckArchiveError::CheckBytesError(
+                CheckInlineStrError::LengthOutOfBounds {
+                    len: 5,
+                    capacity: 4,
+                }
+            ))
+        ));
+
+        // Invalid UTF-8
+        let data = AlignedBytes([2u8, 0xff, 0xfe, 0, 0]);
+        check_archived_root::<Record>(data.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_enum_migration() {
+        use rkyv::Fallible;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        enum OldEvent {
+            Created { name: String },
+            Renamed(String, String),
+            Deleted,
+            Purged,
+        }
+
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Named { old: Option<String>, new: String },
+            Removed { purged: bool },
+        }
+
+        impl<D: Fallible + ?Sized> Deserialize<Event, D> for ArchivedOldEvent {
+            fn deserialize(&self, _: &mut D) -> Result<Event, D::Error> {
+                Ok(match self {
+                    ArchivedOldEvent::Created { name } => Event::Named {
+                        old: None,
+                        new: name.to_string(),
+                    },
+                    ArchivedOldEvent::Renamed(old, new) => Event::Named {
+                        old: Some(old.to_string()),
+                        new: new.to_string(),
+                    },
+                    ArchivedOldEvent::Deleted => Event::Removed { purged: false },
+                    ArchivedOldEvent::Purged => Event::Removed { purged: true },
+                })
+            }
+        }
+
+        let cases = [
+            (
+                OldEvent::Created {
+                    name: "a".to_string(),
+                },
+                Event::Named {
+                    old: None,
+                    new: "a".to_string(),
+                },
+            ),
+            (
+                OldEvent::Renamed("a".to_string(), "b".to_string()),
+                Event::Named {
+                    old: Some("a".to_string()),
+                    new: "b".to_string(),
+                },
+            ),
+            (OldEvent::Deleted, Event::Removed { purged: false }),
+            (OldEvent::Purged, Event::Removed { purged: true }),
+        ];
+
+        for (old, new) in cases {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&old).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = check_archived_root::<OldEvent>(buf.as_ref()).unwrap();
+            let migrated: Event = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(migrated, new);
+        }
+
+        // Invalid tags are rejected before they can be mapped
+        let mut data = AlignedVec::new();
+        data.resize(core::mem::size_of::<ArchivedOldEvent>(), 0);
+        data[0] = 4;
+        assert!(check_archived_root::<OldEvent>(data.as_ref()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_mixed_generic_enum() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes, compare(PartialEq))]
+        enum Mixed<T, U, V> {
+            Unit,
+            Struct { a: T, b: Vec<T> },
+            Tuple(U, V),
+            Both { a: T, b: U, c: Option<V> },
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(check_bytes)]
+        enum Bounded<T, U, const N: usize>
+        where
+            T: Clone,
+        {
+            Struct { a: [T; N] },
+            Tuple(U, Option<T>),
+        }
+
+        serialize_and_check(&Bounded::<u8, String, 2>::Struct { a: [1, 2] });
+        serialize_and_check(&Bounded::<u8, String, 2>::Tuple("hi".to_string(), Some(3)));
+
+        type Test = Mixed<u32, String, Box<[u8]>>;
+
+        let values: [Test; 4] = [
+            Mixed::Unit,
+            Mixed::Struct {
+                a: 42,
+                b: vec![1, 2, 3],
+            },
+            Mixed::Tuple("hello".to_string(), Box::new([1, 2, 3])),
+            Mixed::Both {
+                a: 42,
+                b: "world".to_string(),
+                c: None,
+            },
+        ];
+
+        for value in values {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+            assert!(archived == &value);
+            let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+            assert_eq!(deserialized, value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_associated_type_field() {
+        trait Storage {
+            type Buffer;
+        }
+
+        struct VecStorage;
+
+        impl Storage for VecStorage {
+            type Buffer = Vec<u32>;
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Backed<S: Storage> {
+            buffer: S::Buffer,
+            len: u32,
+        }
+
+        serialize_and_check(&Backed::<VecStorage> {
+            buffer: vec![1, 2, 3],
+            len: 3,
+        });
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn recursive_type() {
@@ -407,130 +809,1302 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn check_b_tree() {
-        let mut value = BTreeMap::new();
-        value.insert("foo".to_string(), 10);
-        value.insert("bar".to_string(), 20);
-        value.insert("baz".to_string(), 40);
-        value.insert("bat".to_string(), 80);
+    fn check_expired_weak_ptr() {
+        #[cfg(not(feature = "std"))]
+        use alloc::rc::Weak;
+        #[cfg(feature = "std")]
+        use std::rc::Weak;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            a: Rc<u32>,
+            live: Weak<u32>,
+            expired: Weak<u32>,
+        }
+
+        let shared = Rc::new(10);
+        let dropped = Rc::new(20);
+        let value = Test {
+            a: shared.clone(),
+            live: Rc::downgrade(&shared),
+            expired: Rc::downgrade(&dropped),
+        };
+        core::mem::drop(dropped);
 
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
         let buf = serializer.into_serializer().into_inner();
 
-        check_archived_root::<BTreeMap<String, i32>>(buf.as_ref()).unwrap();
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(**archived.live.upgrade().unwrap(), 10);
+        assert!(archived.expired.upgrade().is_none());
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn check_invalid_b_tree_set() {
-        let data = AlignedBytes([
-            0, 0, 0, 0, 253, 6, 239, 6, 255, 255, 255, 252, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 5, 0,
-            0, 0, 0, 240, 255, 255, 255, 1, 128, 0, 249, 220, 255, 255, 255, 4, 0, 0, 96, 0, 0, 0,
-            249, 232, 255, 255, 255,
-        ]);
+    fn check_max_shared_ptrs() {
+        use rkyv::{
+            bytecheck::ArrayCheckError,
+            rc::validation::SharedPointerError,
+            validation::{
+                check_archived_root_with_context,
+                validators::{DefaultValidator, DefaultValidatorError, SharedError},
+                CheckArchiveError,
+            },
+        };
 
-        rkyv::from_bytes::<BTreeSet<u8>>(&data.0).unwrap_err();
+        // Every element points to its own shared value
+        let value: [Rc<u32>; 16] = core::array::from_fn(|i| Rc::new(i as u32));
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
 
-        let data = AlignedBytes([
-            1, 29, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 253, 0, 0, 116, 255, 255, 40, 0, 8, 0, 0, 0, 236,
-            255, 255, 255, 1, 128, 72, 0, 220, 255, 255, 255, 236, 255, 255, 255, 0, 0, 0, 0, 32,
-            0, 255, 254, 255, 0, 94, 2, 33, 0, 0, 0, 0, 0, 0, 0, 61, 1, 38, 0, 0, 32, 0, 255, 255,
-            1, 0, 1, 255, 255, 0, 184, 4, 0, 28, 0, 8, 0, 2, 142, 255, 255, 255, 3, 1, 255, 251, 0,
-            184, 255, 255, 255,
-        ]);
+        let mut validator = DefaultValidator::with_max_shared_ptrs(&buf, 16);
+        check_archived_root_with_context::<[Rc<u32>; 16], _>(&buf, &mut validator).unwrap();
 
-        rkyv::from_bytes::<BTreeSet<Box<u8>>>(&data.0).unwrap_err();
+        let mut validator = DefaultValidator::with_max_shared_ptrs(&buf, 4);
+        let result = check_archived_root_with_context::<[Rc<u32>; 16], _>(&buf, &mut validator);
+        assert!(matches!(
+            result,
+            Err(CheckArchiveError::CheckBytesError(ArrayCheckError {
+                index: 4,
+                error: SharedPointerError::ContextError(DefaultValidatorError::SharedError(
+                    SharedError::ExceededMaximumSharedPointers { max_shared_ptrs: 4 }
+                )),
+            }))
+        ));
+
+        // Pointers to the same shared value only count once
+        let shared = Rc::new(10);
+        let value: [Rc<u32>; 16] = core::array::from_fn(|_| shared.clone());
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let mut validator = DefaultValidator::with_max_shared_ptrs(&buf, 1);
+        check_archived_root_with_context::<[Rc<u32>; 16], _>(&buf, &mut validator).unwrap();
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn check_empty_b_tree() {
-        let value = BTreeMap::<u8, ()>::new();
+    fn check_with_validator_builder() {
+        use rkyv::validation::{check_archived_root_with_context, validators::ValidatorBuilder};
 
+        let value: Vec<Rc<String>> = (0..4)
+            .map(|i| Rc::new(format!("shared string number {}", i)))
+            .collect();
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
         let buf = serializer.into_serializer().into_inner();
 
-        check_archived_root::<BTreeMap<u8, ()>>(buf.as_ref()).unwrap();
+        // The defaults match check_archived_root
+        let archived = ValidatorBuilder::new()
+            .access::<Vec<Rc<String>>>(&buf)
+            .unwrap();
+        assert_eq!(archived.len(), 4);
+        assert_eq!(archived[3].as_str(), "shared string number 3");
+
+        ValidatorBuilder::new()
+            .with_max_depth(4)
+            .with_max_shared_ptrs(4)
+            .access::<Vec<Rc<String>>>(&buf)
+            .unwrap();
+        assert!(ValidatorBuilder::new()
+            .with_max_depth(2)
+            .access::<Vec<Rc<String>>>(&buf)
+            .is_err());
+        assert!(ValidatorBuilder::new()
+            .with_max_shared_ptrs(3)
+            .access::<Vec<Rc<String>>>(&buf)
+            .is_err());
+
+        let mut validator = ValidatorBuilder::new().with_max_shared_ptrs(4).build(&buf);
+        check_archived_root_with_context::<Vec<Rc<String>>, _>(&buf, &mut validator).unwrap();
     }
 
     #[test]
-    // This test is unfortunately too slow to run through miri
-    #[cfg_attr(miri, ignore)]
-    // This test creates structures too big to fit in 16-bit offsets
-    #[cfg(not(feature = "size_16"))]
-    fn check_b_tree_large() {
-        let mut value = BTreeMap::new();
-        for i in 0..100_000 {
-            value.insert(i.to_string(), i);
-        }
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_padded_root() {
+        let value = vec!["a string long enough to be stored out of line".to_string()];
 
         let mut serializer = DefaultSerializer::default();
-        serializer.serialize_value(&value).unwrap();
+        serializer.serialize_value_padded(&value, 512).unwrap();
         let buf = serializer.into_serializer().into_inner();
+        assert_eq!(buf.len(), 512);
 
-        check_archived_root::<BTreeMap<String, i32>>(buf.as_ref()).unwrap();
+        let archived = check_archived_root::<Vec<String>>(buf.as_ref()).unwrap();
+        assert_eq!(archived, &value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn b_tree_struct_member() {
-        #[derive(Archive, Serialize, Deserialize, Debug, Default)]
+    fn check_sorted_vec() {
+        use rkyv::{
+            collections::sorted_vec::validation::SortedVecError,
+            validation::CheckArchiveError,
+            with::{SortedVec, With},
+        };
+
+        #[derive(Archive, Serialize)]
         #[archive(check_bytes)]
-        pub struct MyType {
-            pub some_list: BTreeMap<String, Vec<f32>>,
-            pub values: Vec<f32>,
+        struct Test {
+            #[with(SortedVec)]
+            ids: Vec<u32>,
         }
 
-        let mut value = MyType::default();
+        serialize_and_check(&Test {
+            ids: vec![3, 1, 2, 1],
+        });
 
-        value
-            .some_list
-            .entry("Asdf".to_string())
-            .and_modify(|e| e.push(1.0))
-            .or_insert_with(|| vec![2.0]);
+        // A plain vec has the same layout as a sorted vec
+        fn archive(ids: Vec<u32>) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&ids).unwrap();
+            serializer.into_serializer().into_inner()
+        }
 
-        let mut serializer = DefaultSerializer::default();
-        serializer.serialize_value(&value).unwrap();
-        let buf = serializer.into_serializer().into_inner();
+        let buf = archive(vec![1, 2, 3]);
+        check_archived_root::<With<Vec<u32>, SortedVec>>(buf.as_ref()).unwrap();
 
-        let value = check_archived_root::<MyType>(&buf).unwrap();
-        let _: MyType = value.deserialize(&mut Infallible).unwrap();
+        let buf = archive(Vec::new());
+        check_archived_root::<With<Vec<u32>, SortedVec>>(buf.as_ref()).unwrap();
+
+        let buf = archive(vec![1, 3, 2]);
+        assert!(matches!(
+            check_archived_root::<With<Vec<u32>, SortedVec>>(buf.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                SortedVecError::UnsortedElement { index: 2 }
+            )),
+        ));
+
+        let buf = archive(vec![1, 2, 2, 3]);
+        assert!(matches!(
+            check_archived_root::<With<Vec<u32>, SortedVec>>(buf.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                SortedVecError::DuplicateElement { index: 2 }
+            )),
+        ));
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn check_atomics_endianness() {
-        use core::sync::atomic::AtomicU32;
+    fn check_quantize() {
+        use core::mem::size_of;
+        use rkyv::{
+            quantize::QuantizedVecError,
+            validation::CheckArchiveError,
+            with::{Quantize, With},
+            Archived, FixedUsize,
+        };
 
-        let value = AtomicU32::new(0x12345678);
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(Quantize<8>)]
+            values: Vec<f32>,
+        }
+
+        serialize_and_check(&Test {
+            values: vec![-1.0, 0.25, 1.0],
+        });
+
+        type Root = With<Vec<f32>, Quantize<12>>;
 
         let mut serializer = DefaultSerializer::default();
-        serializer.serialize_value(&value).unwrap();
+        serializer
+            .serialize_value(With::<_, Quantize<12>>::cast(&vec![-1.0f32, 0.25, 1.0]))
+            .unwrap();
         let buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Root>(buf.as_ref()).unwrap();
+
+        // Finds the only occurrence of some bytes in the root
+        fn find(buf: &[u8], bytes: &[u8]) -> usize {
+            let start = buf.len() - size_of::<Archived<Root>>();
+            let mut found =
+                (start..buf.len() - bytes.len() + 1).filter(|&i| &buf[i..i + bytes.len()] == bytes);
+            let i = found.next().unwrap();
+            assert!(found.next().is_none());
+            i
+        }
 
-        #[cfg(any(
-            all(target_endian = "little", not(feature = "archive_be")),
-            feature = "archive_le"
-        ))]
-        const ARCHIVED_BYTES: [u8; 4] = [0x78, 0x56, 0x34, 0x12];
-
-        #[cfg(any(
-            all(target_endian = "big", not(feature = "archive_le")),
-            feature = "archive_be"
-        ))]
-        const ARCHIVED_BYTES: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+        let mut corrupted = buf.clone();
+        let i = find(&buf, &(-1.0f32).to_ne_bytes());
+        corrupted[i..i + 4].copy_from_slice(&f32::NAN.to_ne_bytes());
+        assert!(matches!(
+            check_archived_root::<Root>(corrupted.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                QuantizedVecError::InvalidRange { .. }
+            )),
+        ));
 
-        assert_eq!(buf.as_ref(), &ARCHIVED_BYTES);
+        let mut corrupted = buf.clone();
+        let i = find(&buf, &(3 as FixedUsize).to_ne_bytes());
+        corrupted[i..i + size_of::<FixedUsize>()].copy_from_slice(&(4 as FixedUsize).to_ne_bytes());
+        assert!(matches!(
+            check_archived_root::<Root>(corrupted.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                QuantizedVecError::LengthMismatch {
+                    len: 4,
+                    byte_len: 5,
+                }
+            )),
+        ));
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn check_valid_durations() {
-        use core::time::Duration;
+    fn check_reserved_vec() {
+        use core::mem::size_of;
+        use rkyv::{
+            validation::CheckArchiveError,
+            vec::ReservedVecError,
+            with::{ReservedVec, With},
+            Archived, FixedUsize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(ReservedVec<4>)]
+            values: Vec<String>,
+        }
+
+        serialize_and_check(&Test {
+            values: vec!["hello".to_string(), "world".to_string()],
+        });
+
+        type Root = With<Vec<u32>, ReservedVec<2>>;
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(With::<_, ReservedVec<2>>::cast(&vec![1u32, 2, 3]))
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Root>(buf.as_ref()).unwrap();
+
+        // The elements and the reserved space are serialized before the root
+        let root = buf.len() - size_of::<Archived<Root>>();
+        assert!(root >= 3 * 4 + 2 * 4);
+
+        // The capacity is the only field of the root that holds 5
+        let capacity = (5 as FixedUsize).to_ne_bytes();
+        let i = (root..buf.len())
+            .step_by(size_of::<FixedUsize>())
+            .find(|&i| buf[i..i + size_of::<FixedUsize>()] == capacity)
+            .unwrap();
+        let capacity = i..i + size_of::<FixedUsize>();
+        let mut corrupted = buf.clone();
+        corrupted[capacity.clone()].copy_from_slice(&(2 as FixedUsize).to_ne_bytes());
+        assert!(matches!(
+            check_archived_root::<Root>(corrupted.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                ReservedVecError::LengthExceedsCapacity {
+                    len: 3,
+                    capacity: 2,
+                }
+            )),
+        ));
+
+        // The reserved space must be inside the archive
+        let mut corrupted = buf.clone();
+        corrupted[capacity].copy_from_slice(&(64 as FixedUsize).to_ne_bytes());
+        assert!(matches!(
+            check_archived_root::<Root>(corrupted.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                ReservedVecError::CheckVecError(_)
+            )),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_open_ranges() {
+        use core::ops::{RangeFrom, RangeFull, RangeTo};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Slicing {
+            all: RangeFull,
+            tail: RangeFrom<char>,
+            head: RangeTo<String>,
+        }
+
+        serialize_and_check(&RangeFull);
+        serialize_and_check(&(3u8..));
+        serialize_and_check(&(.."end".to_string()));
+        serialize_and_check(&Slicing {
+            all: ..,
+            tail: 'a'..,
+            head: .."z".to_string(),
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_boxed_variants() {
+        use core::mem::size_of;
+        use rkyv::{
+            bytecheck::EnumCheckError,
+            validation::CheckArchiveError,
+            with::{BoxedVariants, Map, With},
+            Archived,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(boxed_variants, check_bytes)]
+        enum Message {
+            Ping,
+            Reading(u8),
+            Flag(bool),
+            Name(String),
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(Map<BoxedVariants>)]
+            messages: Vec<Message>,
+        }
+
+        serialize_and_check(&Test {
+            messages: vec![
+                Message::Ping,
+                Message::Reading(7),
+                Message::Flag(true),
+                Message::Name("hello".to_string()),
+            ],
+        });
+
+        type Root = With<Message, BoxedVariants>;
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(With::<_, BoxedVariants>::cast(&Message::Reading(7)))
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Root>(buf.as_ref()).unwrap();
+
+        // The variant index is the only occurrence of 1 in the root
+        let start = buf.len() - size_of::<Archived<Root>>();
+        let i = (start..buf.len() - 3)
+            .find(|&i| buf[i..i + 4] == 1u32.to_ne_bytes())
+            .unwrap();
+
+        let mut corrupted = buf.clone();
+        corrupted[i..i + 4].copy_from_slice(&9u32.to_ne_bytes());
+        assert!(matches!(
+            check_archived_root::<Root>(corrupted.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                EnumCheckError::InvalidTag(9)
+            )),
+        ));
+
+        // The payload is checked according to the variant index, and 7 isn't a valid bool
+        let mut corrupted = buf.clone();
+        corrupted[i..i + 4].copy_from_slice(&2u32.to_ne_bytes());
+        assert!(matches!(
+            check_archived_root::<Root>(corrupted.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                EnumCheckError::InvalidTuple {
+                    variant_name: "Flag",
+                    ..
+                }
+            )),
+        ));
+
+        // Unit variants don't follow the payload pointer
+        let mut corrupted = buf.clone();
+        corrupted[i..i + 4].copy_from_slice(&0u32.to_ne_bytes());
+        check_archived_root::<Root>(corrupted.as_ref()).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_niche_shared_ptr() {
+        use rkyv::{ser::SharedSerializeRegistry, with::Niche};
+
+        #[derive(Archive, Serialize)]
+        #[archive(bound(serialize = "__S: Serializer + SharedSerializeRegistry"))]
+        #[archive(check_bytes)]
+        #[archive_attr(check_bytes(
+            bound = "__C: ::rkyv::validation::ArchiveContext + ::rkyv::validation::SharedContext, <__C as ::rkyv::Fallible>::Error: ::rkyv::bytecheck::Error"
+        ))]
+        struct Node {
+            value: u32,
+            #[with(Niche)]
+            #[omit_bounds]
+            #[archive_attr(omit_bounds)]
+            next: Option<Rc<Node>>,
+        }
+
+        // Many `None` links and a node that's shared by every list
+        let shared = Rc::new(Node {
+            value: 0,
+            next: None,
+        });
+        let value = (0..8)
+            .map(|i| Node {
+                value: i,
+                next: if i % 2 == 0 {
+                    Some(shared.clone())
+                } else {
+                    None
+                },
+            })
+            .collect::<Vec<_>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let archived = check_archived_root::<Vec<Node>>(buf.as_ref()).unwrap();
+        assert_eq!(archived.len(), 8);
+        for (i, node) in archived.iter().enumerate() {
+            assert_eq!(node.value, i as u32);
+            assert_eq!(node.next.is_some(), i % 2 == 0);
+        }
+        assert!(::core::ptr::eq(
+            archived[0].next.as_deref().unwrap(),
+            archived[2].next.as_deref().unwrap(),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn extract_subtree() {
+        use rkyv::validation::validators::{extract_subtree, ExtractSubtreeError};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Inner {
+            shared: Rc<String>,
+            name: String,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Outer {
+            padding: Vec<u64>,
+            shared: Rc<String>,
+            inner: Inner,
+        }
+
+        // The inner value shares a string with the outer value
+        let shared = Rc::new("a shared string long enough to be out of line".to_string());
+        let value = Outer {
+            padding: (0..64).collect(),
+            shared: shared.clone(),
+            inner: Inner {
+                shared,
+                name: "the inner value that will be forwarded".to_string(),
+            },
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<Outer>(buf.as_ref()).unwrap();
+
+        // The padding isn't reachable from the inner value, so it isn't extracted
+        let extracted = extract_subtree::<Inner>(buf.as_ref(), &archived.inner).unwrap();
+        assert!(extracted.len() + 64 * 8 <= buf.len());
+
+        let archived_inner = check_archived_root::<Inner>(extracted.as_ref()).unwrap();
+        assert_eq!(archived_inner.name, value.inner.name);
+        assert_eq!(*archived_inner.shared, *value.shared);
+
+        // Values from outside of the archive can't be extracted
+        assert!(matches!(
+            extract_subtree::<Inner>(buf.as_ref(), archived_inner),
+            Err(ExtractSubtreeError::NotInArchive),
+        ));
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct InnerReversed {
+            name: String,
+            shared: Rc<String>,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct OuterReversed {
+            shared: Rc<String>,
+            inner: InnerReversed,
+        }
+
+        // The shared string is laid out before the inner name, so the inner value can't be
+        // validated on its own
+        let value = OuterReversed {
+            shared: value.shared.clone(),
+            inner: InnerReversed {
+                name: value.inner.name.clone(),
+                shared: value.shared,
+            },
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<OuterReversed>(buf.as_ref()).unwrap();
+
+        assert!(matches!(
+            extract_subtree::<InnerReversed>(buf.as_ref(), &archived.inner),
+            Err(ExtractSubtreeError::CheckError(_)),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_b_tree() {
+        let mut value = BTreeMap::new();
+        value.insert("foo".to_string(), 10);
+        value.insert("bar".to_string(), 20);
+        value.insert("baz".to_string(), 40);
+        value.insert("bat".to_string(), 80);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        check_archived_root::<BTreeMap<String, i32>>(buf.as_ref()).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_invalid_b_tree_set() {
+        let data = AlignedBytes([
+            0, 0, 0, 0, 253, 6, 239, 6, 255, 255, 255, 252, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 5, 0,
+            0, 0, 0, 240, 255, 255, 255, 1, 128, 0, 249, 220, 255, 255, 255, 4, 0, 0, 96, 0, 0, 0,
+            249, 232, 255, 255, 255,
+        ]);
+
+        rkyv::from_bytes::<BTreeSet<u8>>(&data.0).unwrap_err();
+
+        let data = AlignedBytes([
+            1, 29, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 253, 0, 0, 116, 255, 255, 40, 0, 8, 0, 0, 0, 236,
+            255, 255, 255, 1, 128, 72, 0, 220, 255, 255, 255, 236, 255, 255, 255, 0, 0, 0, 0, 32,
+            0, 255, 254, 255, 0, 94, 2, 33, 0, 0, 0, 0, 0, 0, 0, 61, 1, 38, 0, 0, 32, 0, 255, 255,
+            1, 0, 1, 255, 255, 0, 184, 4, 0, 28, 0, 8, 0, 2, 142, 255, 255, 255, 3, 1, 255, 251, 0,
+            184, 255, 255, 255,
+        ]);
+
+        rkyv::from_bytes::<BTreeSet<Box<u8>>>(&data.0).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_empty_b_tree() {
+        let value = BTreeMap::<u8, ()>::new();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        check_archived_root::<BTreeMap<u8, ()>>(buf.as_ref()).unwrap();
+    }
+
+    #[test]
+    // This test is unfortunately too slow to run through miri
+    #[cfg_attr(miri, ignore)]
+    // This test creates structures too big to fit in 16-bit offsets
+    #[cfg(not(feature = "size_16"))]
+    fn check_b_tree_large() {
+        let mut value = BTreeMap::new();
+        for i in 0..100_000 {
+            value.insert(i.to_string(), i);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        check_archived_root::<BTreeMap<String, i32>>(buf.as_ref()).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn b_tree_struct_member() {
+        #[derive(Archive, Serialize, Deserialize, Debug, Default)]
+        #[archive(check_bytes)]
+        pub struct MyType {
+            pub some_list: BTreeMap<String, Vec<f32>>,
+            pub values: Vec<f32>,
+        }
+
+        let mut value = MyType::default();
+
+        value
+            .some_list
+            .entry("Asdf".to_string())
+            .and_modify(|e| e.push(1.0))
+            .or_insert_with(|| vec![2.0]);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let value = check_archived_root::<MyType>(&buf).unwrap();
+        let _: MyType = value.deserialize(&mut Infallible).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_atomics_endianness() {
+        use core::sync::atomic::AtomicU32;
+
+        let value = AtomicU32::new(0x12345678);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        #[cfg(any(
+            all(target_endian = "little", not(feature = "archive_be")),
+            feature = "archive_le"
+        ))]
+        const ARCHIVED_BYTES: [u8; 4] = [0x78, 0x56, 0x34, 0x12];
+
+        #[cfg(any(
+            all(target_endian = "big", not(feature = "archive_le")),
+            feature = "archive_be"
+        ))]
+        const ARCHIVED_BYTES: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+        assert_eq!(buf.as_ref(), &ARCHIVED_BYTES);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_valid_durations() {
+        use core::time::Duration;
+
+        check_archived_root::<Duration>(&[0xFF, 16]).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn cursor_navigation() {
+        use core::mem::offset_of;
+        use rkyv::validation::cursor::{Cursor, CursorError};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            id: u32,
+            names: Vec<String>,
+        }
+
+        let value = Test {
+            id: 42,
+            names: vec![
+                "a string long enough to be stored out of line".to_string(),
+                "hi".to_string(),
+            ],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let root = Cursor::root::<Test>(buf.as_ref());
+        assert_eq!(root.get::<Test>().unwrap().id, 42);
+
+        let id = root.offset(offset_of!(ArchivedTest, id) as isize).unwrap();
+        assert_eq!(id.get::<u32>().unwrap(), &42);
+
+        let names = root
+            .offset(offset_of!(ArchivedTest, names) as isize)
+            .unwrap();
+        assert_eq!(names.get::<Vec<String>>().unwrap().len(), 2);
+
+        let elements = names.follow_ptr().unwrap();
+        assert_eq!(
+            elements.get::<String>().unwrap(),
+            "a string long enough to be stored out of line"
+        );
+        assert_eq!(
+            elements
+                .index::<String>(1)
+                .unwrap()
+                .get::<String>()
+                .unwrap(),
+            "hi"
+        );
+
+        assert!(matches!(
+            root.offset(buf.len() as isize),
+            Err(CursorError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            Cursor::new(buf.as_ref(), buf.len()).unwrap().follow_ptr(),
+            Err(CursorError::PointerOverrun { .. })
+        ));
+        assert!(matches!(
+            root.offset(1).unwrap().follow_ptr(),
+            Err(CursorError::UnalignedPointer { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn multi_archive() {
+        use rkyv::multi::{MultiArchive, MultiArchiveError, MultiArchiveSerializer};
+
+        let values = [
+            vec!["hello".to_string(), "world".to_string()],
+            Vec::new(),
+            vec!["a string long enough to be stored out of line".to_string()],
+        ];
+
+        let mut serializer = MultiArchiveSerializer::new(DefaultSerializer::default());
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(serializer.serialize_value(value).unwrap(), i);
+        }
+        let buf = serializer.finish().unwrap().into_serializer().into_inner();
+
+        let archive = MultiArchive::<Vec<String>>::new(buf.as_ref()).unwrap();
+        assert_eq!(archive.len(), values.len());
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(archive.get(i).unwrap(), value);
+        }
+        assert!(matches!(
+            archive.get(3),
+            Err(MultiArchiveError::IndexOutOfBounds { index: 3, len: 3 })
+        ));
+
+        // Empty archives are valid
+        let serializer = MultiArchiveSerializer::<_, u32>::new(DefaultSerializer::default());
+        let buf = serializer.finish().unwrap().into_serializer().into_inner();
+        let archive = MultiArchive::<u32>::new(buf.as_ref()).unwrap();
+        assert!(archive.is_empty());
+        assert!(archive.get(0).is_err());
+
+        // An offset table pointing outside of the archive
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&42u32).unwrap();
+        serializer.serialize_value(&vec![1usize << 12]).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archive = MultiArchive::<u32>::new(buf.as_ref()).unwrap();
+        assert!(matches!(
+            archive.get(0),
+            Err(MultiArchiveError::CheckBytesError(_))
+        ));
+
+        // A corrupted offset table
+        assert!(MultiArchive::<u32>::new(&buf[..buf.len() - 1]).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_handles() {
+        use rkyv::validation::handle::{ArchiveRef, OwnedArchive};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            a: u32,
+            b: String,
+        }
+
+        let value = Test {
+            a: 42,
+            b: "a string long enough to be stored out of line".to_string(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
 
-        check_archived_root::<Duration>(&[0xFF, 16]).unwrap_err();
+        let archive = ArchiveRef::<Test>::new(buf.as_ref()).unwrap();
+        assert_eq!(archive.root().a, 42);
+        assert_eq!(archive.root().b, value.b);
+        assert_eq!(archive.bytes(), buf.as_slice());
+        assert_eq!(archive.subslice(1, 2), Some(&buf[1..3]));
+        assert_eq!(archive.subslice(buf.len(), 0), Some(&[][..]));
+        assert!(archive.subslice(buf.len() - 1, 2).is_none());
+        assert!(archive.subslice(1, usize::MAX).is_none());
+
+        // Misaligned bytes
+        let mut misaligned = AlignedVec::new();
+        misaligned.push(0);
+        misaligned.extend_from_slice(buf.as_ref());
+        assert!(ArchiveRef::<Test>::new(&misaligned[1..]).is_err());
+
+        // Short buffers
+        assert!(ArchiveRef::<Test>::new(&buf[buf.len() - 2..]).is_err());
+        assert!(ArchiveRef::<Test>::new(&[]).is_err());
+
+        let archive = OwnedArchive::<Test>::new(buf).unwrap();
+        assert_eq!(archive.root().a, 42);
+        assert_eq!(archive.as_archive_ref().root().b, value.b);
+        assert!(archive.subslice(0, archive.bytes().len()).is_some());
+        let buf = archive.into_bytes();
+
+        let mut short = AlignedVec::new();
+        short.extend_from_slice(&buf[buf.len() - 2..]);
+        assert!(OwnedArchive::<Test>::new(short).is_err());
+        assert!(OwnedArchive::<Test>::new(AlignedVec::new()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn bundle() {
+        use rkyv::multi::{Bundle, BundleError, BundleSerializer};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Record {
+            shared: Rc<String>,
+            id: u32,
+        }
+
+        let shared = Rc::new("a string long enough to be stored out of line".to_string());
+        let record = |id| Record {
+            shared: shared.clone(),
+            id,
+        };
+        let values = [
+            ("first".to_string(), record(1)),
+            ("second".to_string(), record(2)),
+            ("third".to_string(), record(3)),
+        ];
+
+        let mut serializer = BundleSerializer::new(DefaultSerializer::default());
+        for (key, value) in values.iter() {
+            serializer.serialize_value(key.clone(), value).unwrap();
+        }
+        assert_eq!(serializer.len(), values.len());
+        let buf = serializer.finish().unwrap().into_serializer().into_inner();
+
+        // The shared string is only stored once
+        let needle = shared.as_bytes();
+        let count = buf.windows(needle.len()).filter(|w| *w == needle).count();
+        assert_eq!(count, 1);
+
+        let bundle = Bundle::<String, Record>::new(buf.as_ref()).unwrap();
+        assert_eq!(bundle.len(), values.len());
+        assert!(bundle
+            .keys()
+            .map(|k| k.as_str())
+            .eq(["first", "second", "third"]));
+        for (key, value) in values.iter() {
+            let archived = bundle.get(key.as_str()).unwrap();
+            assert_eq!(archived.shared.as_str(), value.shared.as_str());
+            assert_eq!(archived.id, value.id);
+        }
+        assert!(matches!(
+            bundle.get("fourth"),
+            Err(BundleError::KeyNotFound)
+        ));
+
+        // A later value with the same key replaces the earlier one
+        let mut serializer = BundleSerializer::new(DefaultSerializer::default());
+        serializer
+            .serialize_value("key".to_string(), &1u32)
+            .unwrap();
+        serializer
+            .serialize_value("key".to_string(), &2u32)
+            .unwrap();
+        let buf = serializer.finish().unwrap().into_serializer().into_inner();
+        let bundle = Bundle::<String, u32>::new(buf.as_ref()).unwrap();
+        assert_eq!(bundle.len(), 1);
+        assert_eq!(*bundle.get("key").unwrap(), 2);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_var_int() {
+        use rkyv::with::VarInt;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug))]
+        struct Narrow {
+            #[with(VarInt)]
+            value: u16,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Wide {
+            #[with(VarInt)]
+            value: u32,
+        }
+
+        fn serialize_wide(value: u32) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&Wide { value }).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let buf = serialize_wide(u16::MAX as u32);
+        assert_eq!(
+            check_archived_root::<Narrow>(buf.as_ref())
+                .unwrap()
+                .value
+                .get(),
+            u16::MAX
+        );
+
+        // Overflows a u16
+        let buf = serialize_wide(u16::MAX as u32 + 1);
+        check_archived_root::<Wide>(buf.as_ref()).unwrap();
+        check_archived_root::<Narrow>(buf.as_ref()).unwrap_err();
+
+        // Doesn't terminate within the maximum length of a u16
+        let mut buf = serialize_wide(1 << 21);
+        assert_eq!(&buf[..4], &[0x80, 0x80, 0x80, 0x01]);
+        buf[3] = 0;
+        check_archived_root::<Wide>(buf.as_ref()).unwrap();
+        check_archived_root::<Narrow>(buf.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_128_bit_integers() {
+        serialize_and_check(&vec![i128::MIN, 0, i128::MAX]);
+        serialize_and_check(&vec![u128::MIN, u128::MAX]);
+
+        let mut map = BTreeMap::new();
+        map.insert(u128::MAX, i128::MIN);
+        map.insert(u128::MIN, i128::MAX);
+        serialize_and_check(&map);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_ignores_unreachable_bytes() {
+        let value = vec!["hello world, this string is out-of-line".to_string()];
+
+        let mut serializer = DefaultSerializer::default();
+        // Garbage that isn't reachable from the root
+        serializer.write(&[0xff; 64]).unwrap();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let archived = check_archived_root::<Vec<String>>(buf.as_ref()).unwrap();
+        assert_eq!(archived, &value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_reachable_bytes() {
+        use rkyv::validation::validators::{reachable_bytes, unreachable_bytes};
+
+        fn serialize_with_padding(value: &Vec<String>, padding: usize) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.write(&vec![0xff; padding]).unwrap();
+            serializer.serialize_value(value).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let value = vec![
+            "hello world, this string is out-of-line".to_string(),
+            "hi".to_string(),
+        ];
+
+        let buf = serialize_with_padding(&value, 0);
+        let reachable = reachable_bytes::<Vec<String>>(buf.as_ref()).unwrap();
+        let unreachable = unreachable_bytes::<Vec<String>>(buf.as_ref()).unwrap();
+        assert_ne!(reachable, 0);
+        assert_eq!(reachable + unreachable, buf.len());
+
+        // Garbage that isn't reachable from the root
+        let padded = serialize_with_padding(&value, 64);
+        assert_eq!(
+            reachable_bytes::<Vec<String>>(padded.as_ref()).unwrap(),
+            reachable
+        );
+        assert_eq!(
+            unreachable_bytes::<Vec<String>>(padded.as_ref()).unwrap(),
+            unreachable + 64
+        );
+
+        // Shared pointers are only counted once
+        let shared = Rc::new("hello world, this string is out-of-line".to_string());
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&vec![shared.clone()]).unwrap();
+        let once = serializer.into_serializer().into_inner();
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&vec![shared.clone(), shared])
+            .unwrap();
+        let twice = serializer.into_serializer().into_inner();
+        assert_eq!(
+            reachable_bytes::<Vec<Rc<String>>>(twice.as_ref()).unwrap()
+                - reachable_bytes::<Vec<Rc<String>>>(once.as_ref()).unwrap(),
+            core::mem::size_of::<rkyv::Archived<Rc<String>>>(),
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_compact() {
+        use rkyv::validation::validators::{compact, reachable_bytes, unreachable_bytes};
+
+        let shared = Rc::new("hello world, this string is out-of-line".to_string());
+        let value = vec![
+            shared.clone(),
+            Rc::new("hi".to_string()),
+            shared.clone(),
+            shared,
+        ];
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        // Leave unreachable data in front of the values of the archive
+        let mut serializer = DefaultSerializer::default();
+        serializer.write(&[0xff; 64]).unwrap();
+        serializer
+            .serialize_value(&"an old value that was replaced".to_string())
+            .unwrap();
+        serializer.serialize_value(&value).unwrap();
+        let garbage = serializer.into_serializer().into_inner();
+        assert!(unreachable_bytes::<Vec<Rc<String>>>(garbage.as_ref()).unwrap() > 64);
+
+        let compacted = compact::<Vec<Rc<String>>>(garbage.as_ref()).unwrap();
+        assert_eq!(compacted.as_slice(), buf.as_slice());
+        assert_eq!(
+            reachable_bytes::<Vec<Rc<String>>>(compacted.as_ref()).unwrap(),
+            reachable_bytes::<Vec<Rc<String>>>(garbage.as_ref()).unwrap(),
+        );
+
+        // The shared string is still only stored once
+        let archived = check_archived_root::<Vec<Rc<String>>>(compacted.as_ref()).unwrap();
+        assert_eq!(archived[0].as_ptr(), archived[2].as_ptr());
+        assert_eq!(archived[0].as_ptr(), archived[3].as_ptr());
+        let bytes = value[0].as_bytes();
+        assert_eq!(
+            compacted
+                .windows(bytes.len())
+                .filter(|w| *w == bytes)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_compact_interior() {
+        use rkyv::validation::validators::{compact, reachable_bytes};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Record {
+            shared: Rc<Vec<u64>>,
+            name: String,
+            map: BTreeMap<u32, String>,
+            empty: Vec<u64>,
+            boxed: Option<Box<u16>>,
+            missing: Option<Box<u16>>,
+        }
+
+        let shared = Rc::new(vec![1, 2, 3]);
+        let record = |name: &str, boxed| Record {
+            shared: shared.clone(),
+            name: name.to_string(),
+            map: (0..10)
+                .map(|i| (i, format!("{} entry {}", name, i)))
+                .collect(),
+            empty: Vec::new(),
+            boxed: Some(Box::new(boxed)),
+            missing: None,
+        };
+
+        // The root shares data with a value that was serialized before it, so the earlier value is
+        // left between the parts of the root
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&record("a replaced record", 1))
+            .unwrap();
+        serializer
+            .serialize_value(&record("the current record", 2))
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let compacted = compact::<Record>(buf.as_ref()).unwrap();
+        assert!(compacted.len() < buf.len());
+        assert_eq!(
+            reachable_bytes::<Record>(compacted.as_ref()).unwrap(),
+            reachable_bytes::<Record>(buf.as_ref()).unwrap(),
+        );
+        let replaced = b"a replaced record";
+        assert!(!compacted.windows(replaced.len()).any(|w| w == replaced));
+
+        let archived = check_archived_root::<Record>(compacted.as_ref()).unwrap();
+        assert_eq!(archived.shared.as_slice(), [1, 2, 3]);
+        assert_eq!(archived.name, "the current record");
+        assert_eq!(archived.map.len(), 10);
+        for (i, (key, value)) in archived.map.iter().enumerate() {
+            assert_eq!(*key, i as u32);
+            assert_eq!(value, &format!("the current record entry {}", i));
+        }
+        assert!(archived.empty.is_empty());
+        assert_eq!(archived.boxed.as_deref(), Some(&2));
+        assert!(archived.missing.is_none());
+
+        // Compacting an archive without unreachable data doesn't change it
+        let again = compact::<Record>(compacted.as_ref()).unwrap();
+        assert_eq!(again.as_slice(), compacted.as_slice());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_checked_tag() {
+        use rkyv::{
+            validation::{
+                check_archived_root_with_context,
+                validators::{DefaultValidator, TagValidator},
+            },
+            with::CheckedTag,
+        };
+
+        struct PluginId;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Message {
+            #[with(CheckedTag<PluginId>)]
+            plugin: u16,
+            payload: String,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Batch {
+            messages: Vec<Message>,
+        }
+
+        let value = Batch {
+            messages: vec![
+                Message {
+                    plugin: 3,
+                    payload: "hello".to_string(),
+                },
+                Message {
+                    plugin: 500,
+                    payload: "world".to_string(),
+                },
+            ],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let registry = [3u64, 500];
+        let mut validator =
+            TagValidator::<_, PluginId, _>::new(DefaultValidator::new(buf.as_ref()), |tag| {
+                registry.contains(&tag)
+            });
+        let archived =
+            check_archived_root_with_context::<Batch, _>(buf.as_ref(), &mut validator).unwrap();
+        assert_eq!(archived.messages[0].plugin, 3);
+        assert_eq!(archived.messages[1].plugin, 500);
+        let deserialized: Batch = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        let registry = [3u64];
+        let mut validator =
+            TagValidator::<_, PluginId, _>::new(DefaultValidator::new(buf.as_ref()), |tag| {
+                registry.contains(&tag)
+            });
+        assert!(
+            check_archived_root_with_context::<Batch, _>(buf.as_ref(), &mut validator).is_err()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_pair() {
+        use rkyv::validation::validators::{check_archived_pair, PairCheckError};
+
+        fn serialize(value: &Vec<String>) -> AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(value).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let reference = serialize(&vec!["hello".to_string()]);
+        let candidate = serialize(&vec!["a".to_string(), "b".to_string()]);
+
+        let archived = check_archived_pair::<Vec<String>>(&reference, &candidate).unwrap();
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived[1], "b");
+
+        // Too small to hold the root
+        let size = core::mem::size_of::<rkyv::Archived<Vec<String>>>();
+        let align = core::mem::align_of::<rkyv::Archived<Vec<String>>>();
+        let result = check_archived_pair::<Vec<String>>(&reference, &candidate[..size - 1]);
+        assert!(matches!(
+            result,
+            Err(PairCheckError::RootSizeMismatch { expected, actual })
+                if expected == size && actual == size - 1
+        ));
+
+        // Root shifted off of its alignment
+        let mut shifted = AlignedVec::new();
+        shifted.extend_from_slice(&candidate);
+        shifted.push(0);
+        let result = check_archived_pair::<Vec<String>>(&reference, &shifted);
+        assert!(matches!(
+            result,
+            Err(PairCheckError::RootAlignmentMismatch {
+                align: a,
+                expected: 0,
+                actual: 1,
+            }) if a == align
+        ));
+
+        // Invalid archives
+        let mut invalid = candidate.clone();
+        let len = invalid.len();
+        invalid[len - 4..].copy_from_slice(&100u32.to_ne_bytes());
+        let result = check_archived_pair::<Vec<String>>(&reference, &invalid);
+        assert!(matches!(
+            result,
+            Err(PairCheckError::CandidateCheckError(_))
+        ));
+        let result = check_archived_pair::<Vec<String>>(&invalid, &candidate);
+        assert!(matches!(
+            result,
+            Err(PairCheckError::ReferenceCheckError(_))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_bound() {
+        use core::ops::Bound;
+
+        serialize_and_check(&Bound::Included("hello world".to_string()));
+        serialize_and_check(&Bound::Excluded(42u32));
+        serialize_and_check(&Bound::<u32>::Unbounded);
+
+        // Invalid tag
+        let data = AlignedBytes([3u8, 0, 0, 0, 0, 0, 0, 0]);
+        check_archived_root::<Bound<u32>>(data.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_control_flow_and_poll() {
+        use core::{ops::ControlFlow, task::Poll};
+
+        serialize_and_check(&ControlFlow::<String, u32>::Continue(42));
+        serialize_and_check(&ControlFlow::<String, u32>::Break(
+            "hello world".to_string(),
+        ));
+        serialize_and_check(&Poll::Ready("hello world".to_string()));
+        serialize_and_check(&Poll::<u32>::Pending);
+
+        // Invalid tags
+        let data = AlignedBytes([2u8, 0, 0, 0, 0, 0, 0, 0]);
+        check_archived_root::<ControlFlow<u32, u32>>(data.as_ref()).unwrap_err();
+        check_archived_root::<Poll<u32>>(data.as_ref()).unwrap_err();
     }
 
     #[test]
@@ -559,4 +2133,402 @@ mod tests {
             ))
         ));
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_as_owned_slice() {
+        #[cfg(not(feature = "std"))]
+        use alloc::borrow::Cow;
+        use rkyv::with::AsOwned;
+        #[cfg(feature = "std")]
+        use std::borrow::Cow;
+
+        #[derive(Archive, Serialize, Deserialize, Clone, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Record {
+            id: u32,
+            name: String,
+            tags: Vec<String>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Batch<'a> {
+            label: String,
+            #[with(AsOwned)]
+            records: Cow<'a, [Record]>,
+        }
+
+        let records = (0..4)
+            .map(|id| Record {
+                id,
+                name: format!("record {}", id),
+                tags: vec!["a".to_string(); id as usize],
+            })
+            .collect::<Vec<_>>();
+        let value = Batch {
+            label: "batch".to_string(),
+            records: Cow::Borrowed(&records),
+        };
+
+        let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+        let archived = check_archived_root::<Batch>(&bytes).unwrap();
+        assert_eq!(archived.label, "batch");
+        assert_eq!(archived.records.len(), 4);
+        for (archived, record) in archived.records.iter().zip(records.iter()) {
+            assert_eq!(archived, record);
+        }
+
+        let deserialized = rkyv::from_bytes::<Batch<'static>>(&bytes).unwrap();
+        assert!(matches!(deserialized.records, Cow::Owned(_)));
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_with_header() {
+        use rkyv::{access_with_header, validation::validators::CheckHeaderError};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Header {
+            magic: [u8; 4],
+            version: u32,
+        }
+
+        let header = Header {
+            magic: *b"RKYV",
+            version: 2,
+        };
+        let value = vec!["hello".to_string(), "world".to_string()];
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&header).unwrap();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let (header, archived) = access_with_header::<Header, Vec<String>>(&buf, |header| {
+            header.magic == *b"RKYV" && header.version == 2
+        })
+        .unwrap();
+        assert_eq!(header.version, 2);
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived[0], "hello");
+        assert_eq!(archived[1], "world");
+
+        let result = access_with_header::<Header, Vec<String>>(&buf, |header| header.version == 1);
+        assert!(matches!(result, Err(CheckHeaderError::HeaderMismatch)));
+
+        let result = access_with_header::<Header, Vec<String>>(&buf[..8], |_| true);
+        assert!(matches!(result, Err(CheckHeaderError::BodyCheckError(_))));
+    }
+
+    #[cfg(feature = "size_32")]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_collect_errors() {
+        use rkyv::validation::{
+            validators::{
+                check_archived_root_collect_errors,
+                check_archived_root_collect_errors_with_max_depth,
+            },
+            CheckArchiveError,
+        };
+
+        let value = vec!["a string long enough to be stored out of line".to_string()];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root_collect_errors::<Vec<String>>(&buf, 8).unwrap();
+        assert_eq!(archived[0], value[0]);
+
+        // Invalid archive (three boxes that all claim the same bytes)
+        let synthetic_buf = AlignedBytes([
+            // "Hello world"
+            0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x20, 0x77, 0x6f, 0x72, 0x6c, 0x64, 0u8,
+            // First box
+            244u8, 255u8, 255u8, 255u8, // points 12 bytes back
+            11u8, 0u8, 0u8, 0u8, // slice is 11 bytes long
+            // Second box
+            236u8, 255u8, 255u8, 255u8, // points 20 bytes back
+            11u8, 0u8, 0u8, 0u8, // slice is 11 bytes long
+            // Third box
+            228u8, 255u8, 255u8, 255u8, // points 28 bytes back
+            11u8, 0u8, 0u8, 0u8, // slice is 11 bytes long
+        ]);
+
+        check_archived_root::<[Box<[u8]>; 3]>(synthetic_buf.as_ref()).unwrap_err();
+
+        // Both overlapping claims are reported and validation finishes
+        let errors =
+            check_archived_root_collect_errors::<[Box<[u8]>; 3]>(synthetic_buf.as_ref(), 8)
+                .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        for error in errors.iter() {
+            assert_eq!(error.offset, Some(0));
+            assert!(matches!(error.error, CheckArchiveError::ContextError(_)));
+        }
+
+        // Reaching the maximum number of errors stops validation at the last one
+        let errors =
+            check_archived_root_collect_errors::<[Box<[u8]>; 3]>(synthetic_buf.as_ref(), 2)
+                .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0].error,
+            CheckArchiveError::ContextError(_)
+        ));
+        assert_eq!(errors[1].offset, Some(0));
+        assert!(matches!(
+            errors[1].error,
+            CheckArchiveError::CheckBytesError(_)
+        ));
+
+        // Out of bounds pointers can't be recovered from
+        let errors =
+            check_archived_root_collect_errors::<[Box<[u8]>; 3]>(&synthetic_buf.as_ref()[4..], 8)
+                .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].offset, Some(8));
+
+        // Exceeding the maximum depth stops validation
+        let value = vec![vec![1u32, 2, 3], vec![4, 5]];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        check_archived_root_collect_errors_with_max_depth::<Vec<Vec<u32>>>(&buf, 8, 3).unwrap();
+        let errors = check_archived_root_collect_errors_with_max_depth::<Vec<Vec<u32>>>(&buf, 8, 2)
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_archive_builder() {
+        use core::mem::{offset_of, size_of};
+        use rkyv::ser::{
+            builder::{ArchiveBuilder, BuildError},
+            serializers::AlignedSerializer,
+        };
+
+        #[derive(Archive)]
+        #[archive(check_bytes)]
+        #[archive_attr(repr(C))]
+        struct Point {
+            x: i16,
+            y: i64,
+        }
+
+        #[derive(Archive)]
+        #[archive(check_bytes)]
+        #[archive_attr(repr(C))]
+        struct Shape {
+            name: String,
+            visible: bool,
+            origin: Point,
+            points: Vec<Point>,
+            weights: Vec<u32>,
+        }
+
+        fn write_point<S: Serializer>(
+            builder: &mut ArchiveBuilder<S>,
+            x: i16,
+            y: i64,
+        ) -> Result<(), BuildError<S::Error>> {
+            let point = builder.start_struct::<ArchivedPoint>()?;
+            builder.field(&point, offset_of!(ArchivedPoint, x))?;
+            builder.write(&x)?;
+            builder.field(&point, offset_of!(ArchivedPoint, y))?;
+            builder.write(&y)?;
+            builder.finish_struct(point)?;
+            Ok(())
+        }
+
+        let mut builder = ArchiveBuilder::new(AlignedSerializer::new(AlignedVec::new()));
+        let name = builder
+            .write_str("a shape with a long enough name to be out of line")
+            .unwrap();
+        let points = builder
+            .write_slice_with::<ArchivedPoint, _, _>(0..10, |builder, i| {
+                write_point(builder, i, -(i as i64))
+            })
+            .unwrap();
+        let weights = builder.write_slice(1..=4u32).unwrap();
+        assert_eq!(points.len(), 10);
+        assert_eq!(weights.len(), 4);
+
+        let shape = builder.start_struct::<ArchivedShape>().unwrap();
+        builder
+            .field(&shape, offset_of!(ArchivedShape, name))
+            .unwrap();
+        builder.write_string(name).unwrap();
+        builder
+            .field(&shape, offset_of!(ArchivedShape, visible))
+            .unwrap();
+        builder.write(&true).unwrap();
+        builder
+            .field(&shape, offset_of!(ArchivedShape, origin))
+            .unwrap();
+        write_point(&mut builder, 3, 4).unwrap();
+        builder
+            .field(&shape, offset_of!(ArchivedShape, points))
+            .unwrap();
+        builder.write_vec(&points).unwrap();
+        builder
+            .field(&shape, offset_of!(ArchivedShape, weights))
+            .unwrap();
+        builder.write_vec(&weights).unwrap();
+        builder.finish_struct(shape).unwrap();
+
+        let buf = builder.into_serializer().into_inner();
+        let archived = check_archived_root::<Shape>(buf.as_ref()).unwrap();
+        assert_eq!(
+            archived.name,
+            "a shape with a long enough name to be out of line"
+        );
+        assert!(archived.visible);
+        assert_eq!((archived.origin.x, archived.origin.y), (3, 4));
+        assert_eq!(archived.points.len(), 10);
+        for (i, point) in archived.points.iter().enumerate() {
+            assert_eq!((point.x, point.y), (i as i16, -(i as i64)));
+        }
+        assert_eq!(archived.weights.as_slice(), &[1, 2, 3, 4]);
+
+        // Fields have to be written in order and fit in their struct
+        let mut builder = ArchiveBuilder::new(AlignedSerializer::new(AlignedVec::new()));
+        let point = builder.start_struct::<ArchivedPoint>().unwrap();
+        builder.field(&point, offset_of!(ArchivedPoint, y)).unwrap();
+        builder.write(&1i64).unwrap();
+        assert!(matches!(
+            builder.field(&point, offset_of!(ArchivedPoint, x)),
+            Err(BuildError::FieldOutOfOrder { .. })
+        ));
+        builder.write(&1i64).unwrap();
+        assert!(matches!(
+            builder.finish_struct(point),
+            Err(BuildError::StructOverrun { .. })
+        ));
+
+        // Structs have to be finished by the builder that started them
+        let mut other = ArchiveBuilder::new(AlignedSerializer::new(AlignedVec::new()));
+        builder.write(&1i64).unwrap();
+        let point = builder.start_struct::<ArchivedPoint>().unwrap();
+        assert!(matches!(
+            other.field(&point, offset_of!(ArchivedPoint, x)),
+            Err(BuildError::StructNotStarted { pos: 0, .. })
+        ));
+        assert!(matches!(
+            other.finish_struct(point),
+            Err(BuildError::StructNotStarted { pos: 0, .. })
+        ));
+
+        // Elements have to be exactly the size of the element type
+        let mut builder = ArchiveBuilder::new(AlignedSerializer::new(AlignedVec::new()));
+        let result = builder.write_slice_with::<ArchivedPoint, _, _>(0..2, |builder, _| {
+            builder.write(&0i64).map(|_| ())
+        });
+        match result {
+            Err(BuildError::ElementSizeMismatch {
+                index,
+                expected,
+                written,
+            }) => {
+                assert_eq!(index, 0);
+                assert_eq!(expected, size_of::<ArchivedPoint>());
+                assert_eq!(written, 8);
+            }
+            _ => panic!("expected an element size mismatch"),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_shallow_root() {
+        use core::mem::size_of;
+        use rkyv::check_root_shallow;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        enum Shape {
+            Circle(u32),
+            Named(String),
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Shape::Circle(1)).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        check_root_shallow::<Shape>(buf.as_ref()).unwrap();
+
+        let value = Shape::Named("a name that is too long to be inlined".to_string());
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        check_root_shallow::<Shape>(buf.as_ref()).unwrap();
+
+        // Invalid UTF-8 in the out-of-line string isn't checked
+        let start = buf
+            .as_slice()
+            .windows(4)
+            .position(|w| w == b"name")
+            .unwrap();
+        buf[start] = 0xff;
+        check_root_shallow::<Shape>(buf.as_ref()).unwrap();
+        assert!(check_archived_root::<Shape>(buf.as_ref()).is_err());
+
+        // Invalid tags in the root are
+        let root = buf.len() - size_of::<ArchivedShape>();
+        buf[root] = 2;
+        check_root_shallow::<Shape>(buf.as_ref()).unwrap_err();
+
+        // So is a buffer too small to hold the root
+        check_root_shallow::<Shape>(&buf[..2]).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_shallow_root_sorted() {
+        use core::mem::size_of;
+        use rkyv::{
+            check_root_shallow,
+            with::{MapAsSoA, SortedVec, With},
+            Archived, FixedIsize, FixedUsize,
+        };
+
+        // Points every field of the root that isn't a length far out of bounds
+        fn corrupt_offsets<T: Archive>(buf: &mut AlignedVec, len: usize) {
+            let root = buf.len() - size_of::<Archived<T>>();
+            let len = (len as FixedUsize).to_ne_bytes();
+            for i in (root..buf.len()).step_by(size_of::<FixedUsize>()) {
+                let field = &mut buf[i..i + size_of::<FixedUsize>()];
+                if *field != len {
+                    field.copy_from_slice(&(FixedIsize::MIN / 2).to_ne_bytes());
+                }
+            }
+        }
+
+        // Checking whether the elements are sorted would read out of bounds
+        type SortedRoot = With<Vec<u32>, SortedVec>;
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(With::<_, SortedVec>::cast(&vec![1u32, 2, 3]))
+            .unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        corrupt_offsets::<SortedRoot>(&mut buf, 3);
+        check_root_shallow::<SortedRoot>(buf.as_ref()).unwrap();
+        check_archived_root::<SortedRoot>(buf.as_ref()).unwrap_err();
+
+        // So would checking whether the keys are sorted
+        type SoARoot = With<BTreeMap<u32, u32>, MapAsSoA>;
+        let map = (1..4).map(|i| (i, i * 10)).collect::<BTreeMap<_, _>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(With::<_, MapAsSoA>::cast(&map))
+            .unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        corrupt_offsets::<SoARoot>(&mut buf, 3);
+        check_root_shallow::<SoARoot>(buf.as_ref()).unwrap();
+        check_archived_root::<SoARoot>(buf.as_ref()).unwrap_err();
+    }
 }
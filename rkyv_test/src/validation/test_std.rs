@@ -25,4 +25,427 @@ mod tests {
         set.insert("baz".to_string());
         serialize_and_check(&set);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_as_sorted_vec() {
+        use crate::util::alloc::*;
+        use core::mem::size_of;
+        use rkyv::{
+            check_archived_root, check_root_shallow, collections::util::Entry, ser::Serializer,
+            with::AsSortedVec, Archive, FixedIsize, FixedUsize, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug), repr(transparent))]
+        struct Test {
+            #[with(AsSortedVec)]
+            values: HashMap<String, u32>,
+        }
+
+        let mut values = HashMap::new();
+        values.insert("hello".to_string(), 12);
+        values.insert("world".to_string(), 34);
+        values.insert("foo".to_string(), 56);
+        serialize_and_check(&Test { values });
+
+        // Entries with unsorted keys have the same layout as a sorted vec map
+        let (hello, world) = ("hello".to_string(), "world".to_string());
+        let entries = vec![
+            Entry {
+                key: &world,
+                value: &34u32,
+            },
+            Entry {
+                key: &hello,
+                value: &12u32,
+            },
+        ];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&entries).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Test>(buf.as_ref()).unwrap_err();
+
+        // Keys whose archived order differs from their unarchived order are rejected
+        #[derive(Archive, Serialize, PartialEq, Eq, Hash)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug, PartialEq, Eq, PartialOrd, Ord))]
+        struct Descending(u32);
+
+        impl PartialOrd for Descending {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for Descending {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                other.0.cmp(&self.0)
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug))]
+        struct Mismatched {
+            #[with(AsSortedVec)]
+            values: HashMap<Descending, u32>,
+        }
+
+        let values = (0..3).map(|i| (Descending(i), i)).collect();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Mismatched { values }).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Mismatched>(buf.as_ref()).unwrap_err();
+
+        // Shallow checks don't read the keys to check whether they're sorted
+        let values = (0..3).map(|i| (i.to_string(), i)).collect();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Test { values }).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let root = buf.len() - size_of::<ArchivedTest>();
+        for i in (root..buf.len()).step_by(size_of::<FixedUsize>()) {
+            let field = &mut buf[i..i + size_of::<FixedUsize>()];
+            if *field != (3 as FixedUsize).to_ne_bytes() {
+                field.copy_from_slice(&(FixedIsize::MIN / 2).to_ne_bytes());
+            }
+        }
+        check_root_shallow::<Test>(buf.as_ref()).unwrap();
+        check_archived_root::<Test>(buf.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_map_as_soa() {
+        use crate::util::alloc::*;
+        use rkyv::{check_archived_root, ser::Serializer, with::MapAsSoA, Archive, Serialize};
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug), repr(transparent))]
+        struct Test {
+            #[with(MapAsSoA)]
+            values: HashMap<String, u32>,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Parallel {
+            keys: Vec<String>,
+            values: Vec<u32>,
+        }
+
+        let mut values = HashMap::new();
+        values.insert("hello".to_string(), 12);
+        values.insert("world".to_string(), 34);
+        values.insert("foo".to_string(), 56);
+        serialize_and_check(&Test { values });
+
+        // Parallel vecs have the same layout as a struct-of-arrays map
+        let serialize = |keys: &[&str], values: &[u32]| {
+            let mut serializer = DefaultSerializer::default();
+            serializer
+                .serialize_value(&Parallel {
+                    keys: keys.iter().map(|k| k.to_string()).collect(),
+                    values: values.to_vec(),
+                })
+                .unwrap();
+            serializer.into_serializer().into_inner()
+        };
+
+        let buf = serialize(&["hello", "world"], &[12, 34]);
+        check_archived_root::<Test>(buf.as_ref()).unwrap();
+
+        // Unsorted keys
+        let buf = serialize(&["world", "hello"], &[34, 12]);
+        check_archived_root::<Test>(buf.as_ref()).unwrap_err();
+
+        // Mismatched lengths
+        let buf = serialize(&["hello", "world"], &[12]);
+        check_archived_root::<Test>(buf.as_ref()).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_hash_algorithm() {
+        use crate::util::alloc::*;
+        use core::mem::size_of;
+        use rkyv::{
+            archived_root, check_archived_root, collections::hash_index::HashAlgorithm,
+            ser::Serializer, with::SipHash, Archive, FixedUsize, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            #[with(SipHash)]
+            values: HashMap<u32, u32>,
+        }
+
+        let values = (0..10).map(|i| (i, i * 2)).collect::<HashMap<_, _>>();
+        serialize_and_check(&Test {
+            values: values.clone(),
+        });
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Test { values }).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<Test>(&buf).unwrap();
+        assert_eq!(archived.values.hash_algorithm(), HashAlgorithm::SipHash);
+        assert_eq!(archived.values.get(&3), Some(&6));
+
+        // The hash algorithm is stored in the highest bit of the length
+        let sip_hash_len: FixedUsize = 10 | 1 << (FixedUsize::BITS - 1);
+        let root = buf.len() - size_of::<ArchivedTest>();
+        let i = (root..buf.len())
+            .step_by(size_of::<FixedUsize>())
+            .find(|&i| buf[i..i + size_of::<FixedUsize>()] == sip_hash_len.to_ne_bytes())
+            .unwrap();
+
+        // Clearing it switches the map to SeaHash, which doesn't find the keys where SipHash put
+        // them
+        buf[i..i + size_of::<FixedUsize>()].copy_from_slice(&(10 as FixedUsize).to_ne_bytes());
+        let archived = unsafe { archived_root::<Test>(&buf) };
+        assert_eq!(archived.values.hash_algorithm(), HashAlgorithm::SeaHash);
+        assert_eq!(archived.values.len(), 10);
+        check_archived_root::<Test>(&buf).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_as_bloom_filter() {
+        use crate::util::alloc::*;
+        use rkyv::{
+            bloom::ArchivedBloomFilter, check_archived_root, ser::Serializer, with::AsBloomFilter,
+            Archive, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            #[with(AsBloomFilter)]
+            values: HashSet<String>,
+        }
+
+        let values = ["hello", "world"].iter().map(|v| v.to_string()).collect();
+        serialize_and_check(&Test { values });
+
+        // Bit arrays that point out of bounds are rejected
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Test {
+                values: (0..100).map(|i| i.to_string()).collect(),
+            })
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let len = buf.len();
+        check_archived_root::<Test>(&buf[len - 16..]).unwrap_err();
+
+        // Filters with no hashes or too many hashes are rejected
+        let num_hashes = |n: u32| -> [u8; 4] {
+            rkyv::to_bytes::<_, 256>(&n)
+                .unwrap()
+                .as_slice()
+                .try_into()
+                .unwrap()
+        };
+        let root = len - core::mem::size_of::<ArchivedTest>();
+        let pos = (root..len)
+            .step_by(4)
+            .find(|&i| buf[i..i + 4] == num_hashes(ArchivedBloomFilter::<String>::NUM_HASHES))
+            .unwrap();
+        let mut invalid = buf.clone();
+        invalid[pos..pos + 4]
+            .copy_from_slice(&num_hashes(ArchivedBloomFilter::<String>::MAX_NUM_HASHES));
+        check_archived_root::<Test>(&invalid).unwrap();
+        invalid[pos..pos + 4].copy_from_slice(&num_hashes(
+            ArchivedBloomFilter::<String>::MAX_NUM_HASHES + 1,
+        ));
+        check_archived_root::<Test>(&invalid).unwrap_err();
+        invalid[pos..pos + 4].copy_from_slice(&num_hashes(0));
+        check_archived_root::<Test>(&invalid).unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_c_string() {
+        use crate::util::alloc::*;
+        use rkyv::{check_archived_root, ser::Serializer};
+        use std::ffi::CString;
+
+        serialize_and_check(&CString::new("hello world").unwrap());
+        serialize_and_check(&CString::new("").unwrap());
+        // C strings don't have to be valid UTF-8
+        serialize_and_check(&CString::new(vec![0xff, 0xfe]).unwrap());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&CString::new("hello").unwrap())
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert_eq!(&buf[..6], b"hello\0");
+
+        // Interior nul
+        let mut invalid = buf.clone();
+        invalid[2] = 0;
+        check_archived_root::<CString>(invalid.as_ref()).unwrap_err();
+
+        // Missing nul terminator
+        let mut invalid = buf.clone();
+        invalid[5] = b'!';
+        check_archived_root::<CString>(invalid.as_ref()).unwrap_err();
+
+        let archived = check_archived_root::<CString>(buf.as_ref()).unwrap();
+        assert_eq!(
+            archived.as_c_str(),
+            CString::new("hello").unwrap().as_c_str()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_shared_arc_unsized() {
+        use crate::util::alloc::*;
+        use rkyv::{check_archived_root, ser::Serializer, Archive, Serialize};
+        use std::sync::Arc;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            a: Arc<str>,
+            b: Arc<str>,
+            c: Arc<[u32]>,
+        }
+
+        let shared = Arc::<str>::from("hello world");
+        let value = Test {
+            a: shared.clone(),
+            b: shared,
+            c: Arc::from(vec![1, 2, 3]),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<Test>(buf.as_ref()).unwrap();
+        assert_eq!(&*archived.a, "hello world");
+        assert_eq!(archived.a.as_ptr(), archived.b.as_ptr());
+        assert_eq!(&*archived.c, &[1, 2, 3]);
+
+        // The string metadata is checked, so a length past the end of the archive is invalid
+        let len_pos = &archived.a as *const _ as usize - buf.as_ptr() as usize
+            + core::mem::size_of::<rkyv::FixedIsize>();
+        let mut invalid = buf.clone();
+        invalid.as_mut_slice()[len_pos..len_pos + core::mem::size_of::<rkyv::FixedUsize>()]
+            .fill(0xff);
+        assert!(check_archived_root::<Test>(invalid.as_ref()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_vec_of_shared_arcs() {
+        use crate::util::alloc::*;
+        use rkyv::{check_archived_root, ser::Serializer, Archive, Serialize};
+        use std::sync::Arc;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Node {
+            id: u32,
+            name: String,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Group {
+            leader: Arc<Node>,
+            members: Vec<Arc<Node>>,
+        }
+
+        let nodes = (0..4)
+            .map(|id| {
+                Arc::new(Node {
+                    id,
+                    name: format!("node {}", id),
+                })
+            })
+            .collect::<Vec<_>>();
+        // Every node is claimed many times from within one vec, and again from other vecs
+        let value = (0..16)
+            .map(|i| Group {
+                leader: nodes[i % nodes.len()].clone(),
+                members: nodes.iter().cycle().skip(i).take(32).cloned().collect(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = check_archived_root::<Vec<Group>>(buf.as_ref()).unwrap();
+
+        for (i, group) in archived.iter().enumerate() {
+            let leader = &*group.leader;
+            assert_eq!(leader.id as usize, i % nodes.len());
+            for (j, member) in group.members.iter().enumerate() {
+                let expected = &*archived[(i + j) % nodes.len()].leader;
+                assert_eq!(member.id, expected.id);
+                assert_eq!(&**member as *const _, expected as *const _);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_check_large_vec() {
+        use crate::util::alloc::*;
+        use rkyv::bytecheck::SliceCheckError;
+        use rkyv::{
+            check_archived_root,
+            ser::Serializer,
+            validation::{
+                owned::OwnedPointerError, validators::par_check_archived_vec, CheckArchiveError,
+            },
+            Archive, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Record {
+            id: u32,
+            active: bool,
+            name: String,
+            tags: Vec<u16>,
+        }
+
+        let records = (0..200_000)
+            .map(|id| Record {
+                id,
+                active: id % 3 == 0,
+                name: format!("a record name that is stored out of line {}", id),
+                tags: (0..id as u16 % 8).collect(),
+            })
+            .collect::<Vec<_>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&records).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        check_archived_root::<Vec<Record>>(buf.as_ref()).unwrap();
+        let archived = par_check_archived_vec::<Record>(buf.as_ref()).unwrap();
+
+        assert_eq!(archived.len(), records.len());
+        assert_eq!(archived[123_456].id, 123_456);
+        assert_eq!(archived[123_456].tags.len(), 123_456 % 8);
+
+        // Corrupt one element and make sure its index is reported
+        let offset = &archived[150_000].active as *const _ as usize - buf.as_ptr() as usize;
+        buf[offset] = 2;
+        match par_check_archived_vec::<Record>(buf.as_ref()) {
+            Err(CheckArchiveError::CheckBytesError(OwnedPointerError::ValueCheckBytesError(
+                SliceCheckError::CheckBytes { index, .. },
+            ))) => assert_eq!(index, 150_000),
+            _ => panic!("expected the corrupted element to fail validation"),
+        }
+    }
 }